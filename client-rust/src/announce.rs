@@ -0,0 +1,15 @@
+// Screen-reader announcements: kills, deaths and joining or respawning are
+// written as text into a visually hidden aria-live region, which screen
+// readers read out as it changes.
+
+pub const REGION_ID: &str = "announcer";
+
+/// Queue `text` for the screen reader
+pub fn say(text: &str) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(el) = document.get_element_by_id(REGION_ID) {
+        // Repeating the last line wouldn't count as a change, so clear it first
+        el.set_text_content(None);
+        el.set_text_content(Some(text));
+    }
+}
@@ -7,6 +7,7 @@ use crate::lobby;
 use crate::game_loop;
 use crate::input;
 use crate::controller;
+use crate::i18n::{t, Msg};
 
 /// Detect the base path from current URL: "/rust/" if loaded from /rust/*, otherwise "/"
 pub fn base_path() -> &'static str {
@@ -19,38 +20,42 @@ pub fn base_path() -> &'static str {
     BASE.with(|b| if b == "/rust/" { "/rust/" } else { "/" })
 }
 
-const UUID_PATTERN: &str = "[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}";
-
-fn extract_uuid_from_path(pathname: &str) -> Option<String> {
-    let uuid_re = js_sys::RegExp::new(&format!("^(?:/rust)?/({})$", UUID_PATTERN), "");
-    uuid_re.exec(pathname).and_then(|m| m.get(1).as_string())
-}
-
 #[component]
 pub fn App() -> impl IntoView {
-    // Check for controller mode
     let window = web_sys::window().unwrap();
     let location = window.location();
     let search = location.search().unwrap_or_default();
     let params = web_sys::UrlSearchParams::new_with_str(&search).unwrap();
-    let control_pid = params.get("c");
+    let coach_pid = params.get("coach");
+    let coach_key = params.get("ck").unwrap_or_default();
 
     let pathname = location.pathname().unwrap_or_default();
-    let uuid_match = extract_uuid_from_path(&pathname);
+    let uuid_match = crate::route::session_from_path(&pathname);
+
+    let settings = crate::settings::Settings::load();
+    crate::i18n::set_lang(settings.lang);
+    crate::palette::set_vision(settings.vision);
 
     // Controller mode
-    if let Some(pid) = control_pid {
-        if let Some(sid) = &uuid_match {
-            return view! { <ControllerMode sid=sid.clone() pid=pid /> }.into_any();
-        }
+    if let Some(t) = controller::control_target() {
+        return view! { <controller::ControllerMode sid=t.sid pid=t.pid gunner=t.gunner /> }.into_any();
     }
 
     // Normal game mode
     let game_state = state::new_shared_state();
+    game_state.borrow_mut().settings = settings;
 
-    // Check URL for session UUID
+    // ?seed=N pins the visual RNG, for golden-image tests
+    if let Some(seed) = params.get("seed").and_then(|v| v.parse::<u64>().ok()) {
+        crate::rng::seed(Some(seed));
+    }
+
+    // Check URL for session UUID (coach links spectate a player instead of joining)
     if let Some(sid) = uuid_match {
-        game_state.borrow_mut().url_session_id = Some(sid);
+        match coach_pid {
+            Some(pid) => game_state.borrow_mut().coach_target = Some((sid, pid, coach_key)),
+            None => game_state.borrow_mut().url_session_id = Some(sid),
+        }
     }
 
     let phase_signal = RwSignal::new(Phase::Lobby);
@@ -67,6 +72,7 @@ pub fn App() -> impl IntoView {
     );
 
     Network::connect(&net);
+    crate::bugreport::set_reporter(net.clone());
 
     // Start input send loop (20Hz)
     let net_clone = net.clone();
@@ -76,6 +82,32 @@ pub fn App() -> impl IntoView {
     // Leak the interval to keep it alive
     std::mem::forget(_input_interval);
 
+    // Relay camera to an attached coach, and report viewport and death-cam changes for interest culling (10Hz)
+    let net_clone = net.clone();
+    let _view_interval = gloo_timers::callback::Interval::new(1000 / crate::constants::VIEW_RATE, move || {
+        Network::send_view(&net_clone);
+        Network::send_viewport(&net_clone);
+        Network::send_spectate(&net_clone);
+    });
+    std::mem::forget(_view_interval);
+
+    // Heartbeat: detect half-open connections
+    let net_clone = net.clone();
+    let _heartbeat_interval = gloo_timers::callback::Interval::new(crate::constants::HEARTBEAT_INTERVAL, move || {
+        Network::heartbeat(&net_clone);
+    });
+    std::mem::forget(_heartbeat_interval);
+
+    // Page Visibility: pause frames and input while the tab is hidden
+    let net_clone = net.clone();
+    let on_visibility = wasm_bindgen::closure::Closure::wrap(Box::new(move |_: web_sys::Event| {
+        let hidden = web_sys::window().unwrap().document().unwrap().hidden();
+        Network::set_hidden(&net_clone, hidden);
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let _ = window.document().unwrap()
+        .add_event_listener_with_callback("visibilitychange", on_visibility.as_ref().unchecked_ref());
+    on_visibility.forget();
+
     // Start session list refresh (3s) while in lobby
     let net_clone = net.clone();
     let _refresh_interval = gloo_timers::callback::Interval::new(3000, move || {
@@ -152,12 +184,25 @@ fn GameView(
             let phase_pop = _phase;
             let closure = wasm_bindgen::closure::Closure::wrap(Box::new(move |_: web_sys::Event| {
                 let s = state_pop.borrow();
+                if s.replay_mode {
+                    drop(s);
+                    crate::replay::exit_playback();
+                    return;
+                }
+                if s.practice_mode {
+                    drop(s);
+                    crate::practice::exit();
+                    return;
+                }
                 if s.phase == Phase::Playing || s.phase == Phase::Dead {
                     drop(s);
                     Network::send_leave(&net_pop);
+                    crate::replay::stop();
+                    crate::clip::stop();
                     let mut s = state_pop.borrow_mut();
                     s.session_id = None;
                     s.my_id = None;
+                    s.my_pid = None;
                     s.controller_attached = false;
                     s.phase = Phase::Lobby;
                     phase_pop.set(Phase::Lobby);
@@ -172,13 +217,26 @@ fn GameView(
     view! {
         <canvas id="bgCanvas"></canvas>
         <canvas id="gameCanvas"></canvas>
+        <canvas id="hudCanvas"></canvas>
         <DonationBanner />
+        <div id="reconnectBanner">
+            <span id="reconnectText"></span>
+            <button id="reconnectRetry" on:click={
+                let net = net_clone.clone();
+                move |_| Network::retry_now(&net)
+            }>"Retry now"</button>
+        </div>
+        <crate::settings::SettingsPanel state=(*state_clone).clone() net=(*net_clone).clone() />
+        <div id=crate::announce::REGION_ID class="sr-only" aria-live="polite" aria-atomic="true"></div>
 
         {move || {
             let p = phase.get();
             // Subscribe to expired signal to re-render when session expires
             let _expired = expired.get();
             if p == Phase::Lobby {
+                if state_clone.borrow().coach_target.is_some() {
+                    return view! { <lobby::CoachWaiting /> }.into_any();
+                }
                 let has_url_session = state_clone.borrow().url_session_id.is_some();
                 if has_url_session {
                     view! {
@@ -198,6 +256,10 @@ fn GameView(
                         />
                     }.into_any()
                 }
+            } else if state_clone.borrow().replay_mode {
+                view! { <ReplayControls /> }.into_any()
+            } else if state_clone.borrow().practice_mode {
+                view! { <PracticeControls /> }.into_any()
             } else {
                 view! {
                     <IngameUI state=(*state_clone).clone() net=(*net_clone).clone() />
@@ -209,12 +271,20 @@ fn GameView(
 
 #[component]
 fn IngameUI(state: SharedState, net: SharedNetwork) -> impl IntoView {
-    let _ = net;
     // Setup buttons after this component mounts
     let state_for_setup = send_wrapper::SendWrapper::new(state.clone());
+    let net_for_setup = send_wrapper::SendWrapper::new(net.clone());
     Effect::new(move |_| {
         crate::canvas::setup_fullscreen();
-        crate::canvas::setup_controller_btn((*state_for_setup).clone());
+        if state_for_setup.borrow().coach_target.is_none() {
+            crate::canvas::setup_controller_btn((*state_for_setup).clone());
+            crate::canvas::setup_coach_btn((*state_for_setup).clone());
+            crate::replay::setup_replay_btn();
+            crate::bugreport::setup_bug_btn((*state_for_setup).clone(), (*net_for_setup).clone());
+            crate::keybinds::setup_keys_btn();
+            crate::console::setup((*state_for_setup).clone());
+            crate::touch_layout::setup_layout_editor(state_for_setup.borrow().is_mobile);
+        }
     });
 
     view! {
@@ -230,11 +300,128 @@ fn IngameUI(state: SharedState, net: SharedNetwork) -> impl IntoView {
             </svg>
         </button>
         <div id="controllerOverlay">
-            <p class="qr-hint">"Scan with your phone to use as controller"</p>
+            <p class="qr-hint" id="qrHint">"Scan with your phone to use as controller"</p>
             <div class="qr-box"><img id="qrImg" alt="QR Code"/></div>
             <p class="qr-url" id="qrUrl"></p>
+            <button class="btn-close" id="qrRole">"Add a gunner"</button>
             <button class="btn-close" id="qrClose">"Close"</button>
         </div>
+        <button id="coachBtn" title="Invite Coach">
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round">
+                <path d="M1 8s2.5-5 7-5 7 5 7 5-2.5 5-7 5-7-5-7-5z"/>
+                <circle cx="8" cy="8" r="2"/>
+            </svg>
+        </button>
+        <button id="replayBtn" title="Download Replay">
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round">
+                <path d="M8 2v8M4.5 6.5L8 10l3.5-3.5M2 14h12"/>
+            </svg>
+        </button>
+        <div id="coachOverlay">
+            <p class="qr-hint">"Send this link to your coach to share your view"</p>
+            <p class="qr-url" id="coachUrl"></p>
+            <button class="btn-close" id="coachClose">"Close"</button>
+        </div>
+        <button id="bugBtn" title="Report a Bug">
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round">
+                <ellipse cx="8" cy="9.5" rx="3.5" ry="4.5"/>
+                <path d="M8 5v9M4.5 9H1.5M14.5 9h-3M5 5.5L3 3.5M11 5.5l2-2"/>
+            </svg>
+        </button>
+        <div id="bugOverlay">
+            <p class="qr-hint">"Report a bug"</p>
+            <textarea id="bugDesc" maxlength="1000" placeholder="What happened? What did you expect?"></textarea>
+            <label class="bug-shot"><input type="checkbox" id="bugShot" checked=true/>" Include screenshot (download only)"</label>
+            <p class="bug-status" id="bugStatus"></p>
+            <div class="bug-actions">
+                <button class="btn-close" id="bugSend">"Send"</button>
+                <button class="btn-close" id="bugDownload">"Download"</button>
+                <button class="btn-close" id="bugClose">"Close"</button>
+            </div>
+        </div>
+        <div id="debugConsole">
+            <pre id="consoleLog"></pre>
+            <input id="consoleInput" type="text" autocomplete="off" spellcheck="false" placeholder="/help"/>
+        </div>
+        <pre id="inspector"></pre>
+        <pre id="netLog"></pre>
+        <button id="settingsBtn" title="Settings (Esc)" on:click=|_| crate::settings::toggle()>
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round">
+                <circle cx="8" cy="8" r="2.5"/>
+                <path d="M8 1v2M8 13v2M1 8h2M13 8h2M3 3l1.5 1.5M11.5 11.5L13 13M3 13l1.5-1.5M11.5 4.5L13 3"/>
+            </svg>
+        </button>
+        <button id="keysBtn" title="Controls">
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round">
+                <rect x="1" y="4" width="14" height="9" rx="1.5"/>
+                <path d="M4 7h1M7.5 7h1M11 7h1M5 10h6"/>
+            </svg>
+        </button>
+        <div id="keysOverlay">
+            <p class="qr-hint">"Controls \u{2014} click a binding, then press the new key"</p>
+            <div id="keysList"></div>
+            <div class="bug-actions">
+                <button class="btn-close" id="keysTouchLayout">"Touch layout"</button>
+                <button class="btn-close" id="keysReset">"Reset"</button>
+                <button class="btn-close" id="keysClose">"Close"</button>
+            </div>
+        </div>
+        {(state.borrow().phase == Phase::Dead).then(|| view! {
+            <div id="respawnPick">
+                <crate::ship_select::ShipPicker state=state.clone() net=net.clone() caption=t(Msg::RespawnAs) />
+            </div>
+        })}
+        <button id="tutorialSkip" class:visible=state.borrow().tutorial.is_some() on:click={
+            let state = state.clone();
+            move |_| crate::tutorial::finish(&mut state.borrow_mut())
+        }>{t(Msg::SkipTutorial)}</button>
+        <div id="tlOverlay">
+            <div class="tl-zone" id="tlJoystick"><span>"Joystick"</span><div class="tl-handle"></div></div>
+            <div class="tl-zone" id="tlFire"><span>"Fire"</span><div class="tl-handle"></div></div>
+            <div class="tl-zone" id="tlBoost"><span>"Boost"</span><div class="tl-handle"></div></div>
+            <div class="tl-actions">
+                <button class="btn-close" id="tlSave">"Save"</button>
+                <button class="btn-close" id="tlReset">"Default"</button>
+                <button class="btn-close" id="tlCancel">"Cancel"</button>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ReplayControls() -> impl IntoView {
+    let speed = crate::replay::speed();
+    let speed_options = crate::replay::SPEEDS.iter().map(|&sp| {
+        view! { <option value=sp.to_string() selected=sp == speed>{format!("{}x", sp)}</option> }
+    }).collect::<Vec<_>>();
+
+    view! {
+        <div id="replayBar">
+            <button id="replayPlay" on:click=move |_| crate::replay::toggle_pause()>"\u{23F8}"</button>
+            <input type="range" id="replaySeek" min="0" max="0" value="0"
+                on:input=move |e: web_sys::Event| {
+                    let v = event_target_value(&e).parse::<f64>().unwrap_or(0.0);
+                    crate::replay::seek(v);
+                }
+            />
+            <span id="replayTime">"0:00 / 0:00"</span>
+            <select id="replaySpeed" on:change=move |e: web_sys::Event| {
+                if let Ok(sp) = event_target_value(&e).parse::<f64>() {
+                    crate::replay::set_speed(sp);
+                }
+            }>{speed_options}</select>
+            <button id="replayExit" on:click=move |_| crate::replay::exit_playback()>"Exit"</button>
+        </div>
+    }
+}
+
+#[component]
+fn PracticeControls() -> impl IntoView {
+    view! {
+        <div id="replayBar">
+            <span id="replayTime">{t(Msg::PracticeArena)}</span>
+            <button id="replayExit" on:click=move |_| crate::practice::exit()>"Exit"</button>
+        </div>
     }
 }
 
@@ -293,48 +480,3 @@ fn DonationBanner() -> impl IntoView {
         </div>
     }
 }
-
-#[component]
-fn ControllerMode(sid: String, pid: String) -> impl IntoView {
-    // Init controller on mount
-    let sid_clone = sid.clone();
-    let pid_clone = pid.clone();
-    Effect::new(move |_| {
-        controller::init_controller(&sid_clone, &pid_clone);
-    });
-
-    view! {
-        <div id="controllerRoot">
-            <div id="ctrlRotateMsg">
-                <div class="rotate-icon">
-                    <svg width="80" height="80" viewBox="0 0 80 80" fill="none" stroke="#6688aa" stroke-width="2">
-                        <rect x="20" y="10" width="40" height="60" rx="4" stroke-dasharray="4 2"/>
-                        <path d="M50 70 L70 50 L70 30 L30 30 L10 50 L10 70 Z" fill="rgba(50,100,200,0.1)" stroke="#4488ff" stroke-dasharray="4 2"/>
-                        <path d="M55 25 C60 15, 70 20, 65 28" stroke="#ffcc00" stroke-width="2" fill="none"/>
-                        <path d="M63 22 L65 28 L59 27" stroke="#ffcc00" stroke-width="2" fill="none"/>
-                    </svg>
-                </div>
-                <p>"Rotate your phone to landscape"</p>
-            </div>
-            <div id="ctrlPad" style="display:none;">
-                <div id="ctrlStatus">"Connecting..."</div>
-                <div class="ctrl-divider-left"></div>
-                <div class="ctrl-divider-right"></div>
-                <div class="ctrl-center">
-                    <div class="ctrl-boost-indicator" id="boostIndicator"></div>
-                    <div class="ctrl-label">"BOOST"</div>
-                </div>
-                <div class="ctrl-left">
-                    <div class="ctrl-label">"Drag to navigate"</div>
-                    <div class="ctrl-joystick-ring" id="joystickRing">
-                        <div class="ctrl-joystick-knob" id="joystickKnob"></div>
-                    </div>
-                </div>
-                <div class="ctrl-right">
-                    <div class="ctrl-label">"Tap to fire"</div>
-                    <div class="ctrl-fire-indicator" id="fireIndicator"></div>
-                </div>
-            </div>
-        </div>
-    }
-}
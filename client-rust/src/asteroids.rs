@@ -1,7 +1,6 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
 use web_sys::{CanvasRenderingContext2d, HtmlImageElement};
-use crate::constants::ASTEROID_RENDER_SIZE;
+use crate::constants::{ASTEROID_HP, ASTEROID_RENDER_SIZE};
 use crate::protocol::AsteroidState;
 
 const ASTEROID_FILES: [&str; 4] = [
@@ -25,18 +24,48 @@ pub fn load_asteroid_image() {
     ASTEROID_IMGS.with(|ai| *ai.borrow_mut() = images);
 }
 
-fn id_to_variant(id: &str) -> usize {
-    let mut h: i32 = 0;
-    for b in id.bytes() {
-        h = h.wrapping_mul(31).wrapping_add(b as i32);
+// Crack polylines across the rock, as fractions of its half size. The first
+// two show below half HP, all of them below a quarter.
+const CRACKS: [&[(f64, f64)]; 4] = [
+    &[(-0.1, -0.05), (-0.35, -0.2), (-0.45, -0.45), (-0.6, -0.5)],
+    &[(-0.1, -0.05), (0.2, 0.1), (0.3, 0.4), (0.5, 0.55)],
+    &[(0.05, -0.1), (0.25, -0.3), (0.5, -0.35)],
+    &[(-0.05, 0.05), (-0.3, 0.25), (-0.35, 0.5)],
+];
+
+fn id_to_variant(id: u32) -> usize {
+    id as usize % ASTEROID_FILES.len()
+}
+
+/// How many CRACKS to draw at this HP (0 HP means the server didn't say)
+fn crack_count(hp: i32) -> usize {
+    if hp <= 0 || hp * 2 > ASTEROID_HP {
+        0
+    } else if hp * 4 > ASTEROID_HP {
+        2
+    } else {
+        CRACKS.len()
     }
-    ((h % ASTEROID_FILES.len() as i32) + ASTEROID_FILES.len() as i32) as usize % ASTEROID_FILES.len()
 }
 
-pub fn render_asteroids(
+fn draw_cracks(ctx: &CanvasRenderingContext2d, half: f64, count: usize) {
+    ctx.set_stroke_style_str("rgba(20, 12, 6, 0.8)");
+    ctx.set_line_width(2.0);
+    ctx.begin_path();
+    for crack in &CRACKS[..count] {
+        for (i, (x, y)) in crack.iter().enumerate() {
+            if i == 0 { ctx.move_to(x * half, y * half); } else { ctx.line_to(x * half, y * half); }
+        }
+    }
+    ctx.stroke();
+}
+
+/// `since` is seconds since the snapshot, to keep each rock turning at its spin in between
+pub fn render_asteroids<'a>(
     ctx: &CanvasRenderingContext2d,
-    asteroids: &HashMap<String, AsteroidState>,
+    asteroids: impl Iterator<Item = &'a AsteroidState>,
     offset_x: f64, offset_y: f64, vw: f64, vh: f64,
+    since: f64,
 ) {
     ASTEROID_IMGS.with(|ai| {
         let images = ai.borrow();
@@ -44,23 +73,27 @@ pub fn render_asteroids(
 
         let half = ASTEROID_RENDER_SIZE / 2.0;
 
-        for (id, ast) in asteroids {
+        for ast in asteroids {
             let sx = ast.x - offset_x;
             let sy = ast.y - offset_y;
             if sx < -half - 20.0 || sx > vw + half + 20.0 || sy < -half - 20.0 || sy > vh + half + 20.0 {
                 continue;
             }
 
-            let variant = id_to_variant(id);
+            let variant = id_to_variant(ast.id);
             let img = &images[variant];
             if img.natural_width() == 0 { continue; }
 
             ctx.save();
             ctx.translate(sx, sy).unwrap_or(());
-            ctx.rotate(ast.r).unwrap_or(());
+            ctx.rotate(ast.r + ast.sp * since).unwrap_or(());
             let _ = ctx.draw_image_with_html_image_element_and_dw_and_dh(
                 img, -half, -half, ASTEROID_RENDER_SIZE, ASTEROID_RENDER_SIZE,
             );
+            let cracks = crack_count(ast.hp);
+            if cracks > 0 {
+                draw_cracks(ctx, half, cracks);
+            }
             ctx.restore();
         }
     });
@@ -1,8 +1,14 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use serde::{Deserialize, Serialize};
 use web_sys::CanvasRenderingContext2d;
-use crate::state::SharedState;
-
-const AIM_ORBIT_R: f64 = 360.0;
+#[cfg(not(feature = "controller-only"))]
+use crate::state::{SharedState, GameState};
+#[cfg(not(feature = "controller-only"))]
+use crate::spatial::Kind;
+#[cfg(not(feature = "controller-only"))]
+use crate::constants::{mob_radius, PLAYER_RADIUS, SD_MOB_RADIUS};
+
+pub const AIM_ORBIT_R: f64 = 360.0;
 const AIM_DETECT_R: f64 = 150.0;
 const AIM_FREE_R: f64 = 150.0;
 const AIM_LOCK_R: f64 = 20.0;
@@ -11,23 +17,131 @@ const AIM_SPIN_MAX: f64 = 8.0;
 
 thread_local! {
     static AIM_STATE: RefCell<AimState> = RefCell::new(AimState::default());
+    // Touch auto-aim target kept between inputs (stickiness)
+    static TOUCH_LOCK: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// Touch aim-assist strength (phone controller and mobile joystick), per device
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Assist {
+    Off,
+    Low,
+    High,
+}
+
+impl Assist {
+    pub fn parse(v: &str) -> Assist {
+        match v {
+            "off" => Assist::Off,
+            "low" => Assist::Low,
+            _ => Assist::High,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Assist::Off => "off",
+            Assist::Low => "low",
+            Assist::High => "high",
+        }
+    }
+
+    /// How close to the aim point an enemy must be to get picked up
+    pub fn detect_r(self) -> f64 {
+        match self {
+            Assist::Off => 0.0,
+            Assist::Low => AIM_DETECT_R * 0.6,
+            Assist::High => AIM_DETECT_R,
+        }
+    }
+
+    /// How far the aim point can drift off a locked enemy before the lock breaks
+    pub fn sticky_r(self) -> f64 {
+        match self {
+            Assist::Off => 0.0,
+            Assist::Low => self.detect_r(),
+            Assist::High => AIM_DETECT_R * 1.5,
+        }
+    }
+}
+
+/// Pick an enemy near the touch aim point with assist strength `a`
+pub fn pick_target(
+    a: Assist,
+    lock: Option<u32>,
+    aim_x: f64, aim_y: f64,
+    enemies: impl Iterator<Item = (u32, f64, f64, f64)> + Clone,
+) -> Option<(u32, f64, f64)> {
+    if a == Assist::Off {
+        return None;
+    }
+    pick_within(a.detect_r(), a.sticky_r(), lock, aim_x, aim_y, enemies)
+}
+
+/// Keep the current lock while it stays within `sticky_r` of the aim point,
+/// otherwise take the closest enemy within `detect_r`. Distances are to the
+/// enemy's hull, so a Star Destroyer is picked up as far out as it is wide.
+/// `enemies` yields (id, x, y, collision radius); returns the chosen id and position.
+pub fn pick_within(
+    detect_r: f64, sticky_r: f64,
+    lock: Option<u32>,
+    aim_x: f64, aim_y: f64,
+    enemies: impl Iterator<Item = (u32, f64, f64, f64)> + Clone,
+) -> Option<(u32, f64, f64)> {
+    let dist = |x: f64, y: f64, r: f64| ((x - aim_x).hypot(y - aim_y) - r).max(0.0);
+    if let Some(lock) = lock {
+        if let Some((id, x, y, _)) = enemies.clone().find(|&(id, x, y, r)| id == lock && dist(x, y, r) <= sticky_r) {
+            return Some((id, x, y));
+        }
+    }
+    let mut best_dist = detect_r;
+    let mut best = None;
+    for (id, x, y, r) in enemies {
+        let d = dist(x, y, r);
+        if d <= best_dist {
+            best_dist = d;
+            best = Some((id, x, y));
+        }
+    }
+    best
+}
+
+/// Mobile joystick auto-aim: world point to steer at, if an enemy is picked up
+#[cfg(not(feature = "controller-only"))]
+pub fn touch_target(s: &GameState, aim_x: f64, aim_y: f64) -> Option<(f64, f64)> {
+    let r = s.settings.aim_assist.sticky_r().max(s.settings.aim_assist.detect_r());
+    let enemies = s.grid.query_radius(Kind::Player, aim_x, aim_y, r + PLAYER_RADIUS)
+        .filter(|id| Some(*id) != s.my_id)
+        .filter_map(|id| s.players.get(&id).filter(|p| p.a).map(|p| (id, p.x, p.y, PLAYER_RADIUS)))
+        .chain(s.grid.query_radius(Kind::Mob, aim_x, aim_y, r + SD_MOB_RADIUS)
+            .filter_map(|id| s.mobs.get(&id).filter(|m| m.a).map(|m| (id, m.x, m.y, mob_radius(m.s)))));
+    TOUCH_LOCK.with(|lock| {
+        let picked = pick_target(s.settings.aim_assist, lock.get(), aim_x, aim_y, enemies);
+        lock.set(picked.map(|(id, _, _)| id));
+        picked.map(|(_, x, y)| (x, y))
+    })
+}
+
+/// Ease the lock-on animation toward 1 while locked, back to 0 otherwise
+fn step_progress(progress: f64, locked: bool, dt: f64) -> f64 {
+    if locked {
+        (progress + AIM_ANIM_SPEED * dt).min(1.0)
+    } else {
+        (progress - AIM_ANIM_SPEED * dt).max(0.0)
+    }
 }
 
 #[derive(Default)]
 struct AimState {
-    target_id: Option<String>,
+    target_id: Option<u32>,
     target_x: f64,
     target_y: f64,
     progress: f64,
     spin_angle: f64,
 }
 
-struct Enemy {
-    id: String,
-    x: f64,
-    y: f64,
-}
-
+#[cfg(not(feature = "controller-only"))]
 pub fn update_and_draw_controller_aim(
     ctx: &CanvasRenderingContext2d,
     state: &SharedState,
@@ -35,8 +149,8 @@ pub fn update_and_draw_controller_aim(
     dt: f64,
 ) {
     let s = state.borrow();
-    let my_id = match &s.my_id {
-        Some(id) => id.clone(),
+    let my_id = match s.my_id {
+        Some(id) => id,
         None => return,
     };
     let me = match s.players.get(&my_id) {
@@ -47,15 +161,17 @@ pub fn update_and_draw_controller_aim(
     let orbit_wx = me.x + me.r.cos() * AIM_ORBIT_R;
     let orbit_wy = me.y + me.r.sin() * AIM_ORBIT_R;
 
-    // Build enemy list
+    // Build enemy list from what's near the orbit point
     let mut enemies = Vec::new();
-    for (id, p) in &s.players {
-        if *id == my_id || !p.a { continue; }
-        enemies.push(Enemy { id: format!("p_{}", id), x: p.x, y: p.y });
+    for id in s.grid.query_radius(Kind::Player, orbit_wx, orbit_wy, AIM_DETECT_R + PLAYER_RADIUS) {
+        let Some(p) = s.players.get(&id) else { continue };
+        if id == my_id || !p.a { continue; }
+        enemies.push((id, p.x, p.y, PLAYER_RADIUS));
     }
-    for (id, m) in &s.mobs {
+    for id in s.grid.query_radius(Kind::Mob, orbit_wx, orbit_wy, AIM_DETECT_R + SD_MOB_RADIUS) {
+        let Some(m) = s.mobs.get(&id) else { continue };
         if !m.a { continue; }
-        enemies.push(Enemy { id: format!("m_{}", id), x: m.x, y: m.y });
+        enemies.push((id, m.x, m.y, mob_radius(m.s)));
     }
 
     drop(s);
@@ -63,44 +179,16 @@ pub fn update_and_draw_controller_aim(
     AIM_STATE.with(|aim| {
         let mut aim = aim.borrow_mut();
 
-        // Sticky lock check
-        let mut locked = false;
-        if let Some(ref target_id) = aim.target_id {
-            if let Some(t) = enemies.iter().find(|e| &e.id == target_id) {
-                let dx = t.x - orbit_wx;
-                let dy = t.y - orbit_wy;
-                if dx * dx + dy * dy <= AIM_DETECT_R * AIM_DETECT_R {
-                    locked = true;
-                    aim.target_x = t.x;
-                    aim.target_y = t.y;
-                }
-            }
-        }
-
-        if !locked {
-            aim.target_id = None;
-            let mut best_dist = AIM_DETECT_R * AIM_DETECT_R;
-            for e in &enemies {
-                let dx = e.x - orbit_wx;
-                let dy = e.y - orbit_wy;
-                let d2 = dx * dx + dy * dy;
-                if d2 <= best_dist {
-                    best_dist = d2;
-                    aim.target_id = Some(e.id.clone());
-                    aim.target_x = e.x;
-                    aim.target_y = e.y;
-                    locked = true;
-                }
-            }
+        let picked = pick_within(AIM_DETECT_R, AIM_DETECT_R, aim.target_id, orbit_wx, orbit_wy, enemies.iter().copied());
+        aim.target_id = picked.map(|(id, _, _)| id);
+        if let Some((_, x, y)) = picked {
+            aim.target_x = x;
+            aim.target_y = y;
         }
+        let locked = picked.is_some();
 
         // Animate progress
-        let target_progress = if locked { 1.0 } else { 0.0 };
-        if aim.progress < target_progress {
-            aim.progress = (aim.progress + AIM_ANIM_SPEED * dt).min(1.0);
-        } else if aim.progress > target_progress {
-            aim.progress = (aim.progress - AIM_ANIM_SPEED * dt).max(0.0);
-        }
+        aim.progress = step_progress(aim.progress, locked, dt);
 
         let spin_speed = aim.progress * AIM_SPIN_MAX;
         aim.spin_angle += spin_speed * dt;
@@ -133,3 +221,6 @@ pub fn update_and_draw_controller_aim(
         ctx.restore();
     });
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,64 @@
+use super::*;
+
+const R: f64 = 25.0;
+const ENEMIES: [(u32, f64, f64, f64); 3] = [(1, 100.0, 0.0, R), (2, 40.0, 0.0, R), (3, 0.0, 500.0, R)];
+
+#[test]
+fn off_never_picks() {
+    assert_eq!(pick_target(Assist::Off, None, 0.0, 0.0, ENEMIES.into_iter()), None);
+}
+
+#[test]
+fn picks_the_closest_in_range() {
+    assert_eq!(pick_target(Assist::High, None, 0.0, 0.0, ENEMIES.into_iter()), Some((2, 40.0, 0.0)));
+    // Out of detect range from here
+    assert_eq!(pick_target(Assist::High, None, 0.0, 1000.0, ENEMIES.into_iter()), None);
+}
+
+#[test]
+fn low_assist_reaches_less_far() {
+    let far = [(7, AIM_DETECT_R * 0.8 + R, 0.0, R)];
+    assert!(pick_target(Assist::High, None, 0.0, 0.0, far.into_iter()).is_some());
+    assert!(pick_target(Assist::Low, None, 0.0, 0.0, far.into_iter()).is_none());
+}
+
+#[test]
+fn keeps_a_lock_over_a_closer_enemy() {
+    assert_eq!(pick_target(Assist::High, Some(1), 0.0, 0.0, ENEMIES.into_iter()), Some((1, 100.0, 0.0)));
+}
+
+#[test]
+fn a_lock_holds_past_detect_range_until_sticky_range() {
+    let d = AIM_DETECT_R * 1.2 + R; // hull between detect (1x) and sticky (1.5x) on High
+    let locked = [(9, d, 0.0, R)];
+    assert_eq!(pick_target(Assist::High, None, 0.0, 0.0, locked.into_iter()), None);
+    assert_eq!(pick_target(Assist::High, Some(9), 0.0, 0.0, locked.into_iter()), Some((9, d, 0.0)));
+    let gone = [(9, AIM_DETECT_R * 2.0, 0.0, R), (2, 40.0, 0.0, R)];
+    assert_eq!(pick_target(Assist::High, Some(9), 0.0, 0.0, gone.into_iter()), Some((2, 40.0, 0.0)));
+}
+
+#[test]
+fn big_hulls_are_picked_up_from_their_edge() {
+    // A Star Destroyer's center is out of range, but its hull isn't
+    let sd = [(5, AIM_DETECT_R + 80.0, 0.0, 100.0)];
+    assert_eq!(pick_target(Assist::High, None, 0.0, 0.0, sd.into_iter()), Some((5, AIM_DETECT_R + 80.0, 0.0)));
+    let tie = [(6, AIM_DETECT_R + 80.0, 0.0, R)];
+    assert_eq!(pick_target(Assist::High, None, 0.0, 0.0, tie.into_iter()), None);
+    // Aiming inside a hull counts as on it
+    let pair = [(5, 60.0, 0.0, 100.0), (2, 30.0, 0.0, R)];
+    assert_eq!(pick_target(Assist::High, None, 0.0, 0.0, pair.into_iter()).map(|t| t.0), Some(5));
+}
+
+#[test]
+fn lock_on_animation_is_clamped() {
+    assert_eq!(step_progress(0.9, true, 1.0), 1.0);
+    assert_eq!(step_progress(0.1, false, 1.0), 0.0);
+    assert!((step_progress(0.0, true, 0.1) - AIM_ANIM_SPEED * 0.1).abs() < 1e-9);
+}
+
+#[test]
+fn assist_ids_round_trip() {
+    for a in [Assist::Off, Assist::Low, Assist::High] {
+        assert_eq!(Assist::parse(a.id()), a);
+    }
+}
@@ -0,0 +1,419 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use crate::state::{SharedState, GameState};
+use crate::network::{Network, SharedNetwork};
+
+const LOG_CAPACITY: usize = 200;
+const MAX_SEND_BYTES: usize = 3500; // server read limit is 4KB per message
+const MAX_DESC_BYTES: usize = 1000; // server keeps no more than this of the description
+const MAX_META_BYTES: usize = 300; // per free-form meta string (user agent, URL)
+const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const MAX_ERROR_REPORTS: u32 = 5; // per page load, so a panic loop or a confused server can't flood the socket
+const MAX_ERROR_LEN: usize = 1000;
+pub const ENVELOPE_CAPACITY: usize = 60; // received messages kept for state dumps (~3s of snapshots)
+
+thread_local! {
+    static LOG: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+    /// Connection crash reports go out on, once the game is up
+    static REPORTER: RefCell<Option<SharedNetwork>> = const { RefCell::new(None) };
+    static ERRORS_SENT: Cell<u32> = const { Cell::new(0) };
+    /// A report is on its way and the form is waiting for bug_ok or an error
+    static AWAITING: Cell<bool> = const { Cell::new(false) };
+    static ENVELOPES: RefCell<VecDeque<Received>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// A message as it came off the socket, kept raw so a dump can be replayed
+enum Received {
+    Text(f64, String),
+    Binary(f64, Vec<u8>),
+}
+
+/// Append a line to the in-memory log (also echoed to the console)
+pub fn log(msg: &str) {
+    web_sys::console::log_1(&msg.into());
+    push(msg);
+}
+
+/// Like `log`, but echoed as a console error
+pub fn error(msg: &str) {
+    web_sys::console::error_1(&msg.into());
+    push(msg);
+}
+
+fn push(msg: &str) {
+    let t = web_sys::window().and_then(|w| w.performance()).map(|p| p.now()).unwrap_or(0.0);
+    LOG.with(|l| {
+        let mut l = l.borrow_mut();
+        if l.len() >= LOG_CAPACITY {
+            l.pop_front();
+        }
+        l.push_back(format!("[{:.1}s] {}", t / 1000.0, msg));
+    });
+}
+
+/// Send client errors over this connection from now on
+pub fn set_reporter(net: SharedNetwork) {
+    REPORTER.with(|r| *r.borrow_mut() = Some(net));
+}
+
+/// Report a client failure to the server as a `client_error`: `kind` is "panic"
+/// or "envelope" (a server message we couldn't decode or didn't recognize).
+/// Also called from the panic hook, so it never waits on a borrowed RefCell.
+pub fn report_error(kind: &str, msg: &str) {
+    if ERRORS_SENT.with(|n| n.replace(n.get() + 1)) >= MAX_ERROR_REPORTS {
+        return;
+    }
+    let Some(net) = REPORTER.with(|r| r.try_borrow().ok().and_then(|r| r.clone())) else { return };
+    let phase = net.try_borrow().ok()
+        .and_then(|n| n.state.try_borrow().ok().map(|s| format!("{:?}", s.phase)))
+        .unwrap_or_default();
+    let report = serde_json::json!({
+        "kind": kind,
+        "msg": msg.chars().take(MAX_ERROR_LEN).collect::<String>(),
+        "ver": CLIENT_VERSION,
+        "phase": phase,
+    });
+    // A panic can land in the middle of a network borrow; drop the report then
+    let free = net.try_borrow_mut().is_ok();
+    if free {
+        Network::send_raw(&net, "client_error", &report);
+    }
+}
+
+fn remember(msg: Received) {
+    ENVELOPES.with(|e| {
+        let mut e = e.borrow_mut();
+        if e.len() >= ENVELOPE_CAPACITY {
+            e.pop_front();
+        }
+        e.push_back(msg);
+    });
+}
+
+/// Keep a JSON envelope for the next state dump
+pub fn record_envelope(text: &str) {
+    remember(Received::Text(js_sys::Date::now(), text.to_string()));
+}
+
+/// Keep a msgpack snapshot for the next state dump
+pub fn record_snapshot(bytes: &[u8]) {
+    remember(Received::Binary(js_sys::Date::now(), bytes.to_vec()));
+}
+
+/// Drop anything from an envelope that would let a reader take over or attach to a ship
+fn redact(env: &mut serde_json::Value) {
+    let attach = matches!(env["t"].as_str(), Some("coach_ok" | "control_ok"));
+    if let Some(d) = env.get_mut("d").and_then(|d| d.as_object_mut()) {
+        d.remove("ck");
+        if d.remove("tok").is_some() {
+            d.remove("id"); // the welcome's player handle
+        }
+        if attach {
+            d.remove("pid"); // the handle of the ship we attached to
+        }
+    }
+}
+
+/// The last `n` received messages: envelopes as JSON with the resume token, coach key and
+/// player handle blanked, snapshots as base64 msgpack
+fn recent_envelopes(n: usize) -> Vec<serde_json::Value> {
+    let window = web_sys::window().unwrap();
+    ENVELOPES.with(|e| {
+        let e = e.borrow();
+        e.iter().skip(e.len().saturating_sub(n)).map(|msg| match msg {
+            Received::Text(t, text) => {
+                let mut env = serde_json::from_str::<serde_json::Value>(text)
+                    .unwrap_or_else(|_| serde_json::Value::String(text.clone()));
+                redact(&mut env);
+                serde_json::json!({ "at": t, "env": env })
+            }
+            Received::Binary(t, bytes) => {
+                // btoa wants one char per byte
+                let latin1: String = bytes.iter().map(|b| *b as char).collect();
+                serde_json::json!({ "at": t, "state": window.btoa(&latin1).unwrap_or_default() })
+            }
+        }).collect()
+    })
+}
+
+/// Everything the client knows about the match, minus the player handle and
+/// resume token (which would let whoever reads the report take over the ship)
+fn state_dump(s: &GameState, envelopes: usize) -> serde_json::Value {
+    let window = web_sys::window().unwrap();
+    serde_json::json!({
+        "ver": CLIENT_VERSION,
+        "at": js_sys::Date::now(),
+        "url": window.location().href().unwrap_or_default(),
+        "ua": window.navigator().user_agent().unwrap_or_default(),
+        "connection": {
+            "connected": s.connected,
+            "session": s.session_id,
+            "my_id": s.my_id,
+            "my_ship": s.my_ship,
+            "last_rx": s.last_rx,
+            "bytes_in": s.bytes_in,
+        },
+        "phase": format!("{:?}", s.phase),
+        "tick": s.tick,
+        "names": s.names,
+        "players": s.players,
+        "projectiles": s.projectiles,
+        "mobs": s.mobs,
+        "asteroids": s.asteroids,
+        "pickups": s.pickups,
+        "ghosts": s.ghosts,
+        "screen": [s.screen_w, s.screen_h],
+        "camera": [s.cam_x, s.cam_y, s.cam_zoom],
+        "input": {
+            "mouse": [s.mouse_x, s.mouse_y],
+            "firing": s.firing,
+            "boosting": s.boosting,
+            "steer": [s.steer_left, s.steer_right, s.thrusting],
+            "heading": s.steer_heading,
+            "seq": s.input_seq,
+            "acked": s.input_acked,
+            "pending": s.input_pending.len(),
+            "rtt": s.input_rtt,
+            "dropped": s.inputs_dropped,
+        },
+        "predicted": s.predicted.as_ref().map(|p| [p.x, p.y]),
+        "modes": {
+            "controller": s.controller_attached,
+            "coaching": s.coach_target.is_some(),
+            "coached": s.coach_attached,
+            "replay": s.replay_mode,
+            "practice": s.practice_mode,
+            "hidden": s.tab_hidden,
+            "mobile": s.is_mobile,
+        },
+        "interp": {
+            "last_update": s.interp_last_update,
+            "interval": s.interp_interval,
+            "jitter": s.interp_jitter,
+            "delay": s.interp_delay,
+            "pinned": s.interp_override,
+            "gaps": s.snapshot_gaps,
+        },
+        "snapshots": s.snapshots,
+        "effects": {
+            "particles": s.particles.len(),
+            "explosions": s.explosions.len(),
+            "damage_numbers": s.damage_numbers.len(),
+            "shake": s.shake_intensity,
+        },
+        "settings": s.settings,
+        "envelopes": recent_envelopes(envelopes),
+    })
+}
+
+/// Download the state dump with the last `envelopes` received messages; returns how many went in
+pub fn download_state(s: &GameState, envelopes: usize) -> usize {
+    let dump = state_dump(s, envelopes);
+    let n = dump["envelopes"].as_array().map_or(0, |e| e.len());
+    save_json(&dump, "state");
+    n
+}
+
+fn log_lines() -> Vec<String> {
+    LOG.with(|l| l.borrow().iter().cloned().collect())
+}
+
+/// Metadata about the most recent snapshot and client settings
+fn snapshot_meta(state: &SharedState) -> serde_json::Value {
+    let s = state.borrow();
+    let window = web_sys::window().unwrap();
+    let me = s.my_id.as_ref().and_then(|id| s.players.get(id));
+    serde_json::json!({
+        "tick": s.tick,
+        "phase": format!("{:?}", s.phase),
+        "session": s.session_id,
+        "player": s.my_pid,
+        "alive": me.map(|p| p.a),
+        "pos": me.map(|p| [p.x.round(), p.y.round()]),
+        "players": s.players.len(),
+        "mobs": s.mobs.len(),
+        "projectiles": s.projectiles.len(),
+        "interp_ms": s.interp_interval.round(),
+        "input_rtt_ms": s.input_rtt.round(),
+        "inputs_dropped": s.inputs_dropped,
+        "data_saver": s.settings.data_saver,
+        "controller": s.controller_attached,
+        "replay": s.replay_mode,
+        "screen": [s.screen_w, s.screen_h],
+        "zoom": s.cam_zoom,
+        "ua": clip_bytes(&window.navigator().user_agent().unwrap_or_default(), MAX_META_BYTES),
+        "url": clip_bytes(&window.location().href().unwrap_or_default(), MAX_META_BYTES),
+    })
+}
+
+/// The longest prefix of `s` that is at most `max` bytes and ends on a char boundary
+fn clip_bytes(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+fn build_report(state: &SharedState, desc: &str, log: Vec<String>) -> serde_json::Value {
+    serde_json::json!({
+        "desc": clip_bytes(desc, MAX_DESC_BYTES),
+        "ver": CLIENT_VERSION,
+        "meta": snapshot_meta(state),
+        "log": log,
+    })
+}
+
+/// Send the report to the server, dropping the oldest log lines until it fits.
+/// Returns false (and sends nothing) if it is still over the limit without any
+/// log, which heavy escaping in the description can cause
+fn submit(state: &SharedState, net: &SharedNetwork, desc: &str) -> bool {
+    let mut log = log_lines();
+    let mut report = build_report(state, desc, log.clone());
+    while report.to_string().len() > MAX_SEND_BYTES && !log.is_empty() {
+        let drop_n = log.len().div_ceil(4);
+        log.drain(..drop_n);
+        report = build_report(state, desc, log.clone());
+    }
+    if report.to_string().len() > MAX_SEND_BYTES {
+        return false;
+    }
+    Network::send_raw(net, "bug_report", &report);
+    true
+}
+
+/// Download the full report (entire log, optional screenshot) as a JSON bundle
+fn download(state: &SharedState, desc: &str, with_screenshot: bool) {
+    let mut report = build_report(state, desc, log_lines());
+    if with_screenshot {
+        if let Some(url) = crate::screenshot::to_data_url(&state.borrow()) {
+            report["screenshot"] = serde_json::Value::String(url);
+        }
+    }
+    save_json(&report, "bugreport");
+}
+
+/// Offer `value` as a `<prefix>-<timestamp>.json` download
+fn save_json(value: &serde_json::Value, prefix: &str) {
+    let parts = js_sys::Array::new();
+    parts.push(&JsValue::from_str(&value.to_string()));
+    let opts = web_sys::BlobPropertyBag::new();
+    opts.set_type("application/json");
+    let blob = match web_sys::Blob::new_with_str_sequence_and_options(&parts, &opts) {
+        Ok(b) => b,
+        Err(_) => return,
+    };
+    let url = match web_sys::Url::create_object_url_with_blob(&blob) {
+        Ok(u) => u,
+        Err(_) => return,
+    };
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Ok(a) = document.create_element("a") {
+        let a: web_sys::HtmlAnchorElement = a.unchecked_into();
+        a.set_href(&url);
+        let stamp = js_sys::Date::new_0().to_iso_string().as_string().unwrap_or_default();
+        a.set_download(&format!("{}-{}.json", prefix, stamp.replace([':', '.'], "-")));
+        a.click();
+    }
+    let _ = web_sys::Url::revoke_object_url(&url);
+}
+
+/// Server acknowledged the report
+pub fn on_sent() {
+    AWAITING.with(|a| a.set(false));
+    set_status("Report sent \u{2014} thanks!");
+}
+
+/// Server refused something; if a report was pending, that's the answer to it
+pub fn on_server_error(msg: &str) {
+    if AWAITING.with(|a| a.replace(false)) {
+        set_status(msg);
+    }
+}
+
+fn set_status(text: &str) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(el) = document.get_element_by_id("bugStatus") {
+        el.set_text_content(Some(text));
+    }
+}
+
+fn form_values() -> (String, bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let desc = document.get_element_by_id("bugDesc")
+        .and_then(|e| e.dyn_into::<web_sys::HtmlTextAreaElement>().ok())
+        .map(|t| t.value())
+        .unwrap_or_default();
+    let shot = document.get_element_by_id("bugShot")
+        .and_then(|e| e.dyn_into::<web_sys::HtmlInputElement>().ok())
+        .map(|i| i.checked())
+        .unwrap_or(false);
+    (desc.trim().to_string(), shot)
+}
+
+fn set_overlay_visible(visible: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(overlay) = document.get_element_by_id("bugOverlay") {
+        let _ = if visible {
+            overlay.class_list().add_1("visible")
+        } else {
+            overlay.class_list().remove_1("visible")
+        };
+    }
+}
+
+fn on_click(id: &str, f: impl FnMut(web_sys::Event) + 'static) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(el) = document.get_element_by_id(id) {
+        let click = Closure::wrap(Box::new(f) as Box<dyn FnMut(web_sys::Event)>);
+        let _ = el.add_event_listener_with_callback("click", click.as_ref().unchecked_ref());
+        click.forget();
+    }
+}
+
+pub fn setup_bug_btn(state: SharedState, net: SharedNetwork) {
+    on_click("bugBtn", |_| {
+        set_status("");
+        set_overlay_visible(true);
+    });
+    on_click("bugClose", |_| set_overlay_visible(false));
+
+    // Keep game hotkeys (S, R, C, W...) from firing while typing the description
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(desc) = document.get_element_by_id("bugDesc") {
+        let keydown = Closure::wrap(Box::new(|e: web_sys::Event| {
+            e.stop_propagation();
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        let _ = desc.add_event_listener_with_callback("keydown", keydown.as_ref().unchecked_ref());
+        keydown.forget();
+    }
+
+    let state_send = state.clone();
+    on_click("bugSend", move |_| {
+        let (desc, _) = form_values();
+        if desc.is_empty() {
+            set_status("Please describe what happened.");
+            return;
+        }
+        if submit(&state_send, &net, &desc) {
+            log("bug report submitted");
+            AWAITING.with(|a| a.set(true));
+            set_status("Sending\u{2026}");
+        } else {
+            set_status("Too long to send \u{2014} use Download and attach the file instead.");
+        }
+    });
+
+    on_click("bugDownload", move |_| {
+        let (desc, shot) = form_values();
+        download(&state, &desc, shot);
+    });
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn clip_bytes_keeps_short_strings() {
+    assert_eq!(clip_bytes("lag spike", 100), "lag spike");
+    assert_eq!(clip_bytes("", 0), "");
+}
+
+#[test]
+fn clip_bytes_stops_on_a_char_boundary() {
+    // "é" is two bytes; cutting at 3 would split the second one
+    assert_eq!(clip_bytes("éé", 3), "é");
+    assert_eq!(clip_bytes("aé", 2), "a");
+    assert_eq!(clip_bytes("abc", 2), "ab");
+    let long = "🚀".repeat(400);
+    let clipped = clip_bytes(&long, MAX_DESC_BYTES);
+    assert!(clipped.len() <= MAX_DESC_BYTES);
+    assert_eq!(clipped.len(), MAX_DESC_BYTES / 4 * 4);
+}
+
+#[test]
+fn redact_strips_handles_and_secrets() {
+    let mut welcome = serde_json::json!({"t": "welcome", "d": {"id": "p1", "nid": 3, "tok": "secret", "ck": "key"}});
+    redact(&mut welcome);
+    assert_eq!(welcome, serde_json::json!({"t": "welcome", "d": {"nid": 3}}));
+
+    for t in ["coach_ok", "control_ok"] {
+        let mut ok = serde_json::json!({"t": t, "d": {"pid": "p1", "nid": 3}});
+        redact(&mut ok);
+        assert_eq!(ok["d"], serde_json::json!({"nid": 3}), "{t}");
+    }
+
+    // Other messages keep their ids
+    let mut joined = serde_json::json!({"t": "joined", "d": {"id": "s1"}});
+    redact(&mut joined);
+    assert_eq!(joined["d"]["id"], "s1");
+}
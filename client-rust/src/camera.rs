@@ -0,0 +1,55 @@
+// Camera follow: the view chases the ship (or whoever we're spectating) on a
+// critically damped spring instead of snapping to it every frame, so the small
+// corrections prediction and interpolation make don't shake the whole screen.
+// With a dead zone the camera stays put until the ship drifts that far from
+// the middle. Zero lag snaps, as before.
+
+/// Where the view is centered and how fast it's moving
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Follow {
+    pub x: f64,
+    pub y: f64,
+    vx: f64,
+    vy: f64,
+}
+
+const SNAP_DIST: f64 = 1500.0; // jumps this far (respawn, new spectate target) cut instead of panning
+
+/// One axis of a critically damped spring reaching `target` in about
+/// `smooth` seconds, without overshooting. Returns the new position and speed.
+fn spring(pos: f64, vel: f64, target: f64, smooth: f64, dt: f64) -> (f64, f64) {
+    let omega = 2.0 / smooth;
+    let x = omega * dt;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = pos - target;
+    let temp = (vel + omega * change) * dt;
+    (target + (change + temp) * decay, (vel - omega * temp) * decay)
+}
+
+/// The point the camera heads for: the target pulled back to the edge of the
+/// dead zone around the current view, or the view itself while inside it
+fn dead_zone_target(view: (f64, f64), target: (f64, f64), dead_zone: f64) -> (f64, f64) {
+    let (dx, dy) = (target.0 - view.0, target.1 - view.1);
+    let d = dx.hypot(dy);
+    if d <= dead_zone { return view; }
+    let k = (d - dead_zone) / d;
+    (view.0 + dx * k, view.1 + dy * k)
+}
+
+/// Advance the view toward (`x`, `y`) and return where to center it this frame
+pub fn follow(cam: &mut Option<Follow>, x: f64, y: f64, lag: f64, dead_zone: f64, dt: f64) -> (f64, f64) {
+    let f = match cam {
+        Some(f) if lag > 0.0 && (x - f.x).hypot(y - f.y) < SNAP_DIST => f,
+        _ => {
+            *cam = Some(Follow { x, y, ..Default::default() });
+            return (x, y);
+        }
+    };
+    let (tx, ty) = dead_zone_target((f.x, f.y), (x, y), dead_zone);
+    (f.x, f.vx) = spring(f.x, f.vx, tx, lag, dt);
+    (f.y, f.vy) = spring(f.y, f.vy, ty, lag, dt);
+    (f.x, f.y)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,31 @@
+use super::*;
+
+const DT: f64 = 1.0 / 60.0;
+
+#[test]
+fn spring_settles_without_overshooting() {
+    let (mut pos, mut vel) = (0.0, 0.0);
+    for _ in 0..60 {
+        (pos, vel) = spring(pos, vel, 100.0, 0.1, DT);
+        assert!(pos <= 100.0);
+    }
+    assert!((pos - 100.0).abs() < 0.5);
+}
+
+#[test]
+fn dead_zone_holds_until_the_target_leaves_it() {
+    assert_eq!(dead_zone_target((0.0, 0.0), (30.0, 40.0), 60.0), (0.0, 0.0));
+    // 100px away with a 60px zone: head for the point 40px along
+    let (x, y) = dead_zone_target((0.0, 0.0), (60.0, 80.0), 60.0);
+    assert!((x - 24.0).abs() < 1e-9 && (y - 32.0).abs() < 1e-9);
+}
+
+#[test]
+fn snaps_without_lag_or_on_big_jumps() {
+    let mut cam = None;
+    assert_eq!(follow(&mut cam, 500.0, 500.0, 0.1, 0.0, DT), (500.0, 500.0));
+    let (x, _) = follow(&mut cam, 520.0, 500.0, 0.1, 0.0, DT);
+    assert!(x > 500.0 && x < 520.0);
+    assert_eq!(follow(&mut cam, 520.0, 500.0, 0.0, 0.0, DT), (520.0, 500.0));
+    assert_eq!(follow(&mut cam, 4000.0, 500.0, 0.1, 0.0, DT), (4000.0, 500.0));
+}
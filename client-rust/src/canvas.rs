@@ -1,7 +1,8 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlCanvasElement;
-use crate::state::SharedState;
+use crate::state::{SharedState, GameState};
+use crate::constants::{ZOOM_MIN, ZOOM_MAX};
 
 pub fn resize(state: &SharedState) {
     let window = web_sys::window().unwrap();
@@ -9,24 +10,25 @@ pub fn resize(state: &SharedState) {
     let h = window.inner_height().unwrap().as_f64().unwrap();
 
     let document = window.document().unwrap();
+    let scale = state.borrow().settings.render_scale;
 
-    if let Some(canvas) = document.get_element_by_id("gameCanvas") {
-        let canvas: HtmlCanvasElement = canvas.unchecked_into();
-        canvas.set_width(w as u32);
-        canvas.set_height(h as u32);
-    }
-    if let Some(canvas) = document.get_element_by_id("bgCanvas") {
-        let canvas: HtmlCanvasElement = canvas.unchecked_into();
-        canvas.set_width(w as u32);
-        canvas.set_height(h as u32);
+    // Lower resolution settings shrink the backing store; CSS stretches it back
+    for id in ["gameCanvas", "bgCanvas", "hudCanvas"] {
+        if let Some(canvas) = document.get_element_by_id(id) {
+            let canvas: HtmlCanvasElement = canvas.unchecked_into();
+            canvas.set_width((w * scale) as u32);
+            canvas.set_height((h * scale) as u32);
+            let style = canvas.style();
+            let _ = style.set_property("width", &format!("{}px", w));
+            let _ = style.set_property("height", &format!("{}px", h));
+        }
     }
 
     let mut s = state.borrow_mut();
     s.screen_w = w;
     s.screen_h = h;
 
-    let min_dim = w.min(h);
-    s.cam_zoom = (min_dim / 700.0).min(1.0);
+    update_zoom(&mut s);
 
     if s.is_mobile && s.touch_joystick.is_none() {
         s.mouse_x = w / 2.0;
@@ -34,6 +36,36 @@ pub fn resize(state: &SharedState) {
     }
 }
 
+fn update_zoom(s: &mut GameState) {
+    let (w, h) = (s.screen_w, s.screen_h);
+    let min_dim = w.min(h);
+    s.cam_zoom = (min_dim / 700.0).min(1.0) * s.settings.zoom;
+
+    // Coach: keep fitting the player's visible area onto our screen
+    if let Some(ref v) = s.coach_view {
+        if v.vw > 0.0 && v.vh > 0.0 {
+            s.cam_zoom = (w / v.vw).min(h / v.vh);
+        }
+    }
+}
+
+/// Set the player's zoom multiplier (clamped), re-derive cam_zoom and remember it
+pub fn set_zoom_scale(state: &SharedState, scale: f64) {
+    let scale = scale.clamp(ZOOM_MIN, ZOOM_MAX);
+    let mut s = state.borrow_mut();
+    if s.coach_target.is_some() || (scale - s.settings.zoom).abs() < 1e-4 {
+        return;
+    }
+    s.settings.zoom = scale;
+    update_zoom(&mut s);
+    s.settings.save();
+}
+
+/// Draw in CSS pixels whatever the canvas backing resolution (call once per frame)
+pub fn apply_render_scale(ctx: &web_sys::CanvasRenderingContext2d, scale: f64) {
+    let _ = ctx.set_transform(scale, 0.0, 0.0, scale, 0.0, 0.0);
+}
+
 pub fn setup_resize_handler(state: SharedState) {
     let closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
         resize(&state);
@@ -101,22 +133,8 @@ pub fn setup_controller_btn(state: SharedState) {
 
     let state_clone = state.clone();
     let btn_click = Closure::wrap(Box::new(move |_: web_sys::Event| {
-        let s = state_clone.borrow();
-        let (my_id, session_id) = (s.my_id.clone(), s.session_id.clone());
-        drop(s);
-
-        if let (Some(my_id), Some(session_id)) = (my_id, session_id) {
-            let window = web_sys::window().unwrap();
-            let origin = window.location().origin().unwrap_or_default();
-            let controller_url = format!("{}/{}?c={}", origin, session_id, my_id);
-
-            let document = window.document().unwrap();
-            if let Some(qr_img) = document.get_element_by_id("qrImg") {
-                let _ = qr_img.set_attribute("src", &format!("/api/qr?data={}", js_sys::encode_uri_component(&controller_url)));
-            }
-            if let Some(qr_url) = document.get_element_by_id("qrUrl") {
-                qr_url.set_text_content(Some(&controller_url));
-            }
+        if show_controller_qr(&state_clone, false) {
+            let document = web_sys::window().unwrap().document().unwrap();
             if let Some(overlay) = document.get_element_by_id("controllerOverlay") {
                 let _ = overlay.class_list().add_1("visible");
             }
@@ -125,6 +143,20 @@ pub fn setup_controller_btn(state: SharedState) {
     let _ = btn.add_event_listener_with_callback("click", btn_click.as_ref().unchecked_ref());
     btn_click.forget();
 
+    // Switch the code between the pilot link and a second phone's gunner link
+    if let Some(role_btn) = document.get_element_by_id("qrRole") {
+        let state_role = state.clone();
+        let role_click = Closure::wrap(Box::new(move |_: web_sys::Event| {
+            let document = web_sys::window().unwrap().document().unwrap();
+            let gunner = document.get_element_by_id("qrUrl")
+                .and_then(|el| el.text_content())
+                .is_some_and(|url| !url.ends_with("&role=gunner"));
+            show_controller_qr(&state_role, gunner);
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        let _ = role_btn.add_event_listener_with_callback("click", role_click.as_ref().unchecked_ref());
+        role_click.forget();
+    }
+
     // Close button
     if let Some(close_btn) = document.get_element_by_id("qrClose") {
         let close_click = Closure::wrap(Box::new(move |_: web_sys::Event| {
@@ -150,6 +182,82 @@ pub fn setup_controller_btn(state: SharedState) {
     esc_closure.forget();
 }
 
+/// Point the controller QR code at the pilot or gunner link; false before joining
+fn show_controller_qr(state: &SharedState, gunner: bool) -> bool {
+    let s = state.borrow();
+    let (Some(my_pid), Some(session_id)) = (s.my_pid.clone(), s.session_id.clone()) else { return false };
+    drop(s);
+
+    let window = web_sys::window().unwrap();
+    let origin = window.location().origin().unwrap_or_default();
+    let mut controller_url = format!("{}/{}?c={}", origin, session_id, my_pid);
+    if gunner {
+        controller_url.push_str("&role=gunner");
+    }
+
+    let document = window.document().unwrap();
+    if let Some(qr_img) = document.get_element_by_id("qrImg") {
+        let _ = qr_img.set_attribute("src", &format!("/api/qr?data={}", js_sys::encode_uri_component(&controller_url)));
+    }
+    if let Some(qr_url) = document.get_element_by_id("qrUrl") {
+        qr_url.set_text_content(Some(&controller_url));
+    }
+    if let Some(hint) = document.get_element_by_id("qrHint") {
+        hint.set_text_content(Some(if gunner {
+            "Scan with a second phone to man the turret"
+        } else {
+            "Scan with your phone to use as controller"
+        }));
+    }
+    if let Some(role_btn) = document.get_element_by_id("qrRole") {
+        role_btn.set_text_content(Some(if gunner { "Pilot link" } else { "Add a gunner" }));
+    }
+    true
+}
+
+pub fn setup_coach_btn(state: SharedState) {
+    let document = web_sys::window().unwrap().document().unwrap();
+
+    let btn = match document.get_element_by_id("coachBtn") {
+        Some(b) => b,
+        None => return,
+    };
+
+    let state_clone = state.clone();
+    let btn_click = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        let s = state_clone.borrow();
+        let (my_pid, session_id, key) = (s.my_pid.clone(), s.session_id.clone(), s.coach_key.clone());
+        drop(s);
+
+        if let (Some(my_pid), Some(session_id), Some(key)) = (my_pid, session_id, key) {
+            let window = web_sys::window().unwrap();
+            let origin = window.location().origin().unwrap_or_default();
+            let coach_url = format!("{}{}{}?coach={}&ck={}", origin, crate::app::base_path(), session_id, my_pid, key);
+
+            let document = window.document().unwrap();
+            if let Some(url_el) = document.get_element_by_id("coachUrl") {
+                url_el.set_text_content(Some(&coach_url));
+            }
+            if let Some(overlay) = document.get_element_by_id("coachOverlay") {
+                let _ = overlay.class_list().add_1("visible");
+            }
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let _ = btn.add_event_listener_with_callback("click", btn_click.as_ref().unchecked_ref());
+    btn_click.forget();
+
+    if let Some(close_btn) = document.get_element_by_id("coachClose") {
+        let close_click = Closure::wrap(Box::new(move |_: web_sys::Event| {
+            let document = web_sys::window().unwrap().document().unwrap();
+            if let Some(overlay) = document.get_element_by_id("coachOverlay") {
+                let _ = overlay.class_list().remove_1("visible");
+            }
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        let _ = close_btn.add_event_listener_with_callback("click", close_click.as_ref().unchecked_ref());
+        close_click.forget();
+    }
+}
+
 pub fn get_canvas_context(id: &str) -> Option<web_sys::CanvasRenderingContext2d> {
     let document = web_sys::window()?.document()?;
     let canvas = document.get_element_by_id(id)?;
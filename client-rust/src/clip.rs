@@ -0,0 +1,231 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{BlobEvent, CanvasRenderingContext2d, HtmlCanvasElement, MediaRecorder, MediaStream};
+
+// WebM chunks only decode from the start of a recording, so a rolling buffer
+// can't just drop old chunks. Instead two recorders run staggered by half a
+// clip and restart every CLIP_SECONDS; the older one always holds between
+// CLIP_SECONDS/2 and CLIP_SECONDS of the most recent footage.
+// The game draws on three stacked canvases, so the recorders watch an
+// offscreen canvas the layers are flattened onto every frame.
+const CLIP_SECONDS: f64 = 30.0;
+const CLIP_FPS: f64 = 30.0;
+const CHUNK_MS: i32 = 1000;
+
+struct Segment {
+    recorder: MediaRecorder,
+    started: f64, // ms
+    save: Rc<Cell<bool>>,
+    _on_data: Closure<dyn FnMut(BlobEvent)>,
+}
+
+struct Clipper {
+    stream: MediaStream,
+    layers: [HtmlCanvasElement; 3], // bg, game, hud, bottom to top
+    out: HtmlCanvasElement,
+    ctx: CanvasRenderingContext2d,
+    slots: [Option<Segment>; 2],
+    retired: Vec<(f64, Segment)>, // (stopped at ms) saved segments, kept alive until their final chunk lands
+    _ticker: gloo_timers::callback::Interval,
+}
+
+thread_local! {
+    static CLIPPER: RefCell<Option<Clipper>> = const { RefCell::new(None) };
+}
+
+fn start_segment(stream: &MediaStream) -> Option<Segment> {
+    let opts = web_sys::MediaRecorderOptions::new();
+    if MediaRecorder::is_type_supported("video/webm") {
+        opts.set_mime_type("video/webm");
+    }
+    let recorder = MediaRecorder::new_with_media_stream_and_media_recorder_options(stream, &opts).ok()?;
+
+    let chunks = Rc::new(RefCell::new(js_sys::Array::new()));
+    let chunks_data = chunks.clone();
+    let on_data = Closure::wrap(Box::new(move |e: BlobEvent| {
+        if let Some(blob) = e.data() {
+            if blob.size() > 0.0 {
+                chunks_data.borrow().push(&blob);
+            }
+        }
+    }) as Box<dyn FnMut(BlobEvent)>);
+    recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+
+    let save = Rc::new(Cell::new(false));
+    let save_stop = save.clone();
+    let on_stop = Closure::once_into_js(move || {
+        if save_stop.get() {
+            download(&chunks.borrow());
+        }
+    });
+    recorder.set_onstop(Some(on_stop.unchecked_ref()));
+
+    recorder.start_with_time_slice(CHUNK_MS).ok()?;
+    Some(Segment {
+        recorder,
+        started: js_sys::Date::now(),
+        save,
+        _on_data: on_data,
+    })
+}
+
+/// Stop a segment without keeping its footage
+fn discard(seg: Segment) {
+    seg.recorder.set_ondataavailable(None);
+    let _ = seg.recorder.stop();
+}
+
+fn download(chunks: &js_sys::Array) {
+    let opts = web_sys::BlobPropertyBag::new();
+    opts.set_type("video/webm");
+    let blob = match web_sys::Blob::new_with_blob_sequence_and_options(chunks, &opts) {
+        Ok(b) => b,
+        Err(_) => return,
+    };
+    let url = match web_sys::Url::create_object_url_with_blob(&blob) {
+        Ok(u) => u,
+        Err(_) => return,
+    };
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Ok(a) = document.create_element("a") {
+        let a: web_sys::HtmlAnchorElement = a.unchecked_into();
+        a.set_href(&url);
+        let stamp = js_sys::Date::new_0().to_iso_string().as_string().unwrap_or_default();
+        a.set_download(&format!("clip-{}.webm", stamp.replace([':', '.'], "-")));
+        a.click();
+    }
+    let _ = web_sys::Url::revoke_object_url(&url);
+}
+
+fn tick() {
+    CLIPPER.with(|c| {
+        let mut c = c.borrow_mut();
+        let cl = match c.as_mut() {
+            Some(cl) => cl,
+            None => return,
+        };
+        let now = js_sys::Date::now();
+        cl.retired.retain(|(stopped, _)| now - *stopped < 2000.0);
+
+        if cl.slots[0].is_none() {
+            cl.slots[0] = start_segment(&cl.stream);
+        }
+        let first_age = cl.slots[0].as_ref().map(|s| now - s.started).unwrap_or(0.0);
+        if cl.slots[1].is_none() && first_age >= CLIP_SECONDS * 500.0 {
+            cl.slots[1] = start_segment(&cl.stream);
+        }
+
+        for slot in cl.slots.iter_mut() {
+            let expired = slot.as_ref().map(|s| now - s.started >= CLIP_SECONDS * 1000.0).unwrap_or(false);
+            if expired {
+                if let Some(old) = slot.take() {
+                    discard(old);
+                }
+                *slot = start_segment(&cl.stream);
+            }
+        }
+    });
+}
+
+pub fn is_recording() -> bool {
+    CLIPPER.with(|c| c.borrow().is_some())
+}
+
+fn layer(document: &web_sys::Document, id: &str) -> Option<HtmlCanvasElement> {
+    document.get_element_by_id(id)?.dyn_into::<HtmlCanvasElement>().ok()
+}
+
+/// Flatten the canvas layers into the recorded canvas; call once per rendered frame
+pub fn composite() {
+    CLIPPER.with(|c| {
+        let c = c.borrow();
+        let Some(cl) = c.as_ref() else { return };
+        let [bg, game, hud] = &cl.layers;
+        if cl.out.width() != game.width() || cl.out.height() != game.height() {
+            cl.out.set_width(game.width());
+            cl.out.set_height(game.height());
+        }
+        cl.ctx.clear_rect(0.0, 0.0, game.width() as f64, game.height() as f64);
+        for canvas in [bg, game, hud] {
+            let _ = cl.ctx.draw_image_with_html_canvas_element(canvas, 0.0, 0.0);
+        }
+    });
+}
+
+/// Start rolling capture of the game, HUD included
+pub fn start() {
+    if is_recording() { return; }
+    let has_recorder = js_sys::Reflect::has(&js_sys::global(), &"MediaRecorder".into()).unwrap_or(false);
+    if !has_recorder { return; }
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let layers = match (layer(&document, "bgCanvas"), layer(&document, "gameCanvas"), layer(&document, "hudCanvas")) {
+        (Some(bg), Some(game), Some(hud)) => [bg, game, hud],
+        _ => return,
+    };
+    let out: HtmlCanvasElement = match document.create_element("canvas") {
+        Ok(e) => e.unchecked_into(),
+        Err(_) => return,
+    };
+    let ctx: CanvasRenderingContext2d = match out.get_context("2d") {
+        Ok(Some(ctx)) => ctx.unchecked_into(),
+        _ => return,
+    };
+    let stream = match out.capture_stream_with_frame_request_rate(CLIP_FPS) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    let ticker = gloo_timers::callback::Interval::new(1000, tick);
+    CLIPPER.with(|c| {
+        *c.borrow_mut() = Some(Clipper {
+            stream,
+            layers,
+            out,
+            ctx,
+            slots: [None, None],
+            retired: Vec::new(),
+            _ticker: ticker,
+        });
+    });
+    composite();
+    tick();
+}
+
+pub fn stop() {
+    let cl = CLIPPER.with(|c| c.borrow_mut().take());
+    if let Some(cl) = cl {
+        for seg in cl.slots.into_iter().flatten() {
+            discard(seg);
+        }
+    }
+}
+
+pub fn toggle() {
+    if is_recording() { stop(); } else { start(); }
+}
+
+/// Save the longest-running segment as a WebM clip and restart its slot
+pub fn save() {
+    CLIPPER.with(|c| {
+        let mut c = c.borrow_mut();
+        let cl = match c.as_mut() {
+            Some(cl) => cl,
+            None => return,
+        };
+        let oldest = cl.slots.iter().enumerate()
+            .filter_map(|(i, s)| s.as_ref().map(|s| (i, s.started)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i);
+        if let Some(i) = oldest {
+            if let Some(seg) = cl.slots[i].take() {
+                seg.save.set(true);
+                let _ = seg.recorder.stop();
+                cl.retired.push((js_sys::Date::now(), seg));
+            }
+            cl.slots[i] = start_segment(&cl.stream);
+        }
+    });
+}
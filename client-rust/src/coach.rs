@@ -0,0 +1,124 @@
+use web_sys::CanvasRenderingContext2d;
+use crate::state::{GameState, CoachMark, CoachMarkKind};
+
+pub const MARK_DURATION: f64 = 4000.0; // ms
+const MAX_MARKS: usize = 16;
+pub const MAX_STROKE_POINTS: usize = 64;
+const MARK_COLOR: &str = "#ffcc00";
+
+pub fn add_mark(state: &mut GameState, kind: CoachMarkKind, pts: Vec<(f64, f64)>) {
+    if pts.is_empty() { return; }
+    if state.coach_marks.len() >= MAX_MARKS {
+        state.coach_marks.remove(0);
+    }
+    state.coach_marks.push(CoachMark {
+        kind,
+        pts,
+        time: js_sys::Date::now(),
+    });
+}
+
+/// Draw coach annotations (world-space, inside zoom). Shown on both ends.
+pub fn render_marks(ctx: &CanvasRenderingContext2d, s: &GameState, offset_x: f64, offset_y: f64) {
+    let now = js_sys::Date::now();
+
+    ctx.set_line_cap("round");
+    ctx.set_line_join("round");
+    ctx.set_stroke_style_str(MARK_COLOR);
+
+    for mark in &s.coach_marks {
+        let age = now - mark.time;
+        if age > MARK_DURATION { continue; }
+        let alpha = if age > MARK_DURATION - 1000.0 { (MARK_DURATION - age) / 1000.0 } else { 1.0 };
+        ctx.set_global_alpha(alpha.max(0.0));
+
+        match mark.kind {
+            CoachMarkKind::Line => draw_stroke(ctx, &mark.pts, offset_x, offset_y),
+            CoachMarkKind::Ping => {
+                let (x, y) = mark.pts[0];
+                draw_ping(ctx, x - offset_x, y - offset_y, age, MARK_COLOR);
+            }
+        }
+    }
+
+    // Stroke still being drawn by the coach
+    if let Some(ref stroke) = s.coach_stroke {
+        ctx.set_global_alpha(0.8);
+        draw_stroke(ctx, stroke, offset_x, offset_y);
+    }
+
+    ctx.set_global_alpha(1.0);
+}
+
+fn draw_stroke(ctx: &CanvasRenderingContext2d, pts: &[(f64, f64)], offset_x: f64, offset_y: f64) {
+    if pts.len() < 2 { return; }
+    ctx.set_line_width(4.0);
+    ctx.begin_path();
+    ctx.move_to(pts[0].0 - offset_x, pts[0].1 - offset_y);
+    for &(x, y) in &pts[1..] {
+        ctx.line_to(x - offset_x, y - offset_y);
+    }
+    ctx.stroke();
+}
+
+/// Pulsing ping at a screen position: rings in the current stroke style, dot in `color`
+pub fn draw_ping(ctx: &CanvasRenderingContext2d, sx: f64, sy: f64, age: f64, color: &str) {
+    // Two rings pulsing outward, plus a solid center dot
+    for i in 0..2 {
+        let phase = ((age / 800.0) + i as f64 * 0.5) % 1.0;
+        ctx.set_line_width(3.0 * (1.0 - phase) + 1.0);
+        ctx.begin_path();
+        let _ = ctx.arc(sx, sy, 10.0 + phase * 50.0, 0.0, std::f64::consts::PI * 2.0);
+        ctx.stroke();
+    }
+    ctx.set_fill_style_str(color);
+    ctx.begin_path();
+    let _ = ctx.arc(sx, sy, 6.0, 0.0, std::f64::consts::PI * 2.0);
+    ctx.fill();
+}
+
+/// Coach side: the player's own cursor, relayed with their view (world-space)
+pub fn render_player_cursor(ctx: &CanvasRenderingContext2d, s: &GameState, offset_x: f64, offset_y: f64) {
+    let view = match s.coach_view {
+        Some(ref v) => v,
+        None => return,
+    };
+    let mx = view.mx - offset_x;
+    let my = view.my - offset_y;
+    let size = 14.0;
+    let color = if view.firing { "rgba(255, 80, 80, 0.9)" } else { "rgba(120, 200, 255, 0.8)" };
+
+    ctx.set_stroke_style_str(color);
+    ctx.set_line_width(2.0);
+    ctx.begin_path();
+    ctx.move_to(mx - size, my);
+    ctx.line_to(mx + size, my);
+    ctx.move_to(mx, my - size);
+    ctx.line_to(mx, my + size);
+    ctx.stroke();
+    ctx.begin_path();
+    let _ = ctx.arc(mx, my, size * 0.6, 0.0, std::f64::consts::PI * 2.0);
+    ctx.stroke();
+}
+
+/// Screen-space badge: "COACHING <name>" for the coach, "COACH WATCHING" for the player
+pub fn render_badge(ctx: &CanvasRenderingContext2d, s: &GameState) {
+    let text = if s.coach_target.is_some() {
+        let name = s.my_id.map(|id| s.name_of(id)).unwrap_or("");
+        let mut t = format!("COACHING {}", name);
+        if let Some(ref v) = s.coach_view {
+            if v.firing { t.push_str("  [FIRE]"); }
+            if v.boosting { t.push_str("  [BOOST]"); }
+        }
+        t
+    } else if s.coach_attached {
+        "COACH WATCHING".to_string()
+    } else {
+        return;
+    };
+
+    ctx.set_font("bold 13px monospace");
+    ctx.set_text_align("center");
+    ctx.set_fill_style_str(MARK_COLOR);
+    let _ = ctx.fill_text(&text, s.screen_w / 2.0, 62.0);
+}
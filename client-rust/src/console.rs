@@ -0,0 +1,232 @@
+// Debug console: a backtick-toggled prompt over the game. Every debug toggle is
+// a slash command in COMMANDS, so new ones need no key binding of their own.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::CanvasRenderingContext2d;
+use crate::state::{SharedState, GameState};
+use crate::constants::MAX_INTERP_DELAY;
+use crate::keybinds::{self, Action};
+use crate::bugreport;
+
+pub const NET_GRAPH_LEN: usize = 120; // snapshot gaps kept for the net graph
+const LOG_LINES: usize = 40;
+const GRAPH_H: f64 = 60.0;     // px for GRAPH_SCALE ms
+const GRAPH_SCALE: f64 = 100.0;
+
+struct Command {
+    name: &'static str,
+    usage: &'static str,
+    help: &'static str,
+    run: fn(&mut GameState, &[&str]) -> Result<String, String>,
+}
+
+const COMMANDS: &[Command] = &[
+    Command { name: "help", usage: "", help: "list commands", run: help },
+    Command { name: "fps", usage: "", help: "toggle the frame rate counter", run: |s, _| Ok(toggle_flag(&mut s.debug_fps, "FPS counter")) },
+    Command { name: "hitboxes", usage: "", help: "toggle collision circles", run: |s, _| Ok(toggle_flag(&mut s.debug_hitboxes, "hitboxes")) },
+    Command { name: "netgraph", usage: "", help: "toggle the snapshot timing graph", run: |s, _| Ok(toggle_flag(&mut s.debug_netgraph, "net graph")) },
+    Command { name: "inspect", usage: "", help: "toggle click-to-inspect for ships, mobs and lasers", run: |s, _| {
+        s.inspector.target = None;
+        Ok(toggle_flag(&mut s.inspector.on, "inspector (click an entity)"))
+    } },
+    Command { name: "netlog", usage: "[type...|-type...|all|off]", help: "toggle the message log, or list only (or all but) some types", run: |_, args| Ok(crate::netlog::command(args)) },
+    Command { name: "netsim", usage: "[delay_ms] [loss%] [jitter_ms] | off", help: "delay and drop incoming snapshots, or show the current sim", run: |_, args| crate::netsim::command(args) },
+    Command { name: "interp", usage: "[ms|auto]", help: "pin the interpolation delay, or go back to adaptive", run: interp },
+    Command { name: "dump", usage: "[n]", help: "download the game state and the last n received messages as JSON", run: dump },
+    Command { name: "seed", usage: "[n|off]", help: "replay particles, stars and fog from a fixed seed (default 1)", run: seed },
+    Command { name: "give-particles", usage: "[n]", help: "set off n explosions around your ship (default 10)", run: give_particles },
+];
+
+thread_local! {
+    static LOG: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+    static FPS: Cell<f64> = const { Cell::new(0.0) };
+}
+
+fn toggle_flag(flag: &mut bool, what: &str) -> String {
+    *flag = !*flag;
+    format!("{} {}", what, if *flag { "on" } else { "off" })
+}
+
+fn help(_: &mut GameState, _: &[&str]) -> Result<String, String> {
+    Ok(COMMANDS.iter()
+        .map(|c| format!("/{} {} - {}", c.name, c.usage, c.help))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn interp(s: &mut GameState, args: &[&str]) -> Result<String, String> {
+    match args.first() {
+        None | Some(&"auto") => {
+            s.interp_override = None;
+            Ok(format!("interp adaptive (now {:.0}ms)", s.interp_delay))
+        }
+        Some(v) => {
+            let ms = v.parse::<f64>().map_err(|_| format!("not a number: {}", v))?;
+            let ms = ms.clamp(0.0, MAX_INTERP_DELAY);
+            s.interp_override = Some(ms);
+            s.interp_delay = ms;
+            Ok(format!("interp pinned at {:.0}ms", ms))
+        }
+    }
+}
+
+fn dump(s: &mut GameState, args: &[&str]) -> Result<String, String> {
+    let n = match args.first() {
+        Some(v) => v.parse::<usize>().map_err(|_| format!("not a count: {}", v))?,
+        None => bugreport::ENVELOPE_CAPACITY,
+    };
+    let sent = bugreport::download_state(s, n);
+    Ok(format!("state dump saved (tick {}, {} messages)", s.tick, sent))
+}
+
+fn seed(_: &mut GameState, args: &[&str]) -> Result<String, String> {
+    match args.first() {
+        Some(&"off") => {
+            crate::rng::seed(None);
+            Ok("visual RNG unseeded".to_string())
+        }
+        v => {
+            let n = v.map_or(Ok(1), |v| v.parse::<u64>().map_err(|_| format!("not a seed: {}", v)))?;
+            crate::rng::seed(Some(n));
+            Ok(format!("visual RNG seeded with {}", n))
+        }
+    }
+}
+
+fn give_particles(s: &mut GameState, args: &[&str]) -> Result<String, String> {
+    let n = match args.first() {
+        Some(v) => v.parse::<u32>().map_err(|_| format!("not a count: {}", v))?.min(100),
+        None => 10,
+    };
+    let (x, y) = s.my_id.and_then(|id| s.players.get(&id))
+        .map(|p| (p.x, p.y))
+        .unwrap_or((s.cam_x, s.cam_y));
+    let limit = s.settings.particle_limit();
+    for i in 0..n {
+        let a = i as f64 / n as f64 * std::f64::consts::TAU;
+        crate::effects::add_explosion(&mut s.particles, limit, &mut s.explosions, x + a.cos() * 80.0, y + a.sin() * 80.0);
+    }
+    Ok(format!("{} explosions, {} particles alive (limit {})", n, s.particles.len(), limit))
+}
+
+/// Parse and run one line, returning what to print
+fn execute(state: &SharedState, line: &str) -> String {
+    let mut words = line.trim().trim_start_matches('/').split_whitespace();
+    let Some(name) = words.next() else { return String::new() };
+    let args: Vec<&str> = words.collect();
+    let Some(cmd) = COMMANDS.iter().find(|c| c.name == name) else {
+        return format!("unknown command /{}, try /help", name);
+    };
+    match (cmd.run)(&mut state.borrow_mut(), &args) {
+        Ok(out) => out,
+        Err(e) => format!("/{} {}: {}", cmd.name, cmd.usage, e),
+    }
+}
+
+fn print(text: &str) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    LOG.with(|l| {
+        let mut l = l.borrow_mut();
+        for line in text.lines() {
+            if l.len() >= LOG_LINES {
+                l.pop_front();
+            }
+            l.push_back(line.to_string());
+        }
+        if let Some(el) = document.get_element_by_id("consoleLog") {
+            el.set_text_content(Some(&l.iter().cloned().collect::<Vec<_>>().join("\n")));
+            el.set_scroll_top(el.scroll_height());
+        }
+    });
+}
+
+/// Show or hide the console, focusing the prompt when it opens
+pub fn toggle() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(el) = document.get_element_by_id("debugConsole") else { return };
+    let open = el.class_list().toggle("visible").unwrap_or(false);
+    if let Some(input) = document.get_element_by_id("consoleInput")
+        .and_then(|e| e.dyn_into::<web_sys::HtmlElement>().ok())
+    {
+        let _ = if open { input.focus() } else { input.blur() };
+    }
+}
+
+pub fn setup(state: SharedState) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(input) = document.get_element_by_id("consoleInput")
+        .and_then(|e| e.dyn_into::<web_sys::HtmlInputElement>().ok()) else { return };
+    let input_kd = input.clone();
+    let keydown = Closure::wrap(Box::new(move |e: web_sys::KeyboardEvent| {
+        // Typing here must not steer, fire or trigger hotkeys
+        e.stop_propagation();
+        let key = e.key();
+        if key == "Escape" || keybinds::action_for(&key, false) == Some(Action::Console) {
+            e.prevent_default();
+            toggle();
+            return;
+        }
+        if key != "Enter" { return; }
+        let line = input_kd.value();
+        input_kd.set_value("");
+        if line.trim().is_empty() { return; }
+        print(&format!("> {}", line));
+        print(&execute(&state, &line));
+    }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+    let _ = input.add_event_listener_with_callback("keydown", keydown.as_ref().unchecked_ref());
+    keydown.forget();
+}
+
+pub fn record_gap(s: &mut GameState, ms: f64) {
+    if s.snapshot_gaps.len() >= NET_GRAPH_LEN {
+        s.snapshot_gaps.pop_front();
+    }
+    s.snapshot_gaps.push_back(ms);
+}
+
+/// FPS counter and net graph, bottom-left in screen space
+pub fn render(ctx: &CanvasRenderingContext2d, s: &GameState, dt: f64) {
+    if !s.debug_fps && !s.debug_netgraph { return; }
+    let x = 10.0;
+    let mut y = s.screen_h - 10.0;
+    ctx.set_font("11px monospace");
+    ctx.set_text_align("left");
+
+    if s.debug_fps {
+        let fps = FPS.with(|f| {
+            let smoothed = if dt > 0.0 { f.get() * 0.95 + (1.0 / dt) * 0.05 } else { f.get() };
+            f.set(smoothed);
+            smoothed
+        });
+        ctx.set_fill_style_str("#aaffaa");
+        let _ = ctx.fill_text(&format!("{:.0} fps", fps), x, y);
+        y -= 16.0;
+    }
+
+    if s.debug_netgraph {
+        ctx.set_fill_style_str("#aaffaa");
+        let _ = ctx.fill_text(&format!(
+            "snap {:.0}ms  jitter {:.0}ms  delay {:.0}ms{}  rtt {:.0}ms",
+            s.interp_interval, s.interp_jitter, s.interp_delay,
+            if s.interp_override.is_some() { " (pinned)" } else { "" },
+            s.input_rtt,
+        ), x, y);
+        y -= 6.0;
+
+        ctx.set_fill_style_str("rgba(0, 0, 0, 0.4)");
+        ctx.fill_rect(x, y - GRAPH_H, NET_GRAPH_LEN as f64 * 2.0, GRAPH_H);
+        for (i, gap) in s.snapshot_gaps.iter().enumerate() {
+            let h = (gap / GRAPH_SCALE * GRAPH_H).min(GRAPH_H);
+            // Late arrivals (beyond the render delay) would have stalled interpolation
+            ctx.set_fill_style_str(if *gap > s.interp_delay { "#ff5544" } else { "#44cc66" });
+            ctx.fill_rect(x + i as f64 * 2.0, y - h, 1.5, h);
+        }
+        // Render delay line
+        let dy = y - (s.interp_delay / GRAPH_SCALE * GRAPH_H).min(GRAPH_H);
+        ctx.set_fill_style_str("#ffcc00");
+        ctx.fill_rect(x, dy, NET_GRAPH_LEN as f64 * 2.0, 1.0);
+    }
+}
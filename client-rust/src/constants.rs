@@ -5,39 +5,134 @@ pub const PLAYER_RADIUS: f64 = 25.0;
 pub const PROJECTILE_RADIUS: f64 = 4.0;
 // Rendering
 pub const SHIP_SIZE: f64 = 60.0;
+// Player zoom (wheel / pinch) as a multiplier on the screen-size based zoom
+pub const ZOOM_MIN: f64 = 0.6;
+pub const ZOOM_MAX: f64 = 1.6;
+// Dodge dash (cooldown must match server player.go)
+pub const DODGE_COOLDOWN: f64 = 1500.0; // ms
+pub const DOUBLE_TAP_MS: f64 = 300.0; // max gap between taps of a double-tap
 
 // Network
 pub const INPUT_RATE: u32 = 20; // Hz
+pub const INPUT_ACK_TIMEOUT: f64 = 1000.0; // ms before an unacknowledged input counts as dropped
 pub const RECONNECT_DELAY: u32 = 2000; // ms
+// Game socket reconnect: exponential backoff from the base delay, capped, then give up
+pub const RECONNECT_BASE_DELAY: f64 = 1000.0; // ms
+pub const RECONNECT_MAX_DELAY: f64 = 30000.0; // ms
+pub const RECONNECT_MAX_RETRIES: u32 = 10;
+// Heartbeat: ping the server, and treat the socket as dead if nothing (snapshot or pong) arrives
+pub const HEARTBEAT_INTERVAL: u32 = 2000; // ms between pings
+pub const HEARTBEAT_UNSTABLE: f64 = 3000.0; // ms of silence before warning in the HUD
+pub const HEARTBEAT_TIMEOUT: f64 = 10000.0; // ms of silence before dropping the socket
+// Messages sent while disconnected are held and flushed on reconnect, within these limits
+pub const OUTBOX_CAP: usize = 32;
+pub const OUTBOX_MAX_AGE: f64 = 30000.0; // ms
+// Only one-off actions are held; anything else is either resent anyway or would
+// replay a session change (join, create) on top of the resumed one
+pub const OUTBOX_TYPES: &[&str] = &["mark", "marker", "ship_pick", "bug_report", "net_prefs"];
+pub const VIEW_RATE: u32 = 10; // Hz, camera relay to coach
+pub const GHOST_TTL: f64 = 10000.0; // ms a culled entity stays on the minimap at its last known position
+pub const INTERP_INTERVAL: f64 = 33.33; // ms, full-rate snapshots arrive at ~30Hz
+pub const SAVER_INTERP_INTERVAL: f64 = 100.0; // ms, data saver snapshots arrive at ~10Hz
+// Snapshot buffer: render this far behind the newest update, widening with observed jitter
+pub const SNAPSHOT_BUFFER: usize = 16;
+pub const INTERP_JITTER_MUL: f64 = 2.0;
+pub const MAX_INTERP_DELAY: f64 = 300.0; // ms
+pub const MAX_EXTRAPOLATION: f64 = 0.25; // s, how far past the last snapshot entities keep drifting
+// Rough snapshot traffic for the data usage estimate before anything is measured
+pub const EST_MB_PER_HOUR: f64 = 130.0;
+pub const EST_SAVER_MB_PER_HOUR: f64 = 35.0;
 
-// Colors per ship type
-pub struct ShipColor {
-    pub main: &'static str,
+// Ship types 0..PLAYER_SHIPS are player hulls; the rest are mobs (must match server)
+pub const PLAYER_SHIPS: usize = 3;
+
+// Per-hull stats for the ship-select cards (must match server player.go constants)
+pub struct ShipStats {
+    pub speed: f64,   // max speed, px/s
+    pub hp: i32,
+    pub fire_cd: f64, // seconds between shots
+    pub ability: &'static str,
 }
 
-pub const SHIP_COLORS: [ShipColor; 6] = [
-    ShipColor { main: "#ff4444" }, // Rebel 1
-    ShipColor { main: "#4488ff" }, // Rebel 2
-    ShipColor { main: "#44ff44" }, // Rebel 3
-    ShipColor { main: "#88ff88" }, // Star Destroyer
-    ShipColor { main: "#aaaaff" }, // TIE 1
-    ShipColor { main: "#aaaaff" }, // TIE 2
+pub const SHIP_STATS: [ShipStats; PLAYER_SHIPS] = [
+    ShipStats { speed: 350.0, hp: 100, fire_cd: 0.15, ability: "Boost, dodge" }, // Rebel 1
+    ShipStats { speed: 350.0, hp: 100, fire_cd: 0.15, ability: "Boost, dodge" }, // Rebel 2
+    ShipStats { speed: 350.0, hp: 100, fire_cd: 0.15, ability: "Boost, dodge" }, // Rebel 3
 ];
 
+// Colors per ship type (ships, lasers, engine glow) and health bar colors,
+// one set per color-vision setting; looked up through palette.rs
+pub struct Palette {
+    pub ships: [&'static str; 6],
+    pub lasers: [&'static str; 6],
+    pub engines: [&'static str; 6],
+    pub health: [&'static str; 3],     // players: above 60%, above 30%, below
+    pub mob_health: [&'static str; 3], // mobs: same thresholds
+    pub crit: &'static str,            // critical hit numbers and markers
+    pub mob: &'static str,             // mob dots on the minimaps; distinct from every hull
+    pub danger: &'static str,          // kill feed victims, the death screen, lock rings
+}
+
+pub const PALETTE_NORMAL: Palette = Palette {
+    //      Rebel 1    Rebel 2    Rebel 3    Star Destroyer  TIE 1     TIE 2
+    ships: ["#ff4444", "#4488ff", "#44ff44", "#88ff88", "#aaaaff", "#aaaaff"],
+    lasers: ["#ff2222", "#2288ff", "#22ff22", "#22ff22", "#44ff44", "#44ff44"],
+    engines: [
+        "rgba(255, 100, 50, 0.6)", "rgba(50, 150, 255, 0.6)", "rgba(50, 255, 100, 0.6)",
+        "rgba(50, 255, 100, 0.6)", "rgba(100, 100, 255, 0.6)", "rgba(100, 100, 255, 0.6)",
+    ],
+    health: ["#44ff44", "#ffaa00", "#ff4444"],
+    mob_health: ["#ff8844", "#ffaa00", "#ff4444"],
+    crit: "#ffee33",
+    mob: "#ffff44",
+    danger: "#ff4444",
+};
+
+// Red-green safe palettes built from the Okabe-Ito set: hulls differ in hue
+// along the blue-yellow axis and in lightness, enemy lasers are near-white,
+// and health runs blue > yellow > vermillion instead of green > red.
+pub const PALETTE_DEUTERANOPIA: Palette = Palette {
+    ships: ["#d55e00", "#56b4e9", "#f0e442", "#e69f00", "#aaaaff", "#aaaaff"],
+    lasers: ["#ff6a1a", "#56b4e9", "#f0e442", "#eeeeee", "#eeeeee", "#eeeeee"],
+    engines: [
+        "rgba(213, 94, 0, 0.6)", "rgba(86, 180, 233, 0.6)", "rgba(240, 228, 66, 0.6)",
+        "rgba(230, 159, 0, 0.6)", "rgba(100, 100, 255, 0.6)", "rgba(100, 100, 255, 0.6)",
+    ],
+    health: ["#56b4e9", "#f0e442", "#d55e00"],
+    mob_health: ["#e69f00", "#f0e442", "#d55e00"],
+    crit: "#f0e442",
+    mob: "#cc79a7",
+    danger: "#d55e00",
+};
+
+pub const PALETTE_PROTANOPIA: Palette = Palette {
+    ships: ["#e69f00", "#0072b2", "#56b4e9", "#f0e442", "#aaaaff", "#aaaaff"],
+    lasers: ["#ffb000", "#1f8fd6", "#8fd3ff", "#eeeeee", "#eeeeee", "#eeeeee"],
+    engines: [
+        "rgba(230, 159, 0, 0.6)", "rgba(0, 114, 178, 0.6)", "rgba(86, 180, 233, 0.6)",
+        "rgba(240, 228, 66, 0.6)", "rgba(100, 100, 255, 0.6)", "rgba(100, 100, 255, 0.6)",
+    ],
+    health: ["#56b4e9", "#f0e442", "#e69f00"],
+    mob_health: ["#e69f00", "#f0e442", "#cc79a7"],
+    crit: "#f0e442",
+    mob: "#cc79a7",
+    danger: "#e69f00",
+};
+
 // New entity sizes (must match server)
 pub const MOB_RADIUS: f64 = 25.0;       // TIE fighter radius
 pub const SD_MOB_RADIUS: f64 = 100.0;   // Star Destroyer radius
+pub const SD_MOB_TYPE: i32 = 3;
+
+/// Collision radius of a mob by ship type (players are all PLAYER_RADIUS)
+pub fn mob_radius(ship_type: i32) -> f64 {
+    if ship_type == SD_MOB_TYPE { SD_MOB_RADIUS } else { MOB_RADIUS }
+}
 pub const ASTEROID_RADIUS: f64 = 50.0;
 pub const ASTEROID_RENDER_SIZE: f64 = 120.0;
+pub const ASTEROID_HP: i32 = 60;
+pub const PROJ_LASER: u8 = 0;
+pub const PROJ_MISSILE: u8 = 1;
+pub const PROJ_BEAM: u8 = 2;
 pub const PICKUP_RADIUS: f64 = 15.0;
 pub const PICKUP_RENDER_SIZE: f64 = 30.0;
-
-// Laser colors per ship type
-pub const LASER_COLORS: [&str; 6] = [
-    "#ff2222",
-    "#2288ff",
-    "#22ff22",
-    "#22ff22", // Star Destroyer
-    "#44ff44", // TIE 1
-    "#44ff44", // TIE 2
-];
@@ -3,19 +3,27 @@ use wasm_bindgen::JsCast;
 use web_sys::{WebSocket, MessageEvent, CloseEvent, ErrorEvent, TouchEvent};
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::constants::{INPUT_RATE, RECONNECT_DELAY};
+use leptos::prelude::*;
+use crate::constants::{mob_radius, DODGE_COOLDOWN, INPUT_RATE, PLAYER_RADIUS, RECONNECT_DELAY};
+use crate::auto_aim::{self, AIM_ORBIT_R, Assist};
+use crate::settings::{self, Settings, SettingsHost};
+use crate::i18n::{t, tf, Msg};
+use crate::resume::{self, ControlTicket};
 
 const JOYSTICK_SCALE: f64 = 3.0;
-const DEAD_ZONE: f64 = 8.0;
-const AIM_ORBIT_R: f64 = 360.0;
-const AIM_DETECT_R: f64 = 150.0;
 
 const BOOST_COLUMN_HALF: f64 = 50.0;
+const STALE_MS: f64 = 3000.0; // no message for this long while attached means the socket died under us
+const RADAR_RANGE: f64 = 1500.0; // world px from the ship to the radar's edge
+const RADAR_PX: f64 = 96.0;      // CSS size of the radar canvas (backing store is 2x)
+const TURN_IN_PLACE: f64 = 20.0; // target distance inside the server's thrust dead zone: rotate without moving
 
 struct ControllerState {
     ws: Option<WebSocket>,
     sid: String,
     pid: String,
+    nid: Option<u32>, // our ship's entity ID, from control_ok
+    gunner: bool, // co-pilot phone: aims and fires while another controller steers
     connected: bool,
     attached: bool,
     player_x: f64,
@@ -24,7 +32,7 @@ struct ControllerState {
     screen_w: f64,
     screen_h: f64,
     enemies: Vec<Enemy>,
-    lock_target_id: Option<String>,
+    lock_target_id: Option<u32>,
     joystick_dx: f64,
     joystick_dy: f64,
     joystick_touch_id: Option<i32>,
@@ -32,9 +40,19 @@ struct ControllerState {
     joystick_start_y: f64,
     fire_touch_id: Option<i32>,
     firing: bool,
+    aim_touch_id: Option<i32>, // right-hand aim stick (settings.aim_stick)
+    aim_start_x: f64,
+    aim_start_y: f64,
+    aim_dx: f64,
+    aim_dy: f64,
     boost_touch_id: Option<i32>,
     boosting: bool,
     boost_locked_r: Option<f64>,
+    dodge_pending: bool, // ability pressed, goes out with the next input
+    dodge_ready_at: f64, // timestamp (ms) when the ability cooldown ends
+    settings: Settings,
+    last_msg_at: f64, // ms timestamp of the last server message
+    input_loop: Option<gloo_timers::callback::Interval>,
     // Store closures
     _on_open: Option<Closure<dyn FnMut()>>,
     _on_message: Option<Closure<dyn FnMut(MessageEvent)>>,
@@ -43,18 +61,136 @@ struct ControllerState {
 }
 
 struct Enemy {
-    id: String,
+    id: u32,
     x: f64,
     y: f64,
+    ship: Option<i32>, // hull for players, None for mobs
+    r: f64,            // collision radius
 }
 
 type SharedCtrl = Rc<RefCell<ControllerState>>;
 
-pub fn init_controller(session_id: &str, player_id: &str) {
+impl SettingsHost for SharedCtrl {
+    fn with_settings<R>(&self, f: impl FnOnce(&mut Settings) -> R) -> R {
+        f(&mut self.borrow_mut().settings)
+    }
+}
+
+/// Ship this page should drive: ?c= on a session link or, when a reloaded or
+/// restored tab lost the query, the pair remembered for this tab
+pub fn control_target() -> Option<ControlTicket> {
+    let location = web_sys::window().unwrap().location();
+    let params = web_sys::UrlSearchParams::new_with_str(&location.search().unwrap_or_default()).ok()?;
+    let sid = crate::route::session_from_path(&location.pathname().unwrap_or_default())?;
+    let ticket = match params.get("c") {
+        Some(pid) => ControlTicket { sid, pid, gunner: params.get("role").as_deref() == Some("gunner") },
+        None => resume::load_control().filter(|t| t.sid == sid)?,
+    };
+    resume::save_control(&ticket.sid, &ticket.pid, ticket.gunner);
+    Some(ticket)
+}
+
+/// Root of the slim controller build. A link that isn't a controller link
+/// after all goes back to the same path without its query, which the server
+/// answers with the full client.
+#[component]
+pub fn ControllerApp() -> impl IntoView {
+    let settings = Settings::load();
+    crate::i18n::set_lang(settings.lang);
+    crate::palette::set_vision(settings.vision);
+
+    match control_target() {
+        Some(t) => view! { <ControllerMode sid=t.sid pid=t.pid gunner=t.gunner /> }.into_any(),
+        None => {
+            let location = web_sys::window().unwrap().location();
+            let _ = location.replace(&location.pathname().unwrap_or_default());
+            ().into_any()
+        }
+    }
+}
+
+#[component]
+pub fn ControllerMode(sid: String, pid: String, gunner: bool) -> impl IntoView {
+    // Init controller on mount
+    let sid_clone = sid.clone();
+    let pid_clone = pid.clone();
+    Effect::new(move |_| {
+        init_controller(&sid_clone, &pid_clone, gunner);
+    });
+
+    view! {
+        <div id="controllerRoot">
+            <button id="ctrlReconnectBtn">{t(Msg::Reconnect)}</button>
+            <div id="ctrlRotateMsg">
+                <div class="rotate-icon">
+                    <svg width="80" height="80" viewBox="0 0 80 80" fill="none" stroke="#6688aa" stroke-width="2">
+                        <rect x="20" y="10" width="40" height="60" rx="4" stroke-dasharray="4 2"/>
+                        <path d="M50 70 L70 50 L70 30 L30 30 L10 50 L10 70 Z" fill="rgba(50,100,200,0.1)" stroke="#4488ff" stroke-dasharray="4 2"/>
+                        <path d="M55 25 C60 15, 70 20, 65 28" stroke="#ffcc00" stroke-width="2" fill="none"/>
+                        <path d="M63 22 L65 28 L59 27" stroke="#ffcc00" stroke-width="2" fill="none"/>
+                    </svg>
+                </div>
+                <p>{t(Msg::RotatePhone)}</p>
+                <div class="ctrl-options">
+                    <label><input type="checkbox" id="optToggleFire"/>{t(Msg::TapToggleFire)}</label>
+                    <label><input type="checkbox" id="optToggleBoost"/>{t(Msg::TapToggleBoost)}</label>
+                    <label><input type="checkbox" id="optAimStick"/>{t(Msg::AimStickOption)}</label>
+                    <label><input type="checkbox" id="optGyro"/>{t(Msg::GyroOption)}</label>
+                    <label>{t(Msg::GyroSensitivity)}<input type="range" id="optGyroSens" min="0.5" max="2" step="0.1"/><span id="optGyroSensVal"></span></label>
+                    <label>{t(Msg::AimAssist)}<select id="optAimAssist">
+                        <option value="off">{t(Msg::Off)}</option>
+                        <option value="low">{t(Msg::Low)}</option>
+                        <option value="high">{t(Msg::High)}</option>
+                    </select></label>
+                    <label>{t(Msg::JoyDeadZone)}<input type="range" id="optJoyDeadZone" min="0" max="40" step="1"/><span id="optJoyDeadZoneVal"></span></label>
+                    <label>{t(Msg::JoySensitivity)}<input type="range" id="optJoySens" min="0.5" max="2" step="0.1"/><span id="optJoySensVal"></span></label>
+                    <div id="calibPad">
+                        <div id="calibDeadZone"></div>
+                        <div id="calibKnob"></div>
+                    </div>
+                    <div id="calibReadout">{t(Msg::DragToTest)}</div>
+                </div>
+            </div>
+            <div id="ctrlPad" style="display:none;">
+                <div id="ctrlStatus">{t(Msg::Connecting)}</div>
+                <canvas id="ctrlRadar" width="192" height="192"></canvas>
+                <div class="ctrl-divider-left"></div>
+                <div class="ctrl-divider-right"></div>
+                <div class="ctrl-center ctrl-pilot-only">
+                    <div class="ctrl-boost-indicator" id="boostIndicator"></div>
+                    <div class="ctrl-label">{t(Msg::Boost)}</div>
+                </div>
+                <button id="ctrlPingBtn">{t(Msg::Ping)}</button>
+                <button id="ctrlGyroCalib" class="ctrl-gyro-only">{t(Msg::Calibrate)}</button>
+                <div class="ctrl-left">
+                    <div class="ctrl-label ctrl-pilot-only">{t(Msg::DragToNavigate)}</div>
+                    <div class="ctrl-label ctrl-gunner-only">{t(Msg::TapToFire)}</div>
+                    <div class="ctrl-joystick-ring ctrl-pilot-only" id="joystickRing">
+                        <div class="ctrl-joystick-knob" id="joystickKnob"></div>
+                    </div>
+                </div>
+                <div class="ctrl-right">
+                    <div class="ctrl-label ctrl-tap-only">{t(Msg::TapToFire)}</div>
+                    <div class="ctrl-label ctrl-stick-only">{t(Msg::DragToAim)}</div>
+                    <div class="ctrl-joystick-ring ctrl-stick-only" id="aimRing">
+                        <div class="ctrl-joystick-knob ctrl-aim-knob" id="aimKnob"></div>
+                    </div>
+                    <div class="ctrl-fire-indicator" id="fireIndicator"></div>
+                </div>
+                <button id="ctrlFireBtn" class="ctrl-stick-only">{t(Msg::Fire)}</button>
+                <button id="ctrlAbilityBtn" class="ctrl-pilot-only">{t(Msg::Dodge)}</button>
+            </div>
+        </div>
+    }
+}
+
+pub fn init_controller(session_id: &str, player_id: &str, gunner: bool) {
     let ctrl = Rc::new(RefCell::new(ControllerState {
         ws: None,
         sid: session_id.to_string(),
         pid: player_id.to_string(),
+        nid: None,
+        gunner,
         connected: false,
         attached: false,
         player_x: 0.0,
@@ -71,9 +207,19 @@ pub fn init_controller(session_id: &str, player_id: &str) {
         joystick_start_y: 0.0,
         fire_touch_id: None,
         firing: false,
+        aim_touch_id: None,
+        aim_start_x: 0.0,
+        aim_start_y: 0.0,
+        aim_dx: 0.0,
+        aim_dy: 0.0,
         boost_touch_id: None,
         boosting: false,
         boost_locked_r: None,
+        dodge_pending: false,
+        dodge_ready_at: 0.0,
+        settings: Settings::load(),
+        last_msg_at: 0.0,
+        input_loop: None,
         _on_open: None,
         _on_message: None,
         _on_close: None,
@@ -82,6 +228,19 @@ pub fn init_controller(session_id: &str, player_id: &str) {
 
     check_orientation(&ctrl);
 
+    // Fullscreen and the orientation lock need a user gesture, so try once now
+    // (installed web apps allow it) and again on the first tap. The rotate
+    // message stays as the fallback where neither API exists (iOS Safari).
+    lock_landscape();
+    let first_tap = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        lock_landscape();
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let once = web_sys::AddEventListenerOptions::new();
+    once.set_once(true);
+    let _ = web_sys::window().unwrap().document().unwrap()
+        .add_event_listener_with_callback_and_add_event_listener_options("touchend", first_tap.as_ref().unchecked_ref(), &once);
+    first_tap.forget();
+
     // Orientation change handler
     let ctrl_orient = ctrl.clone();
     let orient_closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
@@ -91,6 +250,35 @@ pub fn init_controller(session_id: &str, player_id: &str) {
     let _ = window.add_event_listener_with_callback("resize", orient_closure.as_ref().unchecked_ref());
     orient_closure.forget();
 
+    // Coming back from a screen lock or a suspended tab: the socket is often dead
+    // without having closed, so check and reattach right away instead of waiting
+    let ctrl_vis = ctrl.clone();
+    let vis_closure = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        let document = web_sys::window().unwrap().document().unwrap();
+        if document.hidden() { return; }
+        let stale = {
+            let c = ctrl_vis.borrow();
+            !c.attached || js_sys::Date::now() - c.last_msg_at > STALE_MS
+        };
+        if stale {
+            reconnect_now(&ctrl_vis);
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let document = window.document().unwrap();
+    let _ = document.add_event_listener_with_callback("visibilitychange", vis_closure.as_ref().unchecked_ref());
+    vis_closure.forget();
+
+    // Big reconnect button, shown whenever the ship isn't attached
+    if let Some(btn) = document.get_element_by_id("ctrlReconnectBtn") {
+        let ctrl_btn = ctrl.clone();
+        let reconnect = Closure::wrap(Box::new(move |e: web_sys::Event| {
+            e.prevent_default();
+            reconnect_now(&ctrl_btn);
+        }) as Box<dyn FnMut(web_sys::Event)>);
+        let _ = btn.add_event_listener_with_callback("click", reconnect.as_ref().unchecked_ref());
+        reconnect.forget();
+    }
+
     // Touch handlers
     setup_touch_handlers(&ctrl);
 
@@ -98,6 +286,73 @@ pub fn init_controller(session_id: &str, player_id: &str) {
     connect_ws(&ctrl);
 }
 
+/// Drop the current socket without triggering its reconnect timer and open a fresh one
+fn reconnect_now(ctrl: &SharedCtrl) {
+    let old = {
+        let mut c = ctrl.borrow_mut();
+        c.connected = false;
+        c.attached = false;
+        c.ws.take()
+    };
+    if let Some(ws) = old {
+        ws.set_onopen(None);
+        ws.set_onmessage(None);
+        ws.set_onclose(None);
+        ws.set_onerror(None);
+        let _ = ws.close();
+    }
+    update_status(t(Msg::Reconnecting));
+    connect_ws(ctrl);
+}
+
+fn send_control(ctrl: &SharedCtrl) {
+    let c = ctrl.borrow();
+    if let Some(ws) = c.ws.as_ref().filter(|ws| ws.ready_state() == 1) {
+        let role = if c.gunner { "gunner" } else { "pilot" };
+        let msg = serde_json::json!({"t": "control", "d": {"sid": c.sid, "pid": c.pid, "role": role}});
+        let _ = ws.send_with_str(&msg.to_string());
+    }
+}
+
+fn show_reconnect(visible: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(btn) = document.get_element_by_id("ctrlReconnectBtn") {
+        let _ = btn.class_list().toggle_with_force("visible", visible);
+    }
+}
+
+/// Go fullscreen, then lock the screen to landscape (Chrome only allows the lock in fullscreen)
+fn lock_landscape() {
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let elem = document.document_element().unwrap();
+    let call0 = |target: &JsValue, name: &str| -> Option<JsValue> {
+        js_sys::Reflect::get(target, &name.into()).ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok())
+            .and_then(|f| f.call0(target).ok())
+    };
+    let is_fs = js_sys::Reflect::get(&document, &"fullscreenElement".into())
+        .ok()
+        .map(|v| !v.is_null() && !v.is_undefined())
+        .unwrap_or(false);
+    let entered = if is_fs { None } else { call0(&elem, "requestFullscreen") };
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Some(promise) = entered.and_then(|p| p.dyn_into::<js_sys::Promise>().ok()) {
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+        }
+        let Ok(screen) = window.screen() else { return };
+        let orientation: JsValue = screen.orientation().into();
+        let Some(lock) = js_sys::Reflect::get(&orientation, &"lock".into()).ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok()) else { return };
+        if let Ok(promise) = lock.call1(&orientation, &"landscape".into()) {
+            if let Ok(promise) = promise.dyn_into::<js_sys::Promise>() {
+                // Rejected when unsupported; the rotate message covers that case
+                let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+            }
+        }
+    });
+}
+
 fn check_orientation(ctrl: &SharedCtrl) {
     let window = web_sys::window().unwrap();
     let w = window.inner_width().unwrap().as_f64().unwrap();
@@ -116,6 +371,11 @@ fn check_orientation(ctrl: &SharedCtrl) {
     if let Some(pad) = document.get_element_by_id("ctrlPad") {
         let el: &web_sys::HtmlElement = pad.unchecked_ref();
         let _ = el.style().set_property("display", if landscape { "block" } else { "none" });
+        // Options are only reachable in portrait, so turning the phone picks up the aim stick and tilt settings
+        let c = ctrl.borrow();
+        let _ = pad.class_list().toggle_with_force("aim-stick", c.settings.aim_stick || c.gunner);
+        let _ = pad.class_list().toggle_with_force("gyro", c.settings.gyro_steer && !c.gunner);
+        let _ = pad.class_list().toggle_with_force("gunner", c.gunner);
     }
 }
 
@@ -133,20 +393,19 @@ fn connect_ws(ctrl: &SharedCtrl) {
     // on open
     let ctrl_open = ctrl.clone();
     let on_open = Closure::wrap(Box::new(move || {
-        let mut c = ctrl_open.borrow_mut();
-        c.connected = true;
-        update_status("Attaching...");
-        let sid = c.sid.clone();
-        let pid = c.pid.clone();
-        if let Some(ref ws) = c.ws {
-            let msg = serde_json::json!({"t": "control", "d": {"sid": sid, "pid": pid}});
-            let _ = ws.send_with_str(&msg.to_string());
+        {
+            let mut c = ctrl_open.borrow_mut();
+            c.connected = true;
+            c.last_msg_at = js_sys::Date::now();
         }
+        update_status(t(Msg::Attaching));
+        send_control(&ctrl_open);
     }) as Box<dyn FnMut()>);
 
     // on message — handle both binary (msgpack state) and text (JSON control messages)
     let ctrl_msg = ctrl.clone();
     let on_message = Closure::wrap(Box::new(move |e: MessageEvent| {
+        ctrl_msg.borrow_mut().last_msg_at = js_sys::Date::now();
         let data = e.data();
         if let Some(ab) = data.dyn_ref::<js_sys::ArrayBuffer>() {
             let arr = js_sys::Uint8Array::new(ab);
@@ -169,9 +428,12 @@ fn connect_ws(ctrl: &SharedCtrl) {
             c.connected = false;
             c.attached = false;
         }
-        update_status("Disconnected. Reconnecting...");
+        update_status(t(Msg::Reconnecting));
+        show_reconnect(true);
         let ctrl_reconnect = ctrl_close.clone();
         gloo_timers::callback::Timeout::new(RECONNECT_DELAY, move || {
+            // Skip if the reconnect button or a resume already opened a new socket
+            if ctrl_reconnect.borrow().ws.as_ref().is_some_and(|ws| ws.ready_state() != WebSocket::CLOSED) { return; }
             connect_ws(&ctrl_reconnect);
         }).forget();
     }) as Box<dyn FnMut(CloseEvent)>);
@@ -193,37 +455,105 @@ fn connect_ws(ctrl: &SharedCtrl) {
 
 fn handle_state(ctrl: &SharedCtrl, gs: crate::protocol::GameStateMsg) {
     let mut c = ctrl.borrow_mut();
-    let pid = c.pid.clone();
+    let nid = c.nid;
     let mut new_enemies = Vec::new();
 
     for p in &gs.p {
-        if p.id == pid {
+        if Some(p.id) == nid {
             c.player_x = p.x;
             c.player_y = p.y;
             c.player_r = p.r;
         } else if p.a {
-            new_enemies.push(Enemy { id: format!("p_{}", p.id), x: p.x, y: p.y });
+            new_enemies.push(Enemy { id: p.id, x: p.x, y: p.y, ship: Some(p.s), r: PLAYER_RADIUS });
         }
     }
     for m in &gs.m {
         if m.a {
-            new_enemies.push(Enemy { id: format!("m_{}", m.id), x: m.x, y: m.y });
+            new_enemies.push(Enemy { id: m.id, x: m.x, y: m.y, ship: None, r: mob_radius(m.s) });
         }
     }
     c.enemies = new_enemies;
+    draw_radar(&c);
+}
+
+/// Ship-centered radar: us as an arrow, players in their hull color, mobs in
+/// yellow, and a ring around whatever auto-aim is locked onto
+fn draw_radar(c: &ControllerState) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(canvas) = document.get_element_by_id("ctrlRadar")
+        .and_then(|e| e.dyn_into::<web_sys::HtmlCanvasElement>().ok()) else { return };
+    let Ok(Some(ctx)) = canvas.get_context("2d") else { return };
+    let ctx: web_sys::CanvasRenderingContext2d = ctx.unchecked_into();
+    let tau = std::f64::consts::TAU;
+    let half = RADAR_PX / 2.0;
+    let k = half / RADAR_RANGE;
+
+    let _ = ctx.set_transform(2.0, 0.0, 0.0, 2.0, 0.0, 0.0);
+    ctx.clear_rect(0.0, 0.0, RADAR_PX, RADAR_PX);
+    ctx.set_fill_style_str("rgba(0, 40, 0, 0.5)");
+    ctx.begin_path();
+    let _ = ctx.arc(half, half, half - 1.0, 0.0, tau);
+    ctx.fill();
+    ctx.set_stroke_style_str("rgba(0, 255, 0, 0.5)");
+    ctx.set_line_width(1.0);
+    ctx.stroke();
+    ctx.begin_path();
+    let _ = ctx.arc(half, half, half / 2.0, 0.0, tau);
+    ctx.stroke();
+
+    for e in &c.enemies {
+        let (dx, dy) = ((e.x - c.player_x) * k, (e.y - c.player_y) * k);
+        if dx.hypot(dy) > half - 3.0 { continue; }
+        let (x, y) = (half + dx, half + dy);
+        ctx.set_fill_style_str(e.ship.map(crate::palette::ship).unwrap_or_else(crate::palette::mob));
+        ctx.begin_path();
+        let _ = ctx.arc(x, y, 2.5, 0.0, tau);
+        ctx.fill();
+        if c.lock_target_id == Some(e.id) {
+            ctx.set_stroke_style_str(crate::palette::danger());
+            ctx.set_line_width(1.5);
+            ctx.begin_path();
+            let _ = ctx.arc(x, y, 6.0, 0.0, tau);
+            ctx.stroke();
+        }
+    }
+
+    // Us, pointing where the ship faces
+    let (cos, sin) = (c.player_r.cos(), c.player_r.sin());
+    ctx.set_fill_style_str("#ffffff");
+    ctx.begin_path();
+    ctx.move_to(half + cos * 6.0, half + sin * 6.0);
+    ctx.line_to(half - cos * 4.0 - sin * 4.0, half - sin * 4.0 + cos * 4.0);
+    ctx.line_to(half - cos * 4.0 + sin * 4.0, half - sin * 4.0 - cos * 4.0);
+    ctx.close_path();
+    ctx.fill();
 }
 
 fn handle_message(ctrl: &SharedCtrl, env: crate::protocol::Envelope) {
     let data = env.d.unwrap_or(serde_json::Value::Null);
     match env.t.as_str() {
         "control_ok" => {
-            ctrl.borrow_mut().attached = true;
-            update_status("Connected");
+            let mut c = ctrl.borrow_mut();
+            c.attached = true;
+            c.nid = serde_json::from_value::<crate::protocol::AttachOkMsg>(data).ok().map(|ok| ok.nid);
+            drop(c);
+            update_status(t(Msg::Connected));
+            show_reconnect(false);
             start_input_loop(ctrl);
         }
         "error" => {
             if let Ok(e) = serde_json::from_value::<crate::protocol::ErrorMsg>(data) {
-                update_status(&format!("Error: {}", e.msg));
+                update_status(&tf(Msg::Error, e.msg));
+            }
+            // The desktop may itself be reconnecting, so keep asking for the ship
+            if !ctrl.borrow().attached {
+                show_reconnect(true);
+                let ctrl_retry = ctrl.clone();
+                gloo_timers::callback::Timeout::new(RECONNECT_DELAY, move || {
+                    if !ctrl_retry.borrow().attached {
+                        send_control(&ctrl_retry);
+                    }
+                }).forget();
             }
         }
         _ => {}
@@ -231,8 +561,12 @@ fn handle_message(ctrl: &SharedCtrl, env: crate::protocol::Envelope) {
 }
 
 fn update_status(text: &str) {
+    set_text("ctrlStatus", text);
+}
+
+fn set_text(id: &str, text: &str) {
     let document = web_sys::window().unwrap().document().unwrap();
-    if let Some(el) = document.get_element_by_id("ctrlStatus") {
+    if let Some(el) = document.get_element_by_id(id) {
         el.set_text_content(Some(text));
     }
 }
@@ -241,7 +575,84 @@ fn setup_touch_handlers(ctrl: &SharedCtrl) {
     // Wait a bit for DOM to be ready
     let ctrl_clone = ctrl.clone();
     gloo_timers::callback::Timeout::new(100, move || {
+        settings::bind_checkbox(&ctrl_clone, "optToggleFire", |s| s.toggle_fire, |s, on| s.toggle_fire = on);
+        settings::bind_checkbox(&ctrl_clone, "optToggleBoost", |s| s.toggle_boost, |s, on| s.toggle_boost = on);
+        settings::bind_checkbox(&ctrl_clone, "optAimStick", |s| s.aim_stick, |s, on| s.aim_stick = on);
+        settings::bind_checkbox(&ctrl_clone, "optGyro", |s| s.gyro_steer, |s, on| s.gyro_steer = on);
+        settings::bind_value(&ctrl_clone, "optGyroSens",
+            |s| s.gyro_sensitivity.to_string(),
+            |s, v| if let Ok(v) = v.parse() { s.gyro_sensitivity = v },
+            |s| set_text("optGyroSensVal", &crate::joystick::describe_sensitivity(s.gyro_sensitivity)));
+        settings::bind_value(&ctrl_clone, "optAimAssist",
+            |s| s.aim_assist.id().to_string(),
+            |s, v| s.aim_assist = Assist::parse(v),
+            |_| {});
+        crate::joystick::setup_calibration(&ctrl_clone, JOYSTICK_SCALE);
         let document = web_sys::window().unwrap().document().unwrap();
+        // Motion access has to be asked for from a tap, so ticking the tilt option starts listening
+        if let Some(opt) = document.get_element_by_id("optGyro") {
+            let enable = Closure::wrap(Box::new(move |_: web_sys::Event| {
+                crate::gyro::enable();
+            }) as Box<dyn FnMut(web_sys::Event)>);
+            let _ = opt.add_event_listener_with_callback("click", enable.as_ref().unchecked_ref());
+            enable.forget();
+        }
+        if ctrl_clone.borrow().settings.gyro_steer {
+            crate::gyro::enable();
+        }
+        // Calibrate button: the way the phone is held right now becomes "stick centered"
+        if let Some(btn) = document.get_element_by_id("ctrlGyroCalib") {
+            let calibrate = Closure::wrap(Box::new(move |e: TouchEvent| {
+                e.prevent_default();
+                e.stop_propagation();
+                crate::gyro::calibrate();
+            }) as Box<dyn FnMut(TouchEvent)>);
+            let _ = btn.add_event_listener_with_callback("touchstart", calibrate.as_ref().unchecked_ref());
+            calibrate.forget();
+        }
+        // Ping button: marks where the ship is aiming; kept out of the pad's touch zones
+        if let Some(btn) = document.get_element_by_id("ctrlPingBtn") {
+            let ctrl_ping = ctrl_clone.clone();
+            let ping = Closure::wrap(Box::new(move |e: TouchEvent| {
+                e.prevent_default();
+                e.stop_propagation();
+                send_marker(&ctrl_ping);
+            }) as Box<dyn FnMut(TouchEvent)>);
+            let _ = btn.add_event_listener_with_callback("touchstart", ping.as_ref().unchecked_ref());
+            ping.forget();
+        }
+        // Fire button (aim stick layout); lifting the finger is handled by the pad's touchend
+        if let Some(btn) = document.get_element_by_id("ctrlFireBtn") {
+            let ctrl_fire = ctrl_clone.clone();
+            let fire = Closure::wrap(Box::new(move |e: TouchEvent| {
+                e.prevent_default();
+                e.stop_propagation();
+                let Some(touch) = e.changed_touches().get(0) else { return };
+                let mut c = ctrl_fire.borrow_mut();
+                if c.fire_touch_id.is_some() { return; }
+                c.fire_touch_id = Some(touch.identifier());
+                c.firing = !(c.settings.toggle_fire && c.firing);
+                update_fire_indicator(c.firing);
+            }) as Box<dyn FnMut(TouchEvent)>);
+            let _ = btn.add_event_listener_with_callback("touchstart", fire.as_ref().unchecked_ref());
+            fire.forget();
+        }
+        // Ability button: a dodge dash toward the move stick, or straight ahead when it's idle
+        if let Some(btn) = document.get_element_by_id("ctrlAbilityBtn") {
+            let ctrl_ability = ctrl_clone.clone();
+            let ability = Closure::wrap(Box::new(move |e: TouchEvent| {
+                e.prevent_default();
+                e.stop_propagation();
+                let mut c = ctrl_ability.borrow_mut();
+                let now = js_sys::Date::now();
+                if now < c.dodge_ready_at { return; }
+                c.dodge_pending = true;
+                c.dodge_ready_at = now + DODGE_COOLDOWN;
+                update_ability_indicator(c.dodge_ready_at);
+            }) as Box<dyn FnMut(TouchEvent)>);
+            let _ = btn.add_event_listener_with_callback("touchstart", ability.as_ref().unchecked_ref());
+            ability.forget();
+        }
         if let Some(pad) = document.get_element_by_id("ctrlPad") {
             let opts = web_sys::AddEventListenerOptions::new();
             opts.set_passive(false);
@@ -257,7 +668,12 @@ fn setup_touch_handlers(ctrl: &SharedCtrl) {
                 let has_joystick = c.joystick_touch_id.is_some();
                 let has_fire = c.fire_touch_id.is_some();
                 let has_boost = c.boost_touch_id.is_some();
+                let has_aim = c.aim_touch_id.is_some();
+                let gunner = c.gunner;
+                let aim_stick = c.settings.aim_stick || gunner;
                 let player_r = c.player_r;
+                let toggle_fire = c.settings.toggle_fire;
+                let toggle_boost = c.settings.toggle_boost;
                 drop(c);
 
                 let changed = e.changed_touches();
@@ -267,21 +683,44 @@ fn setup_touch_handlers(ctrl: &SharedCtrl) {
                         let cy = touch.client_y() as f64;
                         let tid = touch.identifier();
                         let mut c = ctrl_ts.borrow_mut();
-                        if cx < center_left && !has_joystick {
+                        if gunner {
+                            // Gunner: the right stick aims, anywhere else is the trigger
+                            if cx > center_right {
+                                if !has_aim {
+                                    c.aim_touch_id = Some(tid);
+                                    c.aim_start_x = cx;
+                                    c.aim_start_y = cy;
+                                    c.aim_dx = 0.0;
+                                    c.aim_dy = 0.0;
+                                }
+                            } else if !has_fire {
+                                c.fire_touch_id = Some(tid);
+                                c.firing = !(toggle_fire && c.firing);
+                                update_fire_indicator(c.firing);
+                            }
+                        } else if cx < center_left && !has_joystick {
                             c.joystick_touch_id = Some(tid);
                             c.joystick_start_x = cx;
                             c.joystick_start_y = cy;
                             c.joystick_dx = 0.0;
                             c.joystick_dy = 0.0;
+                        } else if cx > center_right && aim_stick {
+                            if !has_aim {
+                                c.aim_touch_id = Some(tid);
+                                c.aim_start_x = cx;
+                                c.aim_start_y = cy;
+                                c.aim_dx = 0.0;
+                                c.aim_dy = 0.0;
+                            }
                         } else if cx > center_right && !has_fire {
                             c.fire_touch_id = Some(tid);
-                            c.firing = true;
-                            update_fire_indicator(true);
+                            c.firing = !(toggle_fire && c.firing);
+                            update_fire_indicator(c.firing);
                         } else if cx >= center_left && cx <= center_right && !has_boost {
                             c.boost_touch_id = Some(tid);
-                            c.boosting = true;
-                            c.boost_locked_r = Some(player_r);
-                            update_boost_indicator(true);
+                            c.boosting = !(toggle_boost && c.boosting);
+                            c.boost_locked_r = if c.boosting { Some(player_r) } else { None };
+                            update_boost_indicator(c.boosting);
                         }
                     }
                 }
@@ -307,7 +746,15 @@ fn setup_touch_handlers(ctrl: &SharedCtrl) {
                             let mut c = ctrl_tm.borrow_mut();
                             c.joystick_dx = dx;
                             c.joystick_dy = dy;
-                            update_knob(dx, dy);
+                            update_knob("joystickKnob", dx, dy);
+                        } else if c.aim_touch_id == Some(tid) {
+                            let dx = touch.client_x() as f64 - c.aim_start_x;
+                            let dy = touch.client_y() as f64 - c.aim_start_y;
+                            drop(c);
+                            let mut c = ctrl_tm.borrow_mut();
+                            c.aim_dx = dx;
+                            c.aim_dy = dy;
+                            update_knob("aimKnob", dx, dy);
                         }
                     }
                 }
@@ -330,18 +777,29 @@ fn setup_touch_handlers(ctrl: &SharedCtrl) {
                                 c.joystick_touch_id = None;
                                 c.joystick_dx = 0.0;
                                 c.joystick_dy = 0.0;
-                                update_knob(0.0, 0.0);
+                                update_knob("joystickKnob", 0.0, 0.0);
                             }
+                            if c.aim_touch_id == Some(tid) {
+                                c.aim_touch_id = None;
+                                c.aim_dx = 0.0;
+                                c.aim_dy = 0.0;
+                                update_knob("aimKnob", 0.0, 0.0);
+                            }
+                            // With the toggle options on, lifting the finger keeps the latch
                             if c.fire_touch_id == Some(tid) {
                                 c.fire_touch_id = None;
-                                c.firing = false;
-                                update_fire_indicator(false);
+                                if !c.settings.toggle_fire {
+                                    c.firing = false;
+                                    update_fire_indicator(false);
+                                }
                             }
                             if c.boost_touch_id == Some(tid) {
                                 c.boost_touch_id = None;
-                                c.boosting = false;
-                                c.boost_locked_r = None;
-                                update_boost_indicator(false);
+                                if !c.settings.toggle_boost {
+                                    c.boosting = false;
+                                    c.boost_locked_r = None;
+                                    update_boost_indicator(false);
+                                }
                             }
                         }
                     }
@@ -363,9 +821,9 @@ fn setup_touch_handlers(ctrl: &SharedCtrl) {
     }).forget();
 }
 
-fn update_knob(dx: f64, dy: f64) {
+fn update_knob(id: &str, dx: f64, dy: f64) {
     let document = web_sys::window().unwrap().document().unwrap();
-    if let Some(knob) = document.get_element_by_id("joystickKnob") {
+    if let Some(knob) = document.get_element_by_id(id) {
         let el: &web_sys::HtmlElement = knob.unchecked_ref();
         let max_r = 45.0;
         let dist = (dx * dx + dy * dy).sqrt();
@@ -383,11 +841,9 @@ fn update_knob(dx: f64, dy: f64) {
 
 fn update_fire_indicator(active: bool) {
     let document = web_sys::window().unwrap().document().unwrap();
-    if let Some(ind) = document.get_element_by_id("fireIndicator") {
-        if active {
-            let _ = ind.class_list().add_1("active");
-        } else {
-            let _ = ind.class_list().remove_1("active");
+    for id in ["fireIndicator", "ctrlFireBtn"] {
+        if let Some(ind) = document.get_element_by_id(id) {
+            let _ = ind.class_list().toggle_with_force("active", active);
         }
     }
 }
@@ -403,70 +859,107 @@ fn update_boost_indicator(active: bool) {
     }
 }
 
+/// Fill the ability button's cooldown ring with the fraction still to wait
+fn update_ability_indicator(ready_at: f64) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(btn) = document.get_element_by_id("ctrlAbilityBtn") {
+        let left = ((ready_at - js_sys::Date::now()) / DODGE_COOLDOWN).clamp(0.0, 1.0);
+        let btn: web_sys::HtmlElement = btn.unchecked_into();
+        let _ = btn.style().set_property("--cd", &format!("{:.3}", left));
+        let _ = btn.class_list().toggle_with_force("cooling", left > 0.0);
+    }
+}
+
+fn send_marker(ctrl: &SharedCtrl) {
+    let c = ctrl.borrow();
+    if !c.attached { return; }
+    if let Some(ws) = c.ws.as_ref().filter(|ws| ws.ready_state() == 1) {
+        let msg = serde_json::json!({"t": "marker", "d": {"aim": true}});
+        let _ = ws.send_with_str(&msg.to_string());
+    }
+}
+
+/// Start sending input, once; reattaching keeps the loop that's already running
 fn start_input_loop(ctrl: &SharedCtrl) {
+    if ctrl.borrow().input_loop.is_some() { return; }
     let ctrl_clone = ctrl.clone();
     let interval = gloo_timers::callback::Interval::new(1000 / INPUT_RATE, move || {
+        let stale = {
+            let c = ctrl_clone.borrow();
+            c.attached && js_sys::Date::now() - c.last_msg_at > STALE_MS
+        };
+        if stale {
+            reconnect_now(&ctrl_clone);
+            return;
+        }
         send_input(&ctrl_clone);
     });
-    std::mem::forget(interval);
+    ctrl.borrow_mut().input_loop = Some(interval);
 }
 
 fn send_input(ctrl: &SharedCtrl) {
     let c = ctrl.borrow();
     if !c.connected || !c.attached { return; }
 
-    let dist = (c.joystick_dx * c.joystick_dx + c.joystick_dy * c.joystick_dy).sqrt();
-
     let (mx, my);
-    let mut lock_id: Option<String>;
-
-    if dist > DEAD_ZONE {
-        let aim_angle = c.joystick_dy.atan2(c.joystick_dx);
+    let lock_id: Option<u32>;
+
+    let manual_aim = c.aim_touch_id
+        .and_then(|_| crate::joystick::aim_offset(c.aim_dx, c.aim_dy, JOYSTICK_SCALE, &c.settings));
+
+    if c.gunner {
+        // Only aim and trigger; the server ignores steering from the gunner.
+        // With the stick idle the target sits on the ship, so the turret holds its angle.
+        let (mx, my) = match manual_aim {
+            Some((adx, ady)) => {
+                let a = ady.atan2(adx);
+                (c.player_x + a.cos() * AIM_ORBIT_R, c.player_y + a.sin() * AIM_ORBIT_R)
+            }
+            None => (c.player_x, c.player_y),
+        };
+        if let Some(ws) = c.ws.as_ref().filter(|ws| ws.ready_state() == 1) {
+            let msg = serde_json::json!({"t": "input", "d": { "mx": mx, "my": my, "fire": c.firing }});
+            let _ = ws.send_with_str(&msg.to_string());
+        }
+        return;
+    }
+    // Tilt steering stands in for the left stick whenever no thumb is on it
+    let tilt = (c.settings.gyro_steer && c.joystick_touch_id.is_none())
+        .then(|| crate::gyro::offset(&c.settings))
+        .flatten();
+    if let Some((tx, ty)) = tilt {
+        update_knob("joystickKnob", tx, ty);
+    }
+    let (stick_dx, stick_dy) = tilt.unwrap_or((c.joystick_dx, c.joystick_dy));
+    let move_offset = crate::joystick::aim_offset(stick_dx, stick_dy, JOYSTICK_SCALE, &c.settings);
+
+    if let Some((adx, ady)) = manual_aim {
+        // Aim stick: it picks the heading, the move stick only sets how hard to thrust
+        // (turning in place when idle), and auto-aim stays out of it
+        let dist = move_offset.map(|(dx, dy)| dx.hypot(dy)).unwrap_or(TURN_IN_PLACE);
+        let a = ady.atan2(adx);
+        lock_id = None;
+        mx = c.player_x + a.cos() * dist;
+        my = c.player_y + a.sin() * dist;
+    } else if let Some((aim_dx, aim_dy)) = move_offset {
+        let aim_angle = stick_dy.atan2(stick_dx);
         let orbit_x = c.player_x + aim_angle.cos() * AIM_ORBIT_R;
         let orbit_y = c.player_y + aim_angle.sin() * AIM_ORBIT_R;
 
         // Auto-aim: only when joystick is active
-        let mut locked = false;
-        lock_id = c.lock_target_id.clone();
-        let mut target_x = 0.0;
-        let mut target_y = 0.0;
-
-        if let Some(ref tid) = lock_id {
-            if let Some(t) = c.enemies.iter().find(|e| &e.id == tid) {
-                let dx = t.x - orbit_x;
-                let dy = t.y - orbit_y;
-                if dx * dx + dy * dy <= AIM_DETECT_R * AIM_DETECT_R {
-                    locked = true;
-                    target_x = t.x;
-                    target_y = t.y;
-                }
+        let enemies = c.enemies.iter().map(|e| (e.id, e.x, e.y, e.r));
+        match auto_aim::pick_target(c.settings.aim_assist, c.lock_target_id, orbit_x, orbit_y, enemies) {
+            Some((id, x, y)) => {
+                lock_id = Some(id);
+                mx = x;
+                my = y;
             }
-            if !locked { lock_id = None; }
-        }
-
-        if !locked {
-            let mut best_dist = AIM_DETECT_R * AIM_DETECT_R;
-            for e in &c.enemies {
-                let dx = e.x - orbit_x;
-                let dy = e.y - orbit_y;
-                let d2 = dx * dx + dy * dy;
-                if d2 <= best_dist {
-                    best_dist = d2;
-                    lock_id = Some(e.id.clone());
-                    target_x = e.x;
-                    target_y = e.y;
-                    locked = true;
-                }
+            None => {
+                lock_id = None;
+                mx = c.player_x + aim_dx;
+                my = c.player_y + aim_dy;
             }
         }
-
-        if locked {
-            mx = target_x;
-            my = target_y;
-        } else {
-            mx = c.player_x + c.joystick_dx * JOYSTICK_SCALE;
-            my = c.player_y + c.joystick_dy * JOYSTICK_SCALE;
-        }
     } else {
         // Joystick idle: maintain current heading, clear lock
         lock_id = None;
@@ -487,17 +980,23 @@ fn send_input(ctrl: &SharedCtrl) {
 
     let firing = c.firing;
     let boosting = c.boosting;
+    let ready_at = c.dodge_ready_at;
     let ws = c.ws.clone();
     drop(c);
 
     // Update lock target
-    ctrl.borrow_mut().lock_target_id = lock_id;
+    let dodge = {
+        let mut c = ctrl.borrow_mut();
+        c.lock_target_id = lock_id;
+        std::mem::take(&mut c.dodge_pending)
+    };
+    update_ability_indicator(ready_at);
 
     if let Some(ws) = ws {
         if ws.ready_state() == 1 {
             let msg = serde_json::json!({
                 "t": "input",
-                "d": { "mx": mx, "my": my, "fire": firing, "boost": boosting, "thresh": 50 }
+                "d": { "mx": mx, "my": my, "fire": firing, "boost": boosting, "dodge": dodge, "thresh": 50 }
             });
             let _ = ws.send_with_str(&msg.to_string());
         }
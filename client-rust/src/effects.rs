@@ -2,45 +2,21 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use wasm_bindgen::JsCast;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
-use crate::state::{Particle, ParticleKind, Explosion, DamageNumber, HitMarker, MobSpeech, GameState};
-use crate::constants::SHIP_COLORS;
-
-const MAX_PARTICLES: usize = 200;
-
-// Fast WASM-native xorshift64 RNG (avoids JS interop overhead of Math.random)
-thread_local! {
-    static RNG_STATE: RefCell<u64> = RefCell::new(0);
-}
-
-fn init_rng_if_needed() {
-    RNG_STATE.with(|s| {
-        let mut state = s.borrow_mut();
-        if *state == 0 {
-            // Seed from js_sys::Math::random once
-            let seed = (js_sys::Math::random() * u64::MAX as f64) as u64;
-            *state = if seed == 0 { 1 } else { seed };
-        }
-    });
-}
+use crate::state::{Particle, ParticleKind, Explosion, DamageKind, DamageNumber, HitMarker, MobSpeech, PickupPull, GameState};
+use crate::pool::Pool;
+use crate::rng::{self, Stream};
 
 fn fast_random() -> f64 {
-    RNG_STATE.with(|s| {
-        let mut state = s.borrow_mut();
-        if *state == 0 { *state = 1; }
-        *state ^= *state << 13;
-        *state ^= *state >> 7;
-        *state ^= *state << 17;
-        (*state % 10000) as f64 / 10000.0
-    })
+    rng::random(Stream::Effects)
 }
 
 thread_local! {
-    static PARTICLE_GLOWS: RefCell<HashMap<String, HtmlCanvasElement>> = RefCell::new(HashMap::new());
+    static PARTICLE_GLOWS: RefCell<HashMap<&'static str, HtmlCanvasElement>> = RefCell::new(HashMap::new());
     static FLASH_SPRITE: RefCell<Option<HtmlCanvasElement>> = RefCell::new(None);
     static FILL_SPRITE: RefCell<Option<HtmlCanvasElement>> = RefCell::new(None);
 }
 
-fn get_particle_glow(color: &str) -> HtmlCanvasElement {
+fn get_particle_glow(color: &'static str) -> HtmlCanvasElement {
     PARTICLE_GLOWS.with(|pg| {
         let mut sprites = pg.borrow_mut();
         if let Some(canvas) = sprites.get(color) {
@@ -65,7 +41,7 @@ fn get_particle_glow(color: &str) -> HtmlCanvasElement {
             ctx.fill_rect(0.0, 0.0, size as f64, size as f64);
         }
 
-        sprites.insert(color.to_string(), canvas.clone());
+        sprites.insert(color, canvas.clone());
         canvas
     })
 }
@@ -128,126 +104,172 @@ fn get_fill_sprite() -> HtmlCanvasElement {
     })
 }
 
+/// Streaks thrown out behind a ship dashing toward angle a
+pub fn add_dash(particles: &mut Pool<Particle>, limit: usize, x: f64, y: f64, a: f64) {
+    let colors = ["#ffffff", "#aaeeff", "#66ccff"];
+    for _ in 0..14 {
+        if particles.len() >= limit { break; }
+        let angle = a + std::f64::consts::PI + (fast_random() - 0.5) * 0.7;
+        let spd = 120.0 + fast_random() * 260.0;
+        let life = 0.2 + fast_random() * 0.25;
+        let ci = (fast_random() * colors.len() as f64) as usize;
+        *particles.spawn() = Particle {
+            x: x + (fast_random() - 0.5) * 10.0,
+            y: y + (fast_random() - 0.5) * 10.0,
+            vx: angle.cos() * spd,
+            vy: angle.sin() * spd,
+            life, max_life: life,
+            size: 2.0 + fast_random() * 3.0,
+            color: colors[ci % colors.len()],
+            kind: ParticleKind::Explosion,
+        };
+    }
+}
+
 pub fn add_explosion(
-    particles: &mut Vec<Particle>,
-    explosions: &mut Vec<Explosion>,
+    particles: &mut Pool<Particle>,
+    limit: usize,
+    explosions: &mut Pool<Explosion>,
     x: f64, y: f64,
 ) {
-    init_rng_if_needed();
     // Hot core particles - bright white/yellow, fast but short-lived
     let core_colors = ["#ffffff", "#ffffcc", "#ffeeaa"];
     for _ in 0..5 {
-        if particles.len() >= MAX_PARTICLES { break; }
+        if particles.len() >= limit { break; }
         let angle = fast_random() * std::f64::consts::PI * 2.0;
         let spd = 40.0 + fast_random() * 120.0;
         let life = 0.15 + fast_random() * 0.2;
         let ci = (fast_random() * core_colors.len() as f64) as usize;
-        particles.push(Particle {
+        *particles.spawn() = Particle {
             x: x + (fast_random() - 0.5) * 4.0,
             y: y + (fast_random() - 0.5) * 4.0,
             vx: angle.cos() * spd,
             vy: angle.sin() * spd,
             life, max_life: life,
             size: 4.0 + fast_random() * 4.0,
-            color: core_colors[ci % core_colors.len()].to_string(),
+            color: core_colors[ci % core_colors.len()],
             kind: ParticleKind::Explosion,
-        });
+        };
     }
 
     // Fire particles - orange/red, medium speed
     let fire_colors = ["#ff4400", "#ff6600", "#ff8800", "#ffaa00", "#ff2200"];
     for i in 0..12 {
-        if particles.len() >= MAX_PARTICLES { break; }
+        if particles.len() >= limit { break; }
         let angle = (std::f64::consts::PI * 2.0 * i as f64) / 12.0
             + (fast_random() - 0.5) * 0.8;
         let spd = 80.0 + fast_random() * 250.0;
         let life = 0.4 + fast_random() * 0.6;
         let ci = (fast_random() * fire_colors.len() as f64) as usize;
-        particles.push(Particle {
+        *particles.spawn() = Particle {
             x: x + (fast_random() - 0.5) * 8.0,
             y: y + (fast_random() - 0.5) * 8.0,
             vx: angle.cos() * spd,
             vy: angle.sin() * spd,
             life, max_life: life,
             size: 3.0 + fast_random() * 5.0,
-            color: fire_colors[ci % fire_colors.len()].to_string(),
+            color: fire_colors[ci % fire_colors.len()],
             kind: ParticleKind::Explosion,
-        });
+        };
     }
 
     // Smoke/ember particles - dark red/grey, slow, long-lived
     let smoke_colors = ["#882200", "#664422", "#553311", "#aa4400"];
     for _ in 0..8 {
-        if particles.len() >= MAX_PARTICLES { break; }
+        if particles.len() >= limit { break; }
         let angle = fast_random() * std::f64::consts::PI * 2.0;
         let spd = 20.0 + fast_random() * 80.0;
         let life = 0.8 + fast_random() * 1.0;
         let ci = (fast_random() * smoke_colors.len() as f64) as usize;
-        particles.push(Particle {
+        *particles.spawn() = Particle {
             x: x + (fast_random() - 0.5) * 12.0,
             y: y + (fast_random() - 0.5) * 12.0,
             vx: angle.cos() * spd,
             vy: angle.sin() * spd,
             life, max_life: life,
             size: 4.0 + fast_random() * 6.0,
-            color: smoke_colors[ci % smoke_colors.len()].to_string(),
+            color: smoke_colors[ci % smoke_colors.len()],
             kind: ParticleKind::Explosion,
-        });
+        };
     }
 
     // Primary shockwave - fast expanding
-    explosions.push(Explosion {
+    *explosions.spawn() = Explosion {
         x, y,
         radius: 0.0,
         max_radius: 90.0,
         life: 0.35,
         max_life: 0.35,
-    });
+    };
 
     // Secondary shockwave - slower, wider
-    explosions.push(Explosion {
+    *explosions.spawn() = Explosion {
         x, y,
         radius: 0.0,
         max_radius: 50.0,
         life: 0.5,
         max_life: 0.5,
-    });
+    };
 }
 
-pub fn update_particles(particles: &mut Vec<Particle>, explosions: &mut Vec<Explosion>, dt: f64) {
-    let mut i = 0;
-    while i < particles.len() {
-        let p = &mut particles[i];
+pub fn update_particles(particles: &mut Pool<Particle>, explosions: &mut Pool<Explosion>, dt: f64) {
+    particles.retain_mut(|p| {
         p.x += p.vx * dt;
         p.y += p.vy * dt;
         p.life -= dt;
         p.vx *= 0.98;
         p.vy *= 0.98;
+        p.life > 0.0
+    });
 
-        if p.life <= 0.0 {
-            particles.swap_remove(i);
-        } else {
-            i += 1;
-        }
-    }
-
-    let mut j = 0;
-    while j < explosions.len() {
-        let e = &mut explosions[j];
+    explosions.retain_mut(|e| {
         e.life -= dt;
         e.radius = e.max_radius * (1.0 - e.life / e.max_life);
-        if e.life <= 0.0 {
-            explosions.swap_remove(j);
-        } else {
-            j += 1;
-        }
+        e.life > 0.0
+    });
+}
+
+/// Rock chips and a dust puff where an asteroid broke apart
+pub fn add_asteroid_burst(particles: &mut Pool<Particle>, limit: usize, x: f64, y: f64) {
+    let rock_colors = ["#8a7a66", "#6e6050", "#a39480", "#5a4e42"];
+    for i in 0..16 {
+        if particles.len() >= limit { break; }
+        let angle = (std::f64::consts::PI * 2.0 * i as f64) / 16.0 + (fast_random() - 0.5) * 0.6;
+        let spd = 60.0 + fast_random() * 200.0;
+        let life = 0.6 + fast_random() * 0.6;
+        let ci = (fast_random() * rock_colors.len() as f64) as usize;
+        *particles.spawn() = Particle {
+            x: x + angle.cos() * 20.0,
+            y: y + angle.sin() * 20.0,
+            vx: angle.cos() * spd,
+            vy: angle.sin() * spd,
+            life, max_life: life,
+            size: 2.0 + fast_random() * 4.0,
+            color: rock_colors[ci % rock_colors.len()],
+            kind: ParticleKind::Shard,
+        };
+    }
+    for _ in 0..6 {
+        if particles.len() >= limit { break; }
+        let angle = fast_random() * std::f64::consts::PI * 2.0;
+        let spd = 20.0 + fast_random() * 50.0;
+        let life = 0.5 + fast_random() * 0.4;
+        *particles.spawn() = Particle {
+            x: x + (fast_random() - 0.5) * 30.0,
+            y: y + (fast_random() - 0.5) * 30.0,
+            vx: angle.cos() * spd,
+            vy: angle.sin() * spd,
+            life, max_life: life,
+            size: 10.0 + fast_random() * 8.0,
+            color: "#6e6050",
+            kind: ParticleKind::Explosion,
+        };
     }
 }
 
 /// Draw a glowing engine beam behind a ship (Star Wars style thrust)
 pub fn draw_engine_beam(ctx: &CanvasRenderingContext2d, sx: f64, sy: f64, rotation: f64, speed: f64, ship_type: i32, boosting: bool) {
     if speed < 15.0 && !boosting { return; }
-    init_rng_if_needed();
 
     let mut intensity = ((speed - 15.0) / 200.0).min(1.0).max(0.0);
     let boost_mul = if boosting { 2.0 } else { 1.0 };
@@ -290,15 +312,7 @@ pub fn draw_engine_beam(ctx: &CanvasRenderingContext2d, sx: f64, sy: f64, rotati
     ctx.line_to(tip_x, tip_y);
     ctx.close_path();
 
-    let idx = (ship_type as usize).min(SHIP_COLORS.len() - 1);
-    let glow_color = match idx {
-        0 => "rgba(255, 100, 50, 0.6)",
-        1 => "rgba(50, 150, 255, 0.6)",
-        2 => "rgba(50, 255, 100, 0.6)",
-        3 => "rgba(50, 255, 100, 0.6)",  // Star Destroyer
-        4 | 5 => "rgba(100, 100, 255, 0.6)", // TIE fighters
-        _ => "rgba(255, 200, 50, 0.6)",
-    };
+    let glow_color = crate::palette::engine(ship_type);
     ctx.set_fill_style_str(glow_color);
     ctx.fill();
 
@@ -310,7 +324,7 @@ pub fn draw_engine_beam(ctx: &CanvasRenderingContext2d, sx: f64, sy: f64, rotati
     ctx.line_to(tip_x, tip_y);
     ctx.close_path();
 
-    let core_color = match idx {
+    let core_color = match ship_type {
         0 => "rgba(255, 180, 120, 0.8)",
         1 => "rgba(150, 200, 255, 0.8)",
         2 => "rgba(150, 255, 180, 0.8)",
@@ -337,15 +351,15 @@ pub fn render_particles(ctx: &CanvasRenderingContext2d, particles: &[Particle],
 
             // Draw soft glow with cached sprite
             ctx.set_global_alpha(alpha * 0.9);
-            let glow = get_particle_glow(&p.color);
+            let glow = get_particle_glow(p.color);
             let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(
                 &glow, sx - size, sy - size, size * 2.0, size * 2.0,
             );
         } else {
-            // Engine particles: simple dots that shrink
+            // Engine particles and shards: simple dots that shrink
             let size = p.size * t;
             ctx.set_global_alpha(t);
-            ctx.set_fill_style_str(&p.color);
+            ctx.set_fill_style_str(p.color);
             if size < 3.0 {
                 ctx.fill_rect(sx - size, sy - size, size * 2.0, size * 2.0);
             } else {
@@ -358,10 +372,25 @@ pub fn render_particles(ctx: &CanvasRenderingContext2d, particles: &[Particle],
     ctx.set_global_alpha(1.0);
 }
 
+/// Advance shake, particles, damage numbers and hit markers by one fixed step
+pub fn step(s: &mut GameState, dt: f64) {
+    update_shake(s, dt);
+    update_particles(&mut s.particles, &mut s.explosions, dt);
+    update_damage_numbers(&mut s.damage_numbers, dt);
+    update_hit_markers(&mut s.hit_markers, dt);
+    s.heal_glow = (s.heal_glow - dt).max(0.0);
+    s.pickup_pulls.retain_mut(|p| {
+        p.life -= dt;
+        p.life > 0.0
+    });
+}
+
 // --- Screen Shake ---
 
 pub fn trigger_shake(state: &mut GameState, intensity: f64) {
-    state.shake_intensity = (state.shake_intensity + intensity).min(20.0);
+    if !state.settings.screen_shake || state.settings.reduced_motion { return; }
+    let intensity = intensity * state.settings.shake_scale;
+    state.shake_intensity = (state.shake_intensity + intensity).min(20.0 * state.settings.shake_scale);
     state.shake_decay = state.shake_intensity;
 }
 
@@ -371,7 +400,6 @@ pub fn update_shake(state: &mut GameState, dt: f64) {
         state.shake_y = 0.0;
         return;
     }
-    init_rng_if_needed();
     let angle = fast_random() * std::f64::consts::PI * 2.0;
     state.shake_x = angle.cos() * state.shake_intensity;
     state.shake_y = angle.sin() * state.shake_intensity;
@@ -386,35 +414,76 @@ pub fn update_shake(state: &mut GameState, dt: f64) {
 // --- Damage Numbers ---
 
 const MAX_DAMAGE_NUMBERS: usize = 30;
+const DAMAGE_STACK_WINDOW: f64 = 0.3; // seconds after a hit that the next one still adds to it
+
+fn write_damage_text(dn: &mut DamageNumber) {
+    use std::fmt::Write;
+    dn.text.clear();
+    let _ = match dn.kind {
+        DamageKind::Hit => write!(dn.text, "-{}", dn.total),
+        DamageKind::Crit => write!(dn.text, "-{}!", dn.total),
+        DamageKind::Heal => write!(dn.text, "+{} HP", dn.total),
+    };
+}
 
-pub fn add_damage_number(state: &mut GameState, x: f64, y: f64, dmg: i32, is_heal: bool) {
-    init_rng_if_needed();
-    if state.damage_numbers.len() >= MAX_DAMAGE_NUMBERS {
-        state.damage_numbers.remove(0);
-    }
-    state.damage_numbers.push(DamageNumber {
-        x,
-        y,
-        text: if is_heal { format!("+{}", dmg) } else { format!("-{}", dmg) },
-        color: if is_heal { "#44ff44" } else { "#ff4444" },
-        life: 1.0,
-        max_life: 1.0,
-        vy: -60.0,
-        offset_x: (fast_random() - 0.5) * 20.0,
-    });
+/// Fold a hit into a recent number of the same kind for the same attacker and
+/// victim, unless that one already holds `cap` hits. Returns whether it was folded in.
+fn stack_damage(numbers: &mut Pool<DamageNumber>, attacker: u32, victim: u32, (x, y): (f64, f64), dmg: i32, kind: DamageKind, cap: u32) -> bool {
+    let Some(dn) = numbers.iter_mut().find(|dn| dn.attacker == attacker && dn.victim == victim && dn.kind == kind
+        && dn.since_hit < DAMAGE_STACK_WINDOW && dn.hits < cap) else { return false };
+    dn.total += dmg;
+    dn.hits += 1;
+    dn.since_hit = 0.0;
+    write_damage_text(dn);
+    // Restart over the victim so the number doesn't float off mid-burst
+    dn.x = x;
+    dn.y = y;
+    dn.life = dn.max_life;
+    true
 }
 
-pub fn update_damage_numbers(numbers: &mut Vec<DamageNumber>, dt: f64) {
-    let mut i = 0;
-    while i < numbers.len() {
-        numbers[i].life -= dt;
-        numbers[i].y += numbers[i].vy * dt;
-        if numbers[i].life <= 0.0 {
-            numbers.swap_remove(i);
-        } else {
-            i += 1;
-        }
+/// Show a damage (or heal) number at a hit. Rapid hits from the same attacker
+/// on the same victim add up in one number, up to the damage stack setting.
+pub fn add_damage_number(state: &mut GameState, attacker: u32, victim: u32, x: f64, y: f64, dmg: i32, kind: DamageKind) {
+    let cap = state.settings.damage_stack;
+    if stack_damage(&mut state.damage_numbers, attacker, victim, (x, y), dmg, kind, cap) {
+        return;
     }
+    // When full, the number closest to fading out makes way
+    let dn = if state.damage_numbers.len() >= MAX_DAMAGE_NUMBERS {
+        match state.damage_numbers.iter_mut().min_by(|a, b| a.life.total_cmp(&b.life)) {
+            Some(dn) => dn,
+            None => return,
+        }
+    } else {
+        state.damage_numbers.spawn()
+    };
+    dn.x = x;
+    dn.y = y;
+    dn.attacker = attacker;
+    dn.victim = victim;
+    dn.total = dmg;
+    dn.hits = 1;
+    dn.since_hit = 0.0;
+    dn.kind = kind;
+    write_damage_text(dn);
+    dn.color = match kind {
+        DamageKind::Crit => crate::palette::crit(),
+        _ => crate::palette::heal_damage(kind == DamageKind::Heal),
+    };
+    dn.life = 1.0;
+    dn.max_life = 1.0;
+    dn.vy = -60.0;
+    dn.offset_x = (fast_random() - 0.5) * 20.0;
+}
+
+pub fn update_damage_numbers(numbers: &mut Pool<DamageNumber>, dt: f64) {
+    numbers.retain_mut(|dn| {
+        dn.life -= dt;
+        dn.since_hit += dt;
+        dn.y += dn.vy * dt;
+        dn.life > 0.0
+    });
 }
 
 pub fn render_damage_numbers(ctx: &CanvasRenderingContext2d, numbers: &[DamageNumber], offset_x: f64, offset_y: f64, vw: f64, vh: f64) {
@@ -425,8 +494,11 @@ pub fn render_damage_numbers(ctx: &CanvasRenderingContext2d, numbers: &[DamageNu
         if sx < -50.0 || sx > vw + 50.0 || sy < -50.0 || sy > vh + 50.0 { continue; }
 
         let alpha = (dn.life / dn.max_life).max(0.0);
-        let scale = 1.0 + (1.0 - alpha) * 0.3;
-        let font_size = (14.0 * scale) as i32;
+        // Stacked numbers grow with each hit folded in
+        let stack = (1.0 + (dn.hits.max(1) - 1) as f64 * 0.08).min(1.6);
+        let scale = (1.0 + (1.0 - alpha) * 0.3) * stack;
+        let base = if dn.kind == DamageKind::Crit { 20.0 } else { 14.0 };
+        let font_size = (base * scale) as i32;
 
         ctx.set_global_alpha(alpha);
         ctx.set_font(&format!("bold {}px monospace", font_size));
@@ -440,14 +512,89 @@ pub fn render_damage_numbers(ctx: &CanvasRenderingContext2d, numbers: &[DamageNu
     ctx.set_global_alpha(1.0);
 }
 
+// --- Heals ---
+
+const HEAL_GLOW: f64 = 0.8; // seconds
+const HEAL_GLOW_R: f64 = 55.0;
+
+/// HP our ship gained between two snapshots, given (alive, hp) before and
+/// after. Coming back from a respawn isn't a heal.
+pub fn heal_gain(before: Option<(bool, i32)>, after: (bool, i32)) -> Option<i32> {
+    match before {
+        Some((true, hp)) if after.0 && after.1 > hp => Some(after.1 - hp),
+        _ => None,
+    }
+}
+
+/// "+N" over our ship and a soft aura around it
+pub fn add_heal(state: &mut GameState, x: f64, y: f64, amount: i32) {
+    let me = state.my_id.unwrap_or(0);
+    add_damage_number(state, 0, me, x, y, amount, DamageKind::Heal);
+    state.heal_glow = HEAL_GLOW;
+}
+
+/// Aura under our ship while `heal_glow` runs down (screen coords)
+pub fn draw_heal_aura(ctx: &CanvasRenderingContext2d, sx: f64, sy: f64, heal_glow: f64) {
+    if heal_glow <= 0.0 { return; }
+    let glow = get_particle_glow(crate::palette::heal_damage(true));
+    let r = HEAL_GLOW_R * (1.0 + (1.0 - heal_glow / HEAL_GLOW) * 0.3);
+    ctx.set_global_alpha((heal_glow / HEAL_GLOW).min(1.0) * 0.7);
+    let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(&glow, sx - r, sy - r, r * 2.0, r * 2.0);
+    ctx.set_global_alpha(1.0);
+}
+
+// --- Pickups ---
+
+const PICKUP_PULL: f64 = 0.35; // seconds for a collected pickup to reach the ship
+// A pickup that vanishes this close to our ship was ours; the server collects
+// at PLAYER_RADIUS + PICKUP_RADIUS, plus slack for a snapshot's worth of movement
+const PICKUP_REACH: f64 = crate::constants::PLAYER_RADIUS + crate::constants::PICKUP_RADIUS + 30.0;
+
+/// Whether a pickup at `pk` that just disappeared was collected by us at `me`
+pub fn picked_up(pk: (f64, f64), me: (f64, f64)) -> bool {
+    (pk.0 - me.0).hypot(pk.1 - me.1) <= PICKUP_REACH
+}
+
+pub fn add_pickup_pull(state: &mut GameState, x: f64, y: f64) {
+    state.pickup_pulls.push(PickupPull { x, y, life: PICKUP_PULL });
+}
+
+/// Collected pickups sucked into our ship at (sx, sy), each ending in a flash
+/// ring around it (screen coords)
+pub fn draw_pickup_pulls(ctx: &CanvasRenderingContext2d, pulls: &[PickupPull], sx: f64, sy: f64, offset_x: f64, offset_y: f64) {
+    if pulls.is_empty() { return; }
+    let color = crate::palette::heal_damage(true);
+    let glow = get_particle_glow(color);
+    for p in pulls {
+        let t = 1.0 - p.life / PICKUP_PULL; // 0 at pickup, 1 at the ship
+        let k = t * t; // speeds up as it closes in
+        let (px, py) = (p.x - offset_x, p.y - offset_y);
+        let (ox, oy) = (px + (sx - px) * k, py + (sy - py) * k);
+        let r = 14.0 * (1.0 - t) + 4.0;
+        ctx.set_global_alpha(1.0 - t * 0.5);
+        let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(&glow, ox - r, oy - r, r * 2.0, r * 2.0);
+        if t > 0.6 {
+            let f = (t - 0.6) / 0.4;
+            ctx.set_global_alpha(1.0 - f);
+            ctx.set_stroke_style_str(color);
+            ctx.set_line_width(3.0);
+            ctx.begin_path();
+            let _ = ctx.arc(sx, sy, 30.0 + 25.0 * f, 0.0, std::f64::consts::PI * 2.0);
+            ctx.stroke();
+        }
+    }
+    ctx.set_global_alpha(1.0);
+}
+
 // --- Hit Markers (screen-space) ---
 
 const HIT_MARKER_DURATION: f64 = 0.25;
 
-pub fn add_hit_marker(state: &mut GameState) {
+pub fn add_hit_marker(state: &mut GameState, crit: bool) {
     state.hit_markers.push(HitMarker {
         life: HIT_MARKER_DURATION,
         max_life: HIT_MARKER_DURATION,
+        crit,
     });
 }
 
@@ -475,7 +622,7 @@ pub fn render_hit_markers(ctx: &CanvasRenderingContext2d, markers: &[HitMarker],
         let gap = 3.0;
 
         ctx.set_global_alpha(alpha);
-        ctx.set_stroke_style_str("#ffffff");
+        ctx.set_stroke_style_str(if hm.crit { crate::palette::crit() } else { "#ffffff" });
         ctx.set_line_width(2.5);
         ctx.begin_path();
         // Top-left to center
@@ -491,6 +638,19 @@ pub fn render_hit_markers(ctx: &CanvasRenderingContext2d, markers: &[HitMarker],
         ctx.move_to(cx + size, cy + size);
         ctx.line_to(cx + gap, cy + gap);
         ctx.stroke();
+
+        // Crits get a diamond around the X
+        if hm.crit {
+            let d = size * 1.6;
+            ctx.set_line_width(1.5);
+            ctx.begin_path();
+            ctx.move_to(cx, cy - d);
+            ctx.line_to(cx + d, cy);
+            ctx.line_to(cx, cy + d);
+            ctx.line_to(cx - d, cy);
+            ctx.close_path();
+            ctx.stroke();
+        }
     }
     ctx.set_global_alpha(1.0);
 }
@@ -499,7 +659,7 @@ pub fn render_hit_markers(ctx: &CanvasRenderingContext2d, markers: &[HitMarker],
 
 const MOB_SPEECH_DURATION: f64 = 3000.0; // 3 seconds in ms
 
-pub fn add_mob_speech(state: &mut GameState, mob_id: String, text: String) {
+pub fn add_mob_speech(state: &mut GameState, mob_id: u32, text: String) {
     let now = js_sys::Date::now();
     // Remove existing speech for this mob
     state.mob_speech.retain(|s| s.mob_id != mob_id);
@@ -510,7 +670,7 @@ pub fn add_mob_speech(state: &mut GameState, mob_id: String, text: String) {
     });
 }
 
-pub fn render_mob_speech(ctx: &CanvasRenderingContext2d, speech: &[MobSpeech], mobs: &std::collections::HashMap<String, crate::protocol::MobState>, offset_x: f64, offset_y: f64, vw: f64, vh: f64) {
+pub fn render_mob_speech(ctx: &CanvasRenderingContext2d, speech: &[MobSpeech], mobs: &std::collections::HashMap<u32, crate::protocol::MobState>, offset_x: f64, offset_y: f64, vw: f64, vh: f64) {
     let now = js_sys::Date::now();
 
     for s in speech {
@@ -626,3 +786,6 @@ pub fn render_explosions(ctx: &CanvasRenderingContext2d, explosions: &[Explosion
     }
     ctx.set_global_alpha(1.0);
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,69 @@
+use super::*;
+
+fn hit(numbers: &mut Pool<DamageNumber>, attacker: u32, victim: u32, dmg: i32, cap: u32) -> bool {
+    stack_damage(numbers, attacker, victim, (100.0, 200.0), dmg, DamageKind::Hit, cap)
+}
+
+fn first(attacker: u32, victim: u32, dmg: i32) -> Pool<DamageNumber> {
+    let mut numbers = Pool::with_capacity(4);
+    let dn = numbers.spawn();
+    *dn = DamageNumber {
+        attacker, victim, total: dmg, hits: 1, life: 1.0, max_life: 1.0, ..Default::default()
+    };
+    numbers
+}
+
+#[test]
+fn rapid_hits_add_up() {
+    let mut numbers = first(3, 4, 10);
+    assert!(hit(&mut numbers, 3, 4, 10, 10));
+    assert!(hit(&mut numbers, 3, 4, 15, 10));
+    let dn = &numbers.as_slice()[0];
+    assert_eq!((dn.total, dn.hits, dn.text.as_str()), (35, 3, "-35"));
+}
+
+#[test]
+fn other_pairs_and_stale_numbers_get_their_own() {
+    let mut numbers = first(3, 4, 10);
+    assert!(!hit(&mut numbers, 5, 4, 10, 10)); // another attacker
+    assert!(!hit(&mut numbers, 3, 6, 10, 10)); // another victim
+
+    update_damage_numbers(&mut numbers, DAMAGE_STACK_WINDOW + 0.01);
+    assert!(!hit(&mut numbers, 3, 4, 10, 10));
+}
+
+#[test]
+fn stacks_stop_at_the_cap() {
+    let mut numbers = first(3, 4, 10);
+    assert!(hit(&mut numbers, 3, 4, 10, 2));
+    assert!(!hit(&mut numbers, 3, 4, 10, 2));
+    // A cap of 1 shows every hit on its own
+    let mut numbers = first(3, 4, 10);
+    assert!(!hit(&mut numbers, 3, 4, 10, 1));
+}
+
+#[test]
+fn crits_stack_apart_from_normal_hits() {
+    let mut numbers = first(3, 4, 10);
+    assert!(!stack_damage(&mut numbers, 3, 4, (0.0, 0.0), 30, DamageKind::Crit, 10));
+    let mut dn = numbers.as_slice()[0].clone();
+    dn.kind = DamageKind::Crit;
+    dn.total = 60;
+    write_damage_text(&mut dn);
+    assert_eq!(dn.text, "-60!");
+}
+
+#[test]
+fn heals_count_only_while_alive() {
+    assert_eq!(heal_gain(Some((true, 40)), (true, 70)), Some(30));
+    assert_eq!(heal_gain(Some((true, 70)), (true, 50)), None);
+    // Respawning refills HP but isn't a heal
+    assert_eq!(heal_gain(Some((false, 0)), (true, 100)), None);
+    assert_eq!(heal_gain(None, (true, 100)), None);
+}
+
+#[test]
+fn pickups_count_as_ours_only_close_by() {
+    assert!(picked_up((100.0, 100.0), (130.0, 100.0)));
+    assert!(!picked_up((100.0, 100.0), (400.0, 100.0)));
+}
@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use wasm_bindgen::JsCast;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 use crate::constants::{WORLD_W, WORLD_H};
@@ -6,6 +6,11 @@ use crate::constants::{WORLD_W, WORLD_H};
 thread_local! {
     static FOG_CANVAS: RefCell<Option<HtmlCanvasElement>> = RefCell::new(None);
     static FOG_BUILT: RefCell<bool> = RefCell::new(false);
+    static ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+fn rand() -> f64 {
+    crate::rng::random(crate::rng::Stream::Fog)
 }
 
 fn build_fog_canvas() {
@@ -31,10 +36,10 @@ fn build_fog_canvas() {
     ];
 
     for _ in 0..12 {
-        let x = js_sys::Math::random() * sw as f64;
-        let y = js_sys::Math::random() * sh as f64;
-        let r = 100.0 + js_sys::Math::random() * 300.0;
-        let color_idx = (js_sys::Math::random() * fog_colors.len() as f64) as usize % fog_colors.len();
+        let x = rand() * sw as f64;
+        let y = rand() * sh as f64;
+        let r = 100.0 + rand() * 300.0;
+        let color_idx = (rand() * fog_colors.len() as f64) as usize % fog_colors.len();
 
         if let Ok(gradient) = ctx.create_radial_gradient(x, y, 0.0, x, y, r) {
             let _ = gradient.add_color_stop(0.0_f32, fog_colors[color_idx]);
@@ -48,7 +53,18 @@ fn build_fog_canvas() {
     FOG_BUILT.with(|fb| *fb.borrow_mut() = true);
 }
 
+/// Redraw the fog patches on the next frame (after a reseed)
+pub fn rebuild() {
+    FOG_BUILT.with(|fb| *fb.borrow_mut() = false);
+}
+
+/// Turn fog drawing on or off (the Low background preset skips it)
+pub fn set_enabled(on: bool) {
+    ENABLED.with(|e| e.set(on));
+}
+
 pub fn render_fog(ctx: &CanvasRenderingContext2d, offset_x: f64, offset_y: f64, _vw: f64, _vh: f64) {
+    if !ENABLED.with(|e| e.get()) { return; }
     let built = FOG_BUILT.with(|fb| *fb.borrow());
     if !built {
         build_fog_canvas();
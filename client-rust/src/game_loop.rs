@@ -1,18 +1,60 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use crate::state::{SharedState, Phase};
 use crate::renderer;
 
+/// Effects advance in steps of this many seconds whatever the display refresh rate
+const EFFECT_STEP: f64 = 1.0 / 60.0;
+/// Slack (ms) when honoring an FPS cap, so a 60 cap on a 60 Hz display doesn't drop frames to jitter
+const CAP_SLACK: f64 = 1.5;
+
+type FrameFn = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+
+thread_local! {
+    static FRAME: RefCell<Option<FrameFn>> = const { RefCell::new(None) };
+    static RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+fn request_frame(f: &FrameFn) {
+    let window = web_sys::window().unwrap();
+    let _ = window.request_animation_frame(
+        f.borrow().as_ref().unwrap().as_ref().unchecked_ref()
+    );
+}
+
+/// Restart the frame loop after the tab was hidden
+pub fn wake() {
+    if RUNNING.with(|r| r.replace(true)) { return; }
+    FRAME.with(|f| {
+        if let Some(f) = f.borrow().as_ref() {
+            request_frame(f);
+        }
+    });
+}
+
 pub fn start_game_loop(state: SharedState) {
-    let f: Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>> = Rc::new(RefCell::new(None));
+    let f: FrameFn = Rc::new(RefCell::new(None));
     let g = f.clone();
 
     let last_time = Rc::new(RefCell::new(0.0_f64));
+    let mut effect_acc = 0.0;
 
     *g.borrow_mut() = Some(Closure::wrap(Box::new(move |timestamp: f64| {
+        // Hidden tab: stop asking for frames until wake()
+        if state.borrow().tab_hidden {
+            RUNNING.with(|r| r.set(false));
+            *last_time.borrow_mut() = 0.0;
+            return;
+        }
         let mut lt = last_time.borrow_mut();
+        let cap = state.borrow().settings.fps_cap;
+        if cap > 0 && *lt > 0.0 && timestamp - *lt < 1000.0 / cap as f64 - CAP_SLACK {
+            drop(lt);
+            request_frame(&f);
+            return;
+        }
         let dt = ((timestamp - *lt) / 1000.0).min(0.05);
         *lt = timestamp;
         drop(lt);
@@ -20,27 +62,40 @@ pub fn start_game_loop(state: SharedState) {
         {
             let s = state.borrow();
             if s.phase == Phase::Playing || s.phase == Phase::Dead {
+                let (replaying, practicing) = (s.replay_mode, s.practice_mode);
                 drop(s);
+                if replaying {
+                    crate::replay::advance(dt);
+                }
+                if practicing {
+                    crate::practice::advance(dt);
+                }
+                effect_acc += dt;
+                while effect_acc >= EFFECT_STEP {
+                    crate::effects::step(&mut state.borrow_mut(), EFFECT_STEP);
+                    effect_acc -= EFFECT_STEP;
+                }
                 renderer::render(&state, dt);
+                crate::clip::composite();
             } else if s.phase == Phase::Lobby {
                 let w = s.screen_w;
                 let h = s.screen_h;
+                let render_scale = s.settings.render_scale;
+                let still = s.settings.reduced_motion;
                 drop(s);
                 if let Some(ctx) = crate::canvas::get_canvas_context("bgCanvas") {
-                    crate::hyperspace::render_hyperspace(&ctx, w, h, dt);
+                    crate::canvas::apply_render_scale(&ctx, render_scale);
+                    crate::hyperspace::render_hyperspace(&ctx, w, h, if still { 0.0 } else { dt }, still);
+                    crate::warmup::render(&ctx, w, h, dt);
                 }
             }
         }
 
         // Request next frame
-        let window = web_sys::window().unwrap();
-        let _ = window.request_animation_frame(
-            f.borrow().as_ref().unwrap().as_ref().unchecked_ref()
-        );
+        request_frame(&f);
     }) as Box<dyn FnMut(f64)>));
 
-    let window = web_sys::window().unwrap();
-    let _ = window.request_animation_frame(
-        g.borrow().as_ref().unwrap().as_ref().unchecked_ref()
-    );
+    RUNNING.with(|r| r.set(true));
+    request_frame(&g);
+    FRAME.with(|frame| *frame.borrow_mut() = Some(g));
 }
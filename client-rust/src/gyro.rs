@@ -0,0 +1,74 @@
+// Tilt steering for the phone controller: DeviceOrientation readings turned
+// into the same thumb offset the virtual joystick produces, relative to a
+// neutral pose captured by the Calibrate button (or the first reading).
+
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use crate::settings::Settings;
+
+const PX_PER_DEG: f64 = 2.5; // joystick px per degree of tilt at 1x sensitivity
+const MAX_TILT: f64 = 30.0;  // degrees; tilting further than this adds nothing
+
+thread_local! {
+    static LISTENING: Cell<bool> = const { Cell::new(false) };
+    static TILT: Cell<Option<(f64, f64)>> = const { Cell::new(None) }; // screen-relative (x, y) degrees
+    static NEUTRAL: Cell<Option<(f64, f64)>> = const { Cell::new(None) };
+}
+
+/// Degrees the screen is rotated from the device's natural portrait pose
+fn screen_angle() -> f64 {
+    let window = web_sys::window().unwrap();
+    window.screen().ok()
+        .map(|s| s.orientation().angle().unwrap_or(0) as f64)
+        .unwrap_or(0.0)
+}
+
+/// beta (front/back) and gamma (left/right) rotated into screen axes, so +x
+/// tilts toward the right edge and +y toward the bottom edge as the player sees it
+fn to_screen(beta: f64, gamma: f64) -> (f64, f64) {
+    match screen_angle().rem_euclid(360.0) as i32 {
+        90 => (beta, -gamma),
+        180 => (-gamma, -beta),
+        270 => (-beta, gamma),
+        _ => (gamma, beta),
+    }
+}
+
+/// Start listening for orientation events. On iOS this must run inside a tap,
+/// since that's the only place Safari will grant motion access.
+pub fn enable() {
+    if LISTENING.with(|l| l.replace(true)) { return; }
+    let on_orient = Closure::wrap(Box::new(move |e: web_sys::DeviceOrientationEvent| {
+        let (Some(beta), Some(gamma)) = (e.beta(), e.gamma()) else { return };
+        let tilt = to_screen(beta, gamma);
+        TILT.with(|t| t.set(Some(tilt)));
+        NEUTRAL.with(|n| if n.get().is_none() { n.set(Some(tilt)) });
+    }) as Box<dyn FnMut(web_sys::DeviceOrientationEvent)>);
+    let window = web_sys::window().unwrap();
+    let _ = window.add_event_listener_with_callback("deviceorientation", on_orient.as_ref().unchecked_ref());
+    on_orient.forget();
+
+    // iOS 13+: DeviceOrientationEvent.requestPermission()
+    let ctor = js_sys::Reflect::get(&window, &"DeviceOrientationEvent".into()).unwrap_or(JsValue::UNDEFINED);
+    if let Ok(request) = js_sys::Reflect::get(&ctor, &"requestPermission".into())
+        .and_then(|f| f.dyn_into::<js_sys::Function>())
+    {
+        let _ = request.call0(&ctor);
+    }
+}
+
+/// Take the current pose as "stick centered"
+pub fn calibrate() {
+    enable();
+    NEUTRAL.with(|n| n.set(TILT.with(|t| t.get())));
+}
+
+/// Tilt away from the neutral pose as a joystick offset in px; None until a reading arrives
+pub fn offset(settings: &Settings) -> Option<(f64, f64)> {
+    let (x, y) = TILT.with(|t| t.get())?;
+    let (nx, ny) = NEUTRAL.with(|n| n.get()).unwrap_or((x, y));
+    let k = PX_PER_DEG * settings.gyro_sensitivity;
+    let clamp = |d: f64| d.clamp(-MAX_TILT, MAX_TILT) * k;
+    Some((clamp(x - nx), clamp(y - ny)))
+}
@@ -1,14 +1,22 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use web_sys::CanvasRenderingContext2d;
-use crate::state::{SharedState, Phase};
-use crate::constants::{SHIP_COLORS, WORLD_W, WORLD_H};
+use crate::state::{SharedState, Phase, KillFeedEntry, DeathInfo};
+use crate::palette;
+use crate::constants::{WORLD_W, WORLD_H, HEARTBEAT_UNSTABLE};
+use crate::i18n::{self, t, tf, Msg};
+
+const KILL_FEED_LEN: usize = 5;
 
 thread_local! {
     static TEXT_WIDTH_CACHE: RefCell<HashMap<String, f64>> = RefCell::new(HashMap::new());
     static CACHED_FONT_SIZE: RefCell<i32> = RefCell::new(0);
     /// Cached sorted scoreboard: (tick, sorted player list)
     static SCOREBOARD_CACHE: RefCell<(u64, Vec<crate::protocol::PlayerState>)> = RefCell::new((0, Vec::new()));
+    /// Hash of what the HUD canvas currently shows
+    static LAYER_KEY: Cell<u64> = const { Cell::new(0) };
 }
 
 fn cached_measure_text(ctx: &CanvasRenderingContext2d, text: &str, font_size: i32) -> f64 {
@@ -32,57 +40,211 @@ fn cached_measure_text(ctx: &CanvasRenderingContext2d, text: &str, font_size: i3
     })
 }
 
-pub fn render_hud(ctx: &CanvasRenderingContext2d, state: &SharedState) {
+/// Per-frame overlays on the game canvas, plus the text-heavy panels on the HUD
+/// canvas, which is only repainted when what it shows changes
+pub fn render_hud(ctx: &CanvasRenderingContext2d, hud_ctx: &CanvasRenderingContext2d, state: &SharedState) {
     let s = state.borrow();
     let screen_w = s.screen_w;
     let screen_h = s.screen_h;
 
-    // Health bar
-    if let Some(my_id) = &s.my_id {
-        if let Some(me) = s.players.get(my_id) {
-            if me.a {
-                let min_dim = screen_w.min(screen_h);
-                let bar_w = (min_dim * 0.28).max(120.0).min(200.0);
-                draw_health_bar(ctx, screen_w / 2.0, screen_h - 40.0, bar_w, 16.0, me.hp, me.mhp);
-            }
+    // Minimap
+    draw_minimap(ctx, &s, screen_w, screen_h);
+
+    // Tutorial hint and highlight
+    crate::tutorial::render(ctx, &s, screen_w, screen_h);
+
+    // Crosshair
+    if s.phase == Phase::Playing && !s.is_mobile && !s.controller_attached && s.coach_target.is_none() {
+        draw_crosshair(ctx, s.mouse_x, s.mouse_y);
+    }
+
+    // Mobile controls overlay (joystick only, no visual markers for fire/boost zones)
+    if s.is_mobile && (s.phase == Phase::Playing || s.phase == Phase::Dead) {
+        if let Some(ref tj) = s.touch_joystick {
+            draw_mobile_joystick(ctx, tj.start_x, tj.start_y, tj.current_x, tj.current_y);
         }
     }
 
-    // Minimap
-    draw_minimap(ctx, &s, screen_w, screen_h);
+    // Coach status
+    crate::coach::render_badge(ctx, &s);
+
+    // Clip recording indicator
+    if crate::clip::is_recording() {
+        draw_rec_indicator(ctx);
+    }
+
+    let max_players = if screen_w.min(screen_h) < 500.0 { 5 } else { 8 };
+    refresh_scoreboard(&s, max_players);
+    let key = layer_key(&s);
+    if LAYER_KEY.with(|k| k.replace(key)) != key {
+        hud_ctx.clear_rect(0.0, 0.0, screen_w, screen_h);
+        render_layer(hud_ctx, &s, screen_w, screen_h);
+    }
+}
+
+/// Health, kill feed, scoreboard, death screen and connection warning
+fn render_layer(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, screen_w: f64, screen_h: f64) {
+    // Health bar
+    if let Some(me) = s.my_id.and_then(|id| s.players.get(&id)) {
+        if me.a {
+            let min_dim = screen_w.min(screen_h);
+            let bar_w = (min_dim * 0.28).max(120.0).min(200.0);
+            draw_health_bar(ctx, screen_w / 2.0, screen_h - 40.0, bar_w, 16.0, me.hp, me.mhp);
+        }
+    }
 
     // Kill feed
-    draw_kill_feed(ctx, &s, screen_w, screen_h);
+    draw_kill_feed(ctx, s, screen_w, screen_h);
 
     // Scoreboard
-    draw_scoreboard(ctx, &s, screen_w, screen_h);
+    draw_scoreboard(ctx, s, screen_w, screen_h);
 
     // Death screen
     if s.phase == Phase::Dead {
         if let Some(ref death_info) = s.death_info {
-            draw_death_screen(ctx, screen_w, screen_h, &death_info.killer_name);
+            draw_death_screen(ctx, screen_w, screen_h, death_info, crate::spectate::label(s).as_deref());
         }
     }
 
-    // Crosshair
-    if s.phase == Phase::Playing && !s.is_mobile && !s.controller_attached {
-        draw_crosshair(ctx, s.mouse_x, s.mouse_y);
+    // Connection health (a full disconnect shows the reconnect banner instead)
+    if connection_unstable(s) {
+        ctx.set_fill_style_str("#ffaa00");
+        ctx.set_font("16px monospace");
+        ctx.set_text_align("center");
+        let _ = ctx.fill_text(t(Msg::ConnectionUnstable), screen_w / 2.0, 30.0);
     }
+}
 
-    // Mobile controls overlay (joystick only, no visual markers for fire/boost zones)
-    if s.is_mobile && (s.phase == Phase::Playing || s.phase == Phase::Dead) {
-        if let Some(ref tj) = s.touch_joystick {
-            draw_mobile_joystick(ctx, tj.start_x, tj.start_y, tj.current_x, tj.current_y);
+fn connection_unstable(s: &crate::state::GameState) -> bool {
+    s.connected && !s.replay_mode && !s.practice_mode && js_sys::Date::now() - s.last_rx > HEARTBEAT_UNSTABLE
+}
+
+/// Add a kill to the feed, dropping the oldest past KILL_FEED_LEN
+pub fn push_kill(feed: &mut Vec<KillFeedEntry>, entry: KillFeedEntry) {
+    feed.push(entry);
+    if feed.len() > KILL_FEED_LEN {
+        feed.drain(..feed.len() - KILL_FEED_LEN);
+    }
+}
+
+/// Kill feed entry opacity at `age` seconds, or None once it has faded out
+fn kill_alpha(age: f64) -> Option<f64> {
+    if age > 8.0 { return None; }
+    Some(if age > 6.0 { (8.0 - age) / 2.0 } else { 1.0 })
+}
+
+/// One piece of a kill feed line
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Text(&'a str, &'static str), // text, color
+    Icon(&'a str),               // kill cause
+}
+
+/// A kill feed line, left to right: "killer + assist <icon> victim". Known causes
+/// get an icon between the names, anything else falls back to " killed ".
+/// Asteroid deaths grey out the killer; suicides drop it and grey out the victim.
+fn kill_segments(kill: &KillFeedEntry) -> Vec<Segment<'_>> {
+    let icon = matches!(kill.cause.as_str(), "laser" | "ram" | "asteroid").then_some(Segment::Icon(&kill.cause));
+    if kill.suicide {
+        return icon.into_iter().chain([Segment::Text(&kill.victim, "#aaaaaa")]).collect();
+    }
+    let killer_color = if kill.environment { "#aaaaaa" } else { "#ffaa00" };
+    let mut segments = vec![Segment::Text(&kill.killer, killer_color)];
+    if !kill.assist.is_empty() {
+        segments.extend([Segment::Text(" + ", "#ffffff"), Segment::Text(&kill.assist, "#ffaa00")]);
+    }
+    segments.extend([
+        icon.unwrap_or(Segment::Text(t(Msg::Killed), "#ffffff")),
+        Segment::Text(&kill.victim, palette::danger()),
+    ]);
+    segments
+}
+
+/// Draw a cause icon ending at `right`, vertically centred on the text at
+/// baseline `y`; returns its width. Lasers point from killer to victim.
+fn draw_cause_icon(ctx: &CanvasRenderingContext2d, cause: &str, right: f64, y: f64, font_size: i32, rtl: bool) -> f64 {
+    let size = font_size as f64;
+    let w = size * 2.0;
+    let (cx, cy) = (right - w / 2.0, y - size * 0.35);
+    let dir = if rtl { -1.0 } else { 1.0 };
+    ctx.set_line_width((size / 8.0).max(1.0));
+    ctx.begin_path();
+    match cause {
+        "laser" => {
+            ctx.move_to(cx - size * 0.5 * dir, cy);
+            ctx.line_to(cx + size * 0.35 * dir, cy);
+            ctx.set_stroke_style_str("#ff5555");
+            ctx.stroke();
+            ctx.begin_path();
+            let _ = ctx.arc(cx + size * 0.45 * dir, cy, size * 0.12, 0.0, std::f64::consts::PI * 2.0);
+            ctx.set_fill_style_str("#ffdddd");
+            ctx.fill();
+        }
+        "ram" => {
+            // Two chevrons meeting in the middle
+            let (h, gap) = (size * 0.3, size * 0.1);
+            for side in [-1.0, 1.0] {
+                ctx.move_to(cx + side * (gap + h), cy - h);
+                ctx.line_to(cx + side * gap, cy);
+                ctx.line_to(cx + side * (gap + h), cy + h);
+            }
+            ctx.set_stroke_style_str("#ffaa00");
+            ctx.stroke();
+        }
+        _ => {
+            let _ = ctx.arc(cx, cy, size * 0.35, 0.0, std::f64::consts::PI * 2.0);
+            ctx.set_fill_style_str("#8a7a6a");
+            ctx.fill();
+            ctx.set_stroke_style_str("#c8b8a8");
+            ctx.stroke();
         }
     }
+    w
+}
 
-    // Connection status
-    if !s.connected {
-        ctx.set_fill_style_str("#ff4444");
-        ctx.set_font("16px monospace");
-        ctx.set_text_align("center");
-        let _ = ctx.fill_text("DISCONNECTED - Reconnecting...", screen_w / 2.0, 30.0);
+/// Everything the HUD layer draws, hashed; a repaint happens only when this changes.
+/// Kill feed fades are quantized so a fading line costs a handful of repaints.
+fn layer_key(s: &crate::state::GameState) -> u64 {
+    let mut h = DefaultHasher::new();
+    (s.screen_w.to_bits(), s.screen_h.to_bits(), s.settings.render_scale.to_bits()).hash(&mut h);
+    (i18n::lang() as u8, s.phase == Phase::Dead, connection_unstable(s)).hash(&mut h);
+    if let Some(me) = s.my_id.and_then(|id| s.players.get(&id)) {
+        (me.id, me.a, me.hp, me.mhp).hash(&mut h);
+    }
+    let now = web_sys::window().unwrap().performance().unwrap().now();
+    if s.phase == Phase::Dead {
+        s.death_info.as_ref().map(|d| d.killer_name.as_str()).hash(&mut h);
+        crate::spectate::label(s).hash(&mut h);
+        // The ring is redrawn in 40 steps over the wait
+        let countdown = s.death_info.as_ref().and_then(|d| d.respawn)
+            .and_then(|(due, total)| respawn_countdown(now, due, total));
+        countdown.map(|(secs, left)| (secs, (left * 40.0) as u8)).hash(&mut h);
+    }
+    for kill in &s.kill_feed {
+        if let Some(alpha) = kill_alpha((now - kill.time) / 1000.0) {
+            (kill.killer.as_str(), kill.assist.as_str(), kill.victim.as_str(), kill.cause.as_str(), (alpha * 10.0) as u8).hash(&mut h);
+        }
     }
+    SCOREBOARD_CACHE.with(|cache| {
+        for p in &cache.borrow().1 {
+            (p.id, p.sc, (p.kills, p.deaths, p.assists), s.pings.get(&p.id), p.s, s.name_of(p.id), s.my_id == Some(p.id)).hash(&mut h);
+        }
+    });
+    h.finish()
+}
+
+/// Re-sort the scoreboard only when tick changes (new server state arrived)
+fn refresh_scoreboard(s: &crate::state::GameState, max_players: usize) {
+    SCOREBOARD_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.0 != s.tick {
+            cache.1.clear();
+            cache.1.extend(s.players.values().cloned());
+            cache.1.sort_by(|a, b| b.sc.cmp(&a.sc).then_with(|| a.id.cmp(&b.id)));
+            cache.1.truncate(max_players);
+            cache.0 = s.tick;
+        }
+    });
 }
 
 fn draw_health_bar(ctx: &CanvasRenderingContext2d, x: f64, y: f64, w: f64, h: f64, hp: i32, max_hp: i32) {
@@ -91,7 +253,7 @@ fn draw_health_bar(ctx: &CanvasRenderingContext2d, x: f64, y: f64, w: f64, h: f6
     ctx.set_fill_style_str("rgba(0, 0, 0, 0.5)");
     ctx.fill_rect(x - w / 2.0 - 2.0, y - 2.0, w + 4.0, h + 4.0);
 
-    let color = if ratio > 0.6 { "#44ff44" } else if ratio > 0.3 { "#ffaa00" } else { "#ff4444" };
+    let color = palette::health(ratio);
     ctx.set_fill_style_str(color);
     ctx.fill_rect(x - w / 2.0, y, w * ratio, h);
 
@@ -105,12 +267,16 @@ fn draw_health_bar(ctx: &CanvasRenderingContext2d, x: f64, y: f64, w: f64, h: f6
     let _ = ctx.fill_text(&format!("{}/{}", hp, max_hp), x, y + h - 3.0);
 }
 
-fn draw_minimap(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, screen_w: f64, screen_h: f64) {
+/// Minimap position and edge length (px), top-right corner
+pub fn minimap_rect(screen_w: f64, screen_h: f64) -> (f64, f64, f64) {
     let min_dim = screen_w.min(screen_h);
     let size = (min_dim * 0.22).max(80.0).min(180.0);
     let margin = 10.0;
-    let x = screen_w - size - margin;
-    let y = margin;
+    (screen_w - size - margin, margin, size)
+}
+
+fn draw_minimap(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, screen_w: f64, screen_h: f64) {
+    let (x, y, size) = minimap_rect(screen_w, screen_h);
 
     ctx.set_fill_style_str("rgba(0, 40, 0, 0.5)");
     ctx.fill_rect(x, y, size, size);
@@ -119,18 +285,35 @@ fn draw_minimap(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, scr
     ctx.set_line_width(1.0);
     ctx.stroke_rect(x, y, size, size);
 
+    // Ghosts: last known positions of ships/mobs outside our interest area, fading out
+    let now = js_sys::Date::now();
+    for g in s.ghosts.values() {
+        let age = (now - g.time) / crate::constants::GHOST_TTL;
+        if age >= 1.0 { continue; }
+        let color = if g.mob {
+            palette::mob()
+        } else {
+            palette::ship(g.ship)
+        };
+        ctx.set_global_alpha(0.6 * (1.0 - age));
+        ctx.set_stroke_style_str(color);
+        ctx.begin_path();
+        let _ = ctx.arc(x + (g.x / WORLD_W) * size, y + (g.y / WORLD_H) * size, 2.0, 0.0, std::f64::consts::PI * 2.0);
+        ctx.stroke();
+    }
+    ctx.set_global_alpha(1.0);
+
     // Players
     for p in s.players.values() {
         if !p.a { continue; }
         let is_me = s.my_id.as_ref() == Some(&p.id);
-        let idx = (p.s as usize).min(SHIP_COLORS.len() - 1);
         let dot_x = x + (p.x / WORLD_W) * size;
         let dot_y = y + (p.y / WORLD_H) * size;
         let radius = if is_me { 3.0 } else { 2.0 };
 
         ctx.begin_path();
         let _ = ctx.arc(dot_x, dot_y, radius, 0.0, std::f64::consts::PI * 2.0);
-        ctx.set_fill_style_str(if is_me { "#ffffff" } else { SHIP_COLORS[idx].main });
+        ctx.set_fill_style_str(if is_me { "#ffffff" } else { palette::ship(p.s) });
         ctx.fill();
     }
 
@@ -141,7 +324,7 @@ fn draw_minimap(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, scr
         let dot_y = y + (mob.y / WORLD_H) * size;
         ctx.begin_path();
         let _ = ctx.arc(dot_x, dot_y, 2.0, 0.0, std::f64::consts::PI * 2.0);
-        ctx.set_fill_style_str("#ffff44");
+        ctx.set_fill_style_str(palette::mob());
         ctx.fill();
     }
 
@@ -164,6 +347,8 @@ fn draw_minimap(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, scr
         ctx.set_fill_style_str("#44ff88");
         ctx.fill();
     }
+
+    crate::markers::render_minimap_markers(ctx, s, x, y, size);
 }
 
 fn draw_kill_feed(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, screen_w: f64, screen_h: f64) {
@@ -178,52 +363,55 @@ fn draw_kill_feed(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, s
     ctx.set_font(&format!("{}px monospace", font_size));
 
     for kill in s.kill_feed.iter().rev() {
-        let age = (now - kill.time) / 1000.0;
-        if age > 8.0 { continue; }
-
-        let alpha = if age > 6.0 { (8.0 - age) / 2.0 } else { 1.0 };
+        let Some(alpha) = kill_alpha((now - kill.time) / 1000.0) else { continue };
         ctx.set_global_alpha(alpha);
 
-        // Measure text segments right-to-left (cached)
-        let victim_w = cached_measure_text(ctx, &kill.victim, font_size);
-        let killed_text = " killed ";
-        let killed_w = cached_measure_text(ctx, killed_text, font_size);
-
-        // Draw killer name (orange)
-        ctx.set_fill_style_str("#ffaa00");
-        let _ = ctx.fill_text(&kill.killer, x - victim_w - killed_w, y);
-        // Draw " killed " (white)
-        ctx.set_fill_style_str("#ffffff");
-        let _ = ctx.fill_text(killed_text, x - victim_w, y);
-        // Draw victim name (red)
-        ctx.set_fill_style_str("#ff4444");
-        let _ = ctx.fill_text(&kill.victim, x, y);
+        // Right-to-left languages read the line from the right, so the names swap sides
+        let rtl = i18n::lang().rtl();
+        let mut segments = kill_segments(kill);
+        if rtl {
+            segments.reverse();
+        }
+        // Draw from the right edge, measuring each segment (cached)
+        let mut right = x;
+        for segment in segments.iter().rev() {
+            right -= match segment {
+                Segment::Text(text, color) => {
+                    ctx.set_fill_style_str(color);
+                    let _ = ctx.fill_text(text, right, y);
+                    cached_measure_text(ctx, text, font_size)
+                }
+                Segment::Icon(cause) => draw_cause_icon(ctx, cause, right, y, font_size, rtl),
+            };
+        }
 
         y += 20.0;
     }
     ctx.set_global_alpha(1.0);
 }
 
+/// Scoreboard ping color: green, then yellow past 80ms, red past 150ms
+fn ping_color(ms: u32) -> &'static str {
+    match ms {
+        0..=80 => "#44dd66",
+        81..=150 => "#ddcc44",
+        _ => "#ff5544",
+    }
+}
+
 fn draw_scoreboard(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState, screen_w: f64, screen_h: f64) {
     let min_dim = screen_w.min(screen_h);
     let scale = (min_dim / 800.0).max(0.7).min(1.0);
     let font_size = (13.0 * scale) as i32;
     let header_size = (12.0 * scale) as i32;
     let line_h = (18.0 * scale) as i32;
-    let panel_w = 180.0 * scale;
+    let panel_w = 290.0 * scale;
     let score_x = 150.0 * scale;
-    let max_players = if min_dim < 500.0 { 5 } else { 8 };
+    let kda_x = 185.0 * scale;
+    let ping_x = 245.0 * scale;
 
-    // Re-sort only when tick changes (new server state arrived)
     SCOREBOARD_CACHE.with(|cache| {
-        let mut cache = cache.borrow_mut();
-        if cache.0 != s.tick {
-            cache.1.clear();
-            cache.1.extend(s.players.values().cloned());
-            cache.1.sort_by(|a, b| b.sc.cmp(&a.sc).then_with(|| a.id.cmp(&b.id)));
-            cache.1.truncate(max_players);
-            cache.0 = s.tick;
-        }
+        let cache = cache.borrow();
 
         ctx.set_text_align("left");
         ctx.set_font(&format!("{}px monospace", font_size));
@@ -236,7 +424,8 @@ fn draw_scoreboard(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState,
 
         ctx.set_fill_style_str("#ffffff88");
         ctx.set_font(&format!("bold {}px monospace", header_size));
-        let _ = ctx.fill_text("SCOREBOARD", x, y - 2.0);
+        let _ = ctx.fill_text(t(Msg::Scoreboard), x, y - 2.0);
+        let _ = ctx.fill_text(t(Msg::KdaHeader), x + kda_x, y - 2.0);
         y += line_h as f64;
 
         ctx.set_font(&format!("{}px monospace", font_size));
@@ -244,39 +433,95 @@ fn draw_scoreboard(ctx: &CanvasRenderingContext2d, s: &crate::state::GameState,
 
         for p in &cache.1 {
             let is_me = s.my_id.as_ref() == Some(&p.id);
-            let idx = (p.s as usize).min(SHIP_COLORS.len() - 1);
 
             ctx.set_fill_style_str(if is_me { "#ffffff" } else { "#aaaaaa" });
-            let name = if p.n.len() > max_name_len {
-                format!("{}..", &p.n[..max_name_len])
+            let n = s.name_of(p.id);
+            let name = if n.len() > max_name_len {
+                format!("{}..", &n[..max_name_len])
             } else {
-                p.n.clone()
+                n.to_string()
             };
             let _ = ctx.fill_text(&name, x, y);
 
-            ctx.set_fill_style_str(SHIP_COLORS[idx].main);
+            ctx.set_fill_style_str(palette::ship(p.s));
             let _ = ctx.fill_text(&p.sc.to_string(), x + score_x, y);
+            ctx.set_fill_style_str("#888888");
+            let _ = ctx.fill_text(&format!("{}/{}/{}", p.kills, p.deaths, p.assists), x + kda_x, y);
+            if let Some(&ms) = s.pings.get(&p.id) {
+                ctx.set_fill_style_str(ping_color(ms));
+                let _ = ctx.fill_text(&format!("{}ms", ms), x + ping_x, y);
+            }
             y += line_h as f64;
         }
     });
 }
 
-fn draw_death_screen(ctx: &CanvasRenderingContext2d, screen_w: f64, screen_h: f64, killer_name: &str) {
+/// Whole seconds left to show and the fraction of the wait remaining, or None
+/// once the respawn is due
+fn respawn_countdown(now: f64, due: f64, total: f64) -> Option<(u32, f64)> {
+    let left = due - now;
+    if left <= 0.0 || total <= 0.0 { return None; }
+    Some(((left / 1000.0).ceil() as u32, (left / total).min(1.0)))
+}
+
+fn draw_death_screen(ctx: &CanvasRenderingContext2d, screen_w: f64, screen_h: f64, death: &DeathInfo, watching: Option<&str>) {
     ctx.set_fill_style_str("rgba(0, 0, 0, 0.5)");
     ctx.fill_rect(0.0, 0.0, screen_w, screen_h);
 
     ctx.set_text_align("center");
-    ctx.set_fill_style_str("#ff4444");
+    ctx.set_fill_style_str(palette::danger());
     ctx.set_font("bold 36px monospace");
-    let _ = ctx.fill_text("DESTROYED", screen_w / 2.0, screen_h / 2.0 - 30.0);
+    let _ = ctx.fill_text(t(Msg::Destroyed), screen_w / 2.0, screen_h / 2.0 - 30.0);
 
     ctx.set_fill_style_str("#ffffff");
     ctx.set_font("20px monospace");
-    let _ = ctx.fill_text(&format!("by {}", killer_name), screen_w / 2.0, screen_h / 2.0 + 10.0);
+    let _ = ctx.fill_text(&tf(Msg::KilledBy, &death.killer_name), screen_w / 2.0, screen_h / 2.0 + 10.0);
 
     ctx.set_fill_style_str("#aaaaaa");
     ctx.set_font("16px monospace");
-    let _ = ctx.fill_text("Respawning...", screen_w / 2.0, screen_h / 2.0 + 50.0);
+    let _ = ctx.fill_text(t(Msg::Respawning), screen_w / 2.0, screen_h / 2.0 + 50.0);
+
+    // Countdown ring above the title: the arc empties as the wait runs out
+    let now = web_sys::window().unwrap().performance().unwrap().now();
+    if let Some((secs, left)) = death.respawn.and_then(|(due, total)| respawn_countdown(now, due, total)) {
+        let (cx, cy, r) = (screen_w / 2.0, screen_h / 2.0 - 100.0, 26.0);
+        let top = -std::f64::consts::FRAC_PI_2;
+        ctx.set_line_width(4.0);
+        ctx.begin_path();
+        let _ = ctx.arc(cx, cy, r, 0.0, std::f64::consts::PI * 2.0);
+        ctx.set_stroke_style_str("rgba(255, 255, 255, 0.15)");
+        ctx.stroke();
+        ctx.begin_path();
+        let _ = ctx.arc(cx, cy, r, top, top + left * std::f64::consts::PI * 2.0);
+        ctx.set_stroke_style_str("#ffaa00");
+        ctx.stroke();
+        ctx.set_fill_style_str("#ffffff");
+        ctx.set_font("bold 22px monospace");
+        ctx.set_text_baseline("middle");
+        let _ = ctx.fill_text(&secs.to_string(), cx, cy);
+        ctx.set_text_baseline("alphabetic");
+    }
+
+    if let Some(name) = watching {
+        ctx.set_fill_style_str("#ffaa00");
+        ctx.set_font("14px monospace");
+        let _ = ctx.fill_text(&tf(Msg::Watching, name), screen_w / 2.0, screen_h / 2.0 + 80.0);
+    }
+}
+
+fn draw_rec_indicator(ctx: &CanvasRenderingContext2d) {
+    // Blink once per second
+    let on = (js_sys::Date::now() / 1000.0).fract() < 0.5;
+    if on {
+        ctx.begin_path();
+        let _ = ctx.arc(20.0, 18.0, 5.0, 0.0, std::f64::consts::PI * 2.0);
+        ctx.set_fill_style_str("#ff3333");
+        ctx.fill();
+    }
+    ctx.set_font("bold 11px monospace");
+    ctx.set_text_align("left");
+    ctx.set_fill_style_str("rgba(255, 80, 80, 0.9)");
+    let _ = ctx.fill_text(t(Msg::RecHint), 30.0, 22.0);
 }
 
 fn draw_crosshair(ctx: &CanvasRenderingContext2d, mx: f64, my: f64) {
@@ -352,8 +597,11 @@ pub fn draw_player_health_bar(ctx: &CanvasRenderingContext2d, x: f64, y: f64, hp
     ctx.set_fill_style_str("rgba(0,0,0,0.5)");
     ctx.fill_rect(x - bar_w / 2.0, bar_y, bar_w, bar_h);
 
-    let color = if ratio > 0.6 { "#44ff44" } else if ratio > 0.3 { "#ffaa00" } else { "#ff4444" };
+    let color = palette::health(ratio);
     ctx.set_fill_style_str(color);
     ctx.fill_rect(x - bar_w / 2.0, bar_y, bar_w * ratio, bar_h);
 }
 
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,66 @@
+use super::*;
+
+fn kill(n: usize) -> KillFeedEntry {
+    KillFeedEntry {
+        killer: format!("k{}", n), assist: String::new(), victim: format!("v{}", n), cause: "laser".to_string(),
+        environment: false, suicide: false, time: n as f64,
+    }
+}
+
+#[test]
+fn kill_feed_keeps_the_newest() {
+    let mut feed = Vec::new();
+    for n in 0..KILL_FEED_LEN + 3 {
+        push_kill(&mut feed, kill(n));
+    }
+    assert_eq!(feed.len(), KILL_FEED_LEN);
+    assert_eq!(feed[0].killer, "k3");
+    assert_eq!(feed.last().unwrap().victim, format!("v{}", KILL_FEED_LEN + 2));
+}
+
+#[test]
+fn kill_feed_fades_then_drops() {
+    assert_eq!(kill_alpha(0.0), Some(1.0));
+    assert_eq!(kill_alpha(6.0), Some(1.0));
+    assert_eq!(kill_alpha(7.0), Some(0.5));
+    assert_eq!(kill_alpha(8.5), None);
+}
+
+#[test]
+fn kill_feed_line_styles() {
+    let shot = kill(1);
+    assert_eq!(kill_segments(&shot), [
+        Segment::Text("k1", "#ffaa00"), Segment::Icon("laser"), Segment::Text("v1", "#ff4444"),
+    ]);
+
+    let helped = KillFeedEntry { assist: "a1".to_string(), ..kill(1) };
+    assert_eq!(kill_segments(&helped)[..3], [
+        Segment::Text("k1", "#ffaa00"), Segment::Text(" + ", "#ffffff"), Segment::Text("a1", "#ffaa00"),
+    ]);
+
+    let rock = KillFeedEntry { killer: "Asteroid".to_string(), cause: "asteroid".to_string(), environment: true, ..kill(2) };
+    assert_eq!(kill_segments(&rock)[0], Segment::Text("Asteroid", "#aaaaaa"));
+
+    // A suicide names only the victim
+    let own = KillFeedEntry { cause: "ram".to_string(), suicide: true, ..kill(3) };
+    assert_eq!(kill_segments(&own), [Segment::Icon("ram"), Segment::Text("v3", "#aaaaaa")]);
+    let own_unknown = KillFeedEntry { cause: String::new(), ..own };
+    assert_eq!(kill_segments(&own_unknown), [Segment::Text("v3", "#aaaaaa")]);
+}
+
+#[test]
+fn ping_colors() {
+    assert_eq!(ping_color(30), "#44dd66");
+    assert_eq!(ping_color(80), "#44dd66");
+    assert_eq!(ping_color(120), "#ddcc44");
+    assert_eq!(ping_color(400), "#ff5544");
+}
+
+#[test]
+fn respawn_countdown_rounds_up_and_empties() {
+    // 3s wait, 0.5s in
+    assert_eq!(respawn_countdown(500.0, 3000.0, 3000.0), Some((3, 2500.0 / 3000.0)));
+    assert_eq!(respawn_countdown(2999.0, 3000.0, 3000.0).map(|c| c.0), Some(1));
+    assert_eq!(respawn_countdown(3000.0, 3000.0, 3000.0), None);
+    assert_eq!(respawn_countdown(0.0, 3000.0, 0.0), None);
+}
@@ -28,7 +28,8 @@ fn new_star(random_dist: bool) -> HyperStar {
     }
 }
 
-pub fn render_hyperspace(ctx: &CanvasRenderingContext2d, w: f64, h: f64, dt: f64) {
+/// `still` (reduced motion) draws the stars as fixed points instead of streaks
+pub fn render_hyperspace(ctx: &CanvasRenderingContext2d, w: f64, h: f64, dt: f64, still: bool) {
     INITIALIZED.with(|init| {
         if !*init.borrow() {
             STARS.with(|stars| {
@@ -76,7 +77,7 @@ pub fn render_hyperspace(ctx: &CanvasRenderingContext2d, w: f64, h: f64, dt: f64
             let y = cy + star.angle.sin() * d;
 
             // Trail length grows with distance (short lines, not dots)
-            let trail = (star.dist * star.dist * 60.0 + 2.0).min(50.0);
+            let trail = if still { 1.0 } else { (star.dist * star.dist * 60.0 + 2.0).min(50.0) };
             let x2 = x - star.angle.cos() * trail;
             let y2 = y - star.angle.sin() * trail;
 
@@ -0,0 +1,368 @@
+// UI translations: every user-facing string in the lobby, HUD and phone
+// controller, keyed by `Msg`. Adding a message means adding it to each
+// language's table (the matches are exhaustive, so a missing one won't build).
+// The language is picked in settings and applied at startup.
+
+use std::cell::Cell;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    En,
+    Ar, // right-to-left
+}
+
+pub const LANGS: [(Lang, &str); 2] = [(Lang::En, "English"), (Lang::Ar, "العربية")];
+
+impl Lang {
+    pub fn parse(v: &str) -> Lang {
+        match v {
+            "ar" => Lang::Ar,
+            _ => Lang::En,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Ar => "ar",
+        }
+    }
+
+    pub fn rtl(self) -> bool {
+        self == Lang::Ar
+    }
+}
+
+thread_local! {
+    static LANG: Cell<Lang> = const { Cell::new(Lang::En) };
+}
+
+/// Use this language from now on, and mark the page's language and direction
+pub fn set_lang(lang: Lang) {
+    LANG.with(|l| l.set(lang));
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(root) = document.document_element() {
+        let _ = root.set_attribute("lang", lang.id());
+        let _ = root.set_attribute("dir", if lang.rtl() { "rtl" } else { "ltr" });
+    }
+}
+
+pub fn lang() -> Lang {
+    LANG.with(|l| l.get())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Msg {
+    // Lobby
+    SessionGone,
+    Subtitle,
+    CoachView,
+    PilotName,
+    NamePlaceholder,
+    QuickMatch,
+    CreateBattle,
+    WatchReplay,
+    ReplayDropHint,
+    Practice,
+    PracticeHint,
+    PracticeArena,
+    Warmup,
+    WarmupHint,
+    Hits,
+    Accuracy,
+    AllTime,
+    Done,
+    Settings,
+    ActiveBattles,
+    SearchBattles,
+    SortMost,
+    SortFewest,
+    SortName,
+    HideFull,
+    NoBattles,
+    NoMatches,
+    Join,
+    CheckingSession,
+    GoToLobby,
+    BattleLabel,
+    JoinBattle,
+    WaitingForPilot,
+    CoachHint,
+    DataSaver,
+    MbPerHour,
+    Ship,
+    RespawnAs,
+    Region,
+    ThisServer,
+    Offline,
+    // HUD
+    ConnectionUnstable,
+    Scoreboard,
+    KdaHeader,
+    Killed,
+    Destroyed,
+    KilledBy,
+    Respawning,
+    Watching,
+    RecHint,
+    // Screen-reader announcements
+    EnteredBattle,
+    Respawned,
+    YouDestroyed,
+    YouWereDestroyed,
+    // Tutorial
+    Tutorial,
+    TutMove,
+    TutMoveTouch,
+    TutFire,
+    TutFireTouch,
+    TutBoost,
+    TutBoostTouch,
+    TutMinimap,
+    SkipTutorial,
+    // Phone controller
+    Connecting,
+    Attaching,
+    Connected,
+    Reconnecting,
+    Reconnect,
+    Error,
+    RotatePhone,
+    TapToggleFire,
+    TapToggleBoost,
+    AimStickOption,
+    GyroOption,
+    GyroSensitivity,
+    Calibrate,
+    DragToAim,
+    Fire,
+    Dodge,
+    AimAssist,
+    Off,
+    Low,
+    High,
+    JoyDeadZone,
+    JoySensitivity,
+    DragToTest,
+    DeadZoneHolding,
+    DragToNavigate,
+    TapToFire,
+    Boost,
+    Ping,
+}
+
+/// The current language's text for `m`
+pub fn t(m: Msg) -> &'static str {
+    match lang() {
+        Lang::En => en(m),
+        Lang::Ar => ar(m),
+    }
+}
+
+/// `t(m)` with its "{}" placeholder filled in
+pub fn tf(m: Msg, arg: impl std::fmt::Display) -> String {
+    t(m).replacen("{}", &arg.to_string(), 1)
+}
+
+/// "N pilot(s)" for the session list
+pub fn pilots(n: i32) -> String {
+    match lang() {
+        Lang::En if n == 1 => format!("{} pilot", n),
+        Lang::En => format!("{} pilots", n),
+        Lang::Ar => format!("الطيارون: {}", n),
+    }
+}
+
+fn en(m: Msg) -> &'static str {
+    match m {
+        Msg::SessionGone => "Session does not exist or has ended.",
+        Msg::Subtitle => "Space Battle",
+        Msg::CoachView => "Coach View",
+        Msg::PilotName => "Pilot Name",
+        Msg::NamePlaceholder => "Enter your name...",
+        Msg::QuickMatch => "Quick Match",
+        Msg::CreateBattle => "Create Battle",
+        Msg::WatchReplay => "Watch Replay",
+        Msg::ReplayDropHint => "Or drop a .replay file here",
+        Msg::Practice => "Practice",
+        Msg::PracticeHint => "Offline arena with target drones, no server needed",
+        Msg::PracticeArena => "PRACTICE (offline)",
+        Msg::Warmup => "Warm-up",
+        Msg::WarmupHint => "Shoot practice targets while you wait",
+        Msg::Hits => "Hits",
+        Msg::Accuracy => "Accuracy",
+        Msg::AllTime => "All-time",
+        Msg::Done => "Done",
+        Msg::Settings => "Settings",
+        Msg::ActiveBattles => "Active Battles",
+        Msg::SearchBattles => "Search battles...",
+        Msg::SortMost => "Most pilots",
+        Msg::SortFewest => "Fewest pilots",
+        Msg::SortName => "Name",
+        Msg::HideFull => "Hide full",
+        Msg::NoBattles => "No active battles. Create one!",
+        Msg::NoMatches => "No battles match your filters.",
+        Msg::Join => "Join",
+        Msg::CheckingSession => "Checking session...",
+        Msg::GoToLobby => "Go to Lobby",
+        Msg::BattleLabel => "Battle: ",
+        Msg::JoinBattle => "Join Battle",
+        Msg::WaitingForPilot => "Waiting for pilot...",
+        Msg::CoachHint => "Click to ping, drag to draw on their screen.",
+        Msg::DataSaver => "Data saver",
+        Msg::MbPerHour => "~{} MB/hour",
+        Msg::Ship => "Ship",
+        Msg::RespawnAs => "Respawn as",
+        Msg::Region => "Region",
+        Msg::ThisServer => "This server",
+        Msg::Offline => "offline",
+        Msg::ConnectionUnstable => "CONNECTION UNSTABLE",
+        Msg::Scoreboard => "SCOREBOARD",
+        Msg::KdaHeader => "K/D/A",
+        Msg::Killed => " killed ",
+        Msg::Destroyed => "DESTROYED",
+        Msg::KilledBy => "by {}",
+        Msg::Respawning => "Respawning...",
+        Msg::Watching => "Watching {}  \u{25c0} \u{25b6}",
+        Msg::RecHint => "REC  [C] save clip",
+        Msg::EnteredBattle => "Entered the battle",
+        Msg::Respawned => "Respawned",
+        Msg::YouDestroyed => "You destroyed {}",
+        Msg::YouWereDestroyed => "You were destroyed by {}",
+        Msg::Tutorial => "TUTORIAL",
+        Msg::TutMove => "Move the mouse to steer: your ship flies toward the cursor",
+        Msg::TutMoveTouch => "Drag on the left to steer",
+        Msg::TutFire => "Hold the mouse button or {} to fire",
+        Msg::TutFireTouch => "Hold the right side to fire",
+        Msg::TutBoost => "Hold {} to boost into hyperspace",
+        Msg::TutBoostTouch => "Hold the middle strip to boost into hyperspace",
+        Msg::TutMinimap => "The minimap shows the whole arena. You are the white dot",
+        Msg::SkipTutorial => "Skip tutorial",
+        Msg::Connecting => "Connecting...",
+        Msg::Attaching => "Attaching...",
+        Msg::Connected => "Connected",
+        Msg::Reconnecting => "Disconnected. Reconnecting...",
+        Msg::Reconnect => "RECONNECT",
+        Msg::Error => "Error: {}",
+        Msg::RotatePhone => "Rotate your phone to landscape",
+        Msg::TapToggleFire => " Tap to toggle fire",
+        Msg::TapToggleBoost => " Tap to toggle boost",
+        Msg::AimStickOption => " Aim stick (fire button instead of tap)",
+        Msg::GyroOption => " Tilt to steer (gyroscope)",
+        Msg::GyroSensitivity => "Tilt sensitivity ",
+        Msg::Calibrate => "CALIBRATE",
+        Msg::DragToAim => "Drag to aim",
+        Msg::Fire => "FIRE",
+        Msg::Dodge => "DODGE",
+        Msg::AimAssist => "Aim assist ",
+        Msg::Off => "Off",
+        Msg::Low => "Low",
+        Msg::High => "High",
+        Msg::JoyDeadZone => "Joystick dead zone ",
+        Msg::JoySensitivity => "Joystick sensitivity ",
+        Msg::DragToTest => "Drag to test",
+        Msg::DeadZoneHolding => "Dead zone: holding heading",
+        Msg::DragToNavigate => "Drag to navigate",
+        Msg::TapToFire => "Tap to fire",
+        Msg::Boost => "BOOST",
+        Msg::Ping => "PING",
+    }
+}
+
+fn ar(m: Msg) -> &'static str {
+    match m {
+        Msg::SessionGone => "الجلسة غير موجودة أو انتهت.",
+        Msg::Subtitle => "معركة فضائية",
+        Msg::CoachView => "عرض المدرب",
+        Msg::PilotName => "اسم الطيار",
+        Msg::NamePlaceholder => "أدخل اسمك...",
+        Msg::QuickMatch => "مباراة سريعة",
+        Msg::CreateBattle => "إنشاء معركة",
+        Msg::WatchReplay => "مشاهدة إعادة",
+        Msg::ReplayDropHint => "أو أفلت ملف ‎.replay هنا",
+        Msg::Practice => "تدريب",
+        Msg::PracticeHint => "ساحة دون اتصال فيها طائرات أهداف، لا تحتاج إلى خادم",
+        Msg::PracticeArena => "تدريب (دون اتصال)",
+        Msg::Warmup => "إحماء",
+        Msg::WarmupHint => "أطلق النار على أهداف التدريب أثناء الانتظار",
+        Msg::Hits => "الإصابات",
+        Msg::Accuracy => "الدقة",
+        Msg::AllTime => "الإجمالي",
+        Msg::Done => "تم",
+        Msg::Settings => "الإعدادات",
+        Msg::ActiveBattles => "المعارك النشطة",
+        Msg::SearchBattles => "ابحث عن معركة...",
+        Msg::SortMost => "الأكثر طيارين",
+        Msg::SortFewest => "الأقل طيارين",
+        Msg::SortName => "الاسم",
+        Msg::HideFull => "إخفاء الممتلئة",
+        Msg::NoBattles => "لا توجد معارك نشطة. أنشئ واحدة!",
+        Msg::NoMatches => "لا توجد معارك تطابق التصفية.",
+        Msg::Join => "انضمام",
+        Msg::CheckingSession => "جارٍ التحقق من الجلسة...",
+        Msg::GoToLobby => "العودة إلى الردهة",
+        Msg::BattleLabel => "المعركة: ",
+        Msg::JoinBattle => "انضم إلى المعركة",
+        Msg::WaitingForPilot => "في انتظار الطيار...",
+        Msg::CoachHint => "انقر للإشارة، واسحب للرسم على شاشته.",
+        Msg::DataSaver => "توفير البيانات",
+        Msg::MbPerHour => "~{} ميغابايت/ساعة",
+        Msg::Ship => "المركبة",
+        Msg::RespawnAs => "العودة بمركبة",
+        Msg::Region => "المنطقة",
+        Msg::ThisServer => "هذا الخادم",
+        Msg::Offline => "غير متصل",
+        Msg::ConnectionUnstable => "الاتصال غير مستقر",
+        Msg::Scoreboard => "النتائج",
+        Msg::KdaHeader => "ق/م/س",
+        Msg::Killed => " دمّر ",
+        Msg::Destroyed => "تم تدميرك",
+        Msg::KilledBy => "بواسطة {}",
+        Msg::Respawning => "جارٍ العودة...",
+        Msg::Watching => "تشاهد {}  \u{25c0} \u{25b6}",
+        Msg::RecHint => "تسجيل  [C] حفظ المقطع",
+        Msg::EnteredBattle => "دخلت المعركة",
+        Msg::Respawned => "عدت إلى المعركة",
+        Msg::YouDestroyed => "دمّرت {}",
+        Msg::YouWereDestroyed => "دمّرك {}",
+        Msg::Tutorial => "درس تعليمي",
+        Msg::TutMove => "حرّك الفأرة للتوجيه: تتجه مركبتك نحو المؤشر",
+        Msg::TutMoveTouch => "اسحب على اليسار للتوجيه",
+        Msg::TutFire => "اضغط زر الفأرة أو {} مطولاً لإطلاق النار",
+        Msg::TutFireTouch => "المس الجانب الأيمن مطولاً لإطلاق النار",
+        Msg::TutBoost => "اضغط {} مطولاً للانطلاق إلى الفضاء الفائق",
+        Msg::TutBoostTouch => "المس الشريط الأوسط مطولاً للانطلاق إلى الفضاء الفائق",
+        Msg::TutMinimap => "تعرض الخريطة المصغرة الساحة كلها. أنت النقطة البيضاء",
+        Msg::SkipTutorial => "تخطي الدرس",
+        Msg::Connecting => "جارٍ الاتصال...",
+        Msg::Attaching => "جارٍ الربط...",
+        Msg::Connected => "متصل",
+        Msg::Reconnecting => "انقطع الاتصال. جارٍ إعادة الاتصال...",
+        Msg::Reconnect => "إعادة الاتصال",
+        Msg::Error => "خطأ: {}",
+        Msg::RotatePhone => "أدر هاتفك إلى الوضع الأفقي",
+        Msg::TapToggleFire => " انقر لتبديل الإطلاق",
+        Msg::TapToggleBoost => " انقر لتبديل التسارع",
+        Msg::AimStickOption => " عصا التصويب (زر إطلاق بدل النقر)",
+        Msg::GyroOption => " أمِل الهاتف للتوجيه (الجيروسكوب)",
+        Msg::GyroSensitivity => "حساسية الإمالة ",
+        Msg::Calibrate => "معايرة",
+        Msg::DragToAim => "اسحب للتصويب",
+        Msg::Fire => "إطلاق",
+        Msg::Dodge => "مراوغة",
+        Msg::AimAssist => "مساعدة التصويب ",
+        Msg::Off => "إيقاف",
+        Msg::Low => "منخفضة",
+        Msg::High => "عالية",
+        Msg::JoyDeadZone => "المنطقة الميتة لعصا التحكم ",
+        Msg::JoySensitivity => "حساسية عصا التحكم ",
+        Msg::DragToTest => "اسحب للتجربة",
+        Msg::DeadZoneHolding => "المنطقة الميتة: الحفاظ على الاتجاه",
+        Msg::DragToNavigate => "اسحب للتوجيه",
+        Msg::TapToFire => "انقر للإطلاق",
+        Msg::Boost => "تسارع",
+        Msg::Ping => "إشارة",
+    }
+}
@@ -1,12 +1,27 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{MouseEvent, KeyboardEvent, TouchEvent};
-use crate::state::{SharedState, Phase, TouchJoystick};
-use crate::network::SharedNetwork;
+use std::cell::{Cell, RefCell};
+use web_sys::{MouseEvent, KeyboardEvent, TouchEvent, WheelEvent};
+use crate::state::{SharedState, GameState, Phase, TouchJoystick};
+use crate::network::{Network, SharedNetwork};
+use crate::coach;
+use crate::keybinds::{self, Action};
+use crate::touch_layout::{self, Zone};
 
-const BOOST_COLUMN_HALF: f64 = 50.0;
+const WHEEL_ZOOM_SPEED: f64 = 0.0015; // zoom factor per wheel delta pixel (exponential)
+const JOYSTICK_SCALE: f64 = 2.5; // screen px of aim offset per px of thumb travel
+const KEY_TURN_RATE: f64 = 4.0; // radians/s the keyboard heading turns (server turns up to 8)
 
-pub fn setup_input(state: SharedState, _net: SharedNetwork) {
+thread_local! {
+    // Active two-finger pinch: (finger distance, zoom scale) when it started
+    static PINCH: Cell<Option<(f64, f64)>> = const { Cell::new(None) };
+    // Fingers currently down and the control zone each one is driving
+    static TOUCH_ZONES: RefCell<Vec<(i32, Zone)>> = const { RefCell::new(Vec::new()) };
+    // When the last joystick-zone finger went down, for double-tap dodge
+    static LAST_JOY_TAP: Cell<f64> = const { Cell::new(0.0) };
+}
+
+pub fn setup_input(state: SharedState, net: SharedNetwork) {
     let window = web_sys::window().unwrap();
     let document = window.document().unwrap();
 
@@ -27,6 +42,12 @@ pub fn setup_input(state: SharedState, _net: SharedNetwork) {
         None => return,
     };
 
+    // Coach mode: mouse draws annotations instead of steering
+    if state.borrow().coach_target.is_some() {
+        setup_coach_input(state, net, &canvas);
+        return;
+    }
+
     // Mouse move
     let state_mm = state.clone();
     let is_mobile = state.borrow().is_mobile;
@@ -41,13 +62,28 @@ pub fn setup_input(state: SharedState, _net: SharedNetwork) {
 
     // Mouse down
     let state_md = state.clone();
+    let net_md = net.clone();
     let mousedown = Closure::wrap(Box::new(move |e: MouseEvent| {
         if is_mobile { return; }
         let s = state_md.borrow();
+        // Inspector: clicks select an entity instead of firing
+        if s.inspector.on && e.button() == 0 && s.phase != Phase::Lobby {
+            let (x, y) = s.screen_to_world(e.client_x() as f64, e.client_y() as f64);
+            drop(s);
+            crate::inspector::pick(&mut state_md.borrow_mut(), x, y);
+            return;
+        }
         if s.phase != Phase::Playing { return; }
+        // Alt-click pings the spot for everyone instead of firing
+        if e.button() == 0 && e.alt_key() {
+            let (x, y) = s.screen_to_world(e.client_x() as f64, e.client_y() as f64);
+            drop(s);
+            Network::send_marker(&net_md, x, y);
+            return;
+        }
         drop(s);
         if e.button() == 0 {
-            state_md.borrow_mut().firing = true;
+            fire_pressed(&mut state_md.borrow_mut());
         }
     }) as Box<dyn FnMut(MouseEvent)>);
     let _ = canvas.add_event_listener_with_callback("mousedown", mousedown.as_ref().unchecked_ref());
@@ -58,7 +94,7 @@ pub fn setup_input(state: SharedState, _net: SharedNetwork) {
     let mouseup = Closure::wrap(Box::new(move |e: MouseEvent| {
         if is_mobile { return; }
         if e.button() == 0 {
-            state_mu.borrow_mut().firing = false;
+            fire_released(&mut state_mu.borrow_mut());
         }
     }) as Box<dyn FnMut(MouseEvent)>);
     let _ = canvas.add_event_listener_with_callback("mouseup", mouseup.as_ref().unchecked_ref());
@@ -71,30 +107,82 @@ pub fn setup_input(state: SharedState, _net: SharedNetwork) {
     let _ = canvas.add_event_listener_with_callback("contextmenu", contextmenu.as_ref().unchecked_ref());
     contextmenu.forget();
 
-    // Key down
+    // Mouse wheel zoom
+    let state_wh = state.clone();
+    let wheel = Closure::wrap(Box::new(move |e: WheelEvent| {
+        if state_wh.borrow().phase == Phase::Lobby { return; }
+        e.prevent_default();
+        // Line/page deltas (Firefox) are far coarser than pixel deltas
+        let unit = match e.delta_mode() { 1 => 16.0, 2 => 400.0, _ => 1.0 };
+        let scale = state_wh.borrow().settings.zoom * (-e.delta_y() * unit * WHEEL_ZOOM_SPEED).exp();
+        crate::canvas::set_zoom_scale(&state_wh, scale);
+    }) as Box<dyn FnMut(WheelEvent)>);
+    let wheel_opts = web_sys::AddEventListenerOptions::new();
+    wheel_opts.set_passive(false);
+    let _ = canvas.add_event_listener_with_callback_and_add_event_listener_options(
+        "wheel", wheel.as_ref().unchecked_ref(), &wheel_opts,
+    );
+    wheel.forget();
+
+    // Key down (actions come from the rebindable map in keybinds.rs)
     let state_kd = state.clone();
     let keydown = Closure::wrap(Box::new(move |e: KeyboardEvent| {
+        // The controls panel is waiting for a new binding
+        if keybinds::listening() {
+            e.prevent_default();
+            keybinds::capture(&e.key());
+            return;
+        }
+        if e.key() == "Escape" && state_kd.borrow().phase != Phase::Lobby {
+            crate::settings::toggle();
+            return;
+        }
+        // Dead: the arrow keys pick who to spectate
+        if state_kd.borrow().phase == Phase::Dead {
+            let dir = match e.key().as_str() {
+                "ArrowLeft" => -1,
+                "ArrowRight" => 1,
+                _ => 0,
+            };
+            if dir != 0 {
+                e.prevent_default();
+                crate::spectate::cycle(&mut state_kd.borrow_mut(), dir);
+                return;
+            }
+        }
+        let steer = state_kd.borrow().settings.keyboard_steer;
+        let Some(action) = keybinds::action_for(&e.key(), steer) else { return };
+        if action == Action::Console {
+            e.prevent_default();
+            crate::console::toggle();
+            return;
+        }
         let s = state_kd.borrow();
+        // Screenshot works while alive or on the death screen
+        if action == Action::Screenshot && s.phase != Phase::Lobby {
+            e.prevent_default();
+            crate::screenshot::capture(&s);
+            return;
+        }
+        // Clip capture: toggle the rolling recorder, or save the last 30s
+        if s.phase != Phase::Lobby {
+            match action {
+                Action::ToggleClip => { crate::clip::toggle(); return; }
+                Action::SaveClip => { crate::clip::save(); return; }
+                _ => {}
+            }
+        }
         if s.phase != Phase::Playing { return; }
         drop(s);
-        match e.key().as_str() {
-            "w" | "W" => state_kd.borrow_mut().firing = true,
-            "Shift" => {
-                let mut s = state_kd.borrow_mut();
-                s.boosting = true;
-                s.shift_pressed = true;
-                // Lock rotation at moment shift is pressed
-                if s.hyperspace_locked_r.is_none() {
-                    let locked_r = s.my_id.as_ref()
-                        .and_then(|id| s.players.get(id))
-                        .map(|p| p.r);
-                    s.hyperspace_locked_r = locked_r;
-                }
-            }
-            "d" | "D" => {
-                let mut s = state_kd.borrow_mut();
-                s.debug_hitboxes = !s.debug_hitboxes;
-            }
+        // Held keys auto-repeat, which would flip toggles back and forth
+        if e.repeat() && matches!(action, Action::Fire | Action::Boost | Action::Dodge) { return; }
+        match action {
+            Action::Fire => fire_pressed(&mut state_kd.borrow_mut()),
+            Action::Boost => boost_pressed(&mut state_kd.borrow_mut()),
+            Action::Dodge => request_dodge(&mut state_kd.borrow_mut()),
+            Action::Thrust => state_kd.borrow_mut().thrusting = true,
+            Action::RotateLeft => state_kd.borrow_mut().steer_left = true,
+            Action::RotateRight => state_kd.borrow_mut().steer_right = true,
             _ => {}
         }
     }) as Box<dyn FnMut(KeyboardEvent)>);
@@ -104,14 +192,13 @@ pub fn setup_input(state: SharedState, _net: SharedNetwork) {
     // Key up
     let state_ku = state.clone();
     let keyup = Closure::wrap(Box::new(move |e: KeyboardEvent| {
-        match e.key().as_str() {
-            "w" | "W" => state_ku.borrow_mut().firing = false,
-            "Shift" => {
-                let mut s = state_ku.borrow_mut();
-                s.boosting = false;
-                s.shift_pressed = false;
-                s.hyperspace_locked_r = None;
-            }
+        let steer = state_ku.borrow().settings.keyboard_steer;
+        match keybinds::action_for(&e.key(), steer) {
+            Some(Action::Fire) => fire_released(&mut state_ku.borrow_mut()),
+            Some(Action::Boost) => boost_released(&mut state_ku.borrow_mut()),
+            Some(Action::Thrust) => state_ku.borrow_mut().thrusting = false,
+            Some(Action::RotateLeft) => state_ku.borrow_mut().steer_left = false,
+            Some(Action::RotateRight) => state_ku.borrow_mut().steer_right = false,
             _ => {}
         }
     }) as Box<dyn FnMut(KeyboardEvent)>);
@@ -134,55 +221,166 @@ pub fn setup_input(state: SharedState, _net: SharedNetwork) {
     }
 }
 
-fn setup_touch_input(state: SharedState, canvas: &web_sys::Element) {
-    const JOYSTICK_SCALE: f64 = 2.5;
+fn fire_pressed(s: &mut GameState) {
+    s.firing = !(s.settings.toggle_fire && s.firing);
+}
+
+fn fire_released(s: &mut GameState) {
+    if !s.settings.toggle_fire {
+        s.firing = false;
+    }
+}
+
+fn boost_pressed(s: &mut GameState) {
+    if s.settings.toggle_boost && s.boosting {
+        set_boost(s, false);
+    } else {
+        set_boost(s, true);
+    }
+}
+
+fn boost_released(s: &mut GameState) {
+    if !s.settings.toggle_boost {
+        set_boost(s, false);
+    }
+}
+
+/// Turn the keyboard-steering heading with the held rotate keys (called every frame)
+pub fn update_steering(s: &mut GameState, dt: f64) {
+    let alive_r = s.my_id.as_ref().and_then(|id| s.players.get(id)).filter(|p| p.a).map(|p| p.r);
+    let Some(r) = alive_r.filter(|_| !s.is_mobile && s.settings.keyboard_steer) else {
+        s.steer_heading = None;
+        return;
+    };
+    let turn = match (s.steer_left, s.steer_right) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => 0.0,
+    };
+    let heading = s.steer_heading.unwrap_or(r) + turn * KEY_TURN_RATE * dt;
+    s.steer_heading = Some(heading.rem_euclid(std::f64::consts::TAU));
+}
+
+/// Queue a dodge for the next input packet, unless it's still cooling down
+fn request_dodge(s: &mut GameState) {
+    let now = js_sys::Date::now();
+    if now < s.dodge_ready_at { return; }
+    s.dodge_pending = true;
+    s.dodge_ready_at = now + crate::constants::DODGE_COOLDOWN;
+}
+
+fn set_boost(s: &mut GameState, on: bool) {
+    s.boosting = on;
+    s.shift_pressed = on;
+    if !on {
+        s.hyperspace_locked_r = None;
+    } else if s.hyperspace_locked_r.is_none() {
+        // Lock rotation at moment boost is pressed
+        let locked_r = s.my_id.as_ref()
+            .and_then(|id| s.players.get(id))
+            .map(|p| p.r);
+        s.hyperspace_locked_r = locked_r;
+    }
+}
+
+fn setup_coach_input(state: SharedState, net: SharedNetwork, canvas: &web_sys::Element) {
+    const MIN_SEGMENT: f64 = 8.0; // world units between stroke points
+
+    // Mouse down: start a stroke
+    let state_md = state.clone();
+    let mousedown = Closure::wrap(Box::new(move |e: MouseEvent| {
+        let mut s = state_md.borrow_mut();
+        if s.phase == Phase::Lobby { return; }
+        let pt = s.screen_to_world(e.client_x() as f64, e.client_y() as f64);
+        s.coach_stroke = Some(vec![pt]);
+    }) as Box<dyn FnMut(MouseEvent)>);
+    let _ = canvas.add_event_listener_with_callback("mousedown", mousedown.as_ref().unchecked_ref());
+    mousedown.forget();
+
+    // Mouse move: extend the stroke
+    let state_mm = state.clone();
+    let mousemove = Closure::wrap(Box::new(move |e: MouseEvent| {
+        let mut s = state_mm.borrow_mut();
+        s.mouse_x = e.client_x() as f64;
+        s.mouse_y = e.client_y() as f64;
+        let pt = s.screen_to_world(s.mouse_x, s.mouse_y);
+        if let Some(ref mut stroke) = s.coach_stroke {
+            let (lx, ly) = stroke[stroke.len() - 1];
+            let far_enough = (pt.0 - lx).hypot(pt.1 - ly) >= MIN_SEGMENT;
+            if far_enough && stroke.len() < coach::MAX_STROKE_POINTS {
+                stroke.push(pt);
+            }
+        }
+    }) as Box<dyn FnMut(MouseEvent)>);
+    let _ = canvas.add_event_listener_with_callback("mousemove", mousemove.as_ref().unchecked_ref());
+    mousemove.forget();
+
+    // Mouse up: a click pings, a drag sends a line
+    let state_mu = state.clone();
+    let mouseup = Closure::wrap(Box::new(move |_: MouseEvent| {
+        let mut s = state_mu.borrow_mut();
+        let stroke = match s.coach_stroke.take() {
+            Some(st) => st,
+            None => return,
+        };
+        let (kind, name, pts) = if stroke.len() < 3 {
+            (crate::state::CoachMarkKind::Ping, "ping", vec![stroke[0]])
+        } else {
+            (crate::state::CoachMarkKind::Line, "line", stroke)
+        };
+        Network::send_mark(&net, name, &pts);
+        coach::add_mark(&mut s, kind, pts);
+    }) as Box<dyn FnMut(MouseEvent)>);
+    let _ = canvas.add_event_listener_with_callback("mouseup", mouseup.as_ref().unchecked_ref());
+    mouseup.forget();
+
+    let contextmenu = Closure::wrap(Box::new(move |e: web_sys::Event| {
+        e.prevent_default();
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let _ = canvas.add_event_listener_with_callback("contextmenu", contextmenu.as_ref().unchecked_ref());
+    contextmenu.forget();
+}
 
-    // Touch start
+fn setup_touch_input(state: SharedState, canvas: &web_sys::Element) {
+    // Touch start: each finger drives whichever zone it lands in until lifted
     let state_ts = state.clone();
     let touchstart = Closure::wrap(Box::new(move |e: TouchEvent| {
         e.prevent_default();
+        if start_pinch(&state_ts, &e) { return; }
         let s = state_ts.borrow();
         if s.phase != Phase::Playing { return; }
-        let screen_w = s.screen_w;
+        let (screen_w, screen_h) = (s.screen_w, s.screen_h);
         drop(s);
 
-        let half_w = screen_w / 2.0;
-        let center_left = half_w - BOOST_COLUMN_HALF;
-        let center_right = half_w + BOOST_COLUMN_HALF;
-
         let changed = e.changed_touches();
         for i in 0..changed.length() {
             if let Some(touch) = changed.get(i) {
                 let cx = touch.client_x() as f64;
                 let cy = touch.client_y() as f64;
-
-                // Center column = boost (invisible)
-                if cx >= center_left && cx <= center_right {
-                    let mut s = state_ts.borrow_mut();
-                    s.boosting = true;
-                    s.shift_pressed = true;
-                    if s.hyperspace_locked_r.is_none() {
-                        let locked_r = s.my_id.as_ref()
-                            .and_then(|id| s.players.get(id))
-                            .map(|p| p.r);
-                        s.hyperspace_locked_r = locked_r;
-                    }
-                    continue;
-                }
+                let Some(zone) = touch_layout::zone_at(cx, cy, screen_w, screen_h) else { continue };
 
                 let mut s = state_ts.borrow_mut();
-                if cx < center_left && s.touch_joystick.is_none() {
-                    s.touch_joystick = Some(TouchJoystick {
-                        start_x: cx,
-                        start_y: cy,
-                        current_x: cx,
-                        current_y: cy,
-                    });
-                    s.mouse_x = s.screen_w / 2.0;
-                    s.mouse_y = s.screen_h / 2.0;
-                } else if cx > center_right && !s.firing {
-                    s.firing = true;
+                match zone {
+                    Zone::Boost => boost_pressed(&mut s),
+                    Zone::Joystick if s.touch_joystick.is_none() => {
+                        // Double-tap the joystick to dodge
+                        let now = js_sys::Date::now();
+                        if now - LAST_JOY_TAP.with(|t| t.replace(now)) < crate::constants::DOUBLE_TAP_MS {
+                            request_dodge(&mut s);
+                        }
+                        s.touch_joystick = Some(TouchJoystick {
+                            start_x: cx,
+                            start_y: cy,
+                            current_x: cx,
+                            current_y: cy,
+                        });
+                        s.mouse_x = s.screen_w / 2.0;
+                        s.mouse_y = s.screen_h / 2.0;
+                    }
+                    Zone::Fire if !s.firing || s.settings.toggle_fire => fire_pressed(&mut s),
+                    _ => continue,
                 }
+                TOUCH_ZONES.with(|t| t.borrow_mut().push((touch.identifier(), zone)));
             }
         }
     }) as Box<dyn FnMut(TouchEvent)>);
@@ -197,21 +395,27 @@ fn setup_touch_input(state: SharedState, canvas: &web_sys::Element) {
     let state_tm = state.clone();
     let touchmove = Closure::wrap(Box::new(move |e: TouchEvent| {
         e.prevent_default();
+        if let Some((start_dist, start_scale)) = PINCH.with(|p| p.get()) {
+            if let Some(dist) = touch_spread(&e) {
+                crate::canvas::set_zoom_scale(&state_tm, start_scale * dist / start_dist);
+            }
+            return;
+        }
         let changed = e.changed_touches();
         for i in 0..changed.length() {
             if let Some(touch) = changed.get(i) {
+                if touch_zone(touch.identifier()) != Some(Zone::Joystick) { continue; }
                 let mut s = state_tm.borrow_mut();
-                if let Some(ref mut tj) = s.touch_joystick {
-                    let cx = touch.client_x() as f64;
-                    let cy = touch.client_y() as f64;
-                    // Check if this touch is near the joystick start
-                    tj.current_x = cx;
-                    tj.current_y = cy;
-                    let dx = cx - tj.start_x;
-                    let dy = cy - tj.start_y;
-                    s.mouse_x = s.screen_w / 2.0 + dx * JOYSTICK_SCALE;
-                    s.mouse_y = s.screen_h / 2.0 + dy * JOYSTICK_SCALE;
-                }
+                let Some(tj) = s.touch_joystick.as_mut() else { continue };
+                let cx = touch.client_x() as f64;
+                let cy = touch.client_y() as f64;
+                tj.current_x = cx;
+                tj.current_y = cy;
+                let (dx, dy) = (cx - tj.start_x, cy - tj.start_y);
+                let offset = crate::joystick::aim_offset(dx, dy, JOYSTICK_SCALE, &s.settings);
+                let (ax, ay) = offset.unwrap_or((0.0, 0.0));
+                s.mouse_x = s.screen_w / 2.0 + ax;
+                s.mouse_y = s.screen_h / 2.0 + ay;
             }
         }
     }) as Box<dyn FnMut(TouchEvent)>);
@@ -220,57 +424,85 @@ fn setup_touch_input(state: SharedState, canvas: &web_sys::Element) {
     );
     touchmove.forget();
 
-    // Touch end / cancel
-    let state_te = state.clone();
-    let touchend = Closure::wrap(Box::new(move |e: TouchEvent| {
-        e.prevent_default();
-        let changed = e.changed_touches();
-        for i in 0..changed.length() {
-            if let Some(_touch) = changed.get(i) {
-                let cx = _touch.client_x() as f64;
-
-                let mut s = state_te.borrow_mut();
-                let half_w = s.screen_w / 2.0;
-                let center_left = half_w - BOOST_COLUMN_HALF;
-                let center_right = half_w + BOOST_COLUMN_HALF;
-
-                // Center column = release boost
-                if cx >= center_left && cx <= center_right {
-                    s.boosting = false;
-                    s.shift_pressed = false;
-                    s.hyperspace_locked_r = None;
-                    continue;
+    // Touch end / cancel: release whatever the lifted finger was holding
+    let make_touch_end = |state_te: SharedState| {
+        Closure::wrap(Box::new(move |e: TouchEvent| {
+            e.prevent_default();
+            if PINCH.with(|p| p.get()).is_some() {
+                if e.touches().length() < 2 || e.type_() == "touchcancel" {
+                    PINCH.with(|p| p.set(None));
                 }
-
-                // Left zone: release joystick
-                if cx < center_left {
-                    if s.touch_joystick.is_some() {
-                        if let Some(ref tj) = s.touch_joystick {
-                            if (cx - tj.start_x).abs() < 200.0 {
-                                s.touch_joystick = None;
-                                s.mouse_x = s.screen_w / 2.0;
-                                s.mouse_y = s.screen_h / 2.0;
-                            }
-                        }
+                return;
+            }
+            let changed = e.changed_touches();
+            for i in 0..changed.length() {
+                let Some(touch) = changed.get(i) else { continue };
+                let id = touch.identifier();
+                let zone = TOUCH_ZONES.with(|t| {
+                    let mut t = t.borrow_mut();
+                    let idx = t.iter().position(|(tid, _)| *tid == id)?;
+                    Some(t.swap_remove(idx).1)
+                });
+                let mut s = state_te.borrow_mut();
+                match zone {
+                    Some(Zone::Boost) => boost_released(&mut s),
+                    Some(Zone::Joystick) => {
+                        s.touch_joystick = None;
+                        s.mouse_x = s.screen_w / 2.0;
+                        s.mouse_y = s.screen_h / 2.0;
                     }
-                }
-
-                // Right zone: release fire
-                if cx > center_right {
-                    s.firing = false;
+                    Some(Zone::Fire) => fire_released(&mut s),
+                    None => {}
                 }
             }
-        }
-    }) as Box<dyn FnMut(TouchEvent)>);
+        }) as Box<dyn FnMut(TouchEvent)>)
+    };
+    let touchend = make_touch_end(state.clone());
     let _ = canvas.add_event_listener_with_callback_and_add_event_listener_options(
         "touchend", touchend.as_ref().unchecked_ref(), &opts,
     );
-    let touchend2 = Closure::wrap(Box::new(move |e: TouchEvent| {
-        e.prevent_default();
-    }) as Box<dyn FnMut(TouchEvent)>);
+    let touchcancel = make_touch_end(state.clone());
     let _ = canvas.add_event_listener_with_callback_and_add_event_listener_options(
-        "touchcancel", touchend2.as_ref().unchecked_ref(), &opts,
+        "touchcancel", touchcancel.as_ref().unchecked_ref(), &opts,
     );
     touchend.forget();
-    touchend2.forget();
+    touchcancel.forget();
+}
+
+fn touch_zone(id: i32) -> Option<Zone> {
+    TOUCH_ZONES.with(|t| t.borrow().iter().find(|(tid, _)| *tid == id).map(|(_, z)| *z))
+}
+
+/// Distance between the first two fingers on screen
+fn touch_spread(e: &TouchEvent) -> Option<f64> {
+    let touches = e.touches();
+    let (a, b) = (touches.get(0)?, touches.get(1)?);
+    let dx = (a.client_x() - b.client_x()) as f64;
+    let dy = (a.client_y() - b.client_y()) as f64;
+    Some(dx.hypot(dy))
+}
+
+/// Two fingers landing in the same zone pinch-zoom instead of steering/firing
+/// (one in the joystick zone and one in the fire zone is still joystick + fire)
+fn start_pinch(state: &SharedState, e: &TouchEvent) -> bool {
+    let touches = e.touches();
+    if touches.length() != 2 || state.borrow().phase == Phase::Lobby {
+        return false;
+    }
+    let (w, h) = (state.borrow().screen_w, state.borrow().screen_h);
+    let (Some(a), Some(b)) = (touches.get(0), touches.get(1)) else { return false };
+    let za = touch_layout::zone_at(a.client_x() as f64, a.client_y() as f64, w, h);
+    let zb = touch_layout::zone_at(b.client_x() as f64, b.client_y() as f64, w, h);
+    if za != zb || za == Some(Zone::Boost) {
+        return false;
+    }
+    let Some(dist) = touch_spread(e).filter(|d| *d > 10.0) else { return false };
+    let mut s = state.borrow_mut();
+    s.touch_joystick = None;
+    s.firing = false;
+    s.mouse_x = s.screen_w / 2.0;
+    s.mouse_y = s.screen_h / 2.0;
+    TOUCH_ZONES.with(|t| t.borrow_mut().clear());
+    PINCH.with(|p| p.set(Some((dist, s.settings.zoom))));
+    true
 }
@@ -0,0 +1,167 @@
+// Entity inspector (/inspect in the debug console): click a ship, mob or laser
+// to pin a panel with its raw snapshot fields, how the renderer is
+// interpolating it, and the last tick the server sent it.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt::Write;
+use web_sys::CanvasRenderingContext2d;
+use crate::protocol::GameStateMsg;
+use crate::renderer::{Interp, extrapolate};
+use crate::spatial::Kind;
+use crate::state::{GameState, Snapshot};
+use crate::constants::{WORLD_W, WORLD_H};
+
+const PICK_R: f64 = 60.0;          // world px around the click to search
+const PANEL_REFRESH: f64 = 100.0;  // ms between panel rewrites
+
+#[derive(Default)]
+pub struct Inspector {
+    pub on: bool,
+    pub target: Option<(Kind, u32)>,
+    pub updated_tick: u64, // last snapshot that carried the target
+}
+
+thread_local! {
+    static LAST_PANEL: Cell<f64> = const { Cell::new(0.0) };
+    static PANEL_SHOWN: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Select the entity closest to a world point, or clear the selection
+pub fn pick(s: &mut GameState, x: f64, y: f64) {
+    let d2 = |ex: f64, ey: f64| (ex - x).powi(2) + (ey - y).powi(2);
+    let grid = &s.grid;
+    let players = grid.query_radius(Kind::Player, x, y, PICK_R)
+        .filter_map(|id| s.players.get(&id).filter(|p| p.a).map(|p| (Kind::Player, id, d2(p.x, p.y))));
+    let mobs = grid.query_radius(Kind::Mob, x, y, PICK_R)
+        .filter_map(|id| s.mobs.get(&id).filter(|m| m.a).map(|m| (Kind::Mob, id, d2(m.x, m.y))));
+    let lasers = grid.query_radius(Kind::Projectile, x, y, PICK_R)
+        .filter_map(|id| s.projectiles.get(&id).map(|p| (Kind::Projectile, id, d2(p.x, p.y))));
+    let best = players.chain(mobs).chain(lasers)
+        .filter(|(_, _, d)| *d <= PICK_R * PICK_R)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+    s.inspector.target = best.map(|(kind, id, _)| (kind, id));
+    s.inspector.updated_tick = s.tick;
+    LAST_PANEL.with(|t| t.set(0.0));
+}
+
+/// Remember the tick if this snapshot carries the inspected entity (deltas skip unchanged ones)
+pub fn note_update(s: &mut GameState, gs: &GameStateMsg) {
+    let Some((kind, id)) = s.inspector.target else { return };
+    let carried = match kind {
+        Kind::Player => gs.p.iter().any(|p| p.id == id),
+        Kind::Mob => gs.m.iter().any(|m| m.id == id),
+        Kind::Projectile => gs.pr.iter().any(|p| p.id == id),
+        _ => false,
+    };
+    if carried || !gs.dl {
+        s.inspector.updated_tick = gs.tick;
+    }
+}
+
+/// Ring the inspected entity where it is drawn (world space) and refresh the panel
+pub fn render(ctx: &CanvasRenderingContext2d, s: &GameState, interp: Option<&Interp>, offset_x: f64, offset_y: f64) {
+    let Some((kind, id)) = s.inspector.target.filter(|_| s.inspector.on) else {
+        set_panel(None);
+        return;
+    };
+    let mut text = String::new();
+    let label = match kind {
+        Kind::Player => format!("player #{} \"{}\"", id, s.name_of(id)),
+        Kind::Mob => format!("mob #{}", id),
+        _ => format!("projectile #{}", id),
+    };
+    let _ = writeln!(text, "{}  (updated tick {}, now {})", label, s.inspector.updated_tick, s.tick);
+    let drawn = match kind {
+        Kind::Player => describe(&mut text, s, interp, id, |snap| &snap.players, s.players.get(&id), |p| (p.x, p.y, p.vx, p.vy)),
+        Kind::Mob => describe(&mut text, s, interp, id, |snap| &snap.mobs, s.mobs.get(&id), |m| (m.x, m.y, m.vx, m.vy)),
+        _ => {
+            // Lasers aren't kept in the snapshot history; they're drawn where the latest one put them
+            let p = s.projectiles.get(&id);
+            match p {
+                Some(p) => { let _ = write!(text, "{:#?}", p); }
+                None => text.push_str("(gone)"),
+            }
+            p.map(|p| (p.x, p.y))
+        }
+    };
+
+    // Our own ship is drawn at the locally predicted position instead
+    let predicted = s.predicted.as_ref().filter(|_| kind == Kind::Player && s.my_id == Some(id));
+    let drawn = match predicted {
+        Some(p) => {
+            let _ = write!(text, "\npredicted ({:.1}, {:.1}), drawn there", p.x, p.y);
+            Some((p.x, p.y))
+        }
+        None => drawn,
+    };
+
+    if let Some((x, y)) = drawn {
+        ctx.begin_path();
+        let _ = ctx.arc(x - offset_x, y - offset_y, 32.0, 0.0, std::f64::consts::PI * 2.0);
+        ctx.set_stroke_style_str("#00ffff");
+        ctx.set_line_width(1.5);
+        ctx.stroke();
+    }
+
+    let now = js_sys::Date::now();
+    if now - LAST_PANEL.with(|t| t.get()) >= PANEL_REFRESH {
+        LAST_PANEL.with(|t| t.set(now));
+        set_panel(Some(&text));
+    }
+}
+
+/// Write the panel for an interpolated entity; returns where it is drawn
+fn describe<T: std::fmt::Debug>(
+    out: &mut String,
+    s: &GameState,
+    interp: Option<&Interp>,
+    id: u32,
+    in_snap: impl Fn(&Snapshot) -> &HashMap<u32, T>,
+    latest: Option<&T>,
+    pos: impl Fn(&T) -> (f64, f64, Option<f64>, Option<f64>),
+) -> Option<(f64, f64)> {
+    let Some(latest) = latest else {
+        out.push_str("(gone)");
+        return None;
+    };
+    let _ = writeln!(out, "{:#?}", latest);
+
+    let (lx, ly, _, _) = pos(latest);
+    let Some(i) = interp else {
+        let _ = write!(out, "no snapshots buffered; drawn at latest ({:.1}, {:.1})", lx, ly);
+        return Some((lx, ly));
+    };
+    let (from, to) = (&s.snapshots[i.from], &s.snapshots[i.to]);
+    let Some(b) = in_snap(to).get(&id) else {
+        out.push_str("not in the snapshot being drawn");
+        return None;
+    };
+    let (bx, by, vx, vy) = pos(b);
+    let (ax, ay) = in_snap(from).get(&id).map(|a| { let (x, y, _, _) = pos(a); (x, y) }).unwrap_or((bx, by));
+    let x = extrapolate(ax + (bx - ax) * i.t, vx.unwrap_or(0.0), i.extrap, WORLD_W);
+    let y = extrapolate(ay + (by - ay) * i.t, vy.unwrap_or(0.0), i.extrap, WORLD_H);
+    let now = web_sys::window().unwrap().performance().unwrap().now();
+    let _ = writeln!(out, "-- interpolation --");
+    let _ = writeln!(out, "snapshots {} -> {} of {}  t={:.2}  extrap={:.3}s", i.from, i.to, s.snapshots.len(), i.t, i.extrap);
+    let _ = writeln!(out, "from   ({:.1}, {:.1})  {:.0}ms ago", ax, ay, now - from.time);
+    let _ = writeln!(out, "to     ({:.1}, {:.1})  {:.0}ms ago", bx, by, now - to.time);
+    let _ = writeln!(out, "step   ({:.1}, {:.1})", bx - ax, by - ay);
+    let _ = writeln!(out, "drawn  ({:.1}, {:.1})", x, y);
+    let _ = write!(out, "behind latest by {:.1}px", ((lx - x).powi(2) + (ly - y).powi(2)).sqrt());
+    Some((x, y))
+}
+
+fn set_panel(text: Option<&str>) {
+    if text.is_none() && !PANEL_SHOWN.with(|v| v.get()) { return; }
+    PANEL_SHOWN.with(|v| v.set(text.is_some()));
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(el) = document.get_element_by_id("inspector") else { return };
+    match text {
+        Some(t) => {
+            el.set_text_content(Some(t));
+            let _ = el.class_list().add_1("visible");
+        }
+        None => { let _ = el.class_list().remove_1("visible"); }
+    }
+}
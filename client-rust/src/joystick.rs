@@ -0,0 +1,130 @@
+// Touch joystick tuning: dead zone and sensitivity, shared by the in-game touch
+// controls and the phone controller, with a drag-to-test calibration pad.
+
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::TouchEvent;
+use crate::settings::{self, Settings, SettingsHost};
+use crate::i18n::{t, Msg};
+
+const KNOB_MAX_R: f64 = 45.0; // px, how far the calibration knob follows the thumb
+
+/// Joystick offset (px) after the dead zone, scaled into an aim offset.
+/// None while the thumb is inside the dead zone.
+pub fn aim_offset(dx: f64, dy: f64, scale: f64, settings: &Settings) -> Option<(f64, f64)> {
+    if dx.hypot(dy) <= settings.joy_dead_zone {
+        return None;
+    }
+    let k = scale * settings.joy_sensitivity;
+    Some((dx * k, dy * k))
+}
+
+pub fn describe_dead_zone(px: f64) -> String {
+    format!("{:.0}px", px)
+}
+
+pub fn describe_sensitivity(mul: f64) -> String {
+    format!("{:.1}\u{00d7}", mul)
+}
+
+fn set_text(id: &str, text: &str) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(el) = document.get_element_by_id(id) {
+        el.set_text_content(Some(text));
+    }
+}
+
+fn set_style(id: &str, prop: &str, value: &str) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(el) = document.get_element_by_id(id) {
+        let el: &web_sys::HtmlElement = el.unchecked_ref();
+        let _ = el.style().set_property(prop, value);
+    }
+}
+
+/// Reflect the current tuning in the slider labels and the pad's dead zone ring
+fn show_tuning(settings: &Settings) {
+    let dz = settings.joy_dead_zone;
+    set_text("optJoyDeadZoneVal", &describe_dead_zone(dz));
+    set_text("optJoySensVal", &describe_sensitivity(settings.joy_sensitivity));
+    set_style("calibDeadZone", "width", &format!("{}px", dz * 2.0));
+    set_style("calibDeadZone", "height", &format!("{}px", dz * 2.0));
+}
+
+/// Move the calibration knob and describe what the game would do with this offset
+fn show_probe(dx: f64, dy: f64, scale: f64, settings: &Settings) {
+    let dist = dx.hypot(dy);
+    let (kx, ky) = if dist > KNOB_MAX_R { (dx / dist * KNOB_MAX_R, dy / dist * KNOB_MAX_R) } else { (dx, dy) };
+    set_style("calibKnob", "transform", &format!("translate(calc(-50% + {}px), calc(-50% + {}px))", kx, ky));
+    let text = match aim_offset(dx, dy, scale, settings) {
+        None if dist == 0.0 => t(Msg::DragToTest).to_string(),
+        None => t(Msg::DeadZoneHolding).to_string(),
+        Some((ax, ay)) => {
+            let deg = ay.atan2(ax).to_degrees().round();
+            format!("Aim {:.0}px at {}\u{00b0}", ax.hypot(ay), deg)
+        }
+    };
+    set_text("calibReadout", &text);
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(ring) = document.get_element_by_id("calibDeadZone") {
+        let _ = if dist > 0.0 && dist <= settings.joy_dead_zone {
+            ring.class_list().add_1("active")
+        } else {
+            ring.class_list().remove_1("active")
+        };
+    }
+}
+
+/// Hook up the tuning sliders, plus the calibration pad if this page has one.
+/// `scale` is the page's joystick-to-aim scale, so the readout matches play.
+pub fn setup_calibration<H: SettingsHost>(host: &H, scale: f64) {
+    settings::bind_value(host, "optJoyDeadZone",
+        |s| s.joy_dead_zone.to_string(),
+        |s, v| if let Ok(v) = v.parse() { s.joy_dead_zone = v },
+        show_tuning);
+    settings::bind_value(host, "optJoySens",
+        |s| s.joy_sensitivity.to_string(),
+        |s, v| if let Ok(v) = v.parse() { s.joy_sensitivity = v },
+        show_tuning);
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(pad) = document.get_element_by_id("calibPad") else { return };
+    let start = std::rc::Rc::new(Cell::new(None::<(f64, f64)>));
+    let opts = web_sys::AddEventListenerOptions::new();
+    opts.set_passive(false);
+
+    let (start_ts, host_ts) = (start.clone(), host.clone());
+    let ts = Closure::wrap(Box::new(move |e: TouchEvent| {
+        e.prevent_default();
+        if let Some(t) = e.changed_touches().get(0) {
+            start_ts.set(Some((t.client_x() as f64, t.client_y() as f64)));
+            host_ts.with_settings(|s| show_probe(0.0, 0.0, scale, s));
+        }
+    }) as Box<dyn FnMut(TouchEvent)>);
+    let _ = pad.add_event_listener_with_callback_and_add_event_listener_options(
+        "touchstart", ts.as_ref().unchecked_ref(), &opts,
+    );
+    ts.forget();
+
+    let (start_tm, host_tm) = (start.clone(), host.clone());
+    let tm = Closure::wrap(Box::new(move |e: TouchEvent| {
+        e.prevent_default();
+        let (Some((sx, sy)), Some(t)) = (start_tm.get(), e.changed_touches().get(0)) else { return };
+        let (dx, dy) = (t.client_x() as f64 - sx, t.client_y() as f64 - sy);
+        host_tm.with_settings(|s| show_probe(dx, dy, scale, s));
+    }) as Box<dyn FnMut(TouchEvent)>);
+    let _ = pad.add_event_listener_with_callback_and_add_event_listener_options(
+        "touchmove", tm.as_ref().unchecked_ref(), &opts,
+    );
+    tm.forget();
+
+    let host_te = host.clone();
+    let te = Closure::wrap(Box::new(move |_: TouchEvent| {
+        start.set(None);
+        host_te.with_settings(|s| show_probe(0.0, 0.0, scale, s));
+    }) as Box<dyn FnMut(TouchEvent)>);
+    let _ = pad.add_event_listener_with_callback("touchend", te.as_ref().unchecked_ref());
+    let _ = pad.add_event_listener_with_callback("touchcancel", te.as_ref().unchecked_ref());
+    te.forget();
+}
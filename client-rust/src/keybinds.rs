@@ -0,0 +1,284 @@
+// Rebindable keyboard controls, persisted to localStorage as {"action": ["key", ...]}
+
+use std::cell::{Cell, RefCell};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+const PREF_KEY: &str = "keybinds";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Fire,
+    Boost,
+    Dodge,
+    Thrust,
+    RotateLeft,
+    RotateRight,
+    Screenshot,
+    ToggleClip,
+    SaveClip,
+    Console,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::Fire,
+        Action::Boost,
+        Action::Dodge,
+        Action::Thrust,
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Screenshot,
+        Action::ToggleClip,
+        Action::SaveClip,
+        Action::Console,
+    ];
+
+    fn id(self) -> &'static str {
+        match self {
+            Action::Fire => "fire",
+            Action::Boost => "boost",
+            Action::Dodge => "dodge",
+            Action::Thrust => "thrust",
+            Action::RotateLeft => "rotate_left",
+            Action::RotateRight => "rotate_right",
+            Action::Screenshot => "screenshot",
+            Action::ToggleClip => "clip_toggle",
+            Action::SaveClip => "clip_save",
+            Action::Console => "console",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Fire => "Fire",
+            Action::Boost => "Boost / hyperspace",
+            Action::Dodge => "Dodge / dash",
+            Action::Thrust => "Thrust (keyboard steering)",
+            Action::RotateLeft => "Rotate left (keyboard steering)",
+            Action::RotateRight => "Rotate right (keyboard steering)",
+            Action::Screenshot => "Screenshot",
+            Action::ToggleClip => "Start/stop clip recorder",
+            Action::SaveClip => "Save last 30s clip",
+            Action::Console => "Debug console",
+        }
+    }
+
+    /// Only active in keyboard steering mode, where they win over other uses of their keys
+    fn is_steering(self) -> bool {
+        matches!(self, Action::Thrust | Action::RotateLeft | Action::RotateRight)
+    }
+
+    fn defaults(self) -> &'static [&'static str] {
+        match self {
+            Action::Fire => &["w", " "],
+            Action::Boost => &["Shift"],
+            Action::Dodge => &["q"],
+            Action::Thrust => &["w", "ArrowUp"],
+            Action::RotateLeft => &["a", "ArrowLeft"],
+            Action::RotateRight => &["d", "ArrowRight"],
+            Action::Screenshot => &["s", "F12"],
+            Action::ToggleClip => &["r"],
+            Action::SaveClip => &["c"],
+            Action::Console => &["`"],
+        }
+    }
+}
+
+thread_local! {
+    // Keys bound to each action, indexed like Action::ALL
+    static BINDINGS: RefCell<Option<Vec<Vec<String>>>> = const { RefCell::new(None) };
+    // Action waiting for its next key press in the controls panel
+    static LISTENING: Cell<Option<Action>> = const { Cell::new(None) };
+}
+
+fn index(action: Action) -> usize {
+    Action::ALL.iter().position(|a| *a == action).unwrap_or(0)
+}
+
+fn defaults() -> Vec<Vec<String>> {
+    Action::ALL.iter()
+        .map(|a| a.defaults().iter().map(|k| k.to_string()).collect())
+        .collect()
+}
+
+/// Case-insensitive for letters, so Shift+W still fires
+fn normalize(key: &str) -> String {
+    if key.chars().count() == 1 {
+        key.to_lowercase()
+    } else {
+        key.to_string()
+    }
+}
+
+fn load() -> Vec<Vec<String>> {
+    let mut binds = defaults();
+    let saved = crate::prefs::get(PREF_KEY)
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok());
+    if let Some(saved) = saved {
+        for (i, action) in Action::ALL.iter().enumerate() {
+            if let Some(keys) = saved.get(action.id()).and_then(|v| v.as_array()) {
+                binds[i] = keys.iter().filter_map(|k| k.as_str()).map(normalize).collect();
+            }
+        }
+    }
+    binds
+}
+
+fn save(binds: &[Vec<String>]) {
+    let obj: serde_json::Map<String, serde_json::Value> = Action::ALL.iter()
+        .zip(binds)
+        .map(|(a, keys)| (a.id().to_string(), serde_json::json!(keys)))
+        .collect();
+    crate::prefs::set(PREF_KEY, &serde_json::Value::Object(obj).to_string());
+}
+
+fn with_bindings<R>(f: impl FnOnce(&mut Vec<Vec<String>>) -> R) -> R {
+    BINDINGS.with(|b| {
+        let mut b = b.borrow_mut();
+        f(b.get_or_insert_with(load))
+    })
+}
+
+/// The action bound to a key, if any (`steer`: keyboard steering mode is on)
+pub fn action_for(key: &str, steer: bool) -> Option<Action> {
+    let key = normalize(key);
+    with_bindings(|binds| {
+        let mut hits = Action::ALL.iter()
+            .zip(binds.iter())
+            .filter(|(a, keys)| keys.contains(&key) && (steer || !a.is_steering()))
+            .map(|(a, _)| *a);
+        let first = hits.next()?;
+        if first.is_steering() {
+            return Some(first);
+        }
+        Some(hits.find(|a| a.is_steering()).unwrap_or(first))
+    })
+}
+
+/// Human-readable list of the keys bound to an action
+pub fn describe(action: Action) -> String {
+    with_bindings(|binds| {
+        let keys = &binds[index(action)];
+        if keys.is_empty() {
+            return "\u{2014}".to_string();
+        }
+        keys.iter()
+            .map(|k| match k.as_str() {
+                " " => "Space".to_string(),
+                k if k.chars().count() == 1 => k.to_uppercase(),
+                k => k.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" / ")
+    })
+}
+
+/// Bind a key to an action, taking it away from whatever had it before
+pub fn rebind(action: Action, key: &str) {
+    let key = normalize(key);
+    with_bindings(|binds| {
+        for keys in binds.iter_mut() {
+            keys.retain(|k| *k != key);
+        }
+        binds[index(action)] = vec![key];
+        save(binds);
+    });
+}
+
+pub fn reset() {
+    with_bindings(|binds| {
+        *binds = defaults();
+        save(binds);
+    });
+}
+
+pub fn listening() -> bool {
+    LISTENING.with(|l| l.get()).is_some()
+}
+
+/// Key pressed while the controls panel waits for a binding: Escape cancels,
+/// anything else becomes the action's new key
+pub fn capture(key: &str) {
+    if let Some(action) = LISTENING.with(|l| l.take()) {
+        if key != "Escape" {
+            rebind(action, key);
+        }
+        render_list();
+    }
+}
+
+/// Rebuild the action/key rows in the controls panel
+fn render_list() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(list) = document.get_element_by_id("keysList") else { return };
+    list.set_inner_html("");
+    let waiting = LISTENING.with(|l| l.get());
+    for (i, action) in Action::ALL.iter().enumerate() {
+        let Ok(row) = document.create_element("div") else { continue };
+        row.set_class_name("keys-row");
+        if let Ok(label) = document.create_element("span") {
+            label.set_text_content(Some(action.label()));
+            let _ = row.append_child(&label);
+        }
+        if let Ok(btn) = document.create_element("button") {
+            let text = if waiting == Some(*action) {
+                btn.set_class_name("keys-bind listening");
+                "Press a key\u{2026}".to_string()
+            } else {
+                btn.set_class_name("keys-bind");
+                describe(*action)
+            };
+            btn.set_text_content(Some(&text));
+            let _ = btn.set_attribute("data-action", &i.to_string());
+            let _ = row.append_child(&btn);
+        }
+        let _ = list.append_child(&row);
+    }
+}
+
+fn set_overlay_visible(visible: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(overlay) = document.get_element_by_id("keysOverlay") {
+        let _ = if visible {
+            overlay.class_list().add_1("visible")
+        } else {
+            overlay.class_list().remove_1("visible")
+        };
+    }
+}
+
+fn on_click(id: &str, f: impl FnMut(web_sys::Event) + 'static) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(el) = document.get_element_by_id(id) {
+        let click = Closure::wrap(Box::new(f) as Box<dyn FnMut(web_sys::Event)>);
+        let _ = el.add_event_listener_with_callback("click", click.as_ref().unchecked_ref());
+        click.forget();
+    }
+}
+
+pub fn setup_keys_btn() {
+    on_click("keysBtn", |_| {
+        LISTENING.with(|l| l.set(None));
+        render_list();
+        set_overlay_visible(true);
+    });
+    on_click("keysClose", |_| {
+        LISTENING.with(|l| l.set(None));
+        set_overlay_visible(false);
+    });
+    on_click("keysReset", |_| {
+        LISTENING.with(|l| l.set(None));
+        reset();
+        render_list();
+    });
+    // Clicking a binding waits for the next key press (input.rs routes it to capture)
+    on_click("keysList", |e| {
+        let target = e.target().and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+        let idx = target.and_then(|t| t.get_attribute("data-action")).and_then(|v| v.parse::<usize>().ok());
+        if let Some(action) = idx.and_then(|i| Action::ALL.get(i)) {
+            LISTENING.with(|l| l.set(Some(*action)));
+            render_list();
+        }
+    });
+}
@@ -3,6 +3,58 @@ use wasm_bindgen::JsCast;
 use crate::state::SharedState;
 use crate::network::{Network, SharedNetwork};
 use crate::protocol::{SessionInfo, CheckedMsg};
+use crate::ship_select::ShipPicker;
+use crate::i18n::{t, tf, Msg};
+
+#[derive(Clone, Copy, PartialEq)]
+enum SessionSort {
+    MostPilots,
+    FewestPilots,
+    Name,
+}
+
+impl SessionSort {
+    fn parse(v: &str) -> SessionSort {
+        match v {
+            "fewest" => SessionSort::FewestPilots,
+            "name" => SessionSort::Name,
+            _ => SessionSort::MostPilots,
+        }
+    }
+}
+
+/// Sessions matching the lobby's search box and toggles, in the chosen order
+fn filter_sessions(sessions: &[SessionInfo], query: &str, sort: SessionSort, hide_full: bool) -> Vec<SessionInfo> {
+    let query = query.trim().to_lowercase();
+    let mut list: Vec<SessionInfo> = sessions.iter()
+        .filter(|s| query.is_empty() || s.name.to_lowercase().contains(&query) || s.id.starts_with(&query))
+        .filter(|s| !hide_full || s.max == 0 || s.players < s.max)
+        .cloned()
+        .collect();
+    match sort {
+        SessionSort::MostPilots => list.sort_by(|a, b| b.players.cmp(&a.players).then_with(|| a.name.cmp(&b.name))),
+        SessionSort::FewestPilots => list.sort_by(|a, b| a.players.cmp(&b.players).then_with(|| a.name.cmp(&b.name))),
+        SessionSort::Name => list.sort_by_key(|s| s.name.to_lowercase()),
+    }
+    list
+}
+
+/// Busiest session that still has room, for Quick Match
+fn quick_match_pick(sessions: &[SessionInfo]) -> Option<&SessionInfo> {
+    sessions.iter()
+        .filter(|s| s.max == 0 || s.players < s.max)
+        .max_by_key(|s| s.players)
+}
+
+fn pilot_name() -> String {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let name = document
+        .get_element_by_id("playerName")
+        .and_then(|e| e.dyn_into::<web_sys::HtmlInputElement>().ok())
+        .map(|i: web_sys::HtmlInputElement| i.value())
+        .unwrap_or_default();
+    if name.trim().is_empty() { "Pilot".to_string() } else { name.trim().to_string() }
+}
 
 #[component]
 pub fn NormalLobby(
@@ -13,48 +65,134 @@ pub fn NormalLobby(
 ) -> impl IntoView {
     let net_create = net.clone();
     let net_join = send_wrapper::SendWrapper::new(net.clone());
+    let net_replay_drop = net.clone();
+    let net_replay_pick = net.clone();
+    let net_practice = net.clone();
+    let warming_up = RwSignal::new(false);
+    on_cleanup(crate::warmup::stop);
+    let state_warmup = send_wrapper::SendWrapper::new(state.clone());
+    let on_warmup = move |_| {
+        let (w, h) = {
+            let s = state_warmup.borrow();
+            (s.screen_w, s.screen_h)
+        };
+        crate::warmup::start(w, h);
+        warming_up.set(true);
+    };
+    let query = RwSignal::new(String::new());
+    let sort = RwSignal::new(SessionSort::MostPilots);
+    let hide_full = RwSignal::new(false);
 
     let state_for_create = state.clone();
     let on_create = move |_| {
-        let document = web_sys::window().unwrap().document().unwrap();
-        let name = document
-            .get_element_by_id("playerName")
-            .and_then(|e| e.dyn_into::<web_sys::HtmlInputElement>().ok())
-            .map(|i: web_sys::HtmlInputElement| i.value())
-            .unwrap_or_else(|| "Pilot".to_string());
-        let name = if name.trim().is_empty() { "Pilot".to_string() } else { name.trim().to_string() };
+        let name = pilot_name();
         state_for_create.borrow_mut().pending_name = Some(name.clone());
         Network::create_session(&net_create, &name, "Battle Arena");
     };
 
+    // Join the busiest battle with room, or start one if there's nowhere to go
+    let net_quick = send_wrapper::SendWrapper::new(net.clone());
+    let state_quick = send_wrapper::SendWrapper::new(state.clone());
+    let on_quick = move |_| {
+        let name = pilot_name();
+        match quick_match_pick(&sessions.get_untracked()) {
+            Some(session) => Network::join_session(&net_quick, &name, &session.id),
+            None => {
+                state_quick.borrow_mut().pending_name = Some(name.clone());
+                Network::create_session(&net_quick, &name, "Battle Arena");
+            }
+        }
+    };
+
     view! {
-        <div id="lobby">
-            <div class="lobby-panel">
+        <div id="lobby"
+            on:dragover=move |e: web_sys::DragEvent| e.prevent_default()
+            on:drop=move |e: web_sys::DragEvent| {
+                e.prevent_default();
+                let file = e.data_transfer().and_then(|dt| dt.files()).and_then(|f| f.get(0));
+                if let Some(file) = file {
+                    crate::replay::load_file(net_replay_drop.clone(), file);
+                }
+            }
+        >
+            {move || warming_up.get().then(|| view! {
+                <div id="warmupLayer" on:pointerdown=|e: web_sys::PointerEvent| {
+                    crate::warmup::shoot(e.client_x() as f64, e.client_y() as f64);
+                }></div>
+                <button id="warmupDone" class="btn btn-replay" on:click=move |_| {
+                    crate::warmup::stop();
+                    warming_up.set(false);
+                }>{t(Msg::Done)}</button>
+            })}
+            <div class="lobby-panel" style:display=move || if warming_up.get() { "none" } else { "" }>
                 {move || {
                     if expired.get() {
                         view! {
-                            <div class="expired-banner">"Session does not exist or has ended."</div>
+                            <div class="expired-banner">{t(Msg::SessionGone)}</div>
                         }.into_any()
                     } else {
                         view! { <span></span> }.into_any()
                     }
                 }}
                 <h1 class="title">"STAR WARS"</h1>
-                <h2 class="subtitle">"Space Battle"</h2>
+                <h2 class="subtitle">{t(Msg::Subtitle)}</h2>
                 <div class="name-input-group">
-                    <label for="playerName">"Pilot Name"</label>
-                    <input type="text" id="playerName" maxlength="16" placeholder="Enter your name..." value="Pilot" />
+                    <label for="playerName">{t(Msg::PilotName)}</label>
+                    <input type="text" id="playerName" maxlength="16" placeholder=t(Msg::NamePlaceholder) value="Pilot" />
                 </div>
+                <DataSaverToggle state=state.clone() net=net.clone() />
+                <crate::regions::RegionSelect state=state.clone() net=net.clone() />
+                <ShipPicker state=state.clone() net=net.clone() caption=t(Msg::Ship) />
                 <div class="lobby-actions">
-                    <button class="btn btn-primary" on:click=on_create>"Create Battle"</button>
+                    <button class="btn btn-primary" on:click=on_quick>{t(Msg::QuickMatch)}</button>
+                    <button class="btn btn-primary" on:click=on_create>{t(Msg::CreateBattle)}</button>
+                    <label class="btn btn-replay" title=t(Msg::ReplayDropHint)>
+                        {t(Msg::WatchReplay)}
+                        <input type="file" accept=".replay" style="display:none;"
+                            on:change=move |e: web_sys::Event| {
+                                let file = e.target()
+                                    .and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok())
+                                    .and_then(|i| i.files())
+                                    .and_then(|f| f.get(0));
+                                if let Some(file) = file {
+                                    crate::replay::load_file(net_replay_pick.clone(), file);
+                                }
+                            }
+                        />
+                    </label>
+                    <button class="btn btn-replay" title=t(Msg::PracticeHint)
+                        on:click=move |_| crate::practice::start(net_practice.clone(), pilot_name())
+                    >{t(Msg::Practice)}</button>
+                    <button class="btn btn-replay" title=t(Msg::WarmupHint) on:click=on_warmup>{t(Msg::Warmup)}</button>
+                    <button class="btn btn-replay" on:click=|_| crate::settings::set_visible(true)>{t(Msg::Settings)}</button>
                 </div>
                 <div class="session-list-container">
-                    <h3>"Active Battles"</h3>
+                    <h3>{t(Msg::ActiveBattles)}</h3>
+                    <div class="session-filters">
+                        <input type="search" placeholder=t(Msg::SearchBattles) maxlength="30"
+                            prop:value=move || query.get()
+                            on:input=move |e| query.set(event_target_value(&e))
+                        />
+                        <select on:change=move |e| sort.set(SessionSort::parse(&event_target_value(&e)))>
+                            <option value="most">{t(Msg::SortMost)}</option>
+                            <option value="fewest">{t(Msg::SortFewest)}</option>
+                            <option value="name">{t(Msg::SortName)}</option>
+                        </select>
+                        <label>
+                            <input type="checkbox" prop:checked=move || hide_full.get()
+                                on:change=move |e| hide_full.set(event_target_checked(&e))
+                            />
+                            " "{t(Msg::HideFull)}
+                        </label>
+                    </div>
                     <div class="session-list">
                         {move || {
-                            let sessions = sessions.get();
-                            if sessions.is_empty() {
-                                view! { <p class="no-sessions">"No active battles. Create one!"</p> }.into_any()
+                            let all = sessions.get();
+                            let sessions = filter_sessions(&all, &query.get(), sort.get(), hide_full.get());
+                            if all.is_empty() {
+                                view! { <p class="no-sessions">{t(Msg::NoBattles)}</p> }.into_any()
+                            } else if sessions.is_empty() {
+                                view! { <p class="no-sessions">{t(Msg::NoMatches)}</p> }.into_any()
                             } else {
                                 let net_j = net_join.clone();
                                 view! {
@@ -69,25 +207,14 @@ pub fn NormalLobby(
                                             let players = session.players;
                                             let net_click = (*net_j).clone();
                                             let sid_click = sid.clone();
-                                            let player_text = if players == 1 {
-                                                format!("{} pilot", players)
-                                            } else {
-                                                format!("{} pilots", players)
-                                            };
+                                            let player_text = crate::i18n::pilots(players);
                                             view! {
                                                 <div class="session-item">
                                                     <span class="session-name">{name}</span>
                                                     <span class="session-players">{player_text}</span>
                                                     <button class="btn btn-join" on:click=move |_| {
-                                                        let document = web_sys::window().unwrap().document().unwrap();
-                                                        let pname = document
-                                                            .get_element_by_id("playerName")
-                                                            .and_then(|e| e.dyn_into::<web_sys::HtmlInputElement>().ok())
-                                                            .map(|i: web_sys::HtmlInputElement| i.value())
-                                                            .unwrap_or_else(|| "Pilot".to_string());
-                                                        let pname = if pname.trim().is_empty() { "Pilot".to_string() } else { pname.trim().to_string() };
-                                                        Network::join_session(&net_click, &pname, &sid_click);
-                                                    }>"Join"</button>
+                                                        Network::join_session(&net_click, &pilot_name(), &sid_click);
+                                                    }>{t(Msg::Join)}</button>
                                                 </div>
                                             }
                                         }
@@ -115,32 +242,30 @@ pub fn JoinMode(
         <div id="lobby">
             <div class="lobby-panel">
                 <h1 class="title">"STAR WARS"</h1>
-                <h2 class="subtitle">"Space Battle"</h2>
+                <h2 class="subtitle">{t(Msg::Subtitle)}</h2>
                 <div class="name-input-group">
-                    <label for="playerName">"Pilot Name"</label>
-                    <input type="text" id="playerName" maxlength="16" placeholder="Enter your name..." value="Pilot" />
+                    <label for="playerName">{t(Msg::PilotName)}</label>
+                    <input type="text" id="playerName" maxlength="16" placeholder=t(Msg::NamePlaceholder) value="Pilot" />
                 </div>
+                <DataSaverToggle state=state.clone() net=net.clone() />
+                <ShipPicker state=state.clone() net=net.clone() caption=t(Msg::Ship) />
                 <div class="join-status">
                     {move || {
                         match checked.get() {
-                            None => view! { <p class="no-sessions">"Checking session..."</p> }.into_any(),
+                            None => view! { <p class="no-sessions">{t(Msg::CheckingSession)}</p> }.into_any(),
                             Some(c) => {
                                 if !c.exists {
                                     view! {
                                         <div>
-                                            <p class="error-msg">"Session does not exist or has ended."</p>
-                                            <a href={crate::app::base_path()} class="btn btn-primary" style="text-decoration:none;display:inline-block;margin-top:12px;">"Go to Lobby"</a>
+                                            <p class="error-msg">{t(Msg::SessionGone)}</p>
+                                            <a href={crate::app::base_path()} class="btn btn-primary" style="text-decoration:none;display:inline-block;margin-top:12px;">{t(Msg::GoToLobby)}</a>
                                         </div>
                                     }.into_any()
                                 } else {
-                                    let player_text = if c.players == 1 {
-                                        format!("{} pilot", c.players)
-                                    } else {
-                                        format!("{} pilots", c.players)
-                                    };
+                                    let player_text = crate::i18n::pilots(c.players);
                                     view! {
                                         <p class="session-info">
-                                            "Battle: " <strong>{c.name.clone()}</strong> " — " {player_text}
+                                            {t(Msg::BattleLabel)} <strong>{c.name.clone()}</strong> " — " {player_text}
                                         </p>
                                     }.into_any()
                                 }
@@ -169,7 +294,7 @@ pub fn JoinMode(
                                         if let Some(sid) = &st2.borrow().url_session_id {
                                             Network::join_session(&net_j2, &name, sid);
                                         }
-                                    }>"Join Battle"</button>
+                                    }>{t(Msg::JoinBattle)}</button>
                                 }.into_any()
                             } else {
                                 view! { <span></span> }.into_any()
@@ -181,3 +306,45 @@ pub fn JoinMode(
         </div>
     }
 }
+
+#[component]
+pub fn CoachWaiting() -> impl IntoView {
+    view! {
+        <div id="lobby">
+            <div class="lobby-panel">
+                <h1 class="title">"STAR WARS"</h1>
+                <h2 class="subtitle">{t(Msg::CoachView)}</h2>
+                <p class="no-sessions">{t(Msg::WaitingForPilot)}</p>
+                <p class="no-sessions">{t(Msg::CoachHint)}</p>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn DataSaverToggle(state: SharedState, net: SharedNetwork) -> impl IntoView {
+    let enabled = RwSignal::new(state.borrow().settings.data_saver);
+    let state_usage = send_wrapper::SendWrapper::new(state);
+    let net_toggle = send_wrapper::SendWrapper::new(net);
+
+    view! {
+        <div class="data-saver">
+            <label>
+                <input type="checkbox" prop:checked=move || enabled.get()
+                    on:change=move |_| {
+                        let on = !enabled.get_untracked();
+                        enabled.set(on);
+                        Network::set_data_saver(&net_toggle, on);
+                    }
+                />
+                " "{t(Msg::DataSaver)}
+            </label>
+            <span class="data-usage">
+                {move || {
+                    let _ = enabled.get();
+                    tf(Msg::MbPerHour, format!("{:.0}", state_usage.borrow().data_usage_mb_per_hour()))
+                }}
+            </span>
+        </div>
+    }
+}
@@ -1,27 +1,74 @@
-mod app;
+// `--features controller-only` builds just the phone controller page (see
+// controller.html), leaving out the renderer, lobby and everything else a
+// phone acting as a joystick never runs.
+#![cfg_attr(feature = "controller-only", allow(dead_code, unused_imports))]
+
+// Shared with the controller build
 mod constants;
 mod protocol;
-mod state;
-mod network;
-mod lobby;
-mod canvas;
-mod game_loop;
-mod renderer;
-mod starfield;
-mod ships;
-mod effects;
-mod projectiles;
-mod mobs;
-mod asteroids;
-mod pickups;
-mod fog;
-mod hud;
-mod input;
-mod auto_aim;
 mod controller;
-mod hyperspace;
+mod joystick;
+mod auto_aim;
+mod settings;
+mod prefs;
+mod resume;
+mod route;
+mod i18n;
+mod palette;
+mod gyro;
+
+// Full game client only
+#[cfg(not(feature = "controller-only"))] mod app;
+#[cfg(not(feature = "controller-only"))] mod state;
+#[cfg(not(feature = "controller-only"))] mod network;
+#[cfg(not(feature = "controller-only"))] mod lobby;
+#[cfg(not(feature = "controller-only"))] mod canvas;
+#[cfg(not(feature = "controller-only"))] mod game_loop;
+#[cfg(not(feature = "controller-only"))] mod renderer;
+#[cfg(not(feature = "controller-only"))] mod starfield;
+#[cfg(not(feature = "controller-only"))] mod ships;
+#[cfg(not(feature = "controller-only"))] mod effects;
+#[cfg(not(feature = "controller-only"))] mod projectiles;
+#[cfg(not(feature = "controller-only"))] mod mobs;
+#[cfg(not(feature = "controller-only"))] mod asteroids;
+#[cfg(not(feature = "controller-only"))] mod pickups;
+#[cfg(not(feature = "controller-only"))] mod fog;
+#[cfg(not(feature = "controller-only"))] mod hud;
+#[cfg(not(feature = "controller-only"))] mod input;
+#[cfg(not(feature = "controller-only"))] mod touch_layout;
+#[cfg(not(feature = "controller-only"))] mod keybinds;
+#[cfg(not(feature = "controller-only"))] mod hyperspace;
+#[cfg(not(feature = "controller-only"))] mod coach;
+#[cfg(not(feature = "controller-only"))] mod replay;
+#[cfg(not(feature = "controller-only"))] mod screenshot;
+#[cfg(not(feature = "controller-only"))] mod clip;
+#[cfg(not(feature = "controller-only"))] mod prediction;
+#[cfg(not(feature = "controller-only"))] mod bugreport;
+#[cfg(not(feature = "controller-only"))] mod ship_select;
+#[cfg(not(feature = "controller-only"))] mod regions;
+#[cfg(not(feature = "controller-only"))] mod markers;
+#[cfg(not(feature = "controller-only"))] mod announce;
+#[cfg(not(feature = "controller-only"))] mod tutorial;
+#[cfg(not(feature = "controller-only"))] mod practice;
+#[cfg(not(feature = "controller-only"))] mod warmup;
+#[cfg(not(feature = "controller-only"))] mod pool;
+#[cfg(not(feature = "controller-only"))] mod spatial;
+#[cfg(not(feature = "controller-only"))] mod console;
+#[cfg(not(feature = "controller-only"))] mod inspector;
+#[cfg(not(feature = "controller-only"))] mod netlog;
+#[cfg(not(feature = "controller-only"))] mod netsim;
+#[cfg(not(feature = "controller-only"))] mod rng;
+#[cfg(not(feature = "controller-only"))] mod spectate;
+#[cfg(not(feature = "controller-only"))] mod camera;
 
 fn main() {
-    console_error_panic_hook::set_once();
+    std::panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        #[cfg(not(feature = "controller-only"))]
+        bugreport::report_error("panic", &info.to_string());
+    }));
+    #[cfg(not(feature = "controller-only"))]
     leptos::mount::mount_to_body(app::App);
+    #[cfg(feature = "controller-only")]
+    leptos::mount::mount_to_body(controller::ControllerApp);
 }
@@ -0,0 +1,58 @@
+use web_sys::CanvasRenderingContext2d;
+use crate::constants::{WORLD_W, WORLD_H};
+use crate::state::{GameState, WorldMarker};
+
+pub const MARKER_DURATION: f64 = 5000.0; // ms
+const MAX_MARKERS: usize = 8;
+const MARKER_COLOR: &str = "#44ddff";
+
+pub fn add_marker(state: &mut GameState, x: f64, y: f64, name: String) {
+    if state.markers.len() >= MAX_MARKERS {
+        state.markers.remove(0);
+    }
+    state.markers.push(WorldMarker {
+        x,
+        y,
+        name,
+        time: js_sys::Date::now(),
+    });
+}
+
+/// Fade over the last second of a marker's life
+fn alpha(age: f64) -> f64 {
+    if age > MARKER_DURATION - 1000.0 { ((MARKER_DURATION - age) / 1000.0).max(0.0) } else { 1.0 }
+}
+
+/// Draw players' world pings (world-space, inside zoom) with who placed them
+pub fn render_markers(ctx: &CanvasRenderingContext2d, s: &GameState, offset_x: f64, offset_y: f64) {
+    let now = js_sys::Date::now();
+    ctx.set_stroke_style_str(MARKER_COLOR);
+    ctx.set_font("bold 13px monospace");
+    ctx.set_text_align("center");
+    for m in &s.markers {
+        let age = now - m.time;
+        if age > MARKER_DURATION { continue; }
+        ctx.set_global_alpha(alpha(age));
+        let (sx, sy) = (m.x - offset_x, m.y - offset_y);
+        crate::coach::draw_ping(ctx, sx, sy, age, MARKER_COLOR);
+        let _ = ctx.fill_text(&m.name, sx, sy - 66.0);
+    }
+    ctx.set_global_alpha(1.0);
+}
+
+/// Pulsing dots on the minimap at (map_x, map_y) of the given size
+pub fn render_minimap_markers(ctx: &CanvasRenderingContext2d, s: &GameState, map_x: f64, map_y: f64, size: f64) {
+    let now = js_sys::Date::now();
+    ctx.set_stroke_style_str(MARKER_COLOR);
+    ctx.set_line_width(1.5);
+    for m in &s.markers {
+        let age = now - m.time;
+        if age > MARKER_DURATION { continue; }
+        let pulse = (age / 800.0) % 1.0;
+        ctx.set_global_alpha(alpha(age) * (1.0 - pulse * 0.6));
+        ctx.begin_path();
+        let _ = ctx.arc(map_x + (m.x / WORLD_W) * size, map_y + (m.y / WORLD_H) * size, 2.0 + pulse * 5.0, 0.0, std::f64::consts::PI * 2.0);
+        ctx.stroke();
+    }
+    ctx.set_global_alpha(1.0);
+}
@@ -28,7 +28,7 @@ pub fn render_mob(
     ctx.set_fill_style_str("rgba(0,0,0,0.5)");
     ctx.fill_rect(sx - bar_w / 2.0, bar_y, bar_w, bar_h);
 
-    let color = if ratio > 0.6 { "#ff8844" } else if ratio > 0.3 { "#ffaa00" } else { "#ff4444" };
+    let color = crate::palette::mob_health(ratio);
     ctx.set_fill_style_str(color);
     ctx.fill_rect(sx - bar_w / 2.0, bar_y, bar_w * ratio, bar_h);
 }
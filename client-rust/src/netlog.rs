@@ -0,0 +1,153 @@
+// Network message log (/netlog in the debug console): an overlay listing the
+// envelopes going in and out, newest at the bottom, optionally narrowed to a
+// few message types. Nothing is recorded while it's off.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+const CAPACITY: usize = 300;       // entries kept, before filtering
+const SHOWN: usize = 40;           // lines in the overlay
+const PREVIEW_LEN: usize = 120;    // payload chars per line
+const PANEL_REFRESH: f64 = 250.0;  // ms between overlay rewrites
+
+struct Entry {
+    at: f64, // ms (performance.now)
+    out: bool,
+    t: String,
+    bytes: usize,
+    preview: String,
+}
+
+/// Which types to list: everything, only these, or everything but these
+enum Filter {
+    All,
+    Only(Vec<String>),
+    Except(Vec<String>),
+}
+
+impl Filter {
+    fn shows(&self, t: &str) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Only(ts) => ts.iter().any(|f| f == t),
+            Filter::Except(ts) => !ts.iter().any(|f| f == t),
+        }
+    }
+}
+
+thread_local! {
+    static ON: Cell<bool> = const { Cell::new(false) };
+    static ENTRIES: RefCell<VecDeque<Entry>> = const { RefCell::new(VecDeque::new()) };
+    static FILTER: RefCell<Filter> = const { RefCell::new(Filter::All) };
+    static LAST_PANEL: Cell<f64> = const { Cell::new(0.0) };
+    static DIRTY: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn enabled() -> bool {
+    ON.with(|o| o.get())
+}
+
+fn now() -> f64 {
+    web_sys::window().and_then(|w| w.performance()).map(|p| p.now()).unwrap_or(0.0)
+}
+
+fn record(out: bool, t: &str, bytes: usize, preview: &str) {
+    if !enabled() { return; }
+    let mut short: String = preview.chars().take(PREVIEW_LEN).collect();
+    if short.len() < preview.len() {
+        short.push('\u{2026}');
+    }
+    ENTRIES.with(|e| {
+        let mut e = e.borrow_mut();
+        if e.len() >= CAPACITY {
+            e.pop_front();
+        }
+        e.push_back(Entry { at: now(), out, t: t.to_string(), bytes, preview: short });
+    });
+    DIRTY.with(|d| d.set(true));
+}
+
+/// A message from the server; `preview` is the payload or a summary of it
+pub fn inbound(t: &str, bytes: usize, preview: &str) {
+    record(false, t, bytes, preview);
+}
+
+/// A message to the server
+pub fn outbound(t: &str, bytes: usize, preview: &str) {
+    record(true, t, bytes, preview);
+}
+
+/// Handle `/netlog [type...|-type...|off]`: no args toggles the overlay, type
+/// names show only those, `-type` names hide those, `all` clears the filter
+pub fn command(args: &[&str]) -> String {
+    let set_on = |on: bool| {
+        ON.with(|o| o.set(on));
+        if !on {
+            ENTRIES.with(|e| e.borrow_mut().clear());
+        }
+        DIRTY.with(|d| d.set(true));
+    };
+    match args {
+        [] => {
+            set_on(!enabled());
+            format!("net log {}", if enabled() { "on" } else { "off" })
+        }
+        ["off"] => {
+            set_on(false);
+            "net log off".to_string()
+        }
+        ["all"] => {
+            FILTER.with(|f| *f.borrow_mut() = Filter::All);
+            set_on(true);
+            "net log showing all types".to_string()
+        }
+        _ => {
+            let hide: Vec<String> = args.iter().filter_map(|a| a.strip_prefix('-')).map(str::to_string).collect();
+            let show: Vec<String> = args.iter().filter(|a| !a.starts_with('-')).map(|a| a.to_string()).collect();
+            let (filter, msg) = if show.is_empty() {
+                let msg = format!("net log hiding {}", hide.join(", "));
+                (Filter::Except(hide), msg)
+            } else {
+                let msg = format!("net log showing only {}", show.join(", "));
+                (Filter::Only(show), msg)
+            };
+            FILTER.with(|f| *f.borrow_mut() = filter);
+            set_on(true);
+            msg
+        }
+    }
+}
+
+/// Rewrite the overlay if anything changed since the last refresh
+pub fn refresh() {
+    if !DIRTY.with(|d| d.get()) { return; }
+    let t = now();
+    if t - LAST_PANEL.with(|l| l.get()) < PANEL_REFRESH { return; }
+    LAST_PANEL.with(|l| l.set(t));
+    DIRTY.with(|d| d.set(false));
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(el) = document.get_element_by_id("netLog") else { return };
+    if !enabled() {
+        el.set_text_content(None);
+        let _ = el.class_list().remove_1("visible");
+        return;
+    }
+    let mut text = String::new();
+    ENTRIES.with(|e| FILTER.with(|f| {
+        let f = f.borrow();
+        let e = e.borrow();
+        let mut lines: Vec<&Entry> = e.iter().rev().filter(|en| f.shows(&en.t)).take(SHOWN).collect();
+        lines.reverse();
+        for en in lines {
+            let _ = writeln!(text, "{:>8.2}s {} {:<10} {:>6}B  {}",
+                en.at / 1000.0, if en.out { "\u{2192}" } else { "\u{2190}" }, en.t, en.bytes, en.preview);
+        }
+    }));
+    if text.is_empty() {
+        text.push_str("(no messages yet)");
+    }
+    el.set_text_content(Some(&text));
+    let _ = el.class_list().add_1("visible");
+}
@@ -0,0 +1,88 @@
+// Simulated bad network (/netsim in the debug console): incoming snapshots are
+// held back by a fixed delay plus random jitter, and some are "lost". The socket
+// is TCP, so a lost snapshot isn't gone: it arrives a retransmit timeout late
+// and everything behind it waits too, which is what players on lossy links see.
+// Order is always kept, since delta snapshots only make sense applied in sequence.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use crate::protocol::GameStateMsg;
+
+const RETRANSMIT_MS: f64 = 200.0; // TCP's minimum retransmission timeout
+const MAX_DELAY: f64 = 2000.0;
+
+#[derive(Clone, Copy, Default)]
+struct Sim {
+    delay: f64,  // ms added to every snapshot
+    jitter: f64, // up to this many ms more, at random
+    loss: f64,   // fraction retransmitted
+}
+
+thread_local! {
+    static SIM: Cell<Option<Sim>> = const { Cell::new(None) };
+    static QUEUE: RefCell<VecDeque<(f64, GameStateMsg)>> = const { RefCell::new(VecDeque::new()) };
+}
+
+fn now() -> f64 {
+    web_sys::window().unwrap().performance().unwrap().now()
+}
+
+/// Hand a snapshot to `deliver`, right away or after the simulated delay
+pub fn route(gs: GameStateMsg, deliver: impl Fn(GameStateMsg) + 'static) {
+    let sim = SIM.with(|s| s.get());
+    let queued = QUEUE.with(|q| q.borrow().back().map(|(due, _)| *due));
+    // Turning the sim off mid-run still lets queued snapshots go first
+    if sim.is_none() && queued.is_none() {
+        deliver(gs);
+        return;
+    }
+    let sim = sim.unwrap_or_default();
+    let t = now();
+    let mut due = t + sim.delay + js_sys::Math::random() * sim.jitter;
+    if js_sys::Math::random() < sim.loss {
+        due += RETRANSMIT_MS;
+    }
+    let due = due.max(queued.unwrap_or(0.0));
+    QUEUE.with(|q| q.borrow_mut().push_back((due, gs)));
+    gloo_timers::callback::Timeout::new((due - t).max(0.0) as u32, move || {
+        let t = now();
+        loop {
+            let next = QUEUE.with(|q| {
+                let mut q = q.borrow_mut();
+                if q.front().is_some_and(|(due, _)| *due <= t + 1.0) { q.pop_front() } else { None }
+            });
+            match next {
+                Some((_, gs)) => deliver(gs),
+                None => break,
+            }
+        }
+    }).forget();
+}
+
+/// Handle `/netsim [delay_ms] [loss%] [jitter_ms]` or `/netsim off`
+pub fn command(args: &[&str]) -> Result<String, String> {
+    let describe = |s: Sim| format!("+{:.0}ms (up to {:.0}ms more jitter), {:.0}% loss", s.delay, s.jitter, s.loss * 100.0);
+    match args {
+        [] => Ok(match SIM.with(|s| s.get()) {
+            Some(s) => format!("simulating {}", describe(s)),
+            None => "network sim off".to_string(),
+        }),
+        ["off"] => {
+            SIM.with(|s| s.set(None));
+            Ok("network sim off".to_string())
+        }
+        _ => {
+            let num = |i: usize, what: &str| -> Result<f64, String> {
+                args.get(i).map_or(Ok(0.0), |v| v.trim_end_matches(['%', 's', 'm']).parse::<f64>()
+                    .map_err(|_| format!("not a {}: {}", what, v)))
+            };
+            let sim = Sim {
+                delay: num(0, "delay")?.clamp(0.0, MAX_DELAY),
+                loss: (num(1, "loss percentage")? / 100.0).clamp(0.0, 1.0),
+                jitter: num(2, "jitter")?.clamp(0.0, MAX_DELAY),
+            };
+            SIM.with(|s| s.set(Some(sim)));
+            Ok(format!("simulating {}", describe(sim)))
+        }
+    }
+}
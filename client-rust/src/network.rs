@@ -1,12 +1,17 @@
 use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{WebSocket, MessageEvent, CloseEvent, ErrorEvent};
 use leptos::prelude::Set;
-use crate::state::{SharedState, Phase};
+use crate::state::{SharedState, GameState, Snapshot, Ghost, Phase, CoachMarkKind, DamageKind};
+use crate::constants::{SNAPSHOT_BUFFER, INTERP_JITTER_MUL, MAX_INTERP_DELAY, INPUT_ACK_TIMEOUT, GHOST_TTL,
+    RECONNECT_BASE_DELAY, RECONNECT_MAX_DELAY, RECONNECT_MAX_RETRIES, HEARTBEAT_TIMEOUT,
+    OUTBOX_CAP, OUTBOX_MAX_AGE, OUTBOX_TYPES};
 use crate::protocol::*;
-use crate::effects;
+use crate::{effects, coach, replay, prediction, bugreport, resume, announce, netlog, netsim};
+use crate::i18n::{t, tf, Msg};
 
 pub struct Network {
     ws: Option<WebSocket>,
@@ -15,6 +20,14 @@ pub struct Network {
     sessions_signal: leptos::prelude::RwSignal<Vec<SessionInfo>>,
     checked_signal: leptos::prelude::RwSignal<Option<CheckedMsg>>,
     expired_signal: leptos::prelude::RwSignal<bool>,
+    // Reconnect backoff: failed attempts since the last open, when we dropped, next retry
+    reconnect_attempts: u32,
+    disconnected_at: Option<f64>, // ms (Date.now)
+    reconnect_at: Option<f64>,    // ms (Date.now), None once we've given up
+    reconnect_timer: Option<gloo_timers::callback::Timeout>,
+    banner_timer: Option<gloo_timers::callback::Interval>,
+    // Encoded messages sent while the socket was down, with the time they were queued (ms)
+    outbox: VecDeque<(f64, String)>,
     // Store closures to prevent them from being dropped
     _on_open: Option<Closure<dyn FnMut()>>,
     _on_message: Option<Closure<dyn FnMut(MessageEvent)>>,
@@ -39,6 +52,12 @@ impl Network {
             sessions_signal,
             checked_signal,
             expired_signal,
+            reconnect_attempts: 0,
+            disconnected_at: None,
+            reconnect_at: None,
+            reconnect_timer: None,
+            banner_timer: None,
+            outbox: VecDeque::new(),
             _on_open: None,
             _on_message: None,
             _on_close: None,
@@ -48,12 +67,7 @@ impl Network {
     }
 
     pub fn connect(net: &SharedNetwork) {
-        let window = web_sys::window().unwrap();
-        let location = window.location();
-        let protocol = location.protocol().unwrap_or_default();
-        let host = location.host().unwrap_or_default();
-        let ws_proto = if protocol == "https:" { "wss:" } else { "ws:" };
-        let url = format!("{}//{}/ws", ws_proto, host);
+        let url = crate::regions::url_for(&net.borrow().state.borrow().settings.region);
 
         let ws = WebSocket::new(&url).unwrap();
         ws.set_binary_type(web_sys::BinaryType::Arraybuffer);
@@ -62,13 +76,16 @@ impl Network {
         let state_clone = net.borrow().state.clone();
         let net_clone = net.clone();
         let on_open = Closure::wrap(Box::new(move || {
-            state_clone.borrow_mut().connected = true;
-            web_sys::console::log_1(&"WebSocket connected".into());
-            // Check URL session if present
-            let url_sid = state_clone.borrow().url_session_id.clone();
-            if let Some(sid) = url_sid {
-                Network::send_raw(&net_clone, "check", &serde_json::json!({"sid": sid}));
+            {
+                let mut s = state_clone.borrow_mut();
+                s.connected = true;
+                s.last_rx = js_sys::Date::now();
             }
+            bugreport::log("WebSocket connected");
+            Network::reset_backoff(&net_clone);
+            Network::handshake(&net_clone, &state_clone);
+            // Anything the player did while offline goes out after we're reattached
+            Network::flush_outbox(&net_clone);
         }) as Box<dyn FnMut()>);
 
         // on message
@@ -84,12 +101,38 @@ impl Network {
             if let Some(ab) = data.dyn_ref::<js_sys::ArrayBuffer>() {
                 let arr = js_sys::Uint8Array::new(ab);
                 let bytes = arr.to_vec();
-                if let Ok(gs) = rmp_serde::from_slice::<GameStateMsg>(&bytes) {
-                    handle_state(&state_clone, &phase_signal, gs);
+                {
+                    let mut s = state_clone.borrow_mut();
+                    s.bytes_in += bytes.len() as f64;
+                    s.last_rx = js_sys::Date::now();
+                }
+                replay::record_state(&bytes);
+                bugreport::record_snapshot(&bytes);
+                match rmp_serde::from_slice::<GameStateMsg>(&bytes) {
+                    Ok(gs) => {
+                        if netlog::enabled() {
+                            netlog::inbound("state", bytes.len(), &format!(
+                                "tick {}{} ack {}  p{} pr{} m{} a{} pk{}",
+                                gs.tick, if gs.dl { " delta" } else { "" }, gs.ack,
+                                gs.p.len(), gs.pr.len(), gs.m.len(), gs.a.len(), gs.pk.len(),
+                            ));
+                        }
+                        let (state, phase) = (state_clone.clone(), phase_signal);
+                        netsim::route(gs, move |gs| handle_state(&state, &phase, gs));
+                    }
+                    Err(e) => bugreport::report_error("envelope", &format!("undecodable state ({} bytes): {}", bytes.len(), e)),
                 }
             } else if let Some(text) = data.as_string() {
-                if let Ok(env) = serde_json::from_str::<Envelope>(&text) {
-                    handle_message(&state_clone, &net_for_msg, phase_signal, sessions_signal, checked_signal, expired_signal, env);
+                replay::record_event(&text);
+                bugreport::record_envelope(&text);
+                match serde_json::from_str::<Envelope>(&text) {
+                    Ok(env) => {
+                        if netlog::enabled() {
+                            netlog::inbound(&env.t, text.len(), &env.d.as_ref().map(|d| d.to_string()).unwrap_or_default());
+                        }
+                        handle_message(&state_clone, &net_for_msg, phase_signal, sessions_signal, checked_signal, expired_signal, env)
+                    }
+                    Err(e) => bugreport::report_error("envelope", &format!("malformed envelope: {}", e)),
                 }
             }
         }) as Box<dyn FnMut(MessageEvent)>);
@@ -99,16 +142,12 @@ impl Network {
         let net_clone = net.clone();
         let on_close = Closure::wrap(Box::new(move |_: CloseEvent| {
             state_clone.borrow_mut().connected = false;
-            web_sys::console::log_1(&"WebSocket closed, reconnecting...".into());
-            let net_clone2 = net_clone.clone();
-            let _ = gloo_timers::callback::Timeout::new(crate::constants::RECONNECT_DELAY, move || {
-                Network::connect(&net_clone2);
-            });
+            Network::schedule_reconnect(&net_clone);
         }) as Box<dyn FnMut(CloseEvent)>);
 
         // on error
         let on_error = Closure::wrap(Box::new(move |e: ErrorEvent| {
-            web_sys::console::error_1(&format!("WebSocket error: {:?}", e.message()).into());
+            bugreport::error(&format!("WebSocket error: {:?}", e.message()));
         }) as Box<dyn FnMut(ErrorEvent)>);
 
         ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
@@ -124,14 +163,180 @@ impl Network {
         net_mut._on_error = Some(on_error);
     }
 
-    pub fn send_raw(net: &SharedNetwork, msg_type: &str, data: &serde_json::Value) {
-        let net_ref = net.borrow();
-        if let Some(ws) = &net_ref.ws {
-            if ws.ready_state() == 1 {
-                let env = serde_json::json!({"t": msg_type, "d": data});
-                let _ = ws.send_with_str(&env.to_string());
+    /// First messages on a fresh socket: attach as coach, resume our ship, or check the URL session
+    fn handshake(net: &SharedNetwork, state: &SharedState) {
+        // Coach mode: attach to the player instead of joining
+        let coach_target = state.borrow().coach_target.clone();
+        if let Some((sid, pid, key)) = coach_target {
+            Network::send_raw(net, "coach", &serde_json::json!({"sid": sid, "pid": pid, "ck": key}));
+            return;
+        }
+        // Reclaim our ship after a dropped connection or reload (falls back to check on resume_fail)
+        let url_sid = state.borrow().url_session_id.clone();
+        if let Some(t) = resume::load() {
+            if url_sid.is_none() || url_sid.as_deref() == Some(t.sid.as_str()) {
+                Network::send_raw(net, "resume", &serde_json::json!({"sid": t.sid, "pid": t.pid, "tok": t.tok}));
+                return;
             }
         }
+        // Check URL session if present
+        if let Some(sid) = url_sid {
+            Network::send_raw(net, "check", &serde_json::json!({"sid": sid}));
+        }
+    }
+
+    /// Send messages queued while disconnected, skipping any that went stale
+    fn flush_outbox(net: &SharedNetwork) {
+        let now = js_sys::Date::now();
+        let (ws, queued) = {
+            let mut n = net.borrow_mut();
+            let Some(ws) = n.ws.clone() else { return };
+            (ws, std::mem::take(&mut n.outbox))
+        };
+        let (mut sent, mut stale) = (0, 0);
+        for (at, msg) in queued {
+            if now - at > OUTBOX_MAX_AGE {
+                stale += 1;
+            } else if ws.send_with_str(&msg).is_ok() {
+                sent += 1;
+            }
+        }
+        if sent + stale > 0 {
+            bugreport::log(&format!("flushed {} queued messages ({} stale dropped)", sent, stale));
+        }
+    }
+
+    /// Queue the next connection attempt with exponential backoff (and a little
+    /// jitter so a server restart isn't hit by every client at once), or give up
+    /// after RECONNECT_MAX_RETRIES and leave it to the "Retry now" button.
+    fn schedule_reconnect(net: &SharedNetwork) {
+        let now = js_sys::Date::now();
+        let mut n = net.borrow_mut();
+        n.disconnected_at.get_or_insert(now);
+        n.reconnect_timer = None;
+        if n.reconnect_attempts >= RECONNECT_MAX_RETRIES {
+            bugreport::log("WebSocket closed, giving up after max retries");
+            n.reconnect_at = None;
+        } else {
+            let base = RECONNECT_BASE_DELAY * 2f64.powi(n.reconnect_attempts as i32);
+            let delay = base.min(RECONNECT_MAX_DELAY) * (0.8 + 0.4 * js_sys::Math::random());
+            n.reconnect_attempts += 1;
+            bugreport::log(&format!("WebSocket closed, reconnect attempt {} in {:.0}ms", n.reconnect_attempts, delay));
+            n.reconnect_at = Some(now + delay);
+            let net_retry = net.clone();
+            n.reconnect_timer = Some(gloo_timers::callback::Timeout::new(delay as u32, move || {
+                Network::connect(&net_retry);
+            }));
+        }
+        if n.banner_timer.is_none() {
+            let net_banner = net.clone();
+            n.banner_timer = Some(gloo_timers::callback::Interval::new(250, move || {
+                update_reconnect_banner(&net_banner.borrow());
+            }));
+        }
+        update_reconnect_banner(&n);
+    }
+
+    /// Ping the server, or drop a socket that has gone silent for HEARTBEAT_TIMEOUT
+    /// (a half-open connection can otherwise sit unnoticed for minutes)
+    pub fn heartbeat(net: &SharedNetwork) {
+        let (connected, last_rx) = {
+            let n = net.borrow();
+            let s = n.state.borrow();
+            (s.connected, s.last_rx)
+        };
+        if !connected {
+            return;
+        }
+        let now = js_sys::Date::now();
+        if now - last_rx > HEARTBEAT_TIMEOUT {
+            bugreport::log(&format!("no data for {:.0}ms, dropping connection", now - last_rx));
+            Network::drop_connection(net);
+            return;
+        }
+        Network::send_raw(net, "ping", &serde_json::json!({"t": now}));
+    }
+
+    /// Abandon the current socket without waiting for the close handshake,
+    /// which may never complete on a dead connection, and start reconnecting
+    fn drop_connection(net: &SharedNetwork) {
+        Network::close_socket(net);
+        Network::schedule_reconnect(net);
+    }
+
+    /// Detach and close the current socket so none of its handlers fire again
+    fn close_socket(net: &SharedNetwork) {
+        let ws = net.borrow_mut().ws.take();
+        if let Some(ws) = ws {
+            ws.set_onopen(None);
+            ws.set_onmessage(None);
+            ws.set_onclose(None);
+            ws.set_onerror(None);
+            let _ = ws.close();
+        }
+        net.borrow().state.borrow_mut().connected = false;
+    }
+
+    /// Reconnect to the region now selected in settings (lobby only: the
+    /// session list belongs to the old server, so it's cleared until the next refresh)
+    pub fn switch_endpoint(net: &SharedNetwork) {
+        Network::close_socket(net);
+        {
+            let mut n = net.borrow_mut();
+            n.outbox.clear();
+            n.sessions_signal.set(Vec::new());
+        }
+        Network::reset_backoff(net);
+        bugreport::log("switching region");
+        Network::connect(net);
+    }
+
+    /// Skip the backoff wait and reconnect immediately (also resets the retry count)
+    pub fn retry_now(net: &SharedNetwork) {
+        let busy = net.borrow().ws.as_ref().is_some_and(|ws| ws.ready_state() <= 1);
+        if busy {
+            return;
+        }
+        {
+            let mut n = net.borrow_mut();
+            n.reconnect_timer = None;
+            n.reconnect_attempts = 0;
+            n.reconnect_at = None;
+        }
+        bugreport::log("manual reconnect");
+        update_reconnect_banner(&net.borrow());
+        Network::connect(net);
+    }
+
+    fn reset_backoff(net: &SharedNetwork) {
+        let mut n = net.borrow_mut();
+        n.reconnect_attempts = 0;
+        n.disconnected_at = None;
+        n.reconnect_at = None;
+        n.reconnect_timer = None;
+        n.banner_timer = None;
+        update_reconnect_banner(&n);
+    }
+
+    /// Send a JSON message. While disconnected, the one-off actions in
+    /// OUTBOX_TYPES are queued for flush_outbox; everything else is dropped.
+    pub fn send_raw(net: &SharedNetwork, msg_type: &str, data: &serde_json::Value) {
+        let env = serde_json::json!({"t": msg_type, "d": data}).to_string();
+        if netlog::enabled() {
+            netlog::outbound(msg_type, env.len(), &data.to_string());
+        }
+        let mut net_ref = net.borrow_mut();
+        if let Some(ws) = net_ref.ws.as_ref().filter(|ws| ws.ready_state() == 1) {
+            let _ = ws.send_with_str(&env);
+            return;
+        }
+        if !OUTBOX_TYPES.contains(&msg_type) {
+            return;
+        }
+        if net_ref.outbox.len() >= OUTBOX_CAP {
+            net_ref.outbox.pop_front();
+        }
+        net_ref.outbox.push_back((js_sys::Date::now(), env));
     }
 
     pub fn send_binary(net: &SharedNetwork, data: &[u8]) {
@@ -149,96 +354,225 @@ impl Network {
         if s.phase != Phase::Playing || s.my_id.is_none() {
             return;
         }
-        if s.controller_attached {
+        if s.controller_attached || s.coach_target.is_some() || s.replay_mode || s.practice_mode || s.tab_hidden {
+            return;
+        }
+
+        let (mx, my, thresh) = input_target(&s);
+        drop(s);
+
+        let (fire, boost, dodge, seq) = {
+            let mut s = state.borrow_mut();
+            s.input_seq = s.input_seq.wrapping_add(1);
+            let seq = s.input_seq;
+            s.input_pending.push_back((seq, js_sys::Date::now()));
+            let dodge = std::mem::take(&mut s.dodge_pending);
+            if dodge {
+                // Dash locally right away rather than waiting a round trip
+                prediction::dodge(&mut s, mx, my);
+            }
+            (s.firing, s.boosting, dodge, seq)
+        };
+
+        let packet = InputPacket { mx, my, fire, boost, dodge, thresh, seq };
+        let buf = packet.encode();
+        if netlog::enabled() {
+            netlog::outbound("input", buf.len(), &format!("{:?}", packet));
+        }
+        Network::send_binary(net, &buf);
+    }
+
+    /// Tell the server how much of the world we can see, so it can cull what we can't
+    pub fn send_viewport(net: &SharedNetwork) {
+        let state = net.borrow().state.clone();
+        let mut s = state.borrow_mut();
+        if s.my_id.is_none() || s.replay_mode || s.practice_mode || s.coach_target.is_some() {
             return;
         }
+        let vw = (s.screen_w / s.cam_zoom).round();
+        let vh = (s.screen_h / s.cam_zoom).round();
+        let (sw, sh) = s.viewport_sent;
+        // Only resend on a meaningful change (resize, zoom), not every tick
+        if (vw - sw).abs() <= sw * 0.05 && (vh - sh).abs() <= sh * 0.05 {
+            return;
+        }
+        s.viewport_sent = (vw, vh);
+        drop(s);
+        Network::send_raw(net, "viewport", &serde_json::json!({"vw": vw, "vh": vh}));
+    }
 
+    /// Switch data saver on or off: persist it, start interpolating at the new
+    /// snapshot rate right away and, mid-match, ask the server for that rate
+    pub fn set_data_saver(net: &SharedNetwork, on: bool) {
+        let state = net.borrow().state.clone();
+        let mut s = state.borrow_mut();
+        if s.settings.data_saver == on {
+            return;
+        }
+        s.settings.data_saver = on;
+        s.settings.save();
+        s.bytes_since = 0.0; // the old rate's measurement no longer applies
+        let interval = if on { crate::constants::SAVER_INTERP_INTERVAL } else { crate::constants::INTERP_INTERVAL };
+        s.interp_interval = interval;
+        s.interp_delay = interval;
+        let in_game = s.my_id.is_some() && !s.replay_mode && !s.practice_mode;
+        drop(s);
+        if in_game {
+            Network::send_raw(net, "net_prefs", &serde_json::json!({"saver": on}));
+        }
+    }
+
+    /// Tell the server who the death cam is following, so snapshots are culled
+    /// around them rather than around our wreck
+    pub fn send_spectate(net: &SharedNetwork) {
+        let state = net.borrow().state.clone();
+        let mut s = state.borrow_mut();
+        if s.my_id.is_none() || s.replay_mode || s.practice_mode || s.coach_target.is_some() {
+            return;
+        }
+        let id = crate::spectate::target(&s).unwrap_or(0);
+        if id == s.spectate_sent {
+            return;
+        }
+        s.spectate_sent = id;
+        drop(s);
+        Network::send_raw(net, "spectate", &serde_json::json!({"id": id}));
+    }
+
+    /// Relay camera + cursor to an attached coach so they see exactly our view
+    pub fn send_view(net: &SharedNetwork) {
+        let state = net.borrow().state.clone();
+        let s = state.borrow();
+        if !s.coach_attached || s.my_id.is_none() || s.replay_mode || s.practice_mode || s.tab_hidden {
+            return;
+        }
+        if s.phase != Phase::Playing && s.phase != Phase::Dead {
+            return;
+        }
         let zoom = s.cam_zoom;
-        let mut mx = (s.mouse_x - s.screen_w / 2.0) / zoom + s.cam_x;
-        let mut my = (s.mouse_y - s.screen_h / 2.0) / zoom + s.cam_y;
-
-        // Mobile auto-aim (only when joystick is actively being used)
-        if s.is_mobile {
-            let jdx = s.mouse_x - s.screen_w / 2.0;
-            let jdy = s.mouse_y - s.screen_h / 2.0;
-            let jdist = (jdx * jdx + jdy * jdy).sqrt();
-
-            if jdist > 5.0 {
-                if let Some(my_id) = &s.my_id {
-                    if let Some(me) = s.players.get(my_id) {
-                        if me.a {
-                            let aim_angle = jdy.atan2(jdx);
-
-                            let orbit_r: f64 = 360.0;
-                            let detect_r: f64 = 150.0;
-                            let orbit_x = me.x + aim_angle.cos() * orbit_r;
-                            let orbit_y = me.y + aim_angle.sin() * orbit_r;
-
-                            let mut best_dist = detect_r * detect_r;
-                            let mut best_target: Option<(f64, f64)> = None;
-
-                            for (id, p) in &s.players {
-                                if Some(id) == s.my_id.as_ref() || !p.a { continue; }
-                                let dx = p.x - orbit_x;
-                                let dy = p.y - orbit_y;
-                                let d2 = dx * dx + dy * dy;
-                                if d2 <= best_dist {
-                                    best_dist = d2;
-                                    best_target = Some((p.x, p.y));
-                                }
-                            }
-                            for (_, m) in &s.mobs {
-                                if !m.a { continue; }
-                                let dx = m.x - orbit_x;
-                                let dy = m.y - orbit_y;
-                                let d2 = dx * dx + dy * dy;
-                                if d2 <= best_dist {
-                                    best_dist = d2;
-                                    best_target = Some((m.x, m.y));
-                                }
-                            }
-
-                            if let Some((tx, ty)) = best_target {
-                                mx = tx;
-                                my = ty;
-                            }
-                        }
-                    }
-                }
+        let (mx, my) = s.screen_to_world(s.mouse_x, s.mouse_y);
+        let data = serde_json::json!({
+            "vw": s.screen_w / zoom,
+            "vh": s.screen_h / zoom,
+            "mx": mx.round(),
+            "my": my.round(),
+            "f": s.firing,
+            "b": s.boosting,
+        });
+        drop(s);
+        Network::send_raw(net, "view", &data);
+    }
+
+    /// Send a coach annotation (flat world coords) to the coached player
+    pub fn send_mark(net: &SharedNetwork, kind: &str, pts: &[(f64, f64)]) {
+        let flat: Vec<f64> = pts.iter().flat_map(|&(x, y)| [x.round(), y.round()]).collect();
+        Network::send_raw(net, "mark", &serde_json::json!({"k": kind, "pts": flat}));
+    }
+
+    /// Feed a recorded snapshot through the live state pipeline (replay playback)
+    pub fn replay_state(net: &SharedNetwork, bytes: &[u8]) {
+        let (state, phase_signal) = {
+            let n = net.borrow();
+            (n.state.clone(), n.phase_signal)
+        };
+        if let Ok(gs) = rmp_serde::from_slice::<GameStateMsg>(bytes) {
+            handle_state(&state, &phase_signal, gs);
+        }
+    }
+
+    /// Feed a locally simulated snapshot through the live state pipeline (practice arena)
+    pub fn local_state(net: &SharedNetwork, gs: GameStateMsg) {
+        let (state, phase_signal) = {
+            let n = net.borrow();
+            (n.state.clone(), n.phase_signal)
+        };
+        handle_state(&state, &phase_signal, gs);
+    }
+
+    /// Feed a recorded event through the live message handler (replay playback).
+    /// Session control (welcome, joined, resume, coach...) is dropped so playback
+    /// never touches the URL, the resume token or the recorder.
+    pub fn replay_event(net: &SharedNetwork, text: &str) {
+        let (state, phase_signal, sessions_signal, checked_signal, expired_signal) = {
+            let n = net.borrow();
+            (n.state.clone(), n.phase_signal, n.sessions_signal, n.checked_signal, n.expired_signal)
+        };
+        if let Ok(env) = serde_json::from_str::<Envelope>(text) {
+            if replayable(&env.t) {
+                handle_message(&state, net, phase_signal, sessions_signal, checked_signal, expired_signal, env);
             }
         }
+    }
 
-        let thresh = s.screen_w.min(s.screen_h) / (8.0 * zoom);
+    /// Switch the view into replay playback as the recorded player
+    pub fn enter_replay(net: &SharedNetwork, my_id: u32, names: HashMap<u32, String>) {
+        let n = net.borrow();
+        let mut s = n.state.borrow_mut();
+        s.replay_mode = true;
+        s.my_id = Some(my_id);
+        s.names = names;
+        s.phase = Phase::Playing;
+        n.phase_signal.set(Phase::Playing);
+    }
 
-        // During hyperspace (shift), lock steering to rotation captured at shift press
-        if s.shift_pressed {
-            if let Some(locked_r) = s.hyperspace_locked_r {
-                if let Some(my_id) = &s.my_id {
-                    if let Some(me) = s.players.get(my_id) {
-                        mx = me.x + locked_r.cos() * 1000.0;
-                        my = me.y + locked_r.sin() * 1000.0;
-                    }
-                }
+    /// Switch the view into the offline practice arena
+    pub fn enter_practice(net: &SharedNetwork, my_id: u32) {
+        let n = net.borrow();
+        let mut s = n.state.borrow_mut();
+        s.practice_mode = true;
+        s.my_id = Some(my_id);
+        s.phase = Phase::Playing;
+        n.phase_signal.set(Phase::Playing);
+    }
+
+    /// The tab went into the background or came back. Hiding releases fire and
+    /// boost (one last input tells the server) and stops input; showing drops the
+    /// stale snapshot buffer so interpolation restarts from the next snapshot.
+    pub fn set_hidden(net: &SharedNetwork, hidden: bool) {
+        let state = net.borrow().state.clone();
+        if hidden {
+            {
+                let mut s = state.borrow_mut();
+                s.firing = false;
+                s.boosting = false;
+                s.shift_pressed = false;
             }
+            Network::send_input(net);
+            state.borrow_mut().tab_hidden = true;
+            return;
         }
-        drop(s);
+        {
+            let mut s = state.borrow_mut();
+            s.tab_hidden = false;
+            s.snapshots.clear();
+            s.interp_last_update = 0.0;
+            s.interp_jitter = 0.0;
+            s.input_pending.clear();
+            s.predicted = None;
+        }
+        crate::game_loop::wake();
+    }
 
-        let fire = state.borrow().firing;
-        let boost = state.borrow().boosting;
-
-        // Binary input: 8 bytes [0x01, mx_hi, mx_lo, my_hi, my_lo, flags, thresh_hi, thresh_lo]
-        let mx_i = mx.round() as i16;
-        let my_i = my.round() as i16;
-        let thresh_i = thresh.round().max(0.0).min(65535.0) as u16;
-        let flags: u8 = (if fire { 0x01 } else { 0 }) | (if boost { 0x02 } else { 0 });
-        let buf: [u8; 8] = [
-            0x01,
-            (mx_i as u16 >> 8) as u8, mx_i as u8,
-            (my_i as u16 >> 8) as u8, my_i as u8,
-            flags,
-            (thresh_i >> 8) as u8, thresh_i as u8,
-        ];
-        Network::send_binary(net, &buf);
+    /// Leave replay playback or the practice arena and return to the lobby
+    pub fn exit_replay(net: &SharedNetwork) {
+        let n = net.borrow();
+        let mut s = n.state.borrow_mut();
+        s.replay_mode = false;
+        s.practice_mode = false;
+        s.predicted = None;
+        s.my_id = None;
+        s.players.clear();
+        s.projectiles.clear();
+        s.mobs.clear();
+        s.snapshots.clear();
+        s.asteroids.clear();
+        s.pickups.clear();
+        s.grid.clear();
+        s.kill_feed.clear();
+        s.pings.clear();
+        s.death_info = None;
+        s.phase = Phase::Lobby;
+        n.phase_signal.set(Phase::Lobby);
     }
 
     pub fn list_sessions(net: &SharedNetwork) {
@@ -250,14 +584,33 @@ impl Network {
     }
 
     pub fn join_session(net: &SharedNetwork, name: &str, session_id: &str) {
-        Network::send_raw(net, "join", &serde_json::json!({"name": name, "sid": session_id}));
+        let ship = net.borrow().state.borrow().settings.ship;
+        Network::send_raw(net, "join", &serde_json::json!({"name": name, "sid": session_id, "ship": ship}));
+    }
+
+    /// Place a world ping, shown to everyone in the session
+    pub fn send_marker(net: &SharedNetwork, x: f64, y: f64) {
+        Network::send_raw(net, "marker", &serde_json::json!({"x": x, "y": y}));
+    }
+
+    /// Choose the hull we respawn in
+    pub fn pick_ship(net: &SharedNetwork, ship: i32) {
+        Network::send_raw(net, "ship_pick", &serde_json::json!({"ship": ship}));
     }
 
     pub fn send_leave(net: &SharedNetwork) {
+        resume::clear();
         Network::send_raw(net, "leave", &serde_json::json!({}));
     }
 }
 
+/// Envelopes that only change what is drawn, and so are safe to play back
+const REPLAYABLE: [&str; 9] = ["state", "hit", "names", "pings", "mob_say", "kill", "death", "marker", "mark"];
+
+fn replayable(t: &str) -> bool {
+    REPLAYABLE.contains(&t)
+}
+
 fn handle_message(
     state: &SharedState,
     net: &SharedNetwork,
@@ -277,10 +630,37 @@ fn handle_message(
         }
         "welcome" => {
             if let Ok(w) = serde_json::from_value::<WelcomeMsg>(data) {
+                bugreport::log(&format!("welcome: player {} ({}) ship {}", w.id, w.nid, w.s));
                 let mut s = state.borrow_mut();
-                s.my_id = Some(w.id);
+                // A resumed ship keeps recording into the same replay
+                if s.my_id != Some(w.nid) {
+                    replay::start(w.nid, &w.names);
+                }
+                if let Some(sid) = s.session_id.as_deref() {
+                    if !w.tok.is_empty() {
+                        resume::save(sid, &w.id, &w.tok);
+                    }
+                }
+                s.bytes_in = 0.0;
+                s.bytes_since = js_sys::Date::now();
+                s.input_pending.clear();
+                s.input_acked = s.input_seq;
+                s.viewport_sent = (0.0, 0.0);
+                s.spectate_sent = 0;
+                s.ghosts.clear();
+                if s.settings.data_saver {
+                    s.interp_interval = crate::constants::SAVER_INTERP_INTERVAL;
+                    s.interp_delay = crate::constants::SAVER_INTERP_INTERVAL;
+                    Network::send_raw(net, "net_prefs", &serde_json::json!({"saver": true}));
+                }
+                s.my_id = Some(w.nid);
+                s.my_pid = Some(w.id);
+                s.coach_key = Some(w.ck).filter(|k| !k.is_empty());
+                s.names = w.names;
                 s.my_ship = w.s;
+                announce::say(t(Msg::EnteredBattle));
                 s.phase = Phase::Playing;
+                crate::tutorial::start(&mut s);
                 phase_signal.set(Phase::Playing);
             }
         }
@@ -288,13 +668,16 @@ fn handle_message(
             if let Ok(j) = serde_json::from_value::<JoinedMsg>(data) {
                 let mut s = state.borrow_mut();
                 s.session_id = Some(j.sid.clone());
-                // Update URL
+                // Update URL (a resume may already be on it)
                 let window = web_sys::window().unwrap();
-                let _ = window.history().unwrap().push_state_with_url(
-                    &wasm_bindgen::JsValue::NULL,
-                    "",
-                    Some(&format!("{}{}", crate::app::base_path(), j.sid)),
-                );
+                let path = format!("{}{}", crate::app::base_path(), j.sid);
+                if window.location().pathname().ok().as_deref() != Some(path.as_str()) {
+                    let _ = window.history().unwrap().push_state_with_url(
+                        &wasm_bindgen::JsValue::NULL,
+                        "",
+                        Some(&path),
+                    );
+                }
             }
         }
         "created" => {
@@ -318,25 +701,46 @@ fn handle_message(
         "hit" => {
             if let Ok(h) = serde_json::from_value::<HitMsg>(data) {
                 let mut s = state.borrow_mut();
-                let my_id = s.my_id.clone();
+                let my_id = s.my_id;
 
                 // Damage number at hit position
-                effects::add_damage_number(&mut s, h.x, h.y, h.dmg, false);
+                let kind = if h.cr { DamageKind::Crit } else { DamageKind::Hit };
+                effects::add_damage_number(&mut s, h.aid, h.vid, h.x, h.y, h.dmg, kind);
+
+                // Chip asteroids right away, so cracks show before the next snapshot
+                if let Some(ast) = s.asteroids.get_mut(&h.vid) {
+                    let broke = ast.hp > 0 && ast.hp <= h.dmg;
+                    ast.hp = (ast.hp - h.dmg).max(0);
+                    if broke {
+                        let limit = s.settings.particle_limit();
+                        effects::add_asteroid_burst(&mut s.particles, limit, h.x, h.y);
+                    }
+                }
 
                 // Screen shake — bigger for victim
                 let shake_amount = (h.dmg as f64 / 10.0).min(6.0);
-                if my_id.as_deref() == Some(&h.vid) {
+                if my_id == Some(h.vid) {
                     effects::trigger_shake(&mut s, shake_amount * 1.5);
                 } else {
                     effects::trigger_shake(&mut s, shake_amount * 0.5);
                 }
 
                 // Hit marker if I'm the attacker
-                if my_id.as_deref() == Some(&h.aid) {
-                    effects::add_hit_marker(&mut s);
+                if my_id == Some(h.aid) {
+                    effects::add_hit_marker(&mut s, h.cr);
                 }
             }
         }
+        "names" => {
+            if let Ok(n) = serde_json::from_value::<NamesMsg>(data) {
+                state.borrow_mut().names = n.names;
+            }
+        }
+        "pings" => {
+            if let Ok(p) = serde_json::from_value::<PingsMsg>(data) {
+                state.borrow_mut().pings = p.p;
+            }
+        }
         "mob_say" => {
             if let Ok(ms) = serde_json::from_value::<MobSayMsg>(data) {
                 let mut s = state.borrow_mut();
@@ -346,31 +750,39 @@ fn handle_message(
         "kill" => {
             if let Ok(k) = serde_json::from_value::<KillMsg>(data) {
                 let mut s = state.borrow_mut();
+                s.ghosts.remove(&k.vid);
+                // Our own death is announced from "death"
+                if s.my_id == Some(k.kid) {
+                    announce::say(&tf(Msg::YouDestroyed, &k.vn));
+                } else if s.my_id != Some(k.vid) {
+                    announce::say(&format!("{}{}{}", k.kn, t(Msg::Killed), k.vn));
+                }
                 let now = web_sys::window().unwrap().performance().unwrap().now();
-                s.kill_feed.push(crate::state::KillFeedEntry {
+                crate::hud::push_kill(&mut s.kill_feed, crate::state::KillFeedEntry {
                     killer: k.kn,
+                    assist: k.an,
                     victim: k.vn.clone(),
+                    cause: k.c,
+                    environment: k.kid == 0,
+                    suicide: k.kid == k.vid,
                     time: now,
                 });
-                if s.kill_feed.len() > 5 {
-                    s.kill_feed.remove(0);
-                }
                 // Add explosion at victim location
                 let victim_pos = s.players.get(&k.vid).map(|p| (p.x, p.y))
                     .or_else(|| s.mobs.get(&k.vid).map(|m| (m.x, m.y)));
                 if let Some((vx, vy)) = victim_pos {
                     let mut particles = std::mem::take(&mut s.particles);
                     let mut explosions = std::mem::take(&mut s.explosions);
-                    effects::add_explosion(&mut particles, &mut explosions, vx, vy);
+                    effects::add_explosion(&mut particles, s.settings.particle_limit(), &mut explosions, vx, vy);
                     s.particles = particles;
                     s.explosions = explosions;
                 }
 
                 // Screen shake on kills
-                let my_id = s.my_id.clone();
-                if my_id.as_deref() == Some(k.kid.as_str()) {
+                let my_id = s.my_id;
+                if my_id == Some(k.kid) {
                     effects::trigger_shake(&mut s, 8.0); // I got a kill
-                } else if my_id.as_deref() == Some(k.vid.as_str()) {
+                } else if my_id == Some(k.vid) {
                     effects::trigger_shake(&mut s, 12.0); // I died
                 } else {
                     effects::trigger_shake(&mut s, 3.0); // nearby kill
@@ -379,9 +791,14 @@ fn handle_message(
         }
         "death" => {
             if let Ok(d) = serde_json::from_value::<DeathMsg>(data) {
+                bugreport::log(&format!("death: killed by {}", d.kn));
+                announce::say(&tf(Msg::YouWereDestroyed, &d.kn));
                 let mut s = state.borrow_mut();
-                s.death_info = Some(crate::state::DeathInfo { killer_name: d.kn });
+                let now = web_sys::window().unwrap().performance().unwrap().now();
+                let respawn = (d.rt > 0.0).then_some((now + d.rt * 1000.0, d.rt * 1000.0));
+                s.death_info = Some(crate::state::DeathInfo { killer_name: d.kn, spectating: None, respawn });
                 s.phase = Phase::Dead;
+                crate::spectate::start(&mut s, d.kid);
                 phase_signal.set(Phase::Dead);
             }
         }
@@ -411,33 +828,161 @@ fn handle_message(
         "ctrl_off" => {
             state.borrow_mut().controller_attached = false;
         }
+        "coach_ok" => {
+            let mut s = state.borrow_mut();
+            if let (Some((sid, pid, _)), Ok(ok)) = (s.coach_target.clone(), serde_json::from_value::<AttachOkMsg>(data)) {
+                s.my_id = Some(ok.nid);
+                s.my_pid = Some(pid);
+                s.names = ok.names;
+                s.session_id = Some(sid);
+                s.phase = Phase::Playing;
+                phase_signal.set(Phase::Playing);
+            }
+        }
+        "coach_on" => {
+            state.borrow_mut().coach_attached = true;
+            if let Some(overlay) = web_sys::window().unwrap().document().unwrap()
+                .get_element_by_id("coachOverlay") {
+                let _ = overlay.class_list().remove_1("visible");
+            }
+        }
+        "coach_off" => {
+            let mut s = state.borrow_mut();
+            if s.coach_target.is_some() {
+                // The player we were watching left the session, or another coach took over
+                s.my_id = None;
+                s.my_pid = None;
+                s.coach_view = None;
+                s.phase = Phase::Lobby;
+                phase_signal.set(Phase::Lobby);
+            } else {
+                s.coach_attached = false;
+                s.coach_marks.clear();
+            }
+        }
+        "view" => {
+            if let Ok(v) = serde_json::from_value::<ViewMsg>(data) {
+                let mut s = state.borrow_mut();
+                // Fit the player's visible world area onto our screen
+                if v.vw > 0.0 && v.vh > 0.0 {
+                    s.cam_zoom = (s.screen_w / v.vw).min(s.screen_h / v.vh);
+                }
+                s.boosting = v.b;
+                s.shift_pressed = v.b;
+                s.coach_view = Some(crate::state::CoachView {
+                    vw: v.vw,
+                    vh: v.vh,
+                    mx: v.mx,
+                    my: v.my,
+                    firing: v.f,
+                    boosting: v.b,
+                });
+            }
+        }
+        "mark" => {
+            if let Ok(m) = serde_json::from_value::<MarkMsg>(data) {
+                let kind = if m.k == "ping" { CoachMarkKind::Ping } else { CoachMarkKind::Line };
+                let pts = m.pts.chunks_exact(2).map(|c| (c[0], c[1])).collect();
+                coach::add_mark(&mut state.borrow_mut(), kind, pts);
+            }
+        }
+        "resume_fail" => {
+            bugreport::log("resume rejected, joining normally");
+            resume::clear();
+            let mut s = state.borrow_mut();
+            if s.phase != Phase::Lobby {
+                // Our ship is gone: back to the lobby
+                s.session_id = None;
+                s.my_id = None;
+                s.my_pid = None;
+                s.phase = Phase::Lobby;
+                phase_signal.set(Phase::Lobby);
+            }
+            if let Some(sid) = s.url_session_id.clone() {
+                drop(s);
+                Network::send_raw(net, "check", &serde_json::json!({"sid": sid}));
+            }
+        }
+        "marker" => {
+            if let Ok(m) = serde_json::from_value::<MarkerMsg>(data) {
+                crate::markers::add_marker(&mut state.borrow_mut(), m.x, m.y, m.n);
+            }
+        }
+        "pong" => {
+            let now = js_sys::Date::now();
+            state.borrow_mut().last_rx = now;
+        }
+        "bug_ok" => bugreport::on_sent(),
         "error" => {
             if let Ok(e) = serde_json::from_value::<ErrorMsg>(data) {
-                web_sys::console::error_1(&format!("Server error: {}", e.msg).into());
+                bugreport::error(&format!("Server error: {}", e.msg));
+                bugreport::on_server_error(&e.msg);
             }
         }
-        _ => {}
+        other => bugreport::report_error("envelope", &format!("unknown message type {:?}", other)),
     }
 }
 
-fn handle_state(state: &SharedState, phase_signal: &leptos::prelude::RwSignal<Phase>, gs: GameStateMsg) {
-    let mut s = state.borrow_mut();
+/// World-space steering target + slow-down threshold for the current input
+pub fn input_target(s: &GameState) -> (f64, f64, f64) {
+    let zoom = s.cam_zoom;
+    let (mut mx, mut my) = s.screen_to_world(s.mouse_x, s.mouse_y);
+
+    // Mobile auto-aim (only when joystick is actively being used)
+    if s.is_mobile {
+        let jdx = s.mouse_x - s.screen_w / 2.0;
+        let jdy = s.mouse_y - s.screen_h / 2.0;
+        let jdist = (jdx * jdx + jdy * jdy).sqrt();
 
-    // Save current→prev for interpolation (swap reuses allocations)
-    let mut tmp_players = std::mem::take(&mut s.prev_players);
-    tmp_players.clear();
-    std::mem::swap(&mut tmp_players, &mut s.players);
-    s.prev_players = tmp_players;
+        if jdist > 5.0 {
+            if let Some(my_id) = &s.my_id {
+                if let Some(me) = s.players.get(my_id) {
+                    if me.a {
+                        let aim_angle = jdy.atan2(jdx);
+                        let orbit_x = me.x + aim_angle.cos() * crate::auto_aim::AIM_ORBIT_R;
+                        let orbit_y = me.y + aim_angle.sin() * crate::auto_aim::AIM_ORBIT_R;
+                        if let Some((tx, ty)) = crate::auto_aim::touch_target(s, orbit_x, orbit_y) {
+                            mx = tx;
+                            my = ty;
+                        }
+                    }
+                }
+            }
+        }
+    }
 
-    let mut tmp_mobs = std::mem::take(&mut s.prev_mobs);
-    tmp_mobs.clear();
-    std::mem::swap(&mut tmp_mobs, &mut s.mobs);
-    s.prev_mobs = tmp_mobs;
+    let thresh = s.screen_w.min(s.screen_h) / (8.0 * zoom);
 
-    s.prev_cam_x = s.cam_x;
-    s.prev_cam_y = s.cam_y;
+    // Keyboard steering: aim along the key-driven heading, past the server's
+    // thrust dead zone only while thrusting (close in, it just turns the ship)
+    if let Some(heading) = s.steer_heading {
+        if let Some(me) = s.my_id.as_ref().and_then(|id| s.players.get(id)) {
+            let (x, y) = prediction::camera(s).unwrap_or((me.x, me.y));
+            let dist = if s.thrusting { 1000.0 } else { 20.0 };
+            mx = x + heading.cos() * dist;
+            my = y + heading.sin() * dist;
+        }
+    }
 
-    // Record timing for interpolation
+    // During hyperspace (shift), lock steering to rotation captured at shift press
+    if s.shift_pressed {
+        if let Some(locked_r) = s.hyperspace_locked_r {
+            if let Some(my_id) = &s.my_id {
+                if let Some(me) = s.players.get(my_id) {
+                    mx = me.x + locked_r.cos() * 1000.0;
+                    my = me.y + locked_r.sin() * 1000.0;
+                }
+            }
+        }
+    }
+
+    (mx, my, thresh)
+}
+
+fn handle_state(state: &SharedState, phase_signal: &leptos::prelude::RwSignal<Phase>, gs: GameStateMsg) {
+    let mut s = state.borrow_mut();
+
+    // Record timing and jitter for the interpolation delay
     let now = web_sys::window().unwrap().performance().unwrap().now();
     if s.interp_last_update > 0.0 {
         let elapsed = now - s.interp_last_update;
@@ -445,46 +990,81 @@ fn handle_state(state: &SharedState, phase_signal: &leptos::prelude::RwSignal<Ph
         if elapsed > 10.0 && elapsed < 200.0 {
             s.interp_interval = s.interp_interval * 0.8 + elapsed * 0.2;
         }
+        let deviation = (elapsed - s.interp_interval).abs().min(MAX_INTERP_DELAY);
+        s.interp_jitter = s.interp_jitter * 0.9 + deviation * 0.1;
+        // One interval keeps a snapshot pair to lerp between; jitter headroom absorbs late packets
+        let target = (s.interp_interval + s.interp_jitter * INTERP_JITTER_MUL).min(MAX_INTERP_DELAY);
+        let rate = if target > s.interp_delay { 0.2 } else { 0.02 }; // grow fast, shrink slowly
+        s.interp_delay += (target - s.interp_delay) * rate;
+        if let Some(ms) = s.interp_override {
+            s.interp_delay = ms;
+        }
+        crate::console::record_gap(&mut s, elapsed);
     }
     s.interp_last_update = now;
 
-    // Update current state, merging delta-compressed velocity
-    s.players.clear();
+    // Update current state, merging delta-compressed velocity from the previous snapshot.
+    // Full snapshots replace everything; deltas only carry changed entities plus removals.
+    crate::inspector::note_update(&mut s, &gs);
+    let me_before = s.my_id.and_then(|id| s.players.get(&id)).map(|p| (p.a, p.hp));
+    // Pickups that vanish next to our ship were collected by us
+    let collected: Vec<(f64, f64)> = match s.my_id.and_then(|id| s.players.get(&id)).filter(|p| p.a) {
+        Some(me) => s.pickups.values()
+            .filter(|pk| if gs.dl { gs.rm.contains(&pk.id) } else { !gs.pk.iter().any(|n| n.id == pk.id) })
+            .map(|pk| (pk.x, pk.y))
+            .filter(|&pk| effects::picked_up(pk, (me.x, me.y)))
+            .collect(),
+        None => Vec::new(),
+    };
+    if !gs.dl {
+        s.players.clear();
+        s.projectiles.clear();
+        s.mobs.clear();
+        s.asteroids.clear();
+        s.pickups.clear();
+    }
+    for id in &gs.rm {
+        s.players.remove(id);
+        s.projectiles.remove(id);
+        s.mobs.remove(id);
+        s.asteroids.remove(id);
+        s.pickups.remove(id);
+    }
+
     for mut p in gs.p {
         // If velocity was omitted (delta compression), carry forward from prev
         if p.vx.is_none() || p.vy.is_none() {
-            if let Some(prev) = s.prev_players.get(&p.id) {
+            if let Some(prev) = s.snapshots.back().and_then(|snap| snap.players.get(&p.id)) {
                 if p.vx.is_none() { p.vx = prev.vx; }
                 if p.vy.is_none() { p.vy = prev.vy; }
             }
         }
-        s.players.insert(p.id.clone(), p);
+        s.players.insert(p.id, p);
     }
 
-    s.projectiles.clear();
     for pr in gs.pr {
-        s.projectiles.insert(pr.id.clone(), pr);
+        s.projectiles.insert(pr.id, pr);
     }
 
-    s.mobs.clear();
     for mut m in gs.m {
         if m.vx.is_none() || m.vy.is_none() {
-            if let Some(prev) = s.prev_mobs.get(&m.id) {
+            if let Some(prev) = s.snapshots.back().and_then(|snap| snap.mobs.get(&m.id)) {
                 if m.vx.is_none() { m.vx = prev.vx; }
                 if m.vy.is_none() { m.vy = prev.vy; }
             }
         }
-        s.mobs.insert(m.id.clone(), m);
+        s.mobs.insert(m.id, m);
     }
 
-    s.asteroids.clear();
     for a in gs.a {
-        s.asteroids.insert(a.id.clone(), a);
+        s.asteroids.insert(a.id, a);
     }
 
-    s.pickups.clear();
     for pk in gs.pk {
-        s.pickups.insert(pk.id.clone(), pk);
+        s.pickups.insert(pk.id, pk);
+    }
+    for (x, y) in collected {
+        effects::add_pickup_pull(&mut s, x, y);
     }
 
     s.tick = gs.tick;
@@ -496,8 +1076,12 @@ fn handle_state(state: &SharedState, phase_signal: &leptos::prelude::RwSignal<Ph
             let me_y = me.y;
             let me_alive = me.a;
             let me_boosting = me.b;
+            let healed = effects::heal_gain(me_before, (me.a, me.hp));
             s.cam_x = me_x;
             s.cam_y = me_y;
+            if let Some(amount) = healed {
+                effects::add_heal(&mut s, me_x, me_y, amount);
+            }
 
             // When controller is attached, sync boost visual from server state
             if s.controller_attached {
@@ -509,10 +1093,124 @@ fn handle_state(state: &SharedState, phase_signal: &leptos::prelude::RwSignal<Ph
                 s.phase = Phase::Dead;
                 phase_signal.set(Phase::Dead);
             } else if me_alive && s.phase == Phase::Dead {
+                announce::say(t(Msg::Respawned));
                 s.phase = Phase::Playing;
                 s.death_info = None;
                 phase_signal.set(Phase::Playing);
             }
         }
     }
+    // Dead: follow the killer (or whoever was picked) instead of our wreck
+    crate::spectate::update_camera(&mut s);
+
+    if !s.replay_mode {
+        apply_input_ack(&mut s, gs.ack);
+    }
+    update_ghosts(&mut s, js_sys::Date::now());
+    crate::spatial::rebuild(&mut s);
+    push_snapshot(&mut s, now);
+    prediction::reconcile(&mut s);
+}
+
+/// Remember where ships and mobs were when they dropped out of our snapshots (minimap).
+/// Only those last seen off screen count: they left our interest area. One that
+/// vanishes in plain view was destroyed or despawned, and has nowhere to be.
+fn update_ghosts(s: &mut GameState, now: f64) {
+    let view = (s.screen_w / s.cam_zoom, s.screen_h / s.cam_zoom);
+    let GameState { ghosts, players, mobs, snapshots, my_id, .. } = s;
+    ghosts.retain(|id, g| now - g.time < GHOST_TTL && !players.contains_key(id) && !mobs.contains_key(id));
+    let prev = match snapshots.back() {
+        Some(p) => p,
+        None => return,
+    };
+    let off_screen = |x: f64, y: f64| {
+        (x - prev.cam_x).abs() > view.0 / 2.0 || (y - prev.cam_y).abs() > view.1 / 2.0
+    };
+    for (id, p) in &prev.players {
+        if p.a && !players.contains_key(id) && my_id.as_ref() != Some(id) && off_screen(p.x, p.y) {
+            ghosts.insert(*id, Ghost { x: p.x, y: p.y, ship: p.s, mob: false, time: now });
+        }
+    }
+    for (id, m) in &prev.mobs {
+        if m.a && !mobs.contains_key(id) && off_screen(m.x, m.y) {
+            ghosts.insert(*id, Ghost { x: m.x, y: m.y, ship: m.s, mob: true, time: now });
+        }
+    }
+}
+
+/// Sequence `a` is newer than `b`, allowing for u16 wraparound
+fn seq_newer(a: u16, b: u16) -> bool {
+    (a.wrapping_sub(b) as i16) > 0
+}
+
+/// Retire inputs the server has applied; anything left unacknowledged too long counts as dropped
+fn apply_input_ack(s: &mut GameState, ack: u16) {
+    let now = js_sys::Date::now();
+    if seq_newer(ack, s.input_acked) {
+        s.input_acked = ack;
+    }
+    while let Some(&(seq, sent)) = s.input_pending.front() {
+        if seq_newer(seq, s.input_acked) {
+            if now - sent > INPUT_ACK_TIMEOUT {
+                s.inputs_dropped += 1;
+                s.input_pending.pop_front();
+                continue;
+            }
+            break;
+        }
+        if seq == s.input_acked {
+            let rtt = now - sent;
+            s.input_rtt = if s.input_rtt > 0.0 { s.input_rtt * 0.9 + rtt * 0.1 } else { rtt };
+        }
+        s.input_pending.pop_front();
+    }
 }
+
+/// Copy the interpolated entities into the snapshot ring (recycles the oldest entry's maps)
+fn push_snapshot(s: &mut GameState, now: f64) {
+    let mut snap = if s.snapshots.len() >= SNAPSHOT_BUFFER {
+        s.snapshots.pop_front().unwrap_or_default()
+    } else {
+        Snapshot::default()
+    };
+    snap.time = now;
+    snap.players.clear();
+    snap.players.extend(s.players.iter().map(|(k, v)| (*k, v.clone())));
+    snap.mobs.clear();
+    snap.mobs.extend(s.mobs.iter().map(|(k, v)| (*k, v.clone())));
+    snap.cam_x = s.cam_x;
+    snap.cam_y = s.cam_y;
+    s.snapshots.push_back(snap);
+}
+
+/// Show, refresh or hide the reconnect banner to match the backoff state
+fn update_reconnect_banner(net: &Network) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(banner) = document.get_element_by_id("reconnectBanner") else { return };
+    let Some(since) = net.disconnected_at else {
+        let _ = banner.class_list().remove_1("visible");
+        return;
+    };
+    let now = js_sys::Date::now();
+    let elapsed = ((now - since) / 1000.0).floor() as u64;
+    let offline = if elapsed >= 60 {
+        format!("{}m {:02}s", elapsed / 60, elapsed % 60)
+    } else {
+        format!("{}s", elapsed)
+    };
+    let text = match net.reconnect_at {
+        Some(at) if at > now => format!(
+            "Connection lost {} ago \u{2014} retrying in {}s (attempt {}/{})",
+            offline, ((at - now) / 1000.0).ceil(), net.reconnect_attempts, RECONNECT_MAX_RETRIES,
+        ),
+        Some(_) => format!("Connection lost {} ago \u{2014} reconnecting\u{2026}", offline),
+        None => format!("Connection lost {} ago \u{2014} couldn't reach the server", offline),
+    };
+    if let Some(el) = document.get_element_by_id("reconnectText") {
+        el.set_text_content(Some(&text));
+    }
+    let _ = banner.class_list().add_1("visible");
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,71 @@
+use super::*;
+use crate::camera;
+
+#[test]
+fn input_target_follows_the_drawn_view() {
+    let mut s = GameState::new();
+    s.screen_w = 800.0;
+    s.screen_h = 600.0;
+    s.cam_zoom = 2.0;
+    // The ship has moved on but the smoothed view still lags behind it
+    (s.cam_x, s.cam_y) = (1000.0, 1000.0);
+    camera::follow(&mut s.view, 900.0, 950.0, 0.0, 0.0, 1.0 / 60.0);
+    (s.mouse_x, s.mouse_y) = (600.0, 300.0);
+
+    let (mx, my, _) = input_target(&s);
+    assert_eq!((mx, my), (1000.0, 950.0));
+    assert_eq!((mx, my), s.screen_to_world(s.mouse_x, s.mouse_y));
+}
+
+fn ship(id: u32, x: f64, y: f64) -> PlayerState {
+    serde_json::from_value(serde_json::json!({
+        "id": id, "x": x, "y": y, "r": 0.0, "vx": 0.0, "vy": 0.0,
+        "hp": 100, "mhp": 100, "s": 1, "sc": 0, "a": true,
+    })).unwrap()
+}
+
+/// State with ships 2 and 3 in the last snapshot, camera on (1000, 1000)
+/// showing 800x600 of the world, and both gone from the latest update
+fn lost_two_ships() -> GameState {
+    let mut s = GameState::new();
+    (s.screen_w, s.screen_h, s.cam_zoom) = (800.0, 600.0, 1.0);
+    let mut snap = Snapshot { cam_x: 1000.0, cam_y: 1000.0, ..Default::default() };
+    snap.players.insert(2, ship(2, 1100.0, 1050.0)); // on screen
+    snap.players.insert(3, ship(3, 1500.0, 1000.0)); // past the right edge
+    s.snapshots.push_back(snap);
+    s
+}
+
+#[test]
+fn only_ships_lost_off_screen_leave_ghosts() {
+    let mut s = lost_two_ships();
+    update_ghosts(&mut s, 0.0);
+    assert!(!s.ghosts.contains_key(&2), "a ship vanishing in view was destroyed, not culled");
+    assert!(s.ghosts.contains_key(&3));
+}
+
+#[test]
+fn ghosts_clear_when_the_ship_returns_or_expires() {
+    let mut s = lost_two_ships();
+    update_ghosts(&mut s, 0.0);
+    s.snapshots.clear();
+    s.players.insert(3, ship(3, 1400.0, 1000.0));
+    update_ghosts(&mut s, 100.0);
+    assert!(s.ghosts.is_empty());
+
+    let mut s = lost_two_ships();
+    update_ghosts(&mut s, 0.0);
+    s.snapshots.clear();
+    update_ghosts(&mut s, GHOST_TTL);
+    assert!(s.ghosts.is_empty());
+}
+
+#[test]
+fn playback_skips_session_control() {
+    for t in ["state", "hit", "kill", "death", "mob_say"] {
+        assert!(replayable(t), "{t} should play back");
+    }
+    for t in ["welcome", "joined", "created", "resume_fail", "coach_ok", "coach_off", "ctrl_on", "checked", "error"] {
+        assert!(!replayable(t), "{t} should not play back");
+    }
+}
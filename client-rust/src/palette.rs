@@ -0,0 +1,112 @@
+// Color-vision setting: picks which of the palettes in constants.rs the ships,
+// lasers, engine glow, health bars, HUD and minimap draw with. Applied at
+// startup and whenever the setting changes.
+
+use std::cell::Cell;
+use serde::{Deserialize, Serialize};
+use crate::constants::{Palette, PALETTE_NORMAL, PALETTE_DEUTERANOPIA, PALETTE_PROTANOPIA};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Vision {
+    Normal,
+    Deuteranopia,
+    Protanopia,
+}
+
+pub const VISIONS: [(Vision, &str); 3] = [
+    (Vision::Normal, "Normal"),
+    (Vision::Deuteranopia, "Deuteranopia"),
+    (Vision::Protanopia, "Protanopia"),
+];
+
+impl Vision {
+    pub fn parse(v: &str) -> Vision {
+        match v {
+            "deuteranopia" => Vision::Deuteranopia,
+            "protanopia" => Vision::Protanopia,
+            _ => Vision::Normal,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Vision::Normal => "normal",
+            Vision::Deuteranopia => "deuteranopia",
+            Vision::Protanopia => "protanopia",
+        }
+    }
+
+    fn palette(self) -> &'static Palette {
+        match self {
+            Vision::Normal => &PALETTE_NORMAL,
+            Vision::Deuteranopia => &PALETTE_DEUTERANOPIA,
+            Vision::Protanopia => &PALETTE_PROTANOPIA,
+        }
+    }
+}
+
+thread_local! {
+    static VISION: Cell<Vision> = const { Cell::new(Vision::Normal) };
+}
+
+pub fn set_vision(vision: Vision) {
+    VISION.with(|v| v.set(vision));
+}
+
+fn current() -> &'static Palette {
+    VISION.with(|v| v.get()).palette()
+}
+
+fn idx(ship_type: i32) -> usize {
+    (ship_type.max(0) as usize).min(PALETTE_NORMAL.ships.len() - 1)
+}
+
+pub fn ship(ship_type: i32) -> &'static str {
+    current().ships[idx(ship_type)]
+}
+
+pub fn laser(ship_type: i32) -> &'static str {
+    current().lasers[idx(ship_type)]
+}
+
+pub fn engine(ship_type: i32) -> &'static str {
+    current().engines[idx(ship_type)]
+}
+
+fn by_ratio(colors: &[&'static str; 3], ratio: f64) -> &'static str {
+    if ratio > 0.6 { colors[0] } else if ratio > 0.3 { colors[1] } else { colors[2] }
+}
+
+/// Player health bar color for an hp fraction
+pub fn health(ratio: f64) -> &'static str {
+    by_ratio(&current().health, ratio)
+}
+
+pub fn mob_health(ratio: f64) -> &'static str {
+    by_ratio(&current().mob_health, ratio)
+}
+
+/// Floating heal/damage numbers: the full- and low-health colors
+pub fn heal_damage(is_heal: bool) -> &'static str {
+    let h = &current().health;
+    if is_heal { h[0] } else { h[2] }
+}
+
+/// Critical hit numbers and hit markers
+pub fn crit() -> &'static str {
+    current().crit
+}
+
+/// Mobs on the minimap, ghost layer and controller radar
+pub fn mob() -> &'static str {
+    current().mob
+}
+
+/// Bad news: kill feed victims, the death screen, target lock rings
+pub fn danger() -> &'static str {
+    current().danger
+}
+
+#[cfg(test)]
+mod tests;
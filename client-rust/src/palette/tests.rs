@@ -0,0 +1,9 @@
+use super::*;
+
+#[test]
+fn mob_color_differs_from_every_hull() {
+    for (vision, name) in VISIONS {
+        let p = vision.palette();
+        assert!(!p.ships.contains(&p.mob), "{name}: mob dots share a hull color");
+    }
+}
@@ -1,17 +1,16 @@
-use std::collections::HashMap;
 use web_sys::CanvasRenderingContext2d;
 use crate::constants::PICKUP_RENDER_SIZE;
 use crate::protocol::PickupState;
 
-pub fn render_pickups(
+pub fn render_pickups<'a>(
     ctx: &CanvasRenderingContext2d,
-    pickups: &HashMap<String, PickupState>,
+    pickups: impl Iterator<Item = &'a PickupState>,
     offset_x: f64, offset_y: f64, vw: f64, vh: f64,
     time: f64,
 ) {
     let size = PICKUP_RENDER_SIZE * 2.5; // 2.5x larger radius
 
-    for (_, pk) in pickups {
+    for pk in pickups {
         let sx = pk.x - offset_x;
         let sy = pk.y - offset_y;
         if sx < -size - 20.0 || sx > vw + size + 20.0 || sy < -size - 20.0 || sy > vh + size + 20.0 { continue; }
@@ -0,0 +1,64 @@
+// Slot pool for short-lived effects (particles, shockwaves, damage numbers).
+// The first `live` slots are active; dead ones stay allocated past them and
+// get overwritten by the next spawn, so steady-state play allocates nothing.
+
+pub struct Pool<T> {
+    slots: Vec<T>,
+    live: usize,
+}
+
+impl<T: Default> Pool<T> {
+    pub fn with_capacity(n: usize) -> Self {
+        Pool { slots: Vec::with_capacity(n), live: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.slots[..self.live]
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.slots[..self.live].iter_mut()
+    }
+
+    /// Claim a slot, recycling a dead one when there is one. The caller
+    /// overwrites every field it cares about; leftovers are from the old occupant.
+    pub fn spawn(&mut self) -> &mut T {
+        if self.live == self.slots.len() {
+            self.slots.push(T::default());
+        }
+        self.live += 1;
+        &mut self.slots[self.live - 1]
+    }
+
+    /// Keep the slots `f` returns true for (it may update them as it goes).
+    /// Order is not preserved: a freed slot swaps with the last live one.
+    pub fn retain_mut(&mut self, mut f: impl FnMut(&mut T) -> bool) {
+        let mut i = 0;
+        while i < self.live {
+            if f(&mut self.slots[i]) {
+                i += 1;
+            } else {
+                self.live -= 1;
+                self.slots.swap(i, self.live);
+            }
+        }
+    }
+
+    pub fn truncate(&mut self, n: usize) {
+        self.live = self.live.min(n);
+    }
+
+    pub fn clear(&mut self) {
+        self.live = 0;
+    }
+}
+
+impl<T: Default> Default for Pool<T> {
+    fn default() -> Self {
+        Pool::with_capacity(0)
+    }
+}
@@ -0,0 +1,246 @@
+// Offline practice arena: a client-only stand-in for the server. Our ship flies
+// on the same movement model as prediction.rs, lasers and a handful of target
+// drones are simulated here, and the result goes through the normal snapshot
+// and event handlers, so it renders exactly like a real battle. No WebSocket.
+
+use std::cell::RefCell;
+use crate::network::{Network, SharedNetwork};
+use crate::prediction::{self, PredictedShip};
+use crate::protocol::{GameStateMsg, MobState, PlayerState, ProjectileState};
+use crate::constants::{MOB_RADIUS, PROJ_LASER, SHIP_STATS, WORLD_W, WORLD_H};
+
+const MY_ID: u32 = 1; // drones take the next BOT_COUNT IDs, lasers the ones after
+const SNAPSHOT_MS: f64 = 50.0; // 20 Hz, like the server
+const BOT_COUNT: usize = 6;
+const BOT_HP: i32 = 60;
+const BOT_SHIP: i32 = 4; // TIE fighter
+const BOT_RESPAWN: f64 = 3.0; // seconds
+const ARENA_R: f64 = 600.0; // bots spawn within this distance of the start
+
+// Lasers (must match server projectile.go)
+const LASER_SPEED: f64 = 800.0;
+const LASER_LIFE: f64 = 2.0;
+const LASER_RADIUS: f64 = 4.0;
+const LASER_DAMAGE: i32 = 20;
+const LASER_OFFSET: f64 = 30.0;
+
+struct Laser {
+    id: u32,
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    life: f64,
+}
+
+struct Bot {
+    id: u32,
+    x: f64,
+    y: f64,
+    r: f64,
+    vx: f64,
+    vy: f64,
+    hp: i32,
+    orbit: Option<(f64, f64, f64, f64)>, // (center x, center y, radius, angular speed); None = parked
+    angle: f64,
+    respawn_in: f64, // > 0 while destroyed
+}
+
+impl Bot {
+    fn spawn(id: u32, home_x: f64, home_y: f64) -> Bot {
+        let rand = js_sys::Math::random;
+        let a = rand() * std::f64::consts::TAU;
+        let d = 200.0 + rand() * (ARENA_R - 200.0);
+        let (x, y) = (home_x + a.cos() * d, home_y + a.sin() * d);
+        // Every other drone circles slowly; the rest sit still
+        let orbit = (rand() < 0.5).then(|| (x, y, 80.0 + rand() * 120.0, (0.4 + rand() * 0.6) * if rand() < 0.5 { 1.0 } else { -1.0 }));
+        Bot { id, x, y, r: rand() * std::f64::consts::TAU, vx: 0.0, vy: 0.0, hp: BOT_HP, orbit, angle: 0.0, respawn_in: 0.0 }
+    }
+
+    fn update(&mut self, dt: f64) {
+        let Some((cx, cy, radius, w)) = self.orbit else { return };
+        self.angle += w * dt;
+        let (x, y) = (cx + self.angle.cos() * radius, cy + self.angle.sin() * radius);
+        self.vx = (x - self.x) / dt.max(1e-6);
+        self.vy = (y - self.y) / dt.max(1e-6);
+        self.r = self.vy.atan2(self.vx);
+        self.x = x;
+        self.y = y;
+    }
+}
+
+struct Arena {
+    net: SharedNetwork,
+    name: String,
+    ship: PredictedShip,
+    ship_type: i32,
+    fire_cd: f64,
+    lasers: Vec<Laser>,
+    bots: Vec<Bot>,
+    home: (f64, f64),
+    score: i32,
+    next_id: u32,
+    since_snapshot: f64, // ms
+    tick: u64,
+}
+
+impl Arena {
+    fn next_id(&mut self) -> u32 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+thread_local! {
+    static ARENA: RefCell<Option<Arena>> = const { RefCell::new(None) };
+}
+
+fn wrap(v: f64, size: f64) -> f64 {
+    if v < 0.0 { v + size } else if v > size { v - size } else { v }
+}
+
+/// Enter the practice arena as `name`, flying the hull picked in ship select
+pub fn start(net: SharedNetwork, name: String) {
+    let ship_type = net.borrow().state.borrow().settings.ship.unwrap_or(0).clamp(0, SHIP_STATS.len() as i32 - 1);
+    let home = (WORLD_W / 2.0, WORLD_H / 2.0);
+    let bots = (1..=BOT_COUNT as u32).map(|i| Bot::spawn(MY_ID + i, home.0, home.1)).collect();
+    Network::enter_practice(&net, MY_ID);
+    net.borrow().state.borrow_mut().names.insert(MY_ID, name.clone());
+    ARENA.with(|a| {
+        *a.borrow_mut() = Some(Arena {
+            net,
+            name,
+            ship: PredictedShip::new(home.0, home.1, -std::f64::consts::FRAC_PI_2),
+            ship_type,
+            fire_cd: 0.0,
+            lasers: Vec::new(),
+            bots,
+            home,
+            score: 0,
+            next_id: MY_ID + BOT_COUNT as u32,
+            since_snapshot: SNAPSHOT_MS, // send the first snapshot right away
+            tick: 0,
+        });
+    });
+}
+
+/// Run the arena for `dt` seconds and publish hits, kills and snapshots (called once per frame)
+pub fn advance(dt: f64) {
+    let (net, events, snapshot) = match ARENA.with(|a| {
+        let mut a = a.borrow_mut();
+        let arena = a.as_mut()?;
+        let state = arena.net.borrow().state.clone();
+        let (target, firing, boosting, ack) = {
+            let s = state.borrow();
+            (crate::network::input_target(&s), s.firing, s.boosting, s.input_seq)
+        };
+        let mut events = Vec::new();
+
+        let (tx, ty, thresh) = target;
+        prediction::drive(&mut arena.ship, tx, ty, boosting, thresh, dt);
+
+        // Fire
+        arena.fire_cd -= dt;
+        if firing && arena.fire_cd <= 0.0 {
+            arena.fire_cd = SHIP_STATS[arena.ship_type as usize].fire_cd;
+            let (cos, sin) = (arena.ship.r.cos(), arena.ship.r.sin());
+            let id = arena.next_id();
+            arena.lasers.push(Laser {
+                id,
+                x: arena.ship.x + cos * LASER_OFFSET,
+                y: arena.ship.y + sin * LASER_OFFSET,
+                vx: cos * LASER_SPEED,
+                vy: sin * LASER_SPEED,
+                life: LASER_LIFE,
+            });
+        }
+
+        for bot in arena.bots.iter_mut() {
+            if bot.respawn_in > 0.0 {
+                bot.respawn_in -= dt;
+                if bot.respawn_in <= 0.0 {
+                    *bot = Bot::spawn(bot.id, arena.home.0, arena.home.1);
+                }
+                continue;
+            }
+            bot.update(dt);
+        }
+
+        // Move lasers and hit drones
+        let hit_r = MOB_RADIUS + LASER_RADIUS;
+        for laser in arena.lasers.iter_mut() {
+            laser.x = wrap(laser.x + laser.vx * dt, WORLD_W);
+            laser.y = wrap(laser.y + laser.vy * dt, WORLD_H);
+            laser.life -= dt;
+            let Some(bot) = arena.bots.iter_mut()
+                .find(|b| b.respawn_in <= 0.0 && (b.x - laser.x).hypot(b.y - laser.y) < hit_r) else { continue };
+            laser.life = 0.0;
+            bot.hp -= LASER_DAMAGE;
+            events.push(serde_json::json!({"t": "hit", "d": {
+                "x": bot.x, "y": bot.y, "dmg": LASER_DAMAGE, "vid": bot.id, "aid": MY_ID,
+            }}).to_string());
+            if bot.hp <= 0 {
+                bot.respawn_in = BOT_RESPAWN;
+                arena.score += 1;
+                events.push(serde_json::json!({"t": "kill", "d": {
+                    "kid": MY_ID, "kn": arena.name, "vid": bot.id, "vn": "Drone", "c": "laser",
+                }}).to_string());
+            }
+        }
+        arena.lasers.retain(|l| l.life > 0.0);
+
+        // Snapshot at the server's rate, always full (no deltas)
+        arena.since_snapshot += dt * 1000.0;
+        let snapshot = (arena.since_snapshot >= SNAPSHOT_MS).then(|| {
+            arena.since_snapshot = 0.0;
+            arena.tick += 1;
+            let ship = &arena.ship;
+            GameStateMsg {
+                p: vec![PlayerState {
+                    id: MY_ID,
+                    x: ship.x, y: ship.y, r: ship.r,
+                    vx: Some(ship.vx), vy: Some(ship.vy),
+                    hp: 100, mhp: 100,
+                    s: arena.ship_type,
+                    sc: arena.score,
+                    kills: 0, deaths: 0, assists: 0,
+                    a: true,
+                    b: boosting,
+                }],
+                pr: arena.lasers.iter().map(|l| ProjectileState {
+                    id: l.id, x: l.x, y: l.y, r: l.vy.atan2(l.vx), o: MY_ID, k: PROJ_LASER,
+                }).collect(),
+                m: arena.bots.iter().filter(|b| b.respawn_in <= 0.0).map(|b| MobState {
+                    id: b.id, x: b.x, y: b.y, r: b.r,
+                    vx: Some(b.vx), vy: Some(b.vy),
+                    hp: b.hp, mhp: BOT_HP, s: BOT_SHIP, a: true,
+                }).collect(),
+                a: Vec::new(),
+                pk: Vec::new(),
+                tick: arena.tick,
+                ack, // everything sent so far counts as applied
+                dl: false,
+                rm: Vec::new(),
+            }
+        });
+        Some((arena.net.clone(), events, snapshot))
+    }) {
+        Some(v) => v,
+        None => return,
+    };
+
+    for text in &events {
+        Network::replay_event(&net, text);
+    }
+    if let Some(gs) = snapshot {
+        Network::local_state(&net, gs);
+    }
+}
+
+/// Close the arena and return to the lobby
+pub fn exit() {
+    let arena = ARENA.with(|a| a.borrow_mut().take());
+    if let Some(arena) = arena {
+        Network::exit_replay(&arena.net);
+    }
+}
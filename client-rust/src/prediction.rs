@@ -0,0 +1,184 @@
+use crate::state::GameState;
+use crate::constants::{WORLD_W, WORLD_H};
+
+// Movement model (must match server player.go)
+const PLAYER_ACCEL: f64 = 600.0;
+const PLAYER_MAX_SPEED: f64 = 350.0;
+const PLAYER_FRICTION: f64 = 0.97;
+const PLAYER_BOOST_MUL: f64 = 1.6;
+const TURN_SPEED: f64 = 8.0;
+const DEAD_ZONE: f64 = 50.0;
+const DODGE_SPEED: f64 = 700.0;
+const DODGE_DURATION: f64 = 0.25;
+const TICK: f64 = 1.0 / 60.0;
+
+// Reconciliation
+const SNAP_DIST: f64 = 250.0;   // error beyond this is a teleport/respawn: snap
+const CORRECTION: f64 = 0.2;    // fraction of position error removed per snapshot
+const ROT_CORRECTION: f64 = 0.3;
+
+#[derive(Debug, Clone)]
+pub struct PredictedShip {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub r: f64,
+    target_r: f64,
+    dodge_t: f64, // dash time remaining (speed cap lifted)
+    acc: f64, // leftover frame time not yet simulated
+}
+
+impl PredictedShip {
+    pub fn new(x: f64, y: f64, r: f64) -> PredictedShip {
+        PredictedShip { x, y, vx: 0.0, vy: 0.0, r, target_r: r, dodge_t: 0.0, acc: 0.0 }
+    }
+}
+
+fn normalize_angle(mut a: f64) -> f64 {
+    while a > std::f64::consts::PI { a -= 2.0 * std::f64::consts::PI; }
+    while a < -std::f64::consts::PI { a += 2.0 * std::f64::consts::PI; }
+    a
+}
+
+/// Shortest signed delta on the wrapping world axis
+pub fn wrap_delta(d: f64, size: f64) -> f64 {
+    if d > size / 2.0 { d - size } else if d < -size / 2.0 { d + size } else { d }
+}
+
+/// Prediction only applies when this client is the one steering
+fn enabled(s: &GameState) -> bool {
+    !s.controller_attached && s.coach_target.is_none() && !s.replay_mode
+}
+
+/// Predicted camera center, when prediction is active
+pub fn camera(s: &GameState) -> Option<(f64, f64)> {
+    s.predicted.as_ref().map(|p| (p.x, p.y))
+}
+
+/// One server tick of Player.Update
+fn simulate(p: &mut PredictedShip, tx: f64, ty: f64, boosting: bool, thresh: f64) {
+    let dt = TICK;
+    let diff = normalize_angle(p.target_r - p.r);
+    let max_turn = TURN_SPEED * dt;
+    p.r += diff.clamp(-max_turn, max_turn);
+
+    let mut accel = PLAYER_ACCEL * dt;
+    if boosting { accel *= PLAYER_BOOST_MUL; }
+
+    let dist2 = (tx - p.x) * (tx - p.x) + (ty - p.y) * (ty - p.y);
+    let thresh = thresh.clamp(50.0, 400.0).max(20.0);
+    let mut speed_factor = 1.0;
+    if dist2 <= DEAD_ZONE * DEAD_ZONE {
+        accel = 0.0;
+        speed_factor = 0.0;
+    } else if dist2 < thresh * thresh {
+        speed_factor = (dist2.sqrt() - DEAD_ZONE) / (thresh - DEAD_ZONE);
+        accel *= speed_factor;
+    }
+
+    p.vx += p.r.cos() * accel;
+    p.vy += p.r.sin() * accel;
+
+    let friction = if speed_factor < 1.0 {
+        0.95 + speed_factor * (PLAYER_FRICTION - 0.95)
+    } else {
+        PLAYER_FRICTION
+    };
+    p.vx *= friction;
+    p.vy *= friction;
+
+    let mut max_spd = if boosting { PLAYER_MAX_SPEED * PLAYER_BOOST_MUL } else { PLAYER_MAX_SPEED };
+    if p.dodge_t > 0.0 {
+        max_spd += DODGE_SPEED;
+        p.dodge_t -= dt;
+    }
+    let speed = (p.vx * p.vx + p.vy * p.vy).sqrt();
+    if speed > max_spd {
+        let scale = max_spd / speed;
+        p.vx *= scale;
+        p.vy *= scale;
+    }
+
+    p.x += p.vx * dt;
+    p.y += p.vy * dt;
+
+    if p.x < 0.0 { p.x += WORLD_W; } else if p.x > WORLD_W { p.x -= WORLD_W; }
+    if p.y < 0.0 { p.y += WORLD_H; } else if p.y > WORLD_H { p.y -= WORLD_H; }
+}
+
+/// Advance the local ship with the current input (called every frame)
+pub fn step(s: &mut GameState, dt: f64) {
+    if !enabled(s) || s.predicted.is_none() { return; }
+    let (tx, ty, thresh) = crate::network::input_target(s);
+    let boosting = s.boosting;
+    drive(s.predicted.as_mut().unwrap(), tx, ty, boosting, thresh, dt);
+}
+
+/// Steer toward (tx, ty) and run every whole tick `dt` covers (also flies the practice ship)
+pub fn drive(p: &mut PredictedShip, tx: f64, ty: f64, boosting: bool, thresh: f64, dt: f64) {
+    let dx = tx - p.x;
+    let dy = ty - p.y;
+    if dx * dx + dy * dy > 25.0 {
+        p.target_r = dy.atan2(dx);
+    }
+
+    p.acc += dt;
+    while p.acc >= TICK {
+        simulate(p, tx, ty, boosting, thresh);
+        p.acc -= TICK;
+    }
+}
+
+/// Dash toward (tx, ty) like Player.Dodge, and play the dash effect at the ship
+pub fn dodge(s: &mut GameState, tx: f64, ty: f64) {
+    let Some(p) = s.predicted.as_mut() else { return };
+    let (dx, dy) = (tx - p.x, ty - p.y);
+    let a = if dx * dx + dy * dy > 25.0 { dy.atan2(dx) } else { p.r };
+    p.vx += a.cos() * DODGE_SPEED;
+    p.vy += a.sin() * DODGE_SPEED;
+    p.dodge_t = DODGE_DURATION;
+    let (x, y) = (p.x, p.y);
+    let limit = s.settings.particle_limit();
+    crate::effects::add_dash(&mut s.particles, limit, x, y, a);
+}
+
+/// Pull the prediction toward the authoritative snapshot (called per snapshot)
+pub fn reconcile(s: &mut GameState) {
+    let me = match s.my_id.as_ref().and_then(|id| s.players.get(id)) {
+        Some(me) if me.a && enabled(s) => me.clone(),
+        _ => {
+            s.predicted = None;
+            return;
+        }
+    };
+    let (svx, svy) = (me.vx.unwrap_or(0.0), me.vy.unwrap_or(0.0));
+
+    let p = match s.predicted.as_mut() {
+        Some(p) => p,
+        None => {
+            s.predicted = Some(PredictedShip {
+                x: me.x, y: me.y, vx: svx, vy: svy, r: me.r, target_r: me.r, dodge_t: 0.0, acc: 0.0,
+            });
+            return;
+        }
+    };
+
+    let ex = wrap_delta(me.x - p.x, WORLD_W);
+    let ey = wrap_delta(me.y - p.y, WORLD_H);
+    if ex * ex + ey * ey > SNAP_DIST * SNAP_DIST {
+        p.x = me.x;
+        p.y = me.y;
+        p.vx = svx;
+        p.vy = svy;
+        p.r = me.r;
+        return;
+    }
+
+    // Smoothly bleed off the error instead of snapping, so corrections don't jitter
+    p.x += ex * CORRECTION;
+    p.y += ey * CORRECTION;
+    p.vx += (svx - p.vx) * CORRECTION;
+    p.vy += (svy - p.vy) * CORRECTION;
+    p.r += normalize_angle(me.r - p.r) * ROT_CORRECTION;
+}
@@ -0,0 +1,19 @@
+// Small localStorage helpers for persisted client preferences
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+pub fn get(key: &str) -> Option<String> {
+    storage()?.get_item(key).ok()?
+}
+
+pub fn set(key: &str, value: &str) {
+    if let Some(s) = storage() {
+        let _ = s.set_item(key, value);
+    }
+}
+
+pub fn get_bool(key: &str) -> bool {
+    get(key).as_deref() == Some("1")
+}
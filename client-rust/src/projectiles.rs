@@ -2,8 +2,12 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use wasm_bindgen::JsCast;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
-use crate::constants::LASER_COLORS;
-use crate::protocol::ProjectileState;
+use crate::protocol::{PlayerState, ProjectileState};
+use crate::constants::{PROJ_BEAM, PROJ_MISSILE};
+
+const MISSILE_ARM_R: f64 = 200.0; // missiles blink when a ship other than the shooter is this close
+const SMOKE_PUFFS: usize = 7;
+const BEAM_LEN: f64 = 300.0; // drawn behind the tip when the shooter isn't in view
 
 thread_local! {
     static GLOW_SPRITES: RefCell<HashMap<String, HtmlCanvasElement>> = RefCell::new(HashMap::new());
@@ -94,21 +98,106 @@ fn get_bolt_sprite(color: &str) -> HtmlCanvasElement {
     })
 }
 
-pub fn render_projectiles(
+/// Blink period in seconds for a missile this far from its nearest target:
+/// steady at arming range, quickening to a flicker up close
+fn blink_period(dist: f64) -> Option<f64> {
+    (dist < MISSILE_ARM_R).then(|| 0.06 + 0.34 * dist / MISSILE_ARM_R)
+}
+
+/// Missile body with a smoke trail behind it and a tip that blinks faster
+/// the closer it gets to a ship
+fn draw_missile(ctx: &CanvasRenderingContext2d, proj: &ProjectileState, sx: f64, sy: f64, players: &HashMap<u32, PlayerState>, time_secs: f64) {
+    let (dx, dy) = (proj.r.cos(), proj.r.sin());
+    // Smoke: puffs spaced out behind, growing and fading, with a little wobble
+    ctx.set_fill_style_str("#b8b8b8");
+    for i in 1..=SMOKE_PUFFS {
+        let f = i as f64 / SMOKE_PUFFS as f64;
+        let wobble = (time_secs * 9.0 + i as f64 * 1.7).sin() * 3.0 * f;
+        let (px, py) = (sx - dx * 9.0 * i as f64 - dy * wobble, sy - dy * 9.0 * i as f64 + dx * wobble);
+        ctx.set_global_alpha(0.45 * (1.0 - f));
+        ctx.begin_path();
+        let _ = ctx.arc(px, py, 2.5 + 5.0 * f, 0.0, std::f64::consts::PI * 2.0);
+        ctx.fill();
+    }
+    ctx.set_global_alpha(1.0);
+
+    ctx.save();
+    ctx.translate(sx, sy).unwrap_or(());
+    ctx.rotate(proj.r).unwrap_or(());
+    ctx.set_fill_style_str("#d0d4dc");
+    ctx.fill_rect(-8.0, -2.5, 14.0, 5.0);
+    ctx.set_fill_style_str("#7a808c");
+    ctx.fill_rect(-9.0, -4.5, 4.0, 9.0); // fins
+    ctx.restore();
+
+    let nearest = players.values()
+        .filter(|p| p.a && p.id != proj.o)
+        .map(|p| (p.x - proj.x).hypot(p.y - proj.y))
+        .fold(f64::INFINITY, f64::min);
+    let lit = blink_period(nearest).is_some_and(|period| time_secs % period < period / 2.0);
+    ctx.set_fill_style_str(if lit { "#ff3030" } else { "#601010" });
+    ctx.begin_path();
+    let _ = ctx.arc(sx + dx * 7.0, sy + dy * 7.0, 2.0, 0.0, std::f64::consts::PI * 2.0);
+    ctx.fill();
+}
+
+/// A continuous beam from the shooter's ship at `origin` (screen coords, or
+/// BEAM_LEN straight back when it isn't known) to the projectile's tip, with a
+/// flickering glow around a white core
+fn draw_beam(ctx: &CanvasRenderingContext2d, proj: &ProjectileState, sx: f64, sy: f64, color: &str, origin: Option<(f64, f64)>, time_secs: f64) {
+    let (ox, oy) = origin.unwrap_or((sx - proj.r.cos() * BEAM_LEN, sy - proj.r.sin() * BEAM_LEN));
+    let flicker = 1.0 + (time_secs * 40.0).sin() * 0.2;
+    ctx.save();
+    ctx.set_line_cap("round");
+    ctx.set_stroke_style_str(color);
+    for (width, alpha) in [(12.0, 0.2), (6.0, 0.5)] {
+        ctx.set_global_alpha(alpha);
+        ctx.set_line_width(width * flicker);
+        ctx.begin_path();
+        ctx.move_to(ox, oy);
+        ctx.line_to(sx, sy);
+        ctx.stroke();
+    }
+    ctx.set_global_alpha(0.95);
+    ctx.set_stroke_style_str("#ffffff");
+    ctx.set_line_width(2.0);
+    ctx.begin_path();
+    ctx.move_to(ox, oy);
+    ctx.line_to(sx, sy);
+    ctx.stroke();
+    ctx.restore();
+}
+
+pub fn render_projectiles<'a>(
     ctx: &CanvasRenderingContext2d,
-    projectiles: &HashMap<String, ProjectileState>,
-    players: &HashMap<String, crate::protocol::PlayerState>,
+    projectiles: impl Iterator<Item = &'a ProjectileState>,
+    players: &HashMap<u32, PlayerState>,
     offset_x: f64, offset_y: f64, vw: f64, vh: f64,
 ) {
-    for (_, proj) in projectiles {
+    let time_secs = js_sys::Date::now() / 1000.0;
+    for proj in projectiles {
         let sx = proj.x - offset_x;
         let sy = proj.y - offset_y;
-        if sx < -50.0 || sx > vw + 50.0 || sy < -50.0 || sy > vh + 50.0 { continue; }
+        // Beams reach back to their shooter, so the tip can be well off-screen
+        let margin = if proj.k == PROJ_BEAM { BEAM_LEN + 50.0 } else { 50.0 };
+        if sx < -margin || sx > vw + margin || sy < -margin || sy > vh + margin { continue; }
 
         // Determine color from owner ship type
         let ship_type = players.get(&proj.o).map(|p| p.s).unwrap_or(0);
-        let color_idx = (ship_type as usize).min(LASER_COLORS.len() - 1);
-        let color = LASER_COLORS[color_idx];
+        let color = crate::palette::laser(ship_type);
+
+        match proj.k {
+            PROJ_MISSILE => {
+                draw_missile(ctx, proj, sx, sy, players, time_secs);
+                continue;
+            }
+            PROJ_BEAM => {
+                let origin = players.get(&proj.o).filter(|p| p.a).map(|p| (p.x - offset_x, p.y - offset_y));
+                draw_beam(ctx, proj, sx, sy, color, origin, time_secs);
+                continue;
+            }
+            _ => {}
+        }
 
         // Glow sprite (ambient light around bolt)
         let sprite = get_glow_sprite(color);
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // Envelope wraps all messages
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -11,8 +12,35 @@ pub struct Envelope {
 // Server -> Client: welcome
 #[derive(Deserialize, Debug, Clone)]
 pub struct WelcomeMsg {
-    pub id: String,
+    pub id: String, // player handle for resume, controller and coach links
+    pub nid: u32,   // our entity ID in snapshots and events
     pub s: i32,
+    #[serde(default)]
+    pub tok: String, // resume token for reclaiming this ship after a disconnect
+    #[serde(default)]
+    pub ck: String, // coach key, for the link that lets a coach watch this ship
+    #[serde(default)]
+    pub names: HashMap<u32, String>,
+}
+
+// Server -> Client: player name table, sent whole whenever someone joins
+#[derive(Deserialize, Debug, Clone)]
+pub struct NamesMsg {
+    pub names: HashMap<u32, String>,
+}
+
+// Server -> Client: each connected player's round trip (ms), every 2s
+#[derive(Deserialize, Debug, Clone)]
+pub struct PingsMsg {
+    pub p: HashMap<u32, u32>,
+}
+
+// Server -> Controller/Coach: attach confirmed
+#[derive(Deserialize, Debug, Clone)]
+pub struct AttachOkMsg {
+    pub nid: u32, // the attached player's entity ID
+    #[serde(default)]
+    pub names: HashMap<u32, String>,
 }
 
 // Server -> Client: joined
@@ -28,10 +56,9 @@ pub struct CreatedMsg {
 }
 
 // Server -> Client: player state (vx/vy omitted when unchanged via delta compression)
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PlayerState {
-    pub id: String,
-    pub n: String,
+    pub id: u32,
     pub x: f64,
     pub y: f64,
     pub r: f64,
@@ -41,25 +68,33 @@ pub struct PlayerState {
     pub mhp: i32,
     pub s: i32,
     pub sc: i32,
+    #[serde(rename = "k", default)]
+    pub kills: i32, // players shot or rammed down
+    #[serde(rename = "d", default)]
+    pub deaths: i32,
+    #[serde(rename = "as", default)]
+    pub assists: i32,
     pub a: bool,
     #[serde(default)]
     pub b: bool,
 }
 
 // Server -> Client: projectile state
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProjectileState {
-    pub id: String,
+    pub id: u32,
     pub x: f64,
     pub y: f64,
     pub r: f64,
-    pub o: String,
+    pub o: u32,
+    #[serde(default)]
+    pub k: u8, // PROJ_LASER, PROJ_MISSILE or PROJ_BEAM; left out for lasers
 }
 
 // Server -> Client: mob state (vx/vy omitted when unchanged via delta compression)
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MobState {
-    pub id: String,
+    pub id: u32,
     pub x: f64,
     pub y: f64,
     pub r: f64,
@@ -75,18 +110,22 @@ pub struct MobState {
 fn default_mob_ship() -> i32 { 3 }
 
 // Server -> Client: asteroid state
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AsteroidState {
-    pub id: String,
+    pub id: u32,
     pub x: f64,
     pub y: f64,
     pub r: f64,
+    #[serde(default)]
+    pub hp: i32, // 0 from servers before asteroids could be shot apart
+    #[serde(default)]
+    pub sp: f64, // spin, rad/s
 }
 
 // Server -> Client: pickup state
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PickupState {
-    pub id: String,
+    pub id: u32,
     pub x: f64,
     pub y: f64,
 }
@@ -103,21 +142,44 @@ pub struct GameStateMsg {
     #[serde(default)]
     pub pk: Vec<PickupState>,
     pub tick: u64,
+    #[serde(default)]
+    pub ack: u16, // last input sequence number the server applied for us
+    #[serde(default)]
+    pub dl: bool, // delta: only changed entities, merge into the current state
+    #[serde(default)]
+    pub rm: Vec<u32>, // entity IDs gone since the previous snapshot (deltas only)
 }
 
 // Server -> Client: kill notification
 #[derive(Deserialize, Debug, Clone)]
 pub struct KillMsg {
-    pub kid: String,
+    pub kid: u32, // 0 for an asteroid
     pub kn: String,
-    pub vid: String,
+    pub vid: u32,
     pub vn: String,
+    #[serde(default)]
+    pub c: String, // cause: "laser", "ram" or "asteroid"
+    #[serde(default)]
+    pub an: String, // assisting player's name, if any
 }
 
 // Server -> Client: death notification
 #[derive(Deserialize, Debug, Clone)]
 pub struct DeathMsg {
+    #[serde(default)]
+    pub kid: u32, // 0 for an asteroid
     pub kn: String,
+    #[serde(default)]
+    pub rt: f64, // seconds until respawn; 0 from older servers
+}
+
+// Server -> Client: world ping placed by a player
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarkerMsg {
+    pub x: f64,
+    pub y: f64,
+    #[serde(default)]
+    pub n: String,
 }
 
 // Server -> Client: session list
@@ -126,6 +188,8 @@ pub struct SessionInfo {
     pub id: String,
     pub name: String,
     pub players: i32,
+    #[serde(default)]
+    pub max: i32, // player cap; 0 from older servers that don't send it
 }
 
 // Server -> Client: session check response
@@ -150,13 +214,67 @@ pub struct HitMsg {
     pub x: f64,
     pub y: f64,
     pub dmg: i32,
-    pub vid: String,  // victim ID
-    pub aid: String,  // attacker ID
+    pub vid: u32, // victim ID
+    pub aid: u32, // attacker ID, 0 for an asteroid
+    #[serde(default)]
+    pub cr: bool, // critical: shot from behind
 }
 
 // Server -> Client: mob speech bubble
 #[derive(Deserialize, Debug, Clone)]
 pub struct MobSayMsg {
-    pub mid: String,  // mob ID
+    pub mid: u32,     // mob ID
     pub text: String, // phrase text (with emoji)
 }
+
+// Server -> Coach: player's camera + input overlay
+#[derive(Deserialize, Debug, Clone)]
+pub struct ViewMsg {
+    pub vw: f64, // visible world width
+    pub vh: f64, // visible world height
+    pub mx: f64, // cursor (world coords)
+    pub my: f64,
+    #[serde(default)]
+    pub f: bool, // firing
+    #[serde(default)]
+    pub b: bool, // boosting
+}
+
+// Server -> Client: coach annotation (freehand line or ping)
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarkMsg {
+    pub k: String,     // "line" or "ping"
+    pub pts: Vec<f64>, // flat world coords [x0, y0, x1, y1, ...]
+}
+
+// Client -> Server: binary input, 10 bytes
+// [0x01, mx_hi, mx_lo, my_hi, my_lo, flags, thresh_hi, thresh_lo, seq_hi, seq_lo]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InputPacket {
+    pub mx: f64, // aim point (world coords), rounded to i16
+    pub my: f64,
+    pub fire: bool,
+    pub boost: bool,
+    pub dodge: bool,
+    pub thresh: f64, // speed modulation distance, rounded to u16
+    pub seq: u16,
+}
+
+impl InputPacket {
+    pub fn encode(&self) -> [u8; 10] {
+        let mx = self.mx.round() as i16;
+        let my = self.my.round() as i16;
+        let thresh = self.thresh.round().clamp(0.0, 65535.0) as u16;
+        let flags: u8 = (if self.fire { 0x01 } else { 0 })
+            | (if self.boost { 0x02 } else { 0 })
+            | (if self.dodge { 0x04 } else { 0 });
+        let [mx_hi, mx_lo] = mx.to_be_bytes();
+        let [my_hi, my_lo] = my.to_be_bytes();
+        let [thresh_hi, thresh_lo] = thresh.to_be_bytes();
+        let [seq_hi, seq_lo] = self.seq.to_be_bytes();
+        [0x01, mx_hi, mx_lo, my_hi, my_lo, flags, thresh_hi, thresh_lo, seq_hi, seq_lo]
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,246 @@
+// Server payloads in fixtures/protocol, decoded with the client's types. The
+// JSON lines are envelopes as the server's json.Marshal writes them; the
+// msgpack files are GameState laid out the way msgpack.Marshal encodes it
+// (a map per struct in field order, omitempty fields left out). When a struct
+// in server/protocol.go changes, update the fixture and these tests with it.
+
+use super::*;
+
+const MESSAGES: &str = include_str!("../../fixtures/protocol/server_messages.jsonl");
+const STATE_FULL: &[u8] = include_bytes!("../../fixtures/protocol/state_full.msgpack");
+const STATE_DELTA: &[u8] = include_bytes!("../../fixtures/protocol/state_delta.msgpack");
+
+// Every Server -> Client type in server/protocol.go
+const SERVER_TYPES: &[&str] = &[
+    "state", "welcome", "death", "kill", "sessions", "joined", "created", "error",
+    "checked", "control_ok", "ctrl_on", "ctrl_off", "hit", "mob_say", "coach_ok",
+    "coach_on", "coach_off", "bug_ok", "resume_fail", "pong", "names", "view",
+    "mark", "marker", "pings",
+];
+
+fn envelopes(t: &str) -> Vec<Envelope> {
+    MESSAGES.lines()
+        .map(|l| serde_json::from_str::<Envelope>(l).unwrap_or_else(|e| panic!("bad fixture line {}: {}", l, e)))
+        .filter(|env| env.t == t)
+        .collect()
+}
+
+fn data<T: serde::de::DeserializeOwned>(t: &str) -> T {
+    let env = envelopes(t).into_iter().next().unwrap_or_else(|| panic!("no {} fixture", t));
+    serde_json::from_value(env.d.unwrap_or_default()).unwrap_or_else(|e| panic!("{}: {}", t, e))
+}
+
+#[test]
+fn every_server_type_has_a_fixture() {
+    for t in SERVER_TYPES {
+        assert!(!envelopes(t).is_empty(), "no fixture for {}", t);
+    }
+    for line in MESSAGES.lines() {
+        let env: Envelope = serde_json::from_str(line).unwrap();
+        assert!(SERVER_TYPES.contains(&env.t.as_str()), "fixture for unknown type {}", env.t);
+    }
+}
+
+#[test]
+fn welcome() {
+    let w: WelcomeMsg = data("welcome");
+    assert_eq!(w.id, "9a7e3c51-2d4b-4f8a-b6e1-0c3d5f7a9b12");
+    assert_eq!((w.nid, w.s), (3, 2));
+    assert_eq!(w.tok, "q8Zk2mVt7RwX4pLs");
+    assert_eq!(w.ck, "Hn3bW9cJx5TfYq2D");
+    // JSON object keys are strings; the table is keyed by NetID
+    assert_eq!(w.names.get(&4).map(String::as_str), Some("Grace"));
+}
+
+#[test]
+fn names() {
+    let n: NamesMsg = data("names");
+    assert_eq!(n.names.len(), 3);
+    assert_eq!(n.names[&5], "Linus");
+}
+
+#[test]
+fn pings() {
+    let p: PingsMsg = data("pings");
+    assert_eq!((p.p[&3], p.p[&4]), (42, 118));
+}
+
+#[test]
+fn sessions_and_check() {
+    let list: Vec<SessionInfo> = data("sessions");
+    assert_eq!(list.len(), 2);
+    assert_eq!((list[0].name.as_str(), list[0].players, list[0].max), ("Friday night", 3, 8));
+
+    let checked: Vec<CheckedMsg> = envelopes("checked").into_iter()
+        .map(|e| serde_json::from_value(e.d.unwrap()).unwrap())
+        .collect();
+    assert!(checked[0].exists && checked[0].players == 3);
+    // A missing session omits name and players
+    assert!(!checked[1].exists && checked[1].name.is_empty() && checked[1].players == 0);
+
+    let created: CreatedMsg = data("created");
+    let joined: JoinedMsg = data("joined");
+    assert_eq!(created.sid, joined.sid);
+}
+
+#[test]
+fn combat_events() {
+    let hits: Vec<HitMsg> = envelopes("hit").into_iter()
+        .map(|e| serde_json::from_value(e.d.unwrap()).unwrap())
+        .collect();
+    let h = &hits[0];
+    assert_eq!((h.x, h.y), (1600.5, 990.0)); // whole floats arrive as JSON integers
+    assert_eq!((h.dmg, h.vid, h.aid, h.cr), (10, 4, 3, false));
+    assert_eq!((hits[1].dmg, hits[1].cr), (30, true));
+
+    let kills: Vec<KillMsg> = envelopes("kill").into_iter()
+        .map(|e| serde_json::from_value(e.d.unwrap()).unwrap())
+        .collect();
+    assert_eq!((kills[0].kid, kills[0].kn.as_str(), kills[0].vn.as_str()), (3, "Ada", "Grace"));
+    assert_eq!((kills[0].c.as_str(), kills[1].kid, kills[1].c.as_str()), ("laser", 0, "asteroid"));
+    // Servers from before kill causes leave "c" out
+    let old: KillMsg = serde_json::from_str(r#"{"kid":3,"kn":"Ada","vid":4,"vn":"Grace"}"#).unwrap();
+    assert!(old.c.is_empty());
+    // Assists are omitted when nobody else hit the victim
+    assert_eq!((kills[0].an.as_str(), kills[2].an.as_str()), ("", "Grace"));
+
+    let d: DeathMsg = data("death");
+    assert_eq!((d.kn.as_str(), d.rt), ("Ada", 3.0));
+
+    let say: MobSayMsg = data("mob_say");
+    assert_eq!(say.mid, 50);
+    assert!(say.text.ends_with('\u{1f680}'));
+}
+
+#[test]
+fn marker() {
+    // The server also sends the placer's player handle, which the client ignores
+    let m: MarkerMsg = data("marker");
+    assert_eq!((m.x, m.y, m.n.as_str()), (1200.0, 800.0, "Ada"));
+}
+
+#[test]
+fn attach() {
+    let ctrl: AttachOkMsg = data("control_ok");
+    assert_eq!(ctrl.nid, 3);
+    assert!(ctrl.names.is_empty());
+    let coach: AttachOkMsg = data("coach_ok");
+    assert_eq!(coach.names[&4], "Grace");
+}
+
+#[test]
+fn coaching() {
+    let v: ViewMsg = data("view");
+    assert_eq!((v.vw, v.vh, v.mx, v.my), (1920.0, 1080.0, 1500.5, 900.0));
+    assert!(v.f && !v.b);
+
+    let marks: Vec<MarkMsg> = envelopes("mark").into_iter()
+        .map(|e| serde_json::from_value(e.d.unwrap()).unwrap())
+        .collect();
+    assert_eq!((marks[0].k.as_str(), marks[0].pts.len()), ("line", 4));
+    assert_eq!((marks[1].k.as_str(), marks[1].pts.as_slice()), ("ping", [640.0, 480.0].as_slice()));
+}
+
+#[test]
+fn signals_carry_no_data() {
+    for t in ["ctrl_on", "ctrl_off", "coach_on", "coach_off", "resume_fail", "bug_ok"] {
+        assert!(envelopes(t)[0].d.is_none(), "{} has data", t);
+    }
+}
+
+#[test]
+fn error() {
+    let e: ErrorMsg = data("error");
+    assert_eq!(e.msg, "session full");
+}
+
+#[test]
+fn json_state() {
+    let gs: GameStateMsg = data("state");
+    assert_eq!((gs.tick, gs.ack, gs.dl), (1234, 17, false));
+    assert_eq!(gs.p[0].vx, Some(-120.0));
+    assert_eq!((gs.p[0].kills, gs.p[0].deaths, gs.p[0].assists), (4, 1, 2));
+    assert!(gs.rm.is_empty());
+}
+
+#[test]
+fn msgpack_full_state() {
+    let gs: GameStateMsg = rmp_serde::from_slice(STATE_FULL).unwrap();
+    assert_eq!((gs.tick, gs.ack, gs.dl), (1234, 17, false));
+    assert_eq!(gs.p.len(), 2);
+    let me = &gs.p[0];
+    assert_eq!((me.id, me.x, me.y), (3, 1520.5, 980.25));
+    assert_eq!((me.vx, me.vy), (Some(-120.0), Some(45.5)));
+    assert_eq!((me.hp, me.mhp, me.s, me.sc, me.a, me.b), (85, 100, 2, 7, true, true));
+    // K/D/A are omitempty too
+    assert_eq!((me.kills, me.deaths, me.assists), (0, 0, 0));
+    // Boost is omitempty
+    assert!(!gs.p[1].a && !gs.p[1].b);
+    assert_eq!((gs.pr[0].id, gs.pr[0].o), (101, 3));
+    let mob = &gs.m[0];
+    assert_eq!((mob.id, mob.hp, mob.mhp, mob.s, mob.vy), (50, 40, 60, 3, Some(-5.0)));
+    assert_eq!((gs.a[0].id, gs.a[0].r, gs.a[0].hp, gs.a[0].sp), (70, 0.5, 45, -1.25));
+    assert_eq!((gs.pk[0].id, gs.pk[0].x), (90, 1000.0));
+    assert!(gs.rm.is_empty());
+}
+
+#[test]
+fn msgpack_delta_state() {
+    let gs: GameStateMsg = rmp_serde::from_slice(STATE_DELTA).unwrap();
+    assert_eq!((gs.tick, gs.ack, gs.dl), (1235, 18, true));
+    // Unchanged velocity is left out of deltas
+    assert_eq!((gs.p[0].vx, gs.p[0].vy), (None, None));
+    assert!(gs.pr.is_empty() && gs.m.is_empty() && gs.a.is_empty() && gs.pk.is_empty());
+    assert_eq!(gs.rm, vec![101, 90]);
+}
+
+#[test]
+fn projectile_kinds() {
+    let laser: ProjectileState = serde_json::from_str(r#"{"id":101,"x":10,"y":20,"r":0,"o":3}"#).unwrap();
+    let missile: ProjectileState = serde_json::from_str(r#"{"id":102,"x":10,"y":20,"r":0,"o":3,"k":1}"#).unwrap();
+    assert_eq!((laser.k, missile.k), (crate::constants::PROJ_LASER, crate::constants::PROJ_MISSILE));
+}
+
+#[test]
+fn envelope_round_trip() {
+    let env = Envelope { t: "ping".to_string(), d: Some(serde_json::json!({"t": 12.5})) };
+    let back: Envelope = serde_json::from_str(&serde_json::to_string(&env).unwrap()).unwrap();
+    assert_eq!((back.t.as_str(), back.d), ("ping", env.d));
+
+    // No data means no "d" key, which the server reads as an empty RawMessage
+    let bare = serde_json::to_string(&Envelope { t: "list".to_string(), d: None }).unwrap();
+    assert_eq!(bare, r#"{"t":"list"}"#);
+}
+
+#[test]
+fn entity_round_trip() {
+    // State dumps serialize these; decoding them again must give the same values
+    let gs: GameStateMsg = rmp_serde::from_slice(STATE_DELTA).unwrap();
+    let bytes = rmp_serde::to_vec_named(&gs.p[0]).unwrap();
+    let p: PlayerState = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!((p.id, p.x, p.vx, p.b), (gs.p[0].id, gs.p[0].x, None, true));
+
+    let full: GameStateMsg = rmp_serde::from_slice(STATE_FULL).unwrap();
+    let json = serde_json::to_string(&full.m[0]).unwrap();
+    let m: MobState = serde_json::from_str(&json).unwrap();
+    assert_eq!((m.id, m.vx, m.s), (50, Some(10.0), 3));
+}
+
+#[test]
+fn input_packet_layout() {
+    let packet = InputPacket { mx: 1500.4, my: 900.6, fire: true, boost: false, dodge: true, thresh: 250.0, seq: 0x1234 };
+    assert_eq!(packet.encode(), [0x01, 0x05, 0xdc, 0x03, 0x85, 0x05, 0x00, 0xfa, 0x12, 0x34]);
+}
+
+#[test]
+fn input_packet_edges() {
+    // Off-world aim points are signed; the server clamps them
+    let bytes = InputPacket { mx: -20.0, my: 0.0, boost: true, ..Default::default() }.encode();
+    assert_eq!(i16::from_be_bytes([bytes[1], bytes[2]]), -20);
+    assert_eq!(bytes[5], 0x02);
+    // Threshold saturates instead of wrapping
+    let bytes = InputPacket { thresh: 1e9, ..Default::default() }.encode();
+    assert_eq!(u16::from_be_bytes([bytes[6], bytes[7]]), u16::MAX);
+    let bytes = InputPacket { thresh: -5.0, seq: u16::MAX, ..Default::default() }.encode();
+    assert_eq!(&bytes[6..], &[0, 0, 0xff, 0xff]);
+}
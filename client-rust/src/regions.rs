@@ -0,0 +1,160 @@
+// Server regions: WebSocket endpoints the lobby can choose between, each probed
+// for latency. Extra regions come from a meta tag in index.html:
+//   <meta name="ws-regions" content="EU=wss://eu.example.com/ws, US=wss://us.example.com/ws">
+// Without one the only region is the server that served the page.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use leptos::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, WebSocket};
+use crate::network::{Network, SharedNetwork};
+use crate::state::SharedState;
+use crate::i18n::{t, Msg};
+
+const PROBE_TIMEOUT: u32 = 3000; // ms before a region counts as unreachable
+
+type ProbeDone = Rc<RefCell<Option<Box<dyn FnOnce(Option<f64>)>>>>;
+
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub name: String, // empty for the page's own server
+    pub url: String,
+}
+
+/// WebSocket URL of the server that served this page
+fn local_url() -> String {
+    let location = web_sys::window().unwrap().location();
+    let protocol = location.protocol().unwrap_or_default();
+    let host = location.host().unwrap_or_default();
+    let ws_proto = if protocol == "https:" { "wss:" } else { "ws:" };
+    format!("{}//{}/ws", ws_proto, host)
+}
+
+/// The page's own server followed by any regions configured in the ws-regions meta tag
+pub fn list() -> Vec<Region> {
+    let mut regions = vec![Region { name: String::new(), url: local_url() }];
+    let document = web_sys::window().unwrap().document().unwrap();
+    let content = document.query_selector("meta[name=ws-regions]").ok().flatten()
+        .and_then(|m| m.get_attribute("content"))
+        .unwrap_or_default();
+    for entry in content.split(',') {
+        if let Some((name, url)) = entry.split_once('=') {
+            let (name, url) = (name.trim(), url.trim());
+            if !name.is_empty() && url.starts_with("ws") {
+                regions.push(Region { name: name.to_string(), url: url.to_string() });
+            }
+        }
+    }
+    regions
+}
+
+/// Endpoint for a saved region name, falling back to the page's own server
+pub fn url_for(name: &str) -> String {
+    list().into_iter()
+        .find(|r| !name.is_empty() && r.name == name)
+        .map(|r| r.url)
+        .unwrap_or_else(local_url)
+}
+
+/// Open a throwaway socket and time one ping/pong; None if it fails or times out
+fn probe(url: &str, done: impl FnOnce(Option<f64>) + 'static) {
+    let Ok(ws) = WebSocket::new(url) else {
+        done(None);
+        return;
+    };
+    let done: ProbeDone = Rc::new(RefCell::new(Some(Box::new(done))));
+    let finish = {
+        let ws = ws.clone();
+        move |done: &ProbeDone, rtt: Option<f64>| {
+            ws.set_onopen(None);
+            ws.set_onmessage(None);
+            ws.set_onerror(None);
+            let _ = ws.close();
+            if let Some(f) = done.borrow_mut().take() {
+                f(rtt);
+            }
+        }
+    };
+
+    let sent_at = Rc::new(std::cell::Cell::new(0.0));
+    let (ws_open, sent_open) = (ws.clone(), sent_at.clone());
+    let on_open = Closure::wrap(Box::new(move || {
+        let now = js_sys::Date::now();
+        sent_open.set(now);
+        let _ = ws_open.send_with_str(&serde_json::json!({"t": "ping", "d": {"t": now}}).to_string());
+    }) as Box<dyn FnMut()>);
+    ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    on_open.forget();
+
+    let (done_msg, finish_msg) = (done.clone(), finish.clone());
+    let on_message = Closure::wrap(Box::new(move |e: MessageEvent| {
+        let is_pong = e.data().as_string()
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+            .is_some_and(|v| v["t"] == "pong");
+        if is_pong {
+            finish_msg(&done_msg, Some(js_sys::Date::now() - sent_at.get()));
+        }
+    }) as Box<dyn FnMut(MessageEvent)>);
+    ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    on_message.forget();
+
+    let (done_err, finish_err) = (done.clone(), finish.clone());
+    let on_error = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        finish_err(&done_err, None);
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    on_error.forget();
+
+    gloo_timers::callback::Timeout::new(PROBE_TIMEOUT, move || finish(&done, None)).forget();
+}
+
+fn describe(region: &Region, rtt: Option<Option<f64>>) -> String {
+    let name = if region.name.is_empty() { t(Msg::ThisServer) } else { region.name.as_str() };
+    match rtt {
+        None => format!("{} (…)", name),
+        Some(None) => format!("{} ({})", name, t(Msg::Offline)),
+        Some(Some(ms)) => format!("{} ({:.0} ms)", name, ms),
+    }
+}
+
+/// Lobby dropdown for picking a region; renders nothing unless regions are configured
+#[component]
+pub fn RegionSelect(state: SharedState, net: SharedNetwork) -> impl IntoView {
+    let regions = list();
+    if regions.len() < 2 {
+        return view! { <span></span> }.into_any();
+    }
+    // Latency per region, index-aligned with `regions`; None until its probe finishes
+    let pings = RwSignal::new(vec![None::<Option<f64>>; regions.len()]);
+    for (i, region) in regions.iter().enumerate() {
+        probe(&region.url, move |rtt| pings.update(|p| p[i] = Some(rtt)));
+    }
+    let current = state.borrow().settings.region.clone();
+    let state = send_wrapper::SendWrapper::new(state);
+    let net = send_wrapper::SendWrapper::new(net);
+
+    let options = regions.iter().enumerate().map(|(i, region)| {
+        let (value, selected) = (region.name.clone(), region.name == current);
+        let region = region.clone();
+        view! {
+            <option value=value selected=selected>
+                {move || describe(&region, pings.get()[i])}
+            </option>
+        }
+    }).collect_view();
+
+    view! {
+        <div class="region-select">
+            <label for="regionSelect">{t(Msg::Region)}</label>
+            <select id="regionSelect" on:change=move |e| {
+                let name = event_target_value(&e);
+                crate::settings::update(&*state, |s| s.region = name);
+                Network::switch_endpoint(&net);
+            }>
+                {options}
+            </select>
+        </div>
+    }.into_any()
+}
@@ -1,9 +1,16 @@
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use wasm_bindgen::JsCast;
 use web_sys::CanvasRenderingContext2d;
-use crate::state::SharedState;
+use crate::state::{SharedState, GameState, Snapshot};
 use crate::constants::*;
-use crate::{starfield, ships, effects, projectiles, mobs, asteroids, pickups, fog, hud, auto_aim};
+use crate::{starfield, ships, effects, projectiles, mobs, asteroids, pickups, fog, hud, auto_aim, coach, prediction};
+use crate::spatial::Kind;
+
+// World px added around the view when asking the grid what's visible: covers the
+// largest sprite plus how far entities drift from their snapshot cell while
+// being interpolated or extrapolated
+const CULL_PAD: f64 = 300.0;
 
 fn lerp_angle(from: f64, to: f64, t: f64) -> f64 {
     let mut diff = to - from;
@@ -13,6 +20,47 @@ fn lerp_angle(from: f64, to: f64, t: f64) -> f64 {
     from + diff * t
 }
 
+/// IDs of `kind` near the view, from the current snapshot's grid
+fn visible(s: &GameState, kind: Kind, offset_x: f64, offset_y: f64, vw: f64, vh: f64) -> impl Iterator<Item = u32> + '_ {
+    s.grid.query(kind, offset_x - CULL_PAD, offset_y - CULL_PAD, offset_x + vw + CULL_PAD, offset_y + vh + CULL_PAD)
+}
+
+/// Snapshot pair bracketing the render time, as indices into `GameState::snapshots`
+pub struct Interp {
+    pub from: usize,
+    pub to: usize,
+    pub t: f64,
+    pub extrap: f64, // seconds past the newest snapshot
+}
+
+/// Bracket `render_time` (ms) in the snapshot history. Past the newest snapshot,
+/// `extrapolate` lets entities coast along their velocity for up to MAX_EXTRAPOLATION.
+fn sample_snapshots(snapshots: &VecDeque<Snapshot>, render_time: f64, extrapolate: bool) -> Option<Interp> {
+    let n = snapshots.len();
+    let newest = snapshots.back()?;
+    if render_time >= newest.time {
+        // Snapshot is late: keep entities moving along their velocity (capped) instead of freezing
+        let extrap = if !extrapolate { 0.0 } else {
+            ((render_time - newest.time) / 1000.0).clamp(0.0, MAX_EXTRAPOLATION)
+        };
+        return Some(Interp { from: n - 1, to: n - 1, t: 1.0, extrap });
+    }
+    for i in (0..n - 1).rev() {
+        let (a, b) = (&snapshots[i], &snapshots[i + 1]);
+        if render_time >= a.time {
+            let span = b.time - a.time;
+            let t = if span > 0.0 { ((render_time - a.time) / span).clamp(0.0, 1.0) } else { 1.0 };
+            return Some(Interp { from: i, to: i + 1, t, extrap: 0.0 });
+        }
+    }
+    Some(Interp { from: 0, to: 0, t: 1.0, extrap: 0.0 })
+}
+
+/// Push a position `secs` along its velocity, wrapping around the world edge
+pub fn extrapolate(pos: f64, vel: f64, secs: f64, size: f64) -> f64 {
+    (pos + vel * secs).rem_euclid(size)
+}
+
 thread_local! {
     static SHIPS_LOADED: RefCell<bool> = RefCell::new(false);
     static ASTEROIDS_LOADED: RefCell<bool> = RefCell::new(false);
@@ -48,55 +96,76 @@ pub fn render(state: &SharedState, dt: f64) {
         Some(c) => c.unchecked_into::<web_sys::HtmlCanvasElement>(),
         None => return,
     };
+    let hud_canvas = match document.get_element_by_id("hudCanvas") {
+        Some(c) => c.unchecked_into::<web_sys::HtmlCanvasElement>(),
+        None => return,
+    };
 
     let bg_ctx: CanvasRenderingContext2d = bg_canvas
         .get_context("2d").unwrap().unwrap().unchecked_into();
     let ctx: CanvasRenderingContext2d = game_canvas
         .get_context("2d").unwrap().unwrap().unchecked_into();
+    let hud_ctx: CanvasRenderingContext2d = hud_canvas
+        .get_context("2d").unwrap().unwrap().unchecked_into();
+
+    let render_scale = state.borrow().settings.render_scale;
+    crate::canvas::apply_render_scale(&bg_ctx, render_scale);
+    crate::canvas::apply_render_scale(&ctx, render_scale);
+    crate::canvas::apply_render_scale(&hud_ctx, render_scale);
 
-    // Compute interpolation factor
-    let (screen_w, screen_h, cam_x, cam_y, cam_zoom, interp_t);
+    // Advance the locally predicted ship with the latest input
+    crate::input::update_steering(&mut state.borrow_mut(), dt);
+    prediction::step(&mut state.borrow_mut(), dt);
+
+    // Pick the snapshot pair to interpolate between, `interp_delay` ms behind the newest
+    let (screen_w, screen_h, cam_x, cam_y, cam_zoom, interp, predicted);
     {
         let s = state.borrow();
         screen_w = s.screen_w;
         screen_h = s.screen_h;
         cam_zoom = s.cam_zoom;
 
-        // Interpolate camera between prev and current
-        let elapsed = now - s.interp_last_update;
-        let t = if s.interp_interval > 0.0 { (elapsed / s.interp_interval).min(1.0).max(0.0) } else { 1.0 };
-        interp_t = t;
-        cam_x = s.prev_cam_x + (s.cam_x - s.prev_cam_x) * t;
-        cam_y = s.prev_cam_y + (s.cam_y - s.prev_cam_y) * t;
+        interp = sample_snapshots(&s.snapshots, now - s.interp_delay, !s.replay_mode);
+        let (cx, cy) = match interp {
+            Some(ref i) => {
+                let (from, to) = (&s.snapshots[i.from], &s.snapshots[i.to]);
+                let (me_vx, me_vy) = s.my_id.as_ref()
+                    .and_then(|id| to.players.get(id))
+                    .filter(|p| p.a)
+                    .map(|p| (p.vx.unwrap_or(0.0), p.vy.unwrap_or(0.0)))
+                    .unwrap_or((0.0, 0.0));
+                (extrapolate(from.cam_x + (to.cam_x - from.cam_x) * i.t, me_vx, i.extrap, WORLD_W),
+                 extrapolate(from.cam_y + (to.cam_y - from.cam_y) * i.t, me_vy, i.extrap, WORLD_H))
+            }
+            None => (s.cam_x, s.cam_y),
+        };
+        cam_x = cx;
+        cam_y = cy;
+        predicted = s.predicted.as_ref().map(|p| (p.x, p.y, p.r));
     }
+    // Follow the predicted ship instead of the (older) interpolated snapshot
+    let (cam_x, cam_y) = predicted.map(|(x, y, _)| (x, y)).unwrap_or((cam_x, cam_y));
+    let (cam_x, cam_y) = {
+        let mut s = state.borrow_mut();
+        let (lag, dead_zone) = (s.settings.camera_lag, s.settings.camera_dead_zone);
+        crate::camera::follow(&mut s.view, cam_x, cam_y, lag, dead_zone, dt)
+    };
 
-    // Update effects
+    // Expire timed overlays (effects themselves advance on a fixed step in game_loop)
     {
         let mut s = state.borrow_mut();
-        effects::update_shake(&mut s, dt);
-        let mut particles = std::mem::take(&mut s.particles);
-        let mut explosions = std::mem::take(&mut s.explosions);
-        let mut damage_numbers = std::mem::take(&mut s.damage_numbers);
-        let mut hit_markers = std::mem::take(&mut s.hit_markers);
-        drop(s);
-        effects::update_particles(&mut particles, &mut explosions, dt);
-        effects::update_damage_numbers(&mut damage_numbers, dt);
-        effects::update_hit_markers(&mut hit_markers, dt);
-        let mut s = state.borrow_mut();
-        s.particles = particles;
-        s.explosions = explosions;
-        s.damage_numbers = damage_numbers;
-        s.hit_markers = hit_markers;
         // Clean up expired mob speech
         let now = js_sys::Date::now();
         s.mob_speech.retain(|sp| now - sp.time < 3000.0);
+        s.coach_marks.retain(|m| now - m.time < coach::MARK_DURATION);
+        s.markers.retain(|m| now - m.time < crate::markers::MARKER_DURATION);
     }
 
     // Animate hyperspace_t
     let hyperspace_t;
     {
         let mut s = state.borrow_mut();
-        let target = if s.shift_pressed { 1.0 } else { 0.0 };
+        let target = if s.shift_pressed && !s.settings.reduced_motion { 1.0 } else { 0.0 };
         let speed = 3.0; // transition speed
         if s.hyperspace_t < target {
             s.hyperspace_t = (s.hyperspace_t + speed * dt).min(target);
@@ -104,6 +173,7 @@ pub fn render(state: &SharedState, dt: f64) {
             s.hyperspace_t = (s.hyperspace_t - speed * dt).max(target);
         }
         hyperspace_t = s.hyperspace_t;
+        crate::tutorial::update(&mut s, dt);
     }
 
     // Starfield on bg canvas
@@ -114,7 +184,8 @@ pub fn render(state: &SharedState, dt: f64) {
             .map(|p| p.r)
             .unwrap_or(0.0)
     };
-    starfield::render_starfield(&bg_ctx, cam_x, cam_y, screen_w, screen_h, hyperspace_t, player_rotation);
+    let drift_t = if state.borrow().settings.reduced_motion { 0.0 } else { now / 1000.0 };
+    starfield::render_starfield(&bg_ctx, (cam_x, cam_y), screen_w, screen_h, hyperspace_t, player_rotation, drift_t);
 
     // Clear game canvas
     ctx.clear_rect(0.0, 0.0, screen_w, screen_h);
@@ -142,65 +213,94 @@ pub fn render(state: &SharedState, dt: f64) {
     {
         let s = state.borrow();
         let time_secs = now / 1000.0;
-        pickups::render_pickups(&ctx, &s.pickups, offset_x, offset_y, vw, vh, time_secs);
+        let near = visible(&s, Kind::Pickup, offset_x, offset_y, vw, vh).filter_map(|id| s.pickups.get(&id));
+        pickups::render_pickups(&ctx, near, offset_x, offset_y, vw, vh, time_secs);
     }
 
     // Asteroids
     {
         let s = state.borrow();
-        asteroids::render_asteroids(&ctx, &s.asteroids, offset_x, offset_y, vw, vh);
+        let near = visible(&s, Kind::Asteroid, offset_x, offset_y, vw, vh).filter_map(|id| s.asteroids.get(&id));
+        let since = ((now - s.interp_last_update) / 1000.0).clamp(0.0, 0.25);
+        asteroids::render_asteroids(&ctx, near, offset_x, offset_y, vw, vh, since);
     }
 
     // Projectiles
     {
         let s = state.borrow();
-        projectiles::render_projectiles(&ctx, &s.projectiles, &s.players, offset_x, offset_y, vw, vh);
+        let near = visible(&s, Kind::Projectile, offset_x, offset_y, vw, vh).filter_map(|id| s.projectiles.get(&id));
+        projectiles::render_projectiles(&ctx, near, &s.players, offset_x, offset_y, vw, vh);
     }
 
     // Players (with interpolation — render inline to avoid per-frame Vec/String allocations)
     {
         let s = state.borrow();
-        let my_id = s.my_id.as_deref();
+        let my_id = s.my_id;
         let my_boosting = s.boosting;
 
-        for (id, p) in &s.players {
+        let (players, prev_players, interp_t, extrap) = match interp {
+            Some(ref i) => (&s.snapshots[i.to].players, Some(&s.snapshots[i.from].players), i.t, i.extrap),
+            None => (&s.players, None, 1.0, 0.0),
+        };
+
+        for id in visible(&s, Kind::Player, offset_x, offset_y, vw, vh) {
+            let Some(p) = players.get(&id) else { continue };
             if !p.a { continue; }
-            let (px, py, pr) = if let Some(prev) = s.prev_players.get(id) {
+            let (px, py, pr) = if let Some(prev) = prev_players.and_then(|pp| pp.get(&id)) {
                 (prev.x + (p.x - prev.x) * interp_t,
                  prev.y + (p.y - prev.y) * interp_t,
                  lerp_angle(prev.r, p.r, interp_t))
             } else {
                 (p.x, p.y, p.r)
             };
+            let px = extrapolate(px, p.vx.unwrap_or(0.0), extrap, WORLD_W);
+            let py = extrapolate(py, p.vy.unwrap_or(0.0), extrap, WORLD_H);
+            let is_me = my_id == Some(id);
+            let (px, py, pr) = match predicted {
+                Some(pred) if is_me => pred,
+                _ => (px, py, pr),
+            };
 
             let sx = px - offset_x;
             let sy = py - offset_y;
             if sx < -60.0 || sx > vw + 60.0 || sy < -60.0 || sy > vh + 60.0 { continue; }
 
-            let is_me = my_id == Some(id.as_str());
             let pvx = p.vx.unwrap_or(0.0);
             let pvy = p.vy.unwrap_or(0.0);
             let speed = (pvx * pvx + pvy * pvy).sqrt();
             let boosting = is_me && my_boosting;
 
+            if is_me {
+                effects::draw_heal_aura(&ctx, sx, sy, s.heal_glow);
+            }
             effects::draw_engine_beam(&ctx, sx, sy, pr, speed, p.s, boosting);
             ships::draw_ship(&ctx, sx, sy, pr, p.s);
-            hud::draw_player_health_bar(&ctx, sx, sy, p.hp, p.mhp, &p.n, is_me);
+            if is_me {
+                effects::draw_pickup_pulls(&ctx, &s.pickup_pulls, sx, sy, offset_x, offset_y);
+            }
+            hud::draw_player_health_bar(&ctx, sx, sy, p.hp, p.mhp, s.name_of(id), is_me);
         }
     }
 
     // Mobs (with interpolation — render inline to avoid per-frame HashMap allocation)
     {
         let s = state.borrow();
-        for (id, mob) in &s.mobs {
+        let (mobs, prev_mobs, interp_t, extrap) = match interp {
+            Some(ref i) => (&s.snapshots[i.to].mobs, Some(&s.snapshots[i.from].mobs), i.t, i.extrap),
+            None => (&s.mobs, None, 1.0, 0.0),
+        };
+        for id in visible(&s, Kind::Mob, offset_x, offset_y, vw, vh) {
+            let Some(mob) = mobs.get(&id) else { continue };
             if !mob.a { continue; }
-            let (mx, my, mr) = if let Some(prev) = s.prev_mobs.get(id) {
+            let (mx, my, mr) = if let Some(prev) = prev_mobs.and_then(|pm| pm.get(&id)) {
                 (prev.x + (mob.x - prev.x) * interp_t,
                  prev.y + (mob.y - prev.y) * interp_t,
                  lerp_angle(prev.r, mob.r, interp_t))
             } else {
                 (mob.x, mob.y, mob.r)
             };
+            let mx = extrapolate(mx, mob.vx.unwrap_or(0.0), extrap, WORLD_W);
+            let my = extrapolate(my, mob.vy.unwrap_or(0.0), extrap, WORLD_H);
             mobs::render_mob(&ctx, mx, my, mr, mob.vx.unwrap_or(0.0), mob.vy.unwrap_or(0.0), mob.hp, mob.mhp, mob.s, offset_x, offset_y, vw, vh);
         }
     }
@@ -208,8 +308,8 @@ pub fn render(state: &SharedState, dt: f64) {
     // Particles & Explosions
     {
         let s = state.borrow();
-        effects::render_particles(&ctx, &s.particles, offset_x, offset_y, vw, vh);
-        effects::render_explosions(&ctx, &s.explosions, offset_x, offset_y, vw, vh);
+        effects::render_particles(&ctx, s.particles.as_slice(), offset_x, offset_y, vw, vh);
+        effects::render_explosions(&ctx, s.explosions.as_slice(), offset_x, offset_y, vw, vh);
     }
 
     // Mob speech bubbles (world-space, inside zoom)
@@ -221,7 +321,17 @@ pub fn render(state: &SharedState, dt: f64) {
     // Damage numbers (world-space, inside zoom)
     {
         let s = state.borrow();
-        effects::render_damage_numbers(&ctx, &s.damage_numbers, offset_x, offset_y, vw, vh);
+        effects::render_damage_numbers(&ctx, s.damage_numbers.as_slice(), offset_x, offset_y, vw, vh);
+    }
+
+    // Coach annotations, world pings, and the player's cursor as seen by the coach (world-space, inside zoom)
+    {
+        let s = state.borrow();
+        coach::render_marks(&ctx, &s, offset_x, offset_y);
+        crate::markers::render_markers(&ctx, &s, offset_x, offset_y);
+        if s.coach_target.is_some() {
+            coach::render_player_cursor(&ctx, &s, offset_x, offset_y);
+        }
     }
 
     // Auto-aim reticle (when controller attached or mobile)
@@ -239,6 +349,7 @@ pub fn render(state: &SharedState, dt: f64) {
         if s.debug_hitboxes {
             draw_debug_hitboxes(&ctx, &s, offset_x, offset_y, vw, vh);
         }
+        crate::inspector::render(&ctx, &s, interp.as_ref(), offset_x, offset_y);
     }
 
     ctx.restore();
@@ -249,8 +360,10 @@ pub fn render(state: &SharedState, dt: f64) {
         effects::render_hit_markers(&ctx, &s.hit_markers, screen_w, screen_h);
     }
 
-    // HUD (screen-space, no zoom)
-    hud::render_hud(&ctx, state);
+    // HUD (screen-space, no zoom); text panels go to their own canvas
+    hud::render_hud(&ctx, &hud_ctx, state);
+    crate::console::render(&ctx, &state.borrow(), dt);
+    crate::netlog::refresh();
 }
 
 fn draw_world_bounds(ctx: &CanvasRenderingContext2d, offset_x: f64, offset_y: f64) {
@@ -298,7 +411,7 @@ fn draw_debug_hitboxes(ctx: &CanvasRenderingContext2d, s: &crate::state::GameSta
         if !mob.a { continue; }
         let sx = mob.x - offset_x;
         let sy = mob.y - offset_y;
-        let r = if mob.s == 3 { SD_MOB_RADIUS } else { MOB_RADIUS };
+        let r = mob_radius(mob.s);
         if sx < -r - 10.0 || sx > vw + r + 10.0 || sy < -r - 10.0 || sy > vh + r + 10.0 { continue; }
 
         ctx.begin_path();
@@ -340,3 +453,6 @@ fn draw_debug_hitboxes(ctx: &CanvasRenderingContext2d, s: &crate::state::GameSta
         ctx.stroke();
     }
 }
+
+#[cfg(test)]
+mod tests;
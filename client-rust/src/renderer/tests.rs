@@ -0,0 +1,59 @@
+use super::*;
+
+fn history(times: &[f64]) -> VecDeque<Snapshot> {
+    times.iter().map(|&time| Snapshot { time, ..Default::default() }).collect()
+}
+
+#[test]
+fn nothing_to_sample_without_snapshots() {
+    assert!(sample_snapshots(&VecDeque::new(), 100.0, true).is_none());
+}
+
+#[test]
+fn brackets_the_render_time() {
+    let snaps = history(&[0.0, 50.0, 100.0]);
+    let i = sample_snapshots(&snaps, 75.0, true).unwrap();
+    assert_eq!((i.from, i.to, i.t, i.extrap), (1, 2, 0.5, 0.0));
+    let i = sample_snapshots(&snaps, 50.0, true).unwrap();
+    assert_eq!((i.from, i.to, i.t), (1, 2, 0.0));
+}
+
+#[test]
+fn holds_the_oldest_before_history_starts() {
+    let i = sample_snapshots(&history(&[100.0, 150.0]), 20.0, true).unwrap();
+    assert_eq!((i.from, i.to, i.t), (0, 0, 1.0));
+}
+
+#[test]
+fn same_time_snapshots_dont_divide_by_zero() {
+    let i = sample_snapshots(&history(&[0.0, 50.0, 50.0, 100.0]), 50.0, true).unwrap();
+    assert!(i.t.is_finite());
+}
+
+#[test]
+fn extrapolates_late_snapshots_up_to_the_cap() {
+    let snaps = history(&[0.0, 50.0]);
+    let i = sample_snapshots(&snaps, 80.0, true).unwrap();
+    assert_eq!((i.from, i.to), (1, 1));
+    assert!((i.extrap - 0.03).abs() < 1e-9);
+    let i = sample_snapshots(&snaps, 50.0 + 60_000.0, true).unwrap();
+    assert_eq!(i.extrap, MAX_EXTRAPOLATION);
+    // Replays freeze instead
+    assert_eq!(sample_snapshots(&snaps, 80.0, false).unwrap().extrap, 0.0);
+}
+
+#[test]
+fn extrapolation_wraps_around_the_world() {
+    assert_eq!(extrapolate(100.0, 50.0, 0.5, WORLD_W), 125.0);
+    assert_eq!(extrapolate(10.0, -100.0, 0.5, WORLD_W), WORLD_W - 40.0);
+    assert_eq!(extrapolate(WORLD_H - 1.0, 100.0, 0.5, WORLD_H), 49.0);
+}
+
+#[test]
+fn headings_turn_the_short_way() {
+    use std::f64::consts::PI;
+    assert!((lerp_angle(0.0, PI / 2.0, 0.5) - PI / 4.0).abs() < 1e-9);
+    // From just below +PI to just above -PI crosses the seam, not zero
+    let mid = lerp_angle(PI - 0.1, -PI + 0.1, 0.5);
+    assert!((mid - PI).abs() < 1e-9);
+}
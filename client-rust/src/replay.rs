@@ -0,0 +1,375 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use crate::network::{Network, SharedNetwork};
+
+// Replay file layout (gzip-compressed):
+//   "SSRP" + version byte, then records of
+//   [kind u8][time_ms u32 BE][len u32 BE][payload]
+pub const MAGIC: &[u8; 4] = b"SSRP";
+pub const VERSION: u8 = 2; // 2: numeric entity IDs, names in the meta record
+pub const REC_STATE: u8 = 0; // msgpack GameStateMsg, exactly as received
+pub const REC_EVENT: u8 = 1; // JSON envelope text, exactly as received
+pub const REC_META: u8 = 2;  // JSON {"id": my_id, "names": {id: name}}
+
+const MAX_REPLAY_BYTES: usize = 64 * 1024 * 1024;
+const KEYFRAME_SPAN: usize = 60; // server sends a full snapshot at least this often
+const REPLAY_SEED: u64 = 1; // visual RNG seed during playback
+pub const SPEEDS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+struct Recorder {
+    buf: Vec<u8>,
+    start: f64,
+    active: bool,
+}
+
+/// Who the recording follows, from the REC_META record
+#[derive(Default)]
+pub struct Meta {
+    pub my_id: u32,
+    pub names: HashMap<u32, String>,
+}
+
+pub struct Record {
+    pub kind: u8,
+    pub t: f64, // ms since recording start
+    pub payload: Vec<u8>,
+}
+
+struct Playback {
+    net: SharedNetwork,
+    records: Vec<Record>,
+    pos: usize,    // next record to dispatch
+    clock: f64,    // replay time (ms)
+    duration: f64, // time of last record (ms)
+    speed: f64,
+    paused: bool,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Recorder> = const { RefCell::new(Recorder { buf: Vec::new(), start: 0.0, active: false }) };
+    static PLAYBACK: RefCell<Option<Playback>> = const { RefCell::new(None) };
+}
+
+fn now() -> f64 {
+    web_sys::window().unwrap().performance().unwrap().now()
+}
+
+fn append(rec: &mut Recorder, kind: u8, payload: &[u8]) {
+    if !rec.active { return; }
+    if rec.buf.len() + payload.len() + 9 > MAX_REPLAY_BYTES {
+        // Out of room — keep what we have rather than growing without bound
+        rec.active = false;
+        return;
+    }
+    let t = (now() - rec.start).max(0.0) as u32;
+    rec.buf.push(kind);
+    rec.buf.extend_from_slice(&t.to_be_bytes());
+    rec.buf.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    rec.buf.extend_from_slice(payload);
+}
+
+/// Begin a fresh recording (called when we join a match)
+pub fn start(my_id: u32, names: &HashMap<u32, String>) {
+    RECORDER.with(|r| {
+        let mut rec = r.borrow_mut();
+        rec.buf.clear();
+        rec.buf.extend_from_slice(MAGIC);
+        rec.buf.push(VERSION);
+        rec.start = now();
+        rec.active = true;
+        let meta = serde_json::json!({"id": my_id, "names": names}).to_string();
+        append(&mut rec, REC_META, meta.as_bytes());
+    });
+}
+
+/// Stop appending; the buffer stays available for download
+pub fn stop() {
+    RECORDER.with(|r| r.borrow_mut().active = false);
+}
+
+pub fn record_state(bytes: &[u8]) {
+    RECORDER.with(|r| append(&mut r.borrow_mut(), REC_STATE, bytes));
+}
+
+pub fn record_event(text: &str) {
+    RECORDER.with(|r| append(&mut r.borrow_mut(), REC_EVENT, text.as_bytes()));
+}
+
+fn has_recording() -> bool {
+    RECORDER.with(|r| r.borrow().buf.len() > MAGIC.len() + 1)
+}
+
+/// Pipe a blob through a browser (De)CompressionStream. Built via Reflect since
+/// web-sys only exposes these behind `web_sys_unstable_apis`.
+pub async fn transform_blob(blob: &web_sys::Blob, stream_class: &str) -> Result<web_sys::Blob, JsValue> {
+    let ctor: js_sys::Function = js_sys::Reflect::get(&js_sys::global(), &stream_class.into())?.dyn_into()?;
+    let transform = js_sys::Reflect::construct(&ctor, &js_sys::Array::of1(&"gzip".into()))?;
+    let piped = blob.stream().pipe_through(transform.unchecked_ref());
+    let response = web_sys::Response::new_with_opt_readable_stream(Some(&piped))?;
+    let out = wasm_bindgen_futures::JsFuture::from(response.blob()?).await?;
+    Ok(out.unchecked_into())
+}
+
+async fn download_inner() -> Result<(), JsValue> {
+    let bytes = RECORDER.with(|r| r.borrow().buf.clone());
+    let arr = js_sys::Uint8Array::from(bytes.as_slice());
+    let raw = web_sys::Blob::new_with_u8_array_sequence(&js_sys::Array::of1(&arr))?;
+    let compressed = transform_blob(&raw, "CompressionStream").await?;
+
+    let url = web_sys::Url::create_object_url_with_blob(&compressed)?;
+    let document = web_sys::window().unwrap().document().unwrap();
+    let a: web_sys::HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    a.set_href(&url);
+    let stamp = js_sys::Date::new_0().to_iso_string().as_string().unwrap_or_default();
+    a.set_download(&format!("match-{}.replay", stamp.replace([':', '.'], "-")));
+    a.click();
+    web_sys::Url::revoke_object_url(&url)?;
+    Ok(())
+}
+
+/// Compress the current recording and save it as a `.replay` file
+pub fn download() {
+    if !has_recording() { return; }
+    wasm_bindgen_futures::spawn_local(async {
+        if let Err(e) = download_inner().await {
+            web_sys::console::error_1(&format!("Replay download failed: {:?}", e).into());
+        }
+    });
+}
+
+pub fn setup_replay_btn() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let btn = match document.get_element_by_id("replayBtn") {
+        Some(b) => b,
+        None => return,
+    };
+    let click = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        download();
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let _ = btn.add_event_listener_with_callback("click", click.as_ref().unchecked_ref());
+    click.forget();
+}
+
+/// Parse a decompressed replay into (who it follows, records)
+pub fn parse(bytes: &[u8]) -> Result<(Meta, Vec<Record>), String> {
+    if bytes.len() < 5 || &bytes[..4] != MAGIC {
+        return Err("not a replay file".into());
+    }
+    if bytes[4] != VERSION {
+        return Err(format!("unsupported replay version {}", bytes[4]));
+    }
+    let mut info = Meta::default();
+    let mut records = Vec::new();
+    let mut i = 5;
+    while i + 9 <= bytes.len() {
+        let kind = bytes[i];
+        let t = u32::from_be_bytes([bytes[i + 1], bytes[i + 2], bytes[i + 3], bytes[i + 4]]) as f64;
+        let len = u32::from_be_bytes([bytes[i + 5], bytes[i + 6], bytes[i + 7], bytes[i + 8]]) as usize;
+        i += 9;
+        if i + len > bytes.len() {
+            break; // truncated tail — keep what parsed cleanly
+        }
+        let payload = bytes[i..i + len].to_vec();
+        i += len;
+        if kind == REC_META {
+            if let Ok(meta) = serde_json::from_slice::<serde_json::Value>(&payload) {
+                info.my_id = meta["id"].as_u64().unwrap_or_default() as u32;
+                info.names = serde_json::from_value(meta["names"].clone()).unwrap_or_default();
+            }
+            continue;
+        }
+        records.push(Record { kind, t, payload });
+    }
+    if records.is_empty() {
+        return Err("replay is empty".into());
+    }
+    Ok((info, records))
+}
+
+async fn load_inner(net: SharedNetwork, file: web_sys::File) -> Result<(), JsValue> {
+    let raw = transform_blob(&file, "DecompressionStream").await?;
+    let buf = wasm_bindgen_futures::JsFuture::from(raw.array_buffer()).await?;
+    let bytes = js_sys::Uint8Array::new(&buf).to_vec();
+    let (meta, records) = parse(&bytes).map_err(|e| JsValue::from_str(&e))?;
+    start_playback(net, meta, records);
+    Ok(())
+}
+
+/// Decompress and start playing a `.replay` file picked or dropped in the lobby
+pub fn load_file(net: SharedNetwork, file: web_sys::File) {
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = load_inner(net, file).await {
+            web_sys::console::error_1(&format!("Replay load failed: {:?}", e).into());
+        }
+    });
+}
+
+fn start_playback(net: SharedNetwork, meta: Meta, records: Vec<Record>) {
+    stop();
+    // Same seed every time, so a replay's explosions look the same on every viewing
+    crate::rng::seed(Some(REPLAY_SEED));
+    let duration = records.last().map(|r| r.t).unwrap_or(0.0);
+    Network::enter_replay(&net, meta.my_id, meta.names);
+    PLAYBACK.with(|p| {
+        *p.borrow_mut() = Some(Playback {
+            net,
+            records,
+            pos: 0,
+            clock: 0.0,
+            duration,
+            speed: 1.0,
+            paused: false,
+        });
+    });
+}
+
+/// Dispatch every record up to the current replay time (called once per frame)
+pub fn advance(dt: f64) {
+    let (net, due) = match PLAYBACK.with(|p| {
+        let mut p = p.borrow_mut();
+        let pb = p.as_mut()?;
+        if !pb.paused {
+            pb.clock = (pb.clock + dt * 1000.0 * pb.speed).min(pb.duration);
+        }
+        let mut due = Vec::new();
+        while pb.pos < pb.records.len() && pb.records[pb.pos].t <= pb.clock {
+            let r = &pb.records[pb.pos];
+            due.push((r.kind, r.payload.clone()));
+            pb.pos += 1;
+        }
+        if pb.pos >= pb.records.len() {
+            pb.paused = true;
+        }
+        Some((pb.net.clone(), due))
+    }) {
+        Some(v) => v,
+        None => return,
+    };
+
+    for (kind, payload) in due {
+        dispatch(&net, kind, &payload);
+    }
+    update_controls();
+}
+
+fn dispatch(net: &SharedNetwork, kind: u8, payload: &[u8]) {
+    match kind {
+        REC_STATE => Network::replay_state(net, payload),
+        REC_EVENT => {
+            if let Ok(text) = std::str::from_utf8(payload) {
+                Network::replay_event(net, text);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn toggle_pause() {
+    PLAYBACK.with(|p| {
+        if let Some(pb) = p.borrow_mut().as_mut() {
+            // Restart from the top when pressing play at the end
+            if pb.paused && pb.pos >= pb.records.len() {
+                pb.clock = 0.0;
+                pb.pos = 0;
+            }
+            pb.paused = !pb.paused;
+        }
+    });
+    update_controls();
+}
+
+pub fn set_speed(speed: f64) {
+    PLAYBACK.with(|p| {
+        if let Some(pb) = p.borrow_mut().as_mut() {
+            pb.speed = speed;
+        }
+    });
+}
+
+pub fn speed() -> f64 {
+    PLAYBACK.with(|p| p.borrow().as_ref().map(|pb| pb.speed).unwrap_or(1.0))
+}
+
+/// Jump to `t` ms: rebuild state from the snapshots just before it and skip the events in between
+pub fn seek(t: f64) {
+    let found = PLAYBACK.with(|p| {
+        let mut p = p.borrow_mut();
+        let pb = p.as_mut()?;
+        pb.clock = t.clamp(0.0, pb.duration);
+        pb.pos = pb.records.partition_point(|r| r.t <= pb.clock);
+        // Snapshots may be deltas, so replay enough of them to pass through a keyframe
+        let mut payloads: Vec<Vec<u8>> = pb.records[..pb.pos].iter().rev()
+            .filter(|r| r.kind == REC_STATE)
+            .take(KEYFRAME_SPAN)
+            .map(|r| r.payload.clone())
+            .collect();
+        if payloads.is_empty() { return None; }
+        payloads.reverse();
+        Some((pb.net.clone(), payloads))
+    });
+    let (net, payloads) = match found {
+        Some(v) => v,
+        None => return,
+    };
+
+    {
+        let state = net.borrow().state.clone();
+        let mut s = state.borrow_mut();
+        // Drop interpolation history and transient effects so nothing streaks across the jump
+        s.players.clear();
+        s.mobs.clear();
+        s.snapshots.clear();
+        s.particles.clear();
+        s.explosions.clear();
+        s.damage_numbers.clear();
+        s.kill_feed.clear();
+    }
+    for payload in &payloads {
+        Network::replay_state(&net, payload);
+    }
+    {
+        // They all arrived "now": keep only the newest so interpolation starts fresh
+        let state = net.borrow().state.clone();
+        let mut s = state.borrow_mut();
+        let n = s.snapshots.len();
+        s.snapshots.drain(..n.saturating_sub(1));
+    }
+    update_controls();
+}
+
+/// End playback and return to the lobby
+pub fn exit_playback() {
+    let pb = PLAYBACK.with(|p| p.borrow_mut().take());
+    if let Some(pb) = pb {
+        Network::exit_replay(&pb.net);
+    }
+}
+
+fn format_time(ms: f64) -> String {
+    let secs = (ms / 1000.0) as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Sync the playback bar DOM with the current clock
+fn update_controls() {
+    let (clock, duration, paused) = match PLAYBACK.with(|p| {
+        p.borrow().as_ref().map(|pb| (pb.clock, pb.duration, pb.paused))
+    }) {
+        Some(v) => v,
+        None => return,
+    };
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(seek) = document.get_element_by_id("replaySeek")
+        .and_then(|e| e.dyn_into::<web_sys::HtmlInputElement>().ok()) {
+        seek.set_max(&format!("{}", duration as u32));
+        seek.set_value(&format!("{}", clock as u32));
+    }
+    if let Some(label) = document.get_element_by_id("replayTime") {
+        label.set_text_content(Some(&format!("{} / {}", format_time(clock), format_time(duration))));
+    }
+    if let Some(btn) = document.get_element_by_id("replayPlay") {
+        btn.set_text_content(Some(if paused { "\u{25B6}" } else { "\u{23F8}" }));
+    }
+}
@@ -0,0 +1,57 @@
+// Session resume: remember which ship this tab owns so a dropped connection
+// or an accidental reload can reclaim it instead of starting over.
+// sessionStorage keeps it per tab, and it survives reloads but not closing the tab.
+
+const KEY: &str = "resume";
+const CONTROL_KEY: &str = "control"; // a phone controller's session and player
+
+pub struct ControlTicket {
+    pub sid: String,
+    pub pid: String,
+    pub gunner: bool,
+}
+
+pub struct Ticket {
+    pub sid: String,
+    pub pid: String,
+    pub tok: String,
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.session_storage().ok()?
+}
+
+pub fn save(sid: &str, pid: &str, tok: &str) {
+    if let Some(s) = storage() {
+        let v = serde_json::json!({"sid": sid, "pid": pid, "tok": tok});
+        let _ = s.set_item(KEY, &v.to_string());
+    }
+}
+
+pub fn load() -> Option<Ticket> {
+    let raw = storage()?.get_item(KEY).ok()??;
+    let v: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let field = |k: &str| v.get(k).and_then(|x| x.as_str()).map(String::from);
+    Some(Ticket { sid: field("sid")?, pid: field("pid")?, tok: field("tok")? })
+}
+
+pub fn clear() {
+    if let Some(s) = storage() {
+        let _ = s.remove_item(KEY);
+    }
+}
+
+pub fn save_control(sid: &str, pid: &str, gunner: bool) {
+    if let Some(s) = storage() {
+        let v = serde_json::json!({"sid": sid, "pid": pid, "gunner": gunner});
+        let _ = s.set_item(CONTROL_KEY, &v.to_string());
+    }
+}
+
+pub fn load_control() -> Option<ControlTicket> {
+    let raw = storage()?.get_item(CONTROL_KEY).ok()??;
+    let v: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let field = |k: &str| v.get(k).and_then(|x| x.as_str()).map(String::from);
+    let gunner = v.get("gunner").and_then(|x| x.as_bool()).unwrap_or(false);
+    Some(ControlTicket { sid: field("sid")?, pid: field("pid")?, gunner })
+}
@@ -0,0 +1,54 @@
+// Seedable xorshift64 for visuals (faster than Math.random across the JS
+// boundary). Effects, the starfield and fog each draw from their own stream, so
+// a fixed seed reproduces each of them no matter how much the others are used.
+// Unseeded streams start from Math.random on first use.
+
+use std::cell::Cell;
+
+#[derive(Clone, Copy)]
+pub enum Stream {
+    Effects,
+    Starfield,
+    Fog,
+}
+
+thread_local! {
+    static STATES: Cell<[u64; 3]> = const { Cell::new([0; 3]) };
+}
+
+/// splitmix64, to spread one seed into unrelated, non-zero stream states
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (z ^ (z >> 31)).max(1)
+}
+
+/// Uniform in [0, 1)
+pub fn random(stream: Stream) -> f64 {
+    STATES.with(|s| {
+        let mut states = s.get();
+        let x = &mut states[stream as usize];
+        if *x == 0 {
+            *x = mix((js_sys::Math::random() * u64::MAX as f64) as u64);
+        }
+        *x ^= *x << 13;
+        *x ^= *x >> 7;
+        *x ^= *x << 17;
+        let r = (*x >> 11) as f64 / (1u64 << 53) as f64;
+        s.set(states);
+        r
+    })
+}
+
+/// Restart every stream from `seed` (or from Math.random with None) and redraw
+/// the cached starfield and fog from it
+pub fn seed(seed: Option<u64>) {
+    let states = match seed {
+        Some(seed) => [0u64, 1, 2].map(|i| mix(seed ^ (i << 56))),
+        None => [0; 3],
+    };
+    STATES.with(|s| s.set(states));
+    crate::starfield::rebuild();
+    crate::fog::rebuild();
+}
@@ -0,0 +1,10 @@
+// Session links (/<uuid>, optionally under /rust/), parsed the same way by the
+// game client and the slim controller build.
+
+const UUID_PATTERN: &str = "[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}";
+
+/// Session ID named by the page path, if it is a session link
+pub fn session_from_path(pathname: &str) -> Option<String> {
+    let uuid_re = js_sys::RegExp::new(&format!("^(?:/rust)?/({})$", UUID_PATTERN), "");
+    uuid_re.exec(pathname).and_then(|m| m.get(1).as_string())
+}
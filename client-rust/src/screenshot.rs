@@ -0,0 +1,74 @@
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use crate::state::GameState;
+use crate::palette;
+
+const STAMP_H: f64 = 28.0;
+
+/// Composite bg + game canvases, stamp the scoreboard, and download as PNG
+pub fn capture(s: &GameState) {
+    let url = match to_data_url(s) {
+        Some(u) => u,
+        None => return,
+    };
+    let document = web_sys::window().unwrap().document().unwrap();
+    let a: web_sys::HtmlAnchorElement = match document.create_element("a") {
+        Ok(a) => a.unchecked_into(),
+        Err(_) => return,
+    };
+    a.set_href(&url);
+    let stamp = js_sys::Date::new_0().to_iso_string().as_string().unwrap_or_default();
+    a.set_download(&format!("screenshot-{}.png", stamp.replace([':', '.'], "-")));
+    a.click();
+}
+
+/// The stamped composite as a PNG data URL
+pub fn to_data_url(s: &GameState) -> Option<String> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let bg = document.get_element_by_id("bgCanvas")?.dyn_into::<HtmlCanvasElement>().ok()?;
+    let game = document.get_element_by_id("gameCanvas")?.dyn_into::<HtmlCanvasElement>().ok()?;
+    let hud = document.get_element_by_id("hudCanvas")?.dyn_into::<HtmlCanvasElement>().ok()?;
+
+    let out: HtmlCanvasElement = document.create_element("canvas").ok()?.unchecked_into();
+    out.set_width(game.width());
+    out.set_height(game.height());
+    let ctx: CanvasRenderingContext2d = out.get_context("2d").ok().flatten()?.unchecked_into();
+
+    let _ = ctx.draw_image_with_html_canvas_element(&bg, 0.0, 0.0);
+    let _ = ctx.draw_image_with_html_canvas_element(&game, 0.0, 0.0);
+    let _ = ctx.draw_image_with_html_canvas_element(&hud, 0.0, 0.0);
+    stamp_scoreboard(&ctx, s, game.width() as f64, game.height() as f64);
+
+    out.to_data_url_with_type("image/png").ok()
+}
+
+/// Bottom strip with every pilot ranked by score, plus the capture date
+fn stamp_scoreboard(ctx: &CanvasRenderingContext2d, s: &GameState, w: f64, h: f64) {
+    let mut ranked: Vec<_> = s.players.values().collect();
+    ranked.sort_by(|a, b| b.sc.cmp(&a.sc).then_with(|| a.id.cmp(&b.id)));
+
+    ctx.set_fill_style_str("rgba(0, 0, 0, 0.7)");
+    ctx.fill_rect(0.0, h - STAMP_H, w, STAMP_H);
+
+    ctx.set_font("bold 13px monospace");
+    ctx.set_text_align("left");
+    let y = h - STAMP_H / 2.0 + 5.0;
+    let mut x = 12.0;
+    for (i, p) in ranked.iter().enumerate() {
+        let is_me = s.my_id.as_ref() == Some(&p.id);
+        let entry = format!("{}. {} ", i + 1, s.name_of(p.id));
+        ctx.set_fill_style_str(if is_me { "#ffffff" } else { "#aaaaaa" });
+        let _ = ctx.fill_text(&entry, x, y);
+        x += ctx.measure_text(&entry).map(|m| m.width()).unwrap_or(0.0);
+        let score = p.sc.to_string();
+        ctx.set_fill_style_str(palette::ship(p.s));
+        let _ = ctx.fill_text(&score, x, y);
+        x += ctx.measure_text(&score).map(|m| m.width()).unwrap_or(0.0) + 18.0;
+        if x > w - 220.0 { break; }
+    }
+
+    let date = js_sys::Date::new_0().to_locale_string("en-US", &js_sys::Object::new());
+    ctx.set_text_align("right");
+    ctx.set_fill_style_str("#ffcc00");
+    let _ = ctx.fill_text(&format!("STAR WARS \u{2022} {}", String::from(date)), w - 12.0, y);
+}
@@ -0,0 +1,444 @@
+// Typed client settings, persisted to localStorage as one JSON value, plus the
+// settings panel (lobby button, gear button or Esc in game) that edits them live.
+
+use std::rc::Rc;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+#[cfg(not(feature = "controller-only"))]
+use crate::state::SharedState;
+use crate::auto_aim::Assist;
+use crate::i18n::{Lang, LANGS};
+use crate::palette::{Vision, VISIONS};
+use crate::constants::{ZOOM_MIN, ZOOM_MAX};
+
+const PREF_KEY: &str = "settings";
+const RESOLUTIONS: [(f64, &str); 3] = [(1.0, "Full"), (0.75, "75%"), (0.5, "50%")];
+const FPS_CAPS: [(u32, &str); 4] = [(0, "Uncapped"), (30, "30"), (60, "60"), (120, "120")];
+const DAMAGE_STACKS: [(u32, &str); 4] = [(1, "Every hit"), (5, "Up to 5 hits"), (10, "Up to 10 hits"), (25, "Up to 25 hits")];
+const CAMERA_LAGS: [(f64, &str); 4] = [(0.0, "Locked"), (0.08, "Tight"), (0.15, "Smooth"), (0.3, "Loose")];
+const CAMERA_DEAD_ZONES: [(f64, &str); 3] = [(0.0, "Off"), (40.0, "Small"), (100.0, "Large")];
+const REDUCED_MOTION_PARTICLES: usize = 40;
+
+/// How many particles effects may keep alive at once
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Particles {
+    Full,
+    Reduced,
+    Off,
+}
+
+impl Particles {
+    fn parse(v: &str) -> Particles {
+        match v {
+            "reduced" => Particles::Reduced,
+            "off" => Particles::Off,
+            _ => Particles::Full,
+        }
+    }
+
+    pub fn limit(self) -> usize {
+        match self {
+            Particles::Full => 200,
+            Particles::Reduced => 80,
+            Particles::Off => 0,
+        }
+    }
+}
+
+/// How much background detail to draw: stars, nebulae and fog. Auto is Low
+/// on touch devices and High elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Quality {
+    Auto,
+    Low,
+    Medium,
+    High,
+}
+
+impl Quality {
+    fn parse(v: &str) -> Quality {
+        match v {
+            "low" => Quality::Low,
+            "medium" => Quality::Medium,
+            "high" => Quality::High,
+            _ => Quality::Auto,
+        }
+    }
+
+    pub fn resolve(self, is_mobile: bool) -> Quality {
+        match self {
+            Quality::Auto if is_mobile => Quality::Low,
+            Quality::Auto => Quality::High,
+            q => q,
+        }
+    }
+}
+
+/// Every persisted client preference, stored as one JSON value under "settings".
+/// Missing fields fall back to their defaults, so adding one needs no migration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Graphics
+    pub particles: Particles,
+    pub quality: Quality,
+    pub screen_shake: bool,
+    pub shake_scale: f64,     // multiplier on every camera shake, 0 to 1.5
+    pub reduced_motion: bool, // no shake or hyperspace streaks, and a tight particle cap
+    pub render_scale: f64, // canvas backing resolution as a fraction of CSS pixels
+    pub zoom: f64,         // player's wheel/pinch zoom, multiplied into cam_zoom
+    pub fps_cap: u32,      // max frames per second; 0 draws on every animation frame
+    pub damage_stack: u32, // most rapid hits added up in one damage number; 1 shows each
+    pub camera_lag: f64,       // seconds the camera takes to catch up with the ship; 0 keeps it locked on
+    pub camera_dead_zone: f64, // px the ship can move from the middle before the camera follows
+
+    // Controls
+    pub toggle_fire: bool,
+    pub toggle_boost: bool,
+    pub keyboard_steer: bool,
+    pub aim_assist: Assist,
+    pub joy_dead_zone: f64,   // px of thumb travel ignored around the start point
+    pub joy_sensitivity: f64, // multiplier on the joystick-to-aim scale
+    pub aim_stick: bool,      // phone controller: right-hand aim stick and fire button instead of tap-to-fire
+    pub gyro_steer: bool,     // phone controller: tilt the phone to steer instead of the left stick
+    pub gyro_sensitivity: f64, // multiplier on tilt degrees to joystick px
+
+    // Network
+    pub data_saver: bool,
+    pub region: String, // ws-regions name to connect to; empty for the page's own server
+
+    // Interface
+    pub lang: Lang,
+    pub vision: Vision, // color-vision palette for ships, lasers and health bars
+
+    // Loadout
+    pub ship: Option<i32>, // hull picked in ship select; None lets the server assign one
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            particles: Particles::Full,
+            quality: Quality::Auto,
+            screen_shake: true,
+            shake_scale: 1.0,
+            reduced_motion: false,
+            render_scale: 1.0,
+            zoom: 1.0,
+            fps_cap: 0,
+            damage_stack: 10,
+            camera_lag: 0.08,
+            camera_dead_zone: 0.0,
+            toggle_fire: false,
+            toggle_boost: false,
+            keyboard_steer: false,
+            aim_assist: Assist::High,
+            joy_dead_zone: 8.0,
+            joy_sensitivity: 1.0,
+            aim_stick: false,
+            gyro_steer: false,
+            gyro_sensitivity: 1.0,
+            data_saver: false,
+            region: String::new(),
+            lang: Lang::En,
+            vision: Vision::Normal,
+            ship: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Saved settings, or the defaults if there are none (or they don't parse)
+    pub fn load() -> Settings {
+        let mut s = crate::prefs::get(PREF_KEY)
+            .and_then(|raw| serde_json::from_str::<Settings>(&raw).ok())
+            .unwrap_or_default();
+        s.render_scale = s.render_scale.clamp(0.5, 1.0);
+        s.zoom = s.zoom.clamp(ZOOM_MIN, ZOOM_MAX);
+        s.shake_scale = s.shake_scale.clamp(0.0, 1.5);
+        s
+    }
+
+    /// Particle budget, with reduced motion capping whatever the particles setting allows
+    pub fn particle_limit(&self) -> usize {
+        let limit = self.particles.limit();
+        if self.reduced_motion { limit.min(REDUCED_MOTION_PARTICLES) } else { limit }
+    }
+
+    pub fn save(&self) {
+        if let Ok(raw) = serde_json::to_string(self) {
+            crate::prefs::set(PREF_KEY, &raw);
+        }
+    }
+}
+
+/// Owner of a live Settings copy: the game's GameState, or the phone controller
+pub trait SettingsHost: Clone + 'static {
+    fn with_settings<R>(&self, f: impl FnOnce(&mut Settings) -> R) -> R;
+}
+
+#[cfg(not(feature = "controller-only"))]
+impl SettingsHost for SharedState {
+    fn with_settings<R>(&self, f: impl FnOnce(&mut Settings) -> R) -> R {
+        f(&mut self.borrow_mut().settings)
+    }
+}
+
+/// Change a setting and persist the result
+pub fn update<H: SettingsHost>(host: &H, f: impl FnOnce(&mut Settings)) {
+    host.with_settings(|s| {
+        f(s);
+        s.save();
+    });
+}
+
+/// Wire an existing checkbox (by id) to a boolean setting
+pub fn bind_checkbox<H: SettingsHost>(host: &H, id: &str, get: fn(&Settings) -> bool, set: fn(&mut Settings, bool)) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(input) = document.get_element_by_id(id)
+        .and_then(|e| e.dyn_into::<web_sys::HtmlInputElement>().ok()) else { return };
+    input.set_checked(host.with_settings(|s| get(s)));
+    let (host, input_change) = (host.clone(), input.clone());
+    let change = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        let on = input_change.checked();
+        update(&host, |s| set(s, on));
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let _ = input.add_event_listener_with_callback("change", change.as_ref().unchecked_ref());
+    change.forget();
+}
+
+/// Wire an existing <select> or range input (by id) to a setting, via its string value.
+/// `changed` runs after each update, e.g. to refresh a label.
+pub fn bind_value<H: SettingsHost>(
+    host: &H, id: &str,
+    get: fn(&Settings) -> String,
+    set: fn(&mut Settings, &str),
+    changed: impl Fn(&Settings) + 'static,
+) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let Some(el) = document.get_element_by_id(id) else { return };
+    let value = host.with_settings(|s| get(s));
+    if let Some(select) = el.dyn_ref::<web_sys::HtmlSelectElement>() {
+        select.set_value(&value);
+    } else if let Some(input) = el.dyn_ref::<web_sys::HtmlInputElement>() {
+        input.set_value(&value);
+    }
+    host.with_settings(|s| changed(s));
+    let (host, changed) = (host.clone(), Rc::new(changed));
+    let el_change = el.clone();
+    let change = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        let v = el_change.dyn_ref::<web_sys::HtmlSelectElement>().map(|s| s.value())
+            .or_else(|| el_change.dyn_ref::<web_sys::HtmlInputElement>().map(|i| i.value()))
+            .unwrap_or_default();
+        update(&host, |s| set(s, &v));
+        host.with_settings(|s| changed(s));
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    let _ = el.add_event_listener_with_callback("input", change.as_ref().unchecked_ref());
+    change.forget();
+}
+
+pub fn set_visible(visible: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(overlay) = document.get_element_by_id("settingsOverlay") {
+        let _ = if visible {
+            overlay.class_list().add_1("visible")
+        } else {
+            overlay.class_list().remove_1("visible")
+        };
+    }
+}
+
+pub fn toggle() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(overlay) = document.get_element_by_id("settingsOverlay") {
+        let _ = overlay.class_list().toggle("visible");
+    }
+}
+
+#[cfg(not(feature = "controller-only"))]
+#[component]
+pub fn SettingsPanel(state: SharedState, net: crate::network::SharedNetwork) -> impl IntoView {
+    let current = state.borrow().settings.clone();
+    let dead_zone = RwSignal::new(current.joy_dead_zone);
+    let sensitivity = RwSignal::new(current.joy_sensitivity);
+    let shake_scale = RwSignal::new(current.shake_scale);
+    // Each handler needs its own handle on the state
+    let st = send_wrapper::SendWrapper::new(state);
+    let (st_particles, st_shake, st_shake_scale, st_motion, st_res, st_fps, st_stack) = (st.clone(), st.clone(), st.clone(), st.clone(), st.clone(), st.clone(), st.clone());
+    let (st_lag, st_dead_zone, st_quality) = (st.clone(), st.clone(), st.clone());
+    let (st_fire, st_boost, st_steer, st_assist) = (st.clone(), st.clone(), st.clone(), st.clone());
+    let (st_dead, st_sens, st_lang, st_vision) = (st.clone(), st.clone(), st.clone(), st);
+    let net_saver = send_wrapper::SendWrapper::new(net);
+
+    view! {
+        <div id="settingsOverlay">
+            <p class="qr-hint">"Settings"</p>
+            <div class="settings-groups">
+                <fieldset class="settings-group">
+                    <legend>"Graphics"</legend>
+                    <label>"Particles "<select on:change=move |e| {
+                        let p = Particles::parse(&event_target_value(&e));
+                        update(&*st_particles, |s| s.particles = p);
+                        let mut s = st_particles.borrow_mut();
+                        let limit = s.settings.particle_limit();
+                        s.particles.truncate(limit);
+                    }>
+                        <option value="full" selected=current.particles == Particles::Full>"Full"</option>
+                        <option value="reduced" selected=current.particles == Particles::Reduced>"Reduced"</option>
+                        <option value="off" selected=current.particles == Particles::Off>"Off"</option>
+                    </select></label>
+                    <label>"Background "<select on:change=move |e| {
+                        let q = Quality::parse(&event_target_value(&e));
+                        update(&*st_quality, |s| s.quality = q);
+                        let is_mobile = st_quality.borrow().is_mobile;
+                        crate::starfield::set_quality(q.resolve(is_mobile));
+                    }>
+                        <option value="auto" selected=current.quality == Quality::Auto>"Auto"</option>
+                        <option value="low" selected=current.quality == Quality::Low>"Low"</option>
+                        <option value="medium" selected=current.quality == Quality::Medium>"Medium"</option>
+                        <option value="high" selected=current.quality == Quality::High>"High"</option>
+                    </select></label>
+                    <label><input type="checkbox" prop:checked=current.screen_shake on:change=move |e| {
+                        let on = event_target_checked(&e);
+                        update(&*st_shake, |s| s.screen_shake = on);
+                        st_shake.borrow_mut().shake_intensity = 0.0;
+                    }/>" Screen shake"</label>
+                    <label>"Shake strength "
+                        <input type="range" min="0" max="1.5" step="0.1" prop:value=current.shake_scale.to_string()
+                            on:input=move |e| {
+                                let Ok(v) = event_target_value(&e).parse::<f64>() else { return };
+                                shake_scale.set(v);
+                                update(&*st_shake_scale, |s| s.shake_scale = v);
+                            }/>
+                        <span>{move || format!("{:.0}%", shake_scale.get() * 100.0)}</span>
+                    </label>
+                    <label><input type="checkbox" prop:checked=current.reduced_motion on:change=move |e| {
+                        let on = event_target_checked(&e);
+                        update(&*st_motion, |s| s.reduced_motion = on);
+                        let mut s = st_motion.borrow_mut();
+                        let limit = s.settings.particle_limit();
+                        s.particles.truncate(limit);
+                        s.shake_intensity = 0.0;
+                    }/>" Reduced motion"</label>
+                    <label>"Resolution "<select on:change=move |e| {
+                        let Ok(scale) = event_target_value(&e).parse::<f64>() else { return };
+                        update(&*st_res, |s| s.render_scale = scale);
+                        crate::canvas::resize(&st_res);
+                    }>
+                        {RESOLUTIONS.iter().map(|(scale, label)| view! {
+                            <option value=scale.to_string() selected=*scale == current.render_scale>{*label}</option>
+                        }).collect_view()}
+                    </select></label>
+                    <label>"Frame rate "<select on:change=move |e| {
+                        let Ok(cap) = event_target_value(&e).parse::<u32>() else { return };
+                        update(&*st_fps, |s| s.fps_cap = cap);
+                    }>
+                        {FPS_CAPS.iter().map(|(cap, label)| view! {
+                            <option value=cap.to_string() selected=*cap == current.fps_cap>{*label}</option>
+                        }).collect_view()}
+                    </select></label>
+                    <label>"Damage numbers "<select on:change=move |e| {
+                        let Ok(cap) = event_target_value(&e).parse::<u32>() else { return };
+                        update(&*st_stack, |s| s.damage_stack = cap);
+                    }>
+                        {DAMAGE_STACKS.iter().map(|(cap, label)| view! {
+                            <option value=cap.to_string() selected=*cap == current.damage_stack>{*label}</option>
+                        }).collect_view()}
+                    </select></label>
+                    <label>"Camera follow "<select on:change=move |e| {
+                        let Ok(lag) = event_target_value(&e).parse::<f64>() else { return };
+                        update(&*st_lag, |s| s.camera_lag = lag);
+                    }>
+                        {CAMERA_LAGS.iter().map(|(lag, label)| view! {
+                            <option value=lag.to_string() selected=*lag == current.camera_lag>{*label}</option>
+                        }).collect_view()}
+                    </select></label>
+                    <label>"Camera dead zone "<select on:change=move |e| {
+                        let Ok(px) = event_target_value(&e).parse::<f64>() else { return };
+                        update(&*st_dead_zone, |s| s.camera_dead_zone = px);
+                    }>
+                        {CAMERA_DEAD_ZONES.iter().map(|(px, label)| view! {
+                            <option value=px.to_string() selected=*px == current.camera_dead_zone>{*label}</option>
+                        }).collect_view()}
+                    </select></label>
+                    <label>"Color vision "<select on:change=move |e| {
+                        let v = Vision::parse(&event_target_value(&e));
+                        update(&*st_vision, |s| s.vision = v);
+                        crate::palette::set_vision(v);
+                    }>
+                        {VISIONS.iter().map(|(vision, name)| view! {
+                            <option value=vision.id() selected=*vision == current.vision>{*name}</option>
+                        }).collect_view()}
+                    </select></label>
+                </fieldset>
+                <fieldset class="settings-group">
+                    <legend>"Controls"</legend>
+                    <label><input type="checkbox" prop:checked=current.toggle_fire on:change=move |e| {
+                        let on = event_target_checked(&e);
+                        update(&*st_fire, |s| s.toggle_fire = on);
+                    }/>" Toggle fire"</label>
+                    <label><input type="checkbox" prop:checked=current.toggle_boost on:change=move |e| {
+                        let on = event_target_checked(&e);
+                        update(&*st_boost, |s| s.toggle_boost = on);
+                    }/>" Toggle boost"</label>
+                    <label><input type="checkbox" prop:checked=current.keyboard_steer on:change=move |e| {
+                        let on = event_target_checked(&e);
+                        update(&*st_steer, |s| s.keyboard_steer = on);
+                    }/>" Keyboard steering"</label>
+                    <label>"Touch aim assist "<select on:change=move |e| {
+                        let a = Assist::parse(&event_target_value(&e));
+                        update(&*st_assist, |s| s.aim_assist = a);
+                    }>
+                        <option value="off" selected=current.aim_assist == Assist::Off>"Off"</option>
+                        <option value="low" selected=current.aim_assist == Assist::Low>"Low"</option>
+                        <option value="high" selected=current.aim_assist == Assist::High>"High"</option>
+                    </select></label>
+                    <label>"Joystick dead zone "
+                        <input type="range" min="0" max="40" step="1" prop:value=current.joy_dead_zone.to_string()
+                            on:input=move |e| {
+                                let Ok(v) = event_target_value(&e).parse::<f64>() else { return };
+                                dead_zone.set(v);
+                                update(&*st_dead, |s| s.joy_dead_zone = v);
+                            }/>
+                        <span>{move || crate::joystick::describe_dead_zone(dead_zone.get())}</span>
+                    </label>
+                    <label>"Joystick sensitivity "
+                        <input type="range" min="0.5" max="2" step="0.1" prop:value=current.joy_sensitivity.to_string()
+                            on:input=move |e| {
+                                let Ok(v) = event_target_value(&e).parse::<f64>() else { return };
+                                sensitivity.set(v);
+                                update(&*st_sens, |s| s.joy_sensitivity = v);
+                            }/>
+                        <span>{move || crate::joystick::describe_sensitivity(sensitivity.get())}</span>
+                    </label>
+                </fieldset>
+                <fieldset class="settings-group">
+                    <legend>"Network"</legend>
+                    <label><input type="checkbox" prop:checked=current.data_saver on:change=move |e| {
+                        crate::network::Network::set_data_saver(&net_saver, event_target_checked(&e));
+                    }/>" Data saver"</label>
+                </fieldset>
+                <fieldset class="settings-group">
+                    <legend>"Language"</legend>
+                    <label><select on:change=move |e| {
+                        let lang = Lang::parse(&event_target_value(&e));
+                        update(&*st_lang, |s| s.lang = lang);
+                        // Text is rendered once per view, so reload to redraw everything in the new language
+                        let _ = web_sys::window().unwrap().location().reload();
+                    }>
+                        {LANGS.iter().map(|(lang, name)| view! {
+                            <option value=lang.id() selected=*lang == current.lang>{*name}</option>
+                        }).collect_view()}
+                    </select></label>
+                </fieldset>
+            </div>
+            <div class="bug-actions">
+                <button class="btn-close" on:click=|_| set_visible(false)>"Close"</button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,83 @@
+// Ship select: a row of hull cards (sprite and stats) shown in the lobby and on
+// the death screen. The pick is remembered in Settings, sent with join, and sent
+// as ship_pick while in a match (the server applies it on the next respawn).
+
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+use crate::constants::{PLAYER_SHIPS, SHIP_STATS};
+use crate::network::{Network, SharedNetwork};
+use crate::state::SharedState;
+
+const SHIP_LABELS: [&str; PLAYER_SHIPS] = ["Red Squadron", "Blue Squadron", "Green Squadron"];
+const PREVIEW_SIZE: u32 = 72; // px, preview canvas width and height
+
+fn preview_id(ship: usize) -> String {
+    format!("shipPreview{}", ship)
+}
+
+/// Draw each hull into its preview canvas, retrying while sprites are still loading
+fn draw_previews(tries: u32) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let mut pending = false;
+    for ship in 0..PLAYER_SHIPS {
+        let Some(canvas) = document.get_element_by_id(&preview_id(ship))
+            .and_then(|e| e.dyn_into::<web_sys::HtmlCanvasElement>().ok()) else { return };
+        if !crate::ships::image_ready(ship as i32) {
+            pending = true;
+            continue;
+        }
+        let Some(ctx) = canvas.get_context("2d").ok().flatten()
+            .and_then(|c| c.dyn_into::<web_sys::CanvasRenderingContext2d>().ok()) else { continue };
+        let half = PREVIEW_SIZE as f64 / 2.0;
+        ctx.clear_rect(0.0, 0.0, PREVIEW_SIZE as f64, PREVIEW_SIZE as f64);
+        crate::ships::draw_ship(&ctx, half, half, -std::f64::consts::FRAC_PI_2, ship as i32);
+    }
+    if pending && tries > 0 {
+        gloo_timers::callback::Timeout::new(200, move || draw_previews(tries - 1)).forget();
+    }
+}
+
+#[component]
+pub fn ShipPicker(state: SharedState, net: SharedNetwork, caption: &'static str) -> impl IntoView {
+    let selected = RwSignal::new(state.borrow().settings.ship);
+    let state = send_wrapper::SendWrapper::new(state);
+    let net = send_wrapper::SendWrapper::new(net);
+
+    Effect::new(move |_| draw_previews(10));
+
+    let cards = (0..PLAYER_SHIPS).map(|ship| {
+        let (state, net) = (state.clone(), net.clone());
+        let pick = move |_| {
+            let ship = ship as i32;
+            selected.set(Some(ship));
+            crate::settings::update(&*state, |s| s.ship = Some(ship));
+            if state.borrow().my_id.is_some() {
+                Network::pick_ship(&net, ship);
+            }
+        };
+        let stats = &SHIP_STATS[ship];
+        view! {
+            <button class="ship-card"
+                class:selected=move || selected.get() == Some(ship as i32)
+                style=format!("--ship-color: {}", crate::palette::ship(ship as i32))
+                on:click=pick
+            >
+                <canvas id=preview_id(ship) width=PREVIEW_SIZE height=PREVIEW_SIZE></canvas>
+                <span class="ship-name">{SHIP_LABELS[ship]}</span>
+                <dl class="ship-stats">
+                    <dt>"Speed"</dt><dd>{format!("{:.0}", stats.speed)}</dd>
+                    <dt>"HP"</dt><dd>{stats.hp}</dd>
+                    <dt>"Fire"</dt><dd>{format!("{:.1}/s", 1.0 / stats.fire_cd)}</dd>
+                    <dt>"Ability"</dt><dd>{stats.ability}</dd>
+                </dl>
+            </button>
+        }
+    }).collect_view();
+
+    view! {
+        <div class="ship-picker">
+            <label>{caption}</label>
+            <div class="ship-cards">{cards}</div>
+        </div>
+    }
+}
@@ -33,6 +33,11 @@ const SHIP_ROT_OFFSET: [f64; 6] = [
     std::f64::consts::FRAC_PI_2, // TIE 2 (faces up)
 ];
 
+/// Whether a ship's sprite has finished loading
+pub fn image_ready(ship_type: i32) -> bool {
+    SHIP_IMAGES.with(|si| si.borrow().get(ship_type as usize).is_some_and(|img| img.natural_width() > 0))
+}
+
 pub fn draw_ship(ctx: &CanvasRenderingContext2d, x: f64, y: f64, rotation: f64, ship_type: i32) {
     SHIP_IMAGES.with(|si| {
         let images = si.borrow();
@@ -0,0 +1,87 @@
+// Uniform grid over the world, rebuilt from each snapshot, so viewport culling
+// and auto-aim only look at entities in nearby cells instead of every one.
+// Entities are bucketed by their snapshot position; callers pad queries by the
+// sprite size plus however far things drift between snapshots.
+
+use crate::constants::{WORLD_W, WORLD_H};
+use crate::state::GameState;
+
+const CELL: f64 = 250.0;
+const COLS: usize = (WORLD_W / CELL) as usize + 1;
+const ROWS: usize = (WORLD_H / CELL) as usize + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Player,
+    Projectile,
+    Mob,
+    Asteroid,
+    Pickup,
+}
+
+pub struct SpatialGrid {
+    cells: Vec<Vec<(Kind, u32)>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        SpatialGrid { cells: vec![Vec::new(); COLS * ROWS] }
+    }
+}
+
+fn col(x: f64) -> usize {
+    ((x / CELL).max(0.0) as usize).min(COLS - 1)
+}
+
+fn row(y: f64) -> usize {
+    ((y / CELL).max(0.0) as usize).min(ROWS - 1)
+}
+
+impl SpatialGrid {
+    /// Empty every cell, keeping their capacity for the next rebuild
+    pub fn clear(&mut self) {
+        for c in &mut self.cells {
+            c.clear();
+        }
+    }
+
+    pub fn insert(&mut self, kind: Kind, id: u32, x: f64, y: f64) {
+        self.cells[row(y) * COLS + col(x)].push((kind, id));
+    }
+
+    /// IDs of `kind` bucketed in cells overlapping the box; callers still check exact positions
+    pub fn query(&self, kind: Kind, x0: f64, y0: f64, x1: f64, y1: f64) -> impl Iterator<Item = u32> + Clone + '_ {
+        let (c0, c1, r0, r1) = (col(x0), col(x1), row(y0), row(y1));
+        (r0..=r1)
+            .flat_map(move |r| (c0..=c1).map(move |c| r * COLS + c))
+            .flat_map(move |i| self.cells[i].iter())
+            .filter(move |(k, _)| *k == kind)
+            .map(|(_, id)| *id)
+    }
+
+    /// IDs of `kind` in cells within `r` of (x, y)
+    pub fn query_radius(&self, kind: Kind, x: f64, y: f64, r: f64) -> impl Iterator<Item = u32> + Clone + '_ {
+        self.query(kind, x - r, y - r, x + r, y + r)
+    }
+}
+
+/// Re-bucket everything in the current snapshot
+pub fn rebuild(s: &mut GameState) {
+    let GameState { grid, players, projectiles, mobs, asteroids, pickups, .. } = s;
+    grid.clear();
+    for (id, p) in players.iter() {
+        grid.insert(Kind::Player, *id, p.x, p.y);
+    }
+    for (id, p) in projectiles.iter() {
+        grid.insert(Kind::Projectile, *id, p.x, p.y);
+    }
+    for (id, m) in mobs.iter() {
+        grid.insert(Kind::Mob, *id, m.x, m.y);
+    }
+    for (id, a) in asteroids.iter() {
+        grid.insert(Kind::Asteroid, *id, a.x, a.y);
+    }
+    for (id, pk) in pickups.iter() {
+        grid.insert(Kind::Pickup, *id, pk.x, pk.y);
+    }
+}
@@ -0,0 +1,80 @@
+// Death cam: while waiting to respawn, the camera follows whoever killed us
+// instead of sitting on the wreck. The arrow keys cycle through the other
+// living players; if the one being watched dies or leaves view, the next
+// living player takes over.
+
+use crate::state::{GameState, Phase};
+
+/// Where a living ship or mob is, if it's in our snapshots
+fn position(s: &GameState, id: u32) -> Option<(f64, f64)> {
+    s.players.get(&id).filter(|p| p.a).map(|p| (p.x, p.y))
+        .or_else(|| s.mobs.get(&id).filter(|m| m.a).map(|m| (m.x, m.y)))
+}
+
+/// Living players other than us, in id order
+fn candidates(s: &GameState) -> Vec<u32> {
+    let mut ids: Vec<u32> = s.players.values()
+        .filter(|p| p.a && Some(p.id) != s.my_id)
+        .map(|p| p.id)
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// The id after (dir > 0) or before `current` in `ids`, wrapping around.
+/// Starts from the first (or last) when `current` isn't among them.
+fn step(ids: &[u32], current: Option<u32>, dir: i32) -> Option<u32> {
+    if ids.is_empty() { return None; }
+    let n = ids.len() as i32;
+    let i = match current.and_then(|c| ids.iter().position(|&id| id == c)) {
+        Some(i) => (i as i32 + dir).rem_euclid(n),
+        None => if dir >= 0 { 0 } else { n - 1 },
+    };
+    Some(ids[i as usize])
+}
+
+/// Start following the killer (a player or mob NetID; 0 for an asteroid)
+pub fn start(s: &mut GameState, killer_id: u32) {
+    let target = (Some(killer_id) != s.my_id && position(s, killer_id).is_some()).then_some(killer_id);
+    if let Some(d) = s.death_info.as_mut() {
+        d.spectating = target;
+    }
+}
+
+/// Watch the next (dir 1) or previous (dir -1) living player
+pub fn cycle(s: &mut GameState, dir: i32) {
+    let next = step(&candidates(s), target(s), dir);
+    if let Some(d) = s.death_info.as_mut() {
+        d.spectating = next.or(d.spectating);
+    }
+}
+
+/// Who the camera is following instead of us, while dead
+pub fn target(s: &GameState) -> Option<u32> {
+    s.death_info.as_ref().and_then(|d| d.spectating).filter(|_| s.phase == Phase::Dead)
+}
+
+/// Move the camera onto the watched entity, moving on to the next living
+/// player once it's gone. Leaves the camera alone when there's nobody to watch.
+pub fn update_camera(s: &mut GameState) {
+    let Some(id) = target(s) else { return };
+    if position(s, id).is_none() {
+        let next = step(&candidates(s), Some(id), 1);
+        if let Some(d) = s.death_info.as_mut() {
+            d.spectating = next;
+        }
+    }
+    if let Some((x, y)) = target(s).and_then(|id| position(s, id)) {
+        s.cam_x = x;
+        s.cam_y = y;
+    }
+}
+
+/// Name of whoever is being watched, for the death screen
+pub fn label(s: &GameState) -> Option<String> {
+    let id = target(s)?;
+    Some(if s.players.contains_key(&id) { s.name_of(id).to_string() } else { "Mob".to_string() })
+}
+
+#[cfg(test)]
+mod tests;
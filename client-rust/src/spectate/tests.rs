@@ -0,0 +1,19 @@
+use super::*;
+
+#[test]
+fn cycles_forward_and_back_with_wraparound() {
+    let ids = [3, 5, 9];
+    assert_eq!(step(&ids, Some(3), 1), Some(5));
+    assert_eq!(step(&ids, Some(9), 1), Some(3));
+    assert_eq!(step(&ids, Some(3), -1), Some(9));
+    assert_eq!(step(&ids, Some(5), -1), Some(3));
+}
+
+#[test]
+fn starts_from_an_end_when_not_watching_a_player() {
+    // e.g. following a mob, or the watched player just died
+    let ids = [3, 5, 9];
+    assert_eq!(step(&ids, Some(50), 1), Some(3));
+    assert_eq!(step(&ids, None, -1), Some(9));
+    assert_eq!(step(&[], Some(3), 1), None);
+}
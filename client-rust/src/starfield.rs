@@ -1,23 +1,61 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use wasm_bindgen::JsCast;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 use crate::state::SharedState;
+use crate::settings::Quality;
 
 thread_local! {
     static STAR_LAYERS: RefCell<Vec<HtmlCanvasElement>> = RefCell::new(Vec::new());
-    static NEBULA_CANVAS: RefCell<Option<HtmlCanvasElement>> = RefCell::new(None);
+    static NEBULA_SPRITES: RefCell<Vec<HtmlCanvasElement>> = const { RefCell::new(Vec::new()) };
+    static CLOUDS: RefCell<Vec<Cloud>> = const { RefCell::new(Vec::new()) };
     static CACHED_W: RefCell<f64> = RefCell::new(0.0);
     static CACHED_H: RefCell<f64> = RefCell::new(0.0);
     static STAR_DATA: RefCell<Vec<StarInfo>> = RefCell::new(Vec::new());
     static IS_MOBILE: RefCell<bool> = RefCell::new(false);
+    static QUALITY: Cell<Quality> = const { Cell::new(Quality::High) };
 }
 
-const LAYER_COUNTS: [usize; 3] = [120, 100, 80];
+// Stars per layer and nebula patches for Low, Medium and High
+const LAYER_COUNTS: [[usize; 3]; 3] = [[40, 30, 25], [80, 65, 50], [120, 100, 80]];
+const NEBULA_COUNTS: [usize; 3] = [3, 5, 8];
 const LAYER_SIZES: [(f64, f64); 3] = [(0.5, 1.5), (1.0, 2.5), (2.0, 3.5)];
 const LAYER_ALPHAS: [(f64, f64); 3] = [(0.3, 0.6), (0.4, 0.7), (0.6, 1.0)];
 const LAYER_FACTORS: [f64; 3] = [0.02, 0.05, 0.10];
 const NEBULA_FACTOR: f64 = 0.03;
-const NEBULA_COUNT: usize = 8;
+const NEBULA_MARGIN: f64 = 900.0; // widest a cloud gets; they wrap around this far off-screen
+const NEBULA_SPRITE: u32 = 256;
+
+/// Big set pieces far behind the arena, each pinned to a world position and
+/// scrolling at its own parallax factor, so there's always something to tell
+/// one part of the map from another
+enum Landmark {
+    Planet { r: f64, light: &'static str, dark: &'static str, rim: &'static str },
+    Moon { r: f64 },
+    Wreck { scale: f64, rotation: f64 },
+}
+
+// (world x, world y, parallax factor, what)
+const LANDMARKS: [(f64, f64, f64, Landmark); 3] = [
+    (900.0, 900.0, 0.15, Landmark::Planet { r: 260.0, light: "#3d5f96", dark: "#0b1428", rim: "rgba(120, 170, 255, 0.25)" }),
+    (3200.0, 1000.0, 0.2, Landmark::Moon { r: 70.0 }),
+    (2500.0, 3200.0, 0.4, Landmark::Wreck { scale: 1.6, rotation: 0.6 }),
+];
+
+// Crater offsets and radii as fractions of the moon's radius
+const CRATERS: [(f64, f64, f64); 4] = [(-0.3, -0.2, 0.22), (0.35, 0.1, 0.15), (-0.05, 0.45, 0.12), (0.2, -0.45, 0.1)];
+
+/// One nebula patch: a stretched glow sprite that drifts, turns and breathes
+struct Cloud {
+    x: f64, // position in the wrapping nebula tile at t = 0
+    y: f64,
+    r: f64,
+    sprite: usize,
+    vx: f64, // px/s
+    vy: f64,
+    angle: f64,
+    spin: f64,  // rad/s
+    phase: f64, // offsets the breathing so clouds don't pulse together
+}
 
 struct StarInfo {
     x: f64,
@@ -27,7 +65,16 @@ struct StarInfo {
 }
 
 fn rand() -> f64 {
-    js_sys::Math::random()
+    crate::rng::random(crate::rng::Stream::Starfield)
+}
+
+/// Index into the per-quality tables (Auto is resolved before it gets here)
+fn detail() -> usize {
+    match QUALITY.with(|q| q.get()) {
+        Quality::Low => 0,
+        Quality::Medium => 1,
+        Quality::Auto | Quality::High => 2,
+    }
 }
 
 fn build_offscreen_canvases(w: f64, h: f64) {
@@ -35,6 +82,7 @@ fn build_offscreen_canvases(w: f64, h: f64) {
     let mut layers = Vec::new();
     let mut star_data = Vec::new();
     let size_scale = IS_MOBILE.with(|m| if *m.borrow() { 1.0 / 3.0 } else { 1.0 });
+    let detail = detail();
 
     for layer in 0..3 {
         let canvas: HtmlCanvasElement = document.create_element("canvas").unwrap().unchecked_into();
@@ -43,7 +91,7 @@ fn build_offscreen_canvases(w: f64, h: f64) {
         let ctx: CanvasRenderingContext2d = canvas
             .get_context("2d").unwrap().unwrap().unchecked_into();
 
-        let count = LAYER_COUNTS[layer];
+        let count = LAYER_COUNTS[detail][layer];
         let (min_size, max_size) = (LAYER_SIZES[layer].0 * size_scale, LAYER_SIZES[layer].1 * size_scale);
         let (min_alpha, max_alpha) = LAYER_ALPHAS[layer];
 
@@ -74,38 +122,49 @@ fn build_offscreen_canvases(w: f64, h: f64) {
         layers.push(canvas);
     }
 
-    // Nebula canvas
-    let nebula: HtmlCanvasElement = document.create_element("canvas").unwrap().unchecked_into();
-    let nw = w * 2.0;
-    let nh = h * 2.0;
-    nebula.set_width(nw as u32);
-    nebula.set_height(nh as u32);
-    let nctx: CanvasRenderingContext2d = nebula
-        .get_context("2d").unwrap().unwrap().unchecked_into();
-
+    // Nebula sprites, one soft glow per color, and the clouds that use them
     let nebula_colors = [
         "rgba(30, 0, 60, 0.03)",
         "rgba(0, 20, 60, 0.03)",
         "rgba(60, 0, 30, 0.02)",
         "rgba(0, 40, 40, 0.02)",
     ];
-
-    for _ in 0..NEBULA_COUNT {
-        let x = rand() * nw;
-        let y = rand() * nh;
-        let r = 200.0 + rand() * 400.0;
-        let color = nebula_colors[(rand() * nebula_colors.len() as f64) as usize % nebula_colors.len()];
-
-        if let Ok(gradient) = nctx.create_radial_gradient(x, y, 0.0, x, y, r) {
+    let half = NEBULA_SPRITE as f64 / 2.0;
+    let sprites: Vec<HtmlCanvasElement> = nebula_colors.iter().map(|color| {
+        let sprite: HtmlCanvasElement = document.create_element("canvas").unwrap().unchecked_into();
+        sprite.set_width(NEBULA_SPRITE);
+        sprite.set_height(NEBULA_SPRITE);
+        let sctx: CanvasRenderingContext2d = sprite
+            .get_context("2d").unwrap().unwrap().unchecked_into();
+        if let Ok(gradient) = sctx.create_radial_gradient(half, half, 0.0, half, half, half) {
             let _ = gradient.add_color_stop(0.0_f32, color);
             let _ = gradient.add_color_stop(1.0_f32, "transparent");
-            nctx.set_fill_style_canvas_gradient(&gradient);
-            nctx.fill_rect(x - r, y - r, r * 2.0, r * 2.0);
+            sctx.set_fill_style_canvas_gradient(&gradient);
+            sctx.fill_rect(0.0, 0.0, NEBULA_SPRITE as f64, NEBULA_SPRITE as f64);
         }
-    }
+        sprite
+    }).collect();
+
+    let (tw, th) = (w + NEBULA_MARGIN * 2.0, h + NEBULA_MARGIN * 2.0);
+    let clouds: Vec<Cloud> = (0..NEBULA_COUNTS[detail]).map(|_| {
+        let drift = rand() * std::f64::consts::TAU;
+        let speed = 3.0 + rand() * 5.0;
+        Cloud {
+            x: rand() * tw,
+            y: rand() * th,
+            r: 200.0 + rand() * 400.0,
+            sprite: (rand() * sprites.len() as f64) as usize % sprites.len(),
+            vx: drift.cos() * speed,
+            vy: drift.sin() * speed,
+            angle: rand() * std::f64::consts::PI,
+            spin: (rand() - 0.5) * 0.04,
+            phase: rand() * std::f64::consts::TAU,
+        }
+    }).collect();
 
     STAR_LAYERS.with(|sl| *sl.borrow_mut() = layers);
-    NEBULA_CANVAS.with(|nc| *nc.borrow_mut() = Some(nebula));
+    NEBULA_SPRITES.with(|ns| *ns.borrow_mut() = sprites);
+    CLOUDS.with(|c| *c.borrow_mut() = clouds);
     STAR_DATA.with(|sd| *sd.borrow_mut() = star_data);
     CACHED_W.with(|cw| *cw.borrow_mut() = w);
     CACHED_H.with(|ch| *ch.borrow_mut() = h);
@@ -114,12 +173,107 @@ fn build_offscreen_canvases(w: f64, h: f64) {
 pub fn init_starfield(state: &SharedState) {
     let s = state.borrow();
     IS_MOBILE.with(|m| *m.borrow_mut() = s.is_mobile);
+    QUALITY.with(|q| q.set(s.settings.quality.resolve(s.is_mobile)));
+    crate::fog::set_enabled(detail() > 0);
     if s.screen_w > 0.0 && s.screen_h > 0.0 {
         build_offscreen_canvases(s.screen_w, s.screen_h);
     }
 }
 
-pub fn render_starfield(ctx: &CanvasRenderingContext2d, cx: f64, cy: f64, w: f64, h: f64, hyperspace_t: f64, player_rotation: f64) {
+/// Redraw the star layers on the next frame (after a reseed)
+pub fn rebuild() {
+    CACHED_W.with(|cw| *cw.borrow_mut() = 0.0);
+}
+
+/// Switch background detail (an already resolved preset); Low also drops the fog
+pub fn set_quality(q: Quality) {
+    QUALITY.with(|cur| cur.set(q));
+    crate::fog::set_enabled(detail() > 0);
+    rebuild();
+}
+
+/// Draw the nebula clouds where they've drifted to `t` seconds in. Each is a
+/// stretched sprite, so turning it and easing its proportions slowly reshapes
+/// the patch. The clouds wrap around a tile a margin bigger than the screen,
+/// scrolling with the camera at NEBULA_FACTOR.
+fn draw_nebula(ctx: &CanvasRenderingContext2d, cx: f64, cy: f64, w: f64, h: f64, t: f64) {
+    let (tw, th) = (w + NEBULA_MARGIN * 2.0, h + NEBULA_MARGIN * 2.0);
+    NEBULA_SPRITES.with(|ns| CLOUDS.with(|c| {
+        let sprites = ns.borrow();
+        for cloud in c.borrow().iter() {
+            let Some(sprite) = sprites.get(cloud.sprite) else { continue };
+            let sx = (cloud.x + cloud.vx * t - cx * NEBULA_FACTOR).rem_euclid(tw) - NEBULA_MARGIN;
+            let sy = (cloud.y + cloud.vy * t - cy * NEBULA_FACTOR).rem_euclid(th) - NEBULA_MARGIN;
+            let breathe = (cloud.phase + t * 0.15).sin();
+            let (rw, rh) = (cloud.r * (1.3 + 0.15 * breathe), cloud.r * (0.8 - 0.1 * breathe));
+            ctx.save();
+            let _ = ctx.translate(sx, sy);
+            let _ = ctx.rotate(cloud.angle + cloud.spin * t);
+            let _ = ctx.draw_image_with_html_canvas_element_and_dw_and_dh(sprite, -rw, -rh, rw * 2.0, rh * 2.0);
+            ctx.restore();
+        }
+    }));
+}
+
+fn draw_landmarks(ctx: &CanvasRenderingContext2d, cx: f64, cy: f64, w: f64, h: f64) {
+    use crate::constants::{WORLD_W, WORLD_H};
+    for (x, y, factor, landmark) in &LANDMARKS {
+        // The world wraps, so measure the short way round before scaling
+        let sx = w / 2.0 + crate::prediction::wrap_delta(x - cx, WORLD_W) * factor;
+        let sy = h / 2.0 + crate::prediction::wrap_delta(y - cy, WORLD_H) * factor;
+        let extent = match landmark {
+            Landmark::Planet { r, .. } | Landmark::Moon { r } => r * 1.2,
+            Landmark::Wreck { scale, .. } => crate::constants::SHIP_SIZE * 5.0 * scale,
+        };
+        if sx < -extent || sx > w + extent || sy < -extent || sy > h + extent { continue; }
+
+        match *landmark {
+            Landmark::Planet { r, light, dark, rim } => {
+                // Lit from the upper left
+                if let Ok(g) = ctx.create_radial_gradient(sx - r * 0.4, sy - r * 0.4, r * 0.1, sx, sy, r) {
+                    let _ = g.add_color_stop(0.0_f32, light);
+                    let _ = g.add_color_stop(1.0_f32, dark);
+                    ctx.set_fill_style_canvas_gradient(&g);
+                }
+                ctx.begin_path();
+                let _ = ctx.arc(sx, sy, r, 0.0, std::f64::consts::TAU);
+                ctx.fill();
+                ctx.set_stroke_style_str(rim);
+                ctx.set_line_width(6.0);
+                ctx.stroke();
+            }
+            Landmark::Moon { r } => {
+                if let Ok(g) = ctx.create_radial_gradient(sx - r * 0.4, sy - r * 0.4, r * 0.1, sx, sy, r) {
+                    let _ = g.add_color_stop(0.0_f32, "#8a8a92");
+                    let _ = g.add_color_stop(1.0_f32, "#1c1c22");
+                    ctx.set_fill_style_canvas_gradient(&g);
+                }
+                ctx.begin_path();
+                let _ = ctx.arc(sx, sy, r, 0.0, std::f64::consts::TAU);
+                ctx.fill();
+                ctx.set_fill_style_str("rgba(0, 0, 0, 0.25)");
+                for (dx, dy, cr) in CRATERS {
+                    ctx.begin_path();
+                    let _ = ctx.arc(sx + dx * r, sy + dy * r, cr * r, 0.0, std::f64::consts::TAU);
+                    ctx.fill();
+                }
+            }
+            Landmark::Wreck { scale, rotation } => {
+                // A dim, oversized Star Destroyer hull drifting far below the fight
+                ctx.save();
+                ctx.set_global_alpha(0.3);
+                let _ = ctx.translate(sx, sy);
+                let _ = ctx.scale(scale, scale);
+                crate::ships::draw_ship(ctx, 0.0, 0.0, rotation, crate::constants::SD_MOB_TYPE);
+                ctx.restore();
+            }
+        }
+    }
+}
+
+/// Background centered on `cam`; `t` is seconds of nebula drift (held still under reduced motion)
+pub fn render_starfield(ctx: &CanvasRenderingContext2d, cam: (f64, f64), w: f64, h: f64, hyperspace_t: f64, player_rotation: f64, t: f64) {
+    let (cx, cy) = cam;
     let cached_w = CACHED_W.with(|cw| *cw.borrow());
     let cached_h = CACHED_H.with(|ch| *ch.borrow());
 
@@ -130,14 +284,9 @@ pub fn render_starfield(ctx: &CanvasRenderingContext2d, cx: f64, cy: f64, w: f64
     ctx.set_fill_style_str("#0a0a1a");
     ctx.fill_rect(0.0, 0.0, w, h);
 
-    // Nebula (always rendered)
-    NEBULA_CANVAS.with(|nc| {
-        if let Some(nebula) = nc.borrow().as_ref() {
-            let nebula_off_x = cx * NEBULA_FACTOR + (cx - w / 2.0);
-            let nebula_off_y = cy * NEBULA_FACTOR + (cy - h / 2.0);
-            let _ = ctx.draw_image_with_html_canvas_element(nebula, -nebula_off_x, -nebula_off_y);
-        }
-    });
+    // Nebula and landmarks (always rendered)
+    draw_nebula(ctx, cx, cy, w, h, t);
+    draw_landmarks(ctx, cx, cy, w, h);
 
     if hyperspace_t < 0.01 {
         // Normal mode: use pre-rendered canvases (fast path)
@@ -1,9 +1,32 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
+use serde::Serialize;
 
+use crate::pool::Pool;
+use crate::settings::Settings;
 use crate::protocol::{PlayerState, ProjectileState, MobState, AsteroidState, PickupState};
 
+/// Last known position of a ship or mob that dropped out of our snapshots
+#[derive(Debug, Clone, Serialize)]
+pub struct Ghost {
+    pub x: f64,
+    pub y: f64,
+    pub ship: i32,
+    pub mob: bool,
+    pub time: f64, // ms (Date.now) when it was last seen
+}
+
+/// Interpolated entities from one server update, stamped with local arrival time
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Snapshot {
+    pub time: f64, // ms (performance.now)
+    pub players: HashMap<u32, PlayerState>,
+    pub mobs: HashMap<u32, MobState>,
+    pub cam_x: f64,
+    pub cam_y: f64,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Phase {
     Lobby,
@@ -14,16 +37,22 @@ pub enum Phase {
 #[derive(Debug, Clone)]
 pub struct KillFeedEntry {
     pub killer: String,
+    pub assist: String,    // empty without one
     pub victim: String,
+    pub cause: String,     // KillMsg.c; empty from older servers
+    pub environment: bool, // killed by an asteroid, not a ship
+    pub suicide: bool,
     pub time: f64,
 }
 
 #[derive(Debug, Clone)]
 pub struct DeathInfo {
     pub killer_name: String,
+    pub spectating: Option<u32>, // player or mob the camera follows (spectate.rs)
+    pub respawn: Option<(f64, f64)>, // (performance.now() when due, total ms), if the server said
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Particle {
     pub x: f64,
     pub y: f64,
@@ -32,16 +61,18 @@ pub struct Particle {
     pub life: f64,
     pub max_life: f64,
     pub size: f64,
-    pub color: String,
+    pub color: &'static str,
     pub kind: ParticleKind,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub enum ParticleKind {
+    #[default]
     Explosion,
+    Shard, // solid rock chip, drawn like engine dots
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Explosion {
     pub x: f64,
     pub y: f64,
@@ -51,31 +82,88 @@ pub struct Explosion {
     pub max_life: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DamageKind {
+    #[default]
+    Hit,
+    Crit,
+    Heal,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct DamageNumber {
     pub x: f64,
     pub y: f64,
-    pub text: String,
+    pub text: String, // rewritten in place when the slot is reused
+    pub kind: DamageKind,
     pub color: &'static str,
     pub life: f64,
     pub max_life: f64,
     pub vy: f64,
     pub offset_x: f64,
+    // Stacking: hits from one attacker on one victim in quick succession add up
+    pub attacker: u32,
+    pub victim: u32,
+    pub total: i32,
+    pub hits: u32,
+    pub since_hit: f64, // seconds since the last hit folded in
+}
+
+/// A collected pickup being pulled into our ship
+#[derive(Debug, Clone)]
+pub struct PickupPull {
+    pub x: f64, // world position it was picked up from
+    pub y: f64,
+    pub life: f64,
 }
 
 #[derive(Debug, Clone)]
 pub struct HitMarker {
     pub life: f64,
     pub max_life: f64,
+    pub crit: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct MobSpeech {
-    pub mob_id: String,
+    pub mob_id: u32,
     pub text: String,
     pub time: f64,  // timestamp when created (ms)
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoachMarkKind {
+    Line,
+    Ping,
+}
+
+/// A player's world ping, shown to the whole session
+#[derive(Debug, Clone)]
+pub struct WorldMarker {
+    pub x: f64,
+    pub y: f64,
+    pub name: String, // who placed it
+    pub time: f64,    // timestamp when received (ms)
+}
+
+#[derive(Debug, Clone)]
+pub struct CoachMark {
+    pub kind: CoachMarkKind,
+    pub pts: Vec<(f64, f64)>, // world coords
+    pub time: f64,            // timestamp when created (ms)
+}
+
+/// Player's camera and input as last relayed to their coach
+#[derive(Debug, Clone)]
+pub struct CoachView {
+    pub vw: f64,
+    pub vh: f64,
+    pub mx: f64,
+    pub my: f64,
+    pub firing: bool,
+    pub boosting: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct TouchJoystick {
     pub start_x: f64,
@@ -87,18 +175,24 @@ pub struct TouchJoystick {
 pub struct GameState {
     // Connection
     pub connected: bool,
-    pub my_id: Option<String>,
+    pub my_id: Option<u32>,     // our entity ID in snapshots and events
+    pub my_pid: Option<String>, // our player handle, for resume, controller and coach links
+    pub coach_key: Option<String>, // goes in our coach link; lets a coach watch, not take the ship
     pub my_ship: i32,
     pub session_id: Option<String>,
+    pub last_rx: f64, // ms (Date.now) of the last snapshot or pong
     pub url_session_id: Option<String>,
     pub pending_name: Option<String>, // name saved before create, for auto-join
 
     // Game state from server
-    pub players: HashMap<String, PlayerState>,
-    pub projectiles: HashMap<String, ProjectileState>,
-    pub mobs: HashMap<String, MobState>,
-    pub asteroids: HashMap<String, AsteroidState>,
-    pub pickups: HashMap<String, PickupState>,
+    pub players: HashMap<u32, PlayerState>,
+    pub projectiles: HashMap<u32, ProjectileState>,
+    pub mobs: HashMap<u32, MobState>,
+    pub asteroids: HashMap<u32, AsteroidState>,
+    pub pickups: HashMap<u32, PickupState>,
+    pub names: HashMap<u32, String>, // player names, sent apart from snapshots
+    pub pings: HashMap<u32, u32>,    // player round trips (ms) for the scoreboard
+    pub grid: crate::spatial::SpatialGrid, // current entities bucketed by position
     pub tick: u64,
 
     // Screen
@@ -109,6 +203,7 @@ pub struct GameState {
     pub cam_x: f64,
     pub cam_y: f64,
     pub cam_zoom: f64,
+    pub view: Option<crate::camera::Follow>, // where the screen is actually centered, trailing cam_x/cam_y
 
     // Input
     pub mouse_x: f64,
@@ -118,25 +213,71 @@ pub struct GameState {
     pub shift_pressed: bool,
     pub hyperspace_t: f64, // 0.0 = normal stars, 1.0 = full hyperspace
     pub hyperspace_locked_r: Option<f64>, // rotation locked when shift pressed
+    pub dodge_pending: bool, // dodge requested, goes out with the next input
+    // Keyboard steering: held rotate/thrust keys and the heading they've turned to
+    pub steer_left: bool,
+    pub steer_right: bool,
+    pub thrusting: bool,
+    pub steer_heading: Option<f64>,
+    pub dodge_ready_at: f64, // timestamp (ms) when the dodge cooldown ends
+
+    // Input sequencing: last seq sent, last seq the server applied, and sends awaiting ack
+    pub input_seq: u16,
+    pub input_acked: u16,
+    pub input_pending: VecDeque<(u16, f64)>, // (seq, sent at ms)
+    pub input_rtt: f64,      // smoothed send→ack latency (ms)
+    pub inputs_dropped: u32, // inputs never acknowledged within INPUT_ACK_TIMEOUT
 
     // UI state
     pub phase: Phase,
     pub kill_feed: Vec<KillFeedEntry>,
     pub death_info: Option<DeathInfo>,
+    pub tutorial: Option<crate::tutorial::Tutorial>, // first-launch walkthrough in progress
 
     // Controller
     pub controller_attached: bool,
 
+    // Coach: spectating someone else (session id, player id, coach key), or being watched
+    pub coach_target: Option<(String, String, String)>,
+    pub coach_attached: bool,
+    pub coach_view: Option<CoachView>,
+    pub coach_marks: Vec<CoachMark>,
+    pub coach_stroke: Option<Vec<(f64, f64)>>, // stroke being drawn by the coach
+
+    // Replay playback (snapshots come from a file instead of the server)
+    pub replay_mode: bool,
+
+    // Offline practice arena (snapshots come from practice.rs instead of the server)
+    pub practice_mode: bool,
+
+    // Page Visibility: no frames or input while the tab is in the background
+    pub tab_hidden: bool,
+
+    // Client-side prediction of our own ship (None when not steering locally)
+    pub predicted: Option<crate::prediction::PredictedShip>,
+
+    // Persisted preferences (graphics, controls, network); loaded by app::App
+    pub settings: Settings,
+
+    // Bandwidth: measured inbound traffic since joining
+    pub bytes_in: f64,
+    pub bytes_since: f64, // timestamp when measurement started (ms)
+
     // Mobile
     pub is_mobile: bool,
     pub touch_joystick: Option<TouchJoystick>,
 
-    // Debug
+    // Debug (toggled from the console)
     pub debug_hitboxes: bool,
+    pub debug_fps: bool,
+    pub debug_netgraph: bool,
+    pub snapshot_gaps: VecDeque<f64>, // ms between recent snapshot arrivals, for the net graph
+    pub interp_override: Option<f64>, // fixed render delay (ms) in place of the adaptive one
+    pub inspector: crate::inspector::Inspector,
 
     // Effects
-    pub particles: Vec<Particle>,
-    pub explosions: Vec<Explosion>,
+    pub particles: Pool<Particle>,
+    pub explosions: Pool<Explosion>,
 
     // Screen shake
     pub shake_x: f64,
@@ -145,7 +286,9 @@ pub struct GameState {
     pub shake_decay: f64,
 
     // Damage numbers (world-space floating text)
-    pub damage_numbers: Vec<DamageNumber>,
+    pub damage_numbers: Pool<DamageNumber>,
+    pub heal_glow: f64, // seconds left of the aura around our ship after a heal
+    pub pickup_pulls: Vec<PickupPull>,
 
     // Hit markers (screen-space, brief flash when own shot connects)
     pub hit_markers: Vec<HitMarker>,
@@ -153,13 +296,20 @@ pub struct GameState {
     // Mob speech bubbles
     pub mob_speech: Vec<MobSpeech>,
 
-    // Interpolation: previous state for lerping between server updates
-    pub prev_players: HashMap<String, PlayerState>,
-    pub prev_mobs: HashMap<String, MobState>,
-    pub prev_cam_x: f64,
-    pub prev_cam_y: f64,
+    // World pings placed by players in this session
+    pub markers: Vec<WorldMarker>,
+
+    // Interpolation: recent snapshots, rendered `interp_delay` ms in the past
+    pub snapshots: VecDeque<Snapshot>,
+    // Interest management: viewport last reported to the server, and entities culled out of it
+    pub viewport_sent: (f64, f64),
+    /// Death-cam target last reported to the server (0 for none)
+    pub spectate_sent: u32,
+    pub ghosts: HashMap<u32, Ghost>,
     pub interp_last_update: f64, // timestamp of last state update (ms)
     pub interp_interval: f64,    // estimated interval between updates (ms)
+    pub interp_jitter: f64,      // smoothed deviation of arrivals from the interval (ms)
+    pub interp_delay: f64,       // render delay behind the newest snapshot (ms)
 }
 
 impl GameState {
@@ -167,8 +317,11 @@ impl GameState {
         Self {
             connected: false,
             my_id: None,
+            my_pid: None,
+            coach_key: None,
             my_ship: 0,
             session_id: None,
+            last_rx: 0.0,
             url_session_id: None,
             pending_name: None,
 
@@ -177,6 +330,9 @@ impl GameState {
             mobs: HashMap::new(),
             asteroids: HashMap::new(),
             pickups: HashMap::new(),
+            names: HashMap::new(),
+            pings: HashMap::new(),
+            grid: Default::default(),
             tick: 0,
 
             screen_w: 0.0,
@@ -185,6 +341,7 @@ impl GameState {
             cam_x: 0.0,
             cam_y: 0.0,
             cam_zoom: 1.0,
+            view: None,
 
             mouse_x: 0.0,
             mouse_y: 0.0,
@@ -193,38 +350,105 @@ impl GameState {
             shift_pressed: false,
             hyperspace_t: 0.0,
             hyperspace_locked_r: None,
+            dodge_pending: false,
+            steer_left: false,
+            steer_right: false,
+            thrusting: false,
+            steer_heading: None,
+            dodge_ready_at: 0.0,
+
+            input_seq: 0,
+            input_acked: 0,
+            input_pending: VecDeque::with_capacity(32),
+            input_rtt: 0.0,
+            inputs_dropped: 0,
 
             phase: Phase::Lobby,
             kill_feed: Vec::new(),
             death_info: None,
+            tutorial: None,
 
             controller_attached: false,
 
+            coach_target: None,
+            coach_attached: false,
+            coach_view: None,
+            coach_marks: Vec::new(),
+            coach_stroke: None,
+
+            replay_mode: false,
+            practice_mode: false,
+            tab_hidden: false,
+
+            predicted: None,
+
+            settings: Settings::default(),
+
+            bytes_in: 0.0,
+            bytes_since: 0.0,
+
             is_mobile: false,
             touch_joystick: None,
 
             debug_hitboxes: false,
+            debug_fps: false,
+            debug_netgraph: false,
+            snapshot_gaps: VecDeque::with_capacity(crate::console::NET_GRAPH_LEN),
+            interp_override: None,
+            inspector: Default::default(),
 
-            particles: Vec::with_capacity(200),
-            explosions: Vec::with_capacity(10),
+            particles: Pool::with_capacity(200),
+            explosions: Pool::with_capacity(10),
 
             shake_x: 0.0,
             shake_y: 0.0,
             shake_intensity: 0.0,
             shake_decay: 0.0,
 
-            damage_numbers: Vec::with_capacity(30),
+            damage_numbers: Pool::with_capacity(30),
+            heal_glow: 0.0,
+            pickup_pulls: Vec::with_capacity(4),
             hit_markers: Vec::with_capacity(5),
             mob_speech: Vec::with_capacity(8),
+            markers: Vec::with_capacity(8),
 
-            prev_players: HashMap::new(),
-            prev_mobs: HashMap::new(),
-            prev_cam_x: 0.0,
-            prev_cam_y: 0.0,
+            snapshots: VecDeque::with_capacity(crate::constants::SNAPSHOT_BUFFER),
+            viewport_sent: (0.0, 0.0),
+            spectate_sent: 0,
+            ghosts: HashMap::new(),
             interp_last_update: 0.0,
-            interp_interval: 33.33, // ~30 Hz default
+            interp_interval: crate::constants::INTERP_INTERVAL,
+            interp_jitter: 0.0,
+            interp_delay: crate::constants::INTERP_INTERVAL,
+        }
+    }
+}
+
+impl GameState {
+    /// World point under screen position (`x`, `y`), relative to the camera as
+    /// drawn (the smoothed follow view once the first frame is out)
+    pub fn screen_to_world(&self, x: f64, y: f64) -> (f64, f64) {
+        let (cx, cy) = self.view.map(|v| (v.x, v.y)).unwrap_or((self.cam_x, self.cam_y));
+        ((x - self.screen_w / 2.0) / self.cam_zoom + cx, (y - self.screen_h / 2.0) / self.cam_zoom + cy)
+    }
+
+    /// Estimated data usage in MB/hour: measured if we've played a while, else nominal
+    pub fn data_usage_mb_per_hour(&self) -> f64 {
+        let now = js_sys::Date::now();
+        let elapsed = (now - self.bytes_since) / 1000.0;
+        if self.bytes_since > 0.0 && elapsed > 10.0 {
+            self.bytes_in / elapsed * 3600.0 / 1_000_000.0
+        } else if self.settings.data_saver {
+            crate::constants::EST_SAVER_MB_PER_HOUR
+        } else {
+            crate::constants::EST_MB_PER_HOUR
         }
     }
+
+    /// Display name for a player entity ID; empty until the name table arrives
+    pub fn name_of(&self, id: u32) -> &str {
+        self.names.get(&id).map(String::as_str).unwrap_or("")
+    }
 }
 
 pub type SharedState = Rc<RefCell<GameState>>;
@@ -0,0 +1,278 @@
+// Customizable mobile touch zones (joystick, boost, fire) with a drag-to-edit overlay.
+// Zones are rectangles in screen fractions so a layout survives rotation and resizes.
+
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::PointerEvent;
+
+const PREF_KEY: &str = "touchLayout";
+const BOOST_COLUMN_HALF: f64 = 50.0; // px, default boost column either side of center
+const MIN_ZONE: f64 = 0.08; // smallest zone edge, as a screen fraction
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zone {
+    Joystick,
+    Boost,
+    Fire,
+}
+
+impl Zone {
+    const ALL: [Zone; 3] = [Zone::Joystick, Zone::Boost, Zone::Fire];
+
+    fn id(self) -> &'static str {
+        match self {
+            Zone::Joystick => "joystick",
+            Zone::Boost => "boost",
+            Zone::Fire => "fire",
+        }
+    }
+
+    fn element_id(self) -> &'static str {
+        match self {
+            Zone::Joystick => "tlJoystick",
+            Zone::Boost => "tlBoost",
+            Zone::Fire => "tlFire",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+impl Rect {
+    fn contains(&self, fx: f64, fy: f64) -> bool {
+        fx >= self.x && fx <= self.x + self.w && fy >= self.y && fy <= self.y + self.h
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    pub joystick: Rect,
+    pub boost: Rect,
+    pub fire: Rect,
+}
+
+impl Layout {
+    /// The classic layout: joystick left, fire right, a thin boost column between
+    fn default_for(screen_w: f64) -> Layout {
+        let half = (BOOST_COLUMN_HALF / screen_w.max(1.0)).min(0.25);
+        Layout {
+            joystick: Rect { x: 0.0, y: 0.0, w: 0.5 - half, h: 1.0 },
+            boost: Rect { x: 0.5 - half, y: 0.0, w: half * 2.0, h: 1.0 },
+            fire: Rect { x: 0.5 + half, y: 0.0, w: 0.5 - half, h: 1.0 },
+        }
+    }
+
+    fn get(&self, zone: Zone) -> Rect {
+        match zone {
+            Zone::Joystick => self.joystick,
+            Zone::Boost => self.boost,
+            Zone::Fire => self.fire,
+        }
+    }
+
+    fn get_mut(&mut self, zone: Zone) -> &mut Rect {
+        match zone {
+            Zone::Joystick => &mut self.joystick,
+            Zone::Boost => &mut self.boost,
+            Zone::Fire => &mut self.fire,
+        }
+    }
+
+    fn to_json(self) -> serde_json::Value {
+        let obj: serde_json::Map<String, serde_json::Value> = Zone::ALL.iter()
+            .map(|z| {
+                let r = self.get(*z);
+                (z.id().to_string(), serde_json::json!([r.x, r.y, r.w, r.h]))
+            })
+            .collect();
+        serde_json::Value::Object(obj)
+    }
+
+    fn from_json(v: &serde_json::Value) -> Option<Layout> {
+        let rect = |z: Zone| -> Option<Rect> {
+            let a = v.get(z.id())?.as_array()?;
+            let n: Vec<f64> = a.iter().filter_map(|x| x.as_f64()).collect();
+            (n.len() == 4).then(|| Rect { x: n[0], y: n[1], w: n[2], h: n[3] })
+        };
+        Some(Layout { joystick: rect(Zone::Joystick)?, boost: rect(Zone::Boost)?, fire: rect(Zone::Fire)? })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Drag {
+    zone: Zone,
+    resize: bool,
+    start_x: f64, // px
+    start_y: f64,
+    start: Rect,
+}
+
+thread_local! {
+    // Saved custom layout (outer None = not loaded from storage yet)
+    static CUSTOM: RefCell<Option<Option<Layout>>> = const { RefCell::new(None) };
+    // Layout being edited in the overlay, and the zone being dragged
+    static EDITING: RefCell<Option<Layout>> = const { RefCell::new(None) };
+    static DRAG: RefCell<Option<Drag>> = const { RefCell::new(None) };
+}
+
+fn custom() -> Option<Layout> {
+    CUSTOM.with(|c| {
+        *c.borrow_mut().get_or_insert_with(|| {
+            crate::prefs::get(PREF_KEY)
+                .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+                .and_then(|v| Layout::from_json(&v))
+        })
+    })
+}
+
+pub fn current(screen_w: f64) -> Layout {
+    custom().unwrap_or_else(|| Layout::default_for(screen_w))
+}
+
+/// Which control a touch at (x, y) px lands on. Boost wins where zones overlap,
+/// since it's the smallest target.
+pub fn zone_at(x: f64, y: f64, screen_w: f64, screen_h: f64) -> Option<Zone> {
+    let layout = current(screen_w);
+    let (fx, fy) = (x / screen_w.max(1.0), y / screen_h.max(1.0));
+    [Zone::Boost, Zone::Joystick, Zone::Fire].into_iter()
+        .find(|z| layout.get(*z).contains(fx, fy))
+}
+
+fn screen_size() -> (f64, f64) {
+    let window = web_sys::window().unwrap();
+    let w = window.inner_width().ok().and_then(|v| v.as_f64()).unwrap_or(1.0);
+    let h = window.inner_height().ok().and_then(|v| v.as_f64()).unwrap_or(1.0);
+    (w, h)
+}
+
+/// Position the editor's zone boxes to match the layout being edited
+fn place_zones() {
+    let Some(layout) = EDITING.with(|e| *e.borrow()) else { return };
+    let document = web_sys::window().unwrap().document().unwrap();
+    for zone in Zone::ALL {
+        let Some(el) = document.get_element_by_id(zone.element_id()) else { continue };
+        let el: &web_sys::HtmlElement = el.unchecked_ref();
+        let r = layout.get(zone);
+        let style = el.style();
+        let _ = style.set_property("left", &format!("{}%", r.x * 100.0));
+        let _ = style.set_property("top", &format!("{}%", r.y * 100.0));
+        let _ = style.set_property("width", &format!("{}%", r.w * 100.0));
+        let _ = style.set_property("height", &format!("{}%", r.h * 100.0));
+    }
+}
+
+fn set_overlay_visible(visible: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(overlay) = document.get_element_by_id("tlOverlay") {
+        let _ = if visible {
+            overlay.class_list().add_1("visible")
+        } else {
+            overlay.class_list().remove_1("visible")
+        };
+    }
+}
+
+fn open_editor() {
+    let (w, _) = screen_size();
+    EDITING.with(|e| *e.borrow_mut() = Some(current(w)));
+    place_zones();
+    set_overlay_visible(true);
+}
+
+fn close_editor() {
+    EDITING.with(|e| *e.borrow_mut() = None);
+    DRAG.with(|d| *d.borrow_mut() = None);
+    set_overlay_visible(false);
+}
+
+fn on_click(id: &str, f: impl FnMut(web_sys::Event) + 'static) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(el) = document.get_element_by_id(id) {
+        let click = Closure::wrap(Box::new(f) as Box<dyn FnMut(web_sys::Event)>);
+        let _ = el.add_event_listener_with_callback("click", click.as_ref().unchecked_ref());
+        click.forget();
+    }
+}
+
+fn on_pointer(el: &web_sys::Element, event: &str, f: impl FnMut(PointerEvent) + 'static) {
+    let closure = Closure::wrap(Box::new(f) as Box<dyn FnMut(PointerEvent)>);
+    let _ = el.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+    closure.forget();
+}
+
+/// Drag a zone box to move it, or its corner handle to resize it
+fn setup_dragging(overlay: &web_sys::Element) {
+    on_pointer(overlay, "pointerdown", |e| {
+        let Some(target) = e.target().and_then(|t| t.dyn_into::<web_sys::Element>().ok()) else { return };
+        let Some(zone_el) = target.closest(".tl-zone").ok().flatten() else { return };
+        let Some(zone) = Zone::ALL.into_iter().find(|z| z.element_id() == zone_el.id()) else { return };
+        let Some(layout) = EDITING.with(|ed| *ed.borrow()) else { return };
+        e.prevent_default();
+        let _ = zone_el.set_pointer_capture(e.pointer_id());
+        DRAG.with(|d| *d.borrow_mut() = Some(Drag {
+            zone,
+            resize: target.class_list().contains("tl-handle"),
+            start_x: e.client_x() as f64,
+            start_y: e.client_y() as f64,
+            start: layout.get(zone),
+        }));
+    });
+
+    on_pointer(overlay, "pointermove", |e| {
+        let Some(drag) = DRAG.with(|d| *d.borrow()) else { return };
+        let (w, h) = screen_size();
+        let dx = (e.client_x() as f64 - drag.start_x) / w;
+        let dy = (e.client_y() as f64 - drag.start_y) / h;
+        EDITING.with(|ed| {
+            let mut ed = ed.borrow_mut();
+            let Some(layout) = ed.as_mut() else { return };
+            let r = layout.get_mut(drag.zone);
+            let s = drag.start;
+            if drag.resize {
+                r.w = (s.w + dx).clamp(MIN_ZONE, 1.0 - s.x);
+                r.h = (s.h + dy).clamp(MIN_ZONE, 1.0 - s.y);
+            } else {
+                r.x = (s.x + dx).clamp(0.0, 1.0 - s.w);
+                r.y = (s.y + dy).clamp(0.0, 1.0 - s.h);
+            }
+        });
+        place_zones();
+    });
+
+    let end = |_: PointerEvent| DRAG.with(|d| *d.borrow_mut() = None);
+    on_pointer(overlay, "pointerup", end);
+    on_pointer(overlay, "pointercancel", end);
+}
+
+pub fn setup_layout_editor(is_mobile: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    // Only touch screens have zones to lay out
+    if let Some(btn) = document.get_element_by_id("keysTouchLayout") {
+        let btn: &web_sys::HtmlElement = btn.unchecked_ref();
+        let _ = btn.style().set_property("display", if is_mobile { "" } else { "none" });
+    }
+    on_click("keysTouchLayout", |_| open_editor());
+    on_click("tlCancel", |_| close_editor());
+    on_click("tlReset", |_| {
+        let (w, _) = screen_size();
+        EDITING.with(|e| *e.borrow_mut() = Some(Layout::default_for(w)));
+        place_zones();
+    });
+    on_click("tlSave", |_| {
+        if let Some(layout) = EDITING.with(|e| *e.borrow()) {
+            crate::prefs::set(PREF_KEY, &layout.to_json().to_string());
+            CUSTOM.with(|c| *c.borrow_mut() = Some(Some(layout)));
+        }
+        close_editor();
+    });
+    if let Some(overlay) = document.get_element_by_id("tlOverlay") {
+        setup_dragging(&overlay);
+    }
+}
@@ -0,0 +1,162 @@
+// First-launch tutorial: a few steps (steer, fire, boost, minimap) drawn over
+// the HUD, each highlighting the screen region it talks about and advancing
+// once the player has actually done it. Finishing or skipping sets a
+// localStorage flag so it only ever shows once.
+
+use web_sys::CanvasRenderingContext2d;
+use crate::state::{GameState, Phase};
+use crate::keybinds::{self, Action};
+use crate::i18n::{t, tf, Msg};
+
+const DONE_KEY: &str = "tutorialDone";
+const SKIP_ID: &str = "tutorialSkip";
+const MOVE_SPEED: f64 = 80.0; // px/s of ship speed that counts as flying
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Move,
+    Fire,
+    Boost,
+    Minimap,
+}
+
+impl Step {
+    const ALL: [Step; 4] = [Step::Move, Step::Fire, Step::Boost, Step::Minimap];
+
+    /// Seconds of doing the thing (or just reading, for the minimap) to pass the step
+    fn needed(self) -> f64 {
+        match self {
+            Step::Move => 1.5,
+            Step::Fire => 0.6,
+            Step::Boost => 0.8,
+            Step::Minimap => 5.0,
+        }
+    }
+
+    fn text(self, touch: bool) -> String {
+        match (self, touch) {
+            (Step::Move, false) => t(Msg::TutMove).to_string(),
+            (Step::Move, true) => t(Msg::TutMoveTouch).to_string(),
+            (Step::Fire, false) => tf(Msg::TutFire, keybinds::describe(Action::Fire)),
+            (Step::Fire, true) => t(Msg::TutFireTouch).to_string(),
+            (Step::Boost, false) => tf(Msg::TutBoost, keybinds::describe(Action::Boost)),
+            (Step::Boost, true) => t(Msg::TutBoostTouch).to_string(),
+            (Step::Minimap, _) => t(Msg::TutMinimap).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tutorial {
+    pub step: Step,
+    pub progress: f64, // seconds credited toward the current step
+}
+
+pub fn done() -> bool {
+    crate::prefs::get_bool(DONE_KEY)
+}
+
+/// Begin the tutorial when joining a battle, unless it has been seen before
+pub fn start(s: &mut GameState) {
+    if s.tutorial.is_some() || s.replay_mode || s.coach_target.is_some() || done() {
+        return;
+    }
+    s.tutorial = Some(Tutorial { step: Step::Move, progress: 0.0 });
+    set_skip_visible(true);
+}
+
+/// End the tutorial for good (finished or skipped)
+pub fn finish(s: &mut GameState) {
+    s.tutorial = None;
+    crate::prefs::set(DONE_KEY, "1");
+    set_skip_visible(false);
+}
+
+fn set_skip_visible(visible: bool) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if let Some(btn) = document.get_element_by_id(SKIP_ID) {
+        let _ = if visible {
+            btn.class_list().add_1("visible")
+        } else {
+            btn.class_list().remove_1("visible")
+        };
+    }
+}
+
+/// Credit the current step with whatever the player did this frame (called every frame)
+pub fn update(s: &mut GameState, dt: f64) {
+    let Some(tut) = s.tutorial.as_ref() else { return };
+    if s.phase != Phase::Playing { return; }
+    let step = tut.step;
+    let doing = match step {
+        Step::Move => s.my_id.as_ref().and_then(|id| s.players.get(id))
+            .map(|me| me.vx.unwrap_or(0.0).hypot(me.vy.unwrap_or(0.0)) > MOVE_SPEED)
+            .unwrap_or(false),
+        Step::Fire => s.firing,
+        Step::Boost => s.boosting || s.shift_pressed,
+        Step::Minimap => true,
+    };
+    if !doing { return; }
+    let tut = s.tutorial.as_mut().unwrap();
+    tut.progress += dt;
+    if tut.progress < step.needed() { return; }
+    match Step::ALL.iter().position(|st| *st == step).and_then(|i| Step::ALL.get(i + 1)) {
+        Some(next) => {
+            tut.step = *next;
+            tut.progress = 0.0;
+        }
+        None => finish(s),
+    }
+}
+
+/// The step's hint panel, progress bar and highlighted region (screen space)
+pub fn render(ctx: &CanvasRenderingContext2d, s: &GameState, screen_w: f64, screen_h: f64) {
+    let Some(tut) = s.tutorial.as_ref() else { return };
+    if s.phase != Phase::Playing { return; }
+    let touch = s.is_mobile;
+
+    // Highlight: (x, y, w, h) in px
+    let region = match tut.step {
+        Step::Move if touch => zone_px(crate::touch_layout::current(screen_w).joystick, screen_w, screen_h),
+        Step::Fire if touch => zone_px(crate::touch_layout::current(screen_w).fire, screen_w, screen_h),
+        Step::Boost if touch => zone_px(crate::touch_layout::current(screen_w).boost, screen_w, screen_h),
+        Step::Move | Step::Boost => (screen_w / 2.0 - 60.0, screen_h / 2.0 - 60.0, 120.0, 120.0),
+        Step::Fire => (s.mouse_x - 30.0, s.mouse_y - 30.0, 60.0, 60.0),
+        Step::Minimap => {
+            let (x, y, size) = crate::hud::minimap_rect(screen_w, screen_h);
+            (x - 4.0, y - 4.0, size + 8.0, size + 8.0)
+        }
+    };
+    let pulse = 0.5 + 0.5 * (js_sys::Date::now() / 250.0).sin();
+    ctx.set_stroke_style_str(&format!("rgba(255, 220, 80, {})", 0.5 + 0.5 * pulse));
+    ctx.set_line_width(2.0);
+    ctx.stroke_rect(region.0, region.1, region.2, region.3);
+
+    // Hint panel, below the kill feed and clear of the minimap
+    let panel_w = (screen_w * 0.7).min(460.0);
+    let (px, py, ph) = (screen_w / 2.0 - panel_w / 2.0, screen_h * 0.18, 64.0);
+    ctx.set_fill_style_str("rgba(0, 0, 0, 0.65)");
+    ctx.fill_rect(px, py, panel_w, ph);
+    ctx.set_stroke_style_str("rgba(255, 220, 80, 0.6)");
+    ctx.set_line_width(1.0);
+    ctx.stroke_rect(px, py, panel_w, ph);
+
+    let index = Step::ALL.iter().position(|st| *st == tut.step).unwrap_or(0) + 1;
+    ctx.set_text_align("center");
+    ctx.set_fill_style_str("#ffdd55");
+    ctx.set_font("bold 12px monospace");
+    let _ = ctx.fill_text(&format!("{} {}/{}", t(Msg::Tutorial), index, Step::ALL.len()), screen_w / 2.0, py + 18.0);
+    ctx.set_fill_style_str("#ffffff");
+    ctx.set_font("14px monospace");
+    let _ = ctx.fill_text(&tut.step.text(touch), screen_w / 2.0, py + 40.0);
+
+    let done = (tut.progress / tut.step.needed()).min(1.0);
+    ctx.set_fill_style_str("rgba(255, 255, 255, 0.15)");
+    ctx.fill_rect(px + 12.0, py + ph - 12.0, panel_w - 24.0, 4.0);
+    ctx.set_fill_style_str("#ffdd55");
+    ctx.fill_rect(px + 12.0, py + ph - 12.0, (panel_w - 24.0) * done, 4.0);
+}
+
+fn zone_px(r: crate::touch_layout::Rect, screen_w: f64, screen_h: f64) -> (f64, f64, f64, f64) {
+    (r.x * screen_w, r.y * screen_h, r.w * screen_w, r.h * screen_h)
+}
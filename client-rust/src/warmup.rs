@@ -0,0 +1,189 @@
+// Lobby warm-up range: click-to-shoot targets drawn over the hyperspace
+// background before joining a battle. Some targets sit still and some drift
+// and bounce off the screen edges. Hits and shots are counted for this visit
+// and added to an all-time total in localStorage.
+
+use std::cell::RefCell;
+use serde::{Deserialize, Serialize};
+use web_sys::CanvasRenderingContext2d;
+use crate::i18n::{t, Msg};
+
+const STATS_KEY: &str = "warmupStats";
+const TARGET_COUNT: usize = 6;
+const TARGET_R: f64 = 22.0;
+const MOVING_SPEED: (f64, f64) = (80.0, 220.0); // px/s, min and max
+const POP_TIME: f64 = 0.3;  // s a hit target spends bursting before it respawns
+const MISS_TIME: f64 = 0.4; // s a miss spark stays on screen
+
+struct Target {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    pop: f64, // > 0 while bursting
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Stats {
+    shots: u32,
+    hits: u32,
+}
+
+impl Stats {
+    fn accuracy(self) -> u32 {
+        if self.shots == 0 { 0 } else { (self.hits as f64 * 100.0 / self.shots as f64).round() as u32 }
+    }
+}
+
+struct Range {
+    targets: Vec<Target>,
+    misses: Vec<(f64, f64, f64)>, // (x, y, time left)
+    session: Stats,
+    all_time: Stats, // saved totals from earlier visits
+}
+
+thread_local! {
+    static RANGE: RefCell<Option<Range>> = const { RefCell::new(None) };
+}
+
+fn spawn_target(index: usize, w: f64, h: f64) -> Target {
+    let rand = js_sys::Math::random;
+    let margin = TARGET_R * 2.0;
+    let x = margin + rand() * (w - margin * 2.0).max(1.0);
+    let y = margin + rand() * (h - margin * 2.0).max(1.0);
+    // Every other target moves
+    let (vx, vy) = if index % 2 == 1 {
+        let a = rand() * std::f64::consts::TAU;
+        let speed = MOVING_SPEED.0 + rand() * (MOVING_SPEED.1 - MOVING_SPEED.0);
+        (a.cos() * speed, a.sin() * speed)
+    } else {
+        (0.0, 0.0)
+    };
+    Target { x, y, vx, vy, pop: 0.0 }
+}
+
+fn load_stats() -> Stats {
+    crate::prefs::get(STATS_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn start(w: f64, h: f64) {
+    RANGE.with(|r| {
+        *r.borrow_mut() = Some(Range {
+            targets: (0..TARGET_COUNT).map(|i| spawn_target(i, w, h)).collect(),
+            misses: Vec::new(),
+            session: Stats::default(),
+            all_time: load_stats(),
+        });
+    });
+}
+
+/// Close the range and fold this visit's shots into the saved totals
+pub fn stop() {
+    let Some(range) = RANGE.with(|r| r.borrow_mut().take()) else { return };
+    if range.session.shots == 0 { return; }
+    let total = Stats {
+        shots: range.all_time.shots + range.session.shots,
+        hits: range.all_time.hits + range.session.hits,
+    };
+    if let Ok(raw) = serde_json::to_string(&total) {
+        crate::prefs::set(STATS_KEY, &raw);
+    }
+}
+
+/// A click at (x, y) CSS px: pop the target under it, or count a miss
+pub fn shoot(x: f64, y: f64) {
+    RANGE.with(|r| {
+        let mut r = r.borrow_mut();
+        let Some(range) = r.as_mut() else { return };
+        range.session.shots += 1;
+        let hit = range.targets.iter_mut()
+            .find(|tg| tg.pop <= 0.0 && (tg.x - x).hypot(tg.y - y) <= TARGET_R);
+        match hit {
+            Some(tg) => {
+                tg.pop = POP_TIME;
+                range.session.hits += 1;
+            }
+            None => range.misses.push((x, y, MISS_TIME)),
+        }
+    });
+}
+
+/// Move and draw the targets and the accuracy readout (called each lobby frame, after the background)
+pub fn render(ctx: &CanvasRenderingContext2d, w: f64, h: f64, dt: f64) {
+    RANGE.with(|r| {
+        let mut r = r.borrow_mut();
+        let Some(range) = r.as_mut() else { return };
+
+        for (i, tg) in range.targets.iter_mut().enumerate() {
+            if tg.pop > 0.0 {
+                tg.pop -= dt;
+                if tg.pop <= 0.0 {
+                    *tg = spawn_target(i, w, h);
+                }
+                continue;
+            }
+            tg.x += tg.vx * dt;
+            tg.y += tg.vy * dt;
+            if tg.x < TARGET_R || tg.x > w - TARGET_R { tg.vx = -tg.vx; }
+            if tg.y < TARGET_R || tg.y > h - TARGET_R { tg.vy = -tg.vy; }
+            tg.x = tg.x.clamp(TARGET_R, (w - TARGET_R).max(TARGET_R));
+            tg.y = tg.y.clamp(TARGET_R, (h - TARGET_R).max(TARGET_R));
+        }
+        range.misses.retain_mut(|m| {
+            m.2 -= dt;
+            m.2 > 0.0
+        });
+
+        let tau = std::f64::consts::TAU;
+        for tg in &range.targets {
+            if tg.pop > 0.0 {
+                // Burst: an expanding, fading ring
+                let k = 1.0 - tg.pop / POP_TIME;
+                ctx.set_global_alpha(1.0 - k);
+                ctx.set_stroke_style_str("#ffdd55");
+                ctx.set_line_width(3.0);
+                ctx.begin_path();
+                let _ = ctx.arc(tg.x, tg.y, TARGET_R * (1.0 + k), 0.0, tau);
+                ctx.stroke();
+                ctx.set_global_alpha(1.0);
+                continue;
+            }
+            // Bullseye: three rings, red for movers and blue for fixed targets
+            let color = if tg.vx != 0.0 || tg.vy != 0.0 { "#ff5544" } else { "#44aaff" };
+            for (ring, fill) in [(1.0, color), (0.66, "#ffffff"), (0.33, color)] {
+                ctx.set_fill_style_str(fill);
+                ctx.begin_path();
+                let _ = ctx.arc(tg.x, tg.y, TARGET_R * ring, 0.0, tau);
+                ctx.fill();
+            }
+        }
+        ctx.set_stroke_style_str("rgba(255, 255, 255, 0.6)");
+        ctx.set_line_width(1.5);
+        for &(x, y, left) in &range.misses {
+            let s = 6.0 * (left / MISS_TIME);
+            ctx.begin_path();
+            ctx.move_to(x - s, y - s);
+            ctx.line_to(x + s, y + s);
+            ctx.move_to(x + s, y - s);
+            ctx.line_to(x - s, y + s);
+            ctx.stroke();
+        }
+
+        let all = Stats {
+            shots: range.all_time.shots + range.session.shots,
+            hits: range.all_time.hits + range.session.hits,
+        };
+        let line = format!(
+            "{} {}/{}   {} {}%   {} {}%",
+            t(Msg::Hits), range.session.hits, range.session.shots,
+            t(Msg::Accuracy), range.session.accuracy(),
+            t(Msg::AllTime), all.accuracy(),
+        );
+        ctx.set_text_align("center");
+        ctx.set_font("bold 16px monospace");
+        ctx.set_fill_style_str("#ffffff");
+        let _ = ctx.fill_text(&line, w / 2.0, 36.0);
+    });
+}
@@ -15,6 +15,8 @@ done
 if $BUILD_RUST; then
     echo ">> Building Rust/WASM client..."
     (cd client-rust && PATH="$HOME/.cargo/bin:$PATH" trunk build --release)
+    echo ">> Building slim controller bundle..."
+    (cd client-rust && PATH="$HOME/.cargo/bin:$PATH" trunk build --release controller.html -d dist/controller --public-url /controller/)
 fi
 
 echo ">> Building server binary..."
@@ -8,15 +8,18 @@ const (
 	AsteroidMaxSpeed = 150.0
 	AsteroidSpinMin  = 0.5
 	AsteroidSpinMax  = 2.0
+	AsteroidHP       = 60
 )
 
 // Asteroid flies in a straight line across the map
 type Asteroid struct {
 	ID       string
+	NetID    uint32
 	X, Y     float64
 	VX, VY   float64
 	Rotation float64
 	Spin     float64
+	HP       int
 	Alive    bool
 }
 
@@ -25,6 +28,8 @@ func NewAsteroid() *Asteroid {
 	id := GenerateID(4)
 	a := &Asteroid{
 		ID:    id,
+		NetID: NextNetID(),
+		HP:    AsteroidHP,
 		Alive: true,
 	}
 
@@ -97,12 +102,28 @@ func (a *Asteroid) Update(dt float64) {
 	}
 }
 
+// TakeDamage chips the asteroid and returns true if that broke it apart
+func (a *Asteroid) TakeDamage(dmg int) bool {
+	if !a.Alive {
+		return false
+	}
+	a.HP -= dmg
+	if a.HP <= 0 {
+		a.HP = 0
+		a.Alive = false
+		return true
+	}
+	return false
+}
+
 // ToState converts to protocol state
 func (a *Asteroid) ToState() AsteroidState {
 	return AsteroidState{
-		ID: a.ID,
-		X:  round1(a.X),
-		Y:  round1(a.Y),
-		R:  math.Round(a.Rotation*100) / 100,
+		ID:   a.NetID,
+		X:    round1(a.X),
+		Y:    round1(a.Y),
+		R:    math.Round(a.Rotation*100) / 100,
+		HP:   a.HP,
+		Spin: math.Round(a.Spin*100) / 100,
 	}
 }
@@ -61,6 +61,19 @@ func TestAsteroidSpins(t *testing.T) {
 	}
 }
 
+func TestAsteroidBreaksAfterEnoughDamage(t *testing.T) {
+	a := NewAsteroid()
+	if a.TakeDamage(AsteroidHP - 1) {
+		t.Fatal("asteroid should survive one short of its HP")
+	}
+	if !a.TakeDamage(1) || a.Alive || a.HP != 0 {
+		t.Errorf("asteroid should break at 0 HP, got alive=%v hp=%d", a.Alive, a.HP)
+	}
+	if a.TakeDamage(10) {
+		t.Error("a broken asteroid can't break again")
+	}
+}
+
 func abs(x float64) float64 {
 	if x < 0 {
 		return -x
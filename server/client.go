@@ -1,9 +1,13 @@
 package main
 
 import (
+	"encoding/binary"
 	"encoding/json"
 	"log"
+	"math"
+	"sync/atomic"
 	"time"
+	"unicode/utf8"
 
 	"github.com/gorilla/websocket"
 )
@@ -11,11 +15,18 @@ import (
 const (
 	writeWait         = 10 * time.Second
 	pongWait          = 60 * time.Second
-	pingPeriod        = (pongWait * 9) / 10
+	pingPeriod        = 2 * time.Second // often enough to show each player's ping
 	maxMessageSize    = 4096
 	sendBufSize       = 256
 	maxMessagesPerSec = 50
 	maxNameLen        = 16
+	maxMarkPoints     = 64
+	maxBugDescLen     = 1000
+	bugReportCooldown = 30 * time.Second
+	maxClientErrLen   = 1000
+	maxClientErrField = 64 // kind, version and phase
+	clientErrCooldown = 5 * time.Second
+	markerCooldown    = time.Second
 )
 
 // Client represents a WebSocket connection
@@ -27,8 +38,14 @@ type Client struct {
 	sessionID    string
 	remoteAddr   string
 	isController bool
+	isGunner     bool // controller attached as co-pilot (turret) rather than pilot
+	isCoach      bool
 	msgCount     int
 	msgResetAt   time.Time
+	lastBugAt    time.Time
+	lastErrAt    time.Time
+	lastMarkerAt time.Time
+	rtt          atomic.Int64 // latest ping round trip (ns), set from ReadPump
 }
 
 // NewClient creates a new Client
@@ -51,8 +68,13 @@ func (c *Client) ReadPump() {
 
 	c.conn.SetReadLimit(maxMessageSize)
 	c.conn.SetReadDeadline(time.Now().Add(pongWait))
-	c.conn.SetPongHandler(func(string) error {
+	c.conn.SetPongHandler(func(appData string) error {
 		c.conn.SetReadDeadline(time.Now().Add(pongWait))
+		// Our pings carry the time they were sent
+		if len(appData) == 8 {
+			sent := int64(binary.BigEndian.Uint64([]byte(appData)))
+			c.rtt.Store(time.Now().UnixNano() - sent)
+		}
 		return nil
 	})
 
@@ -77,8 +99,9 @@ func (c *Client) ReadPump() {
 			break
 		}
 
-		// Binary input messages: 8 bytes [0x01, mx_hi, mx_lo, my_hi, my_lo, flags, thresh_hi, thresh_lo]
-		if msgType == websocket.BinaryMessage && len(message) == 8 && message[0] == 0x01 {
+		// Binary input messages: [0x01, mx_hi, mx_lo, my_hi, my_lo, flags, thresh_hi, thresh_lo, seq_hi, seq_lo]
+		// (legacy 8-byte packets without the sequence number are still accepted)
+		if msgType == websocket.BinaryMessage && (len(message) == 8 || len(message) == 10) && message[0] == 0x01 {
 			c.handleBinaryInput(message)
 		} else {
 			c.handleMessage(message)
@@ -115,13 +138,19 @@ func (c *Client) WritePump() {
 
 		case <-ticker.C:
 			c.conn.SetWriteDeadline(time.Now().Add(writeWait))
-			if err := c.conn.WriteMessage(websocket.PingMessage, nil); err != nil {
+			sent := binary.BigEndian.AppendUint64(nil, uint64(time.Now().UnixNano()))
+			if err := c.conn.WriteMessage(websocket.PingMessage, sent); err != nil {
 				return
 			}
 		}
 	}
 }
 
+// RTT returns the latest ping round trip, or 0 before the first pong
+func (c *Client) RTT() time.Duration {
+	return time.Duration(c.rtt.Load())
+}
+
 // SendJSON sends a JSON message to the client
 func (c *Client) SendJSON(msg interface{}) {
 	data, err := json.Marshal(msg)
@@ -178,6 +207,31 @@ func (c *Client) handleMessage(raw []byte) {
 		c.handleCheck(env.D)
 	case MsgControl:
 		c.handleControl(env.D)
+	case MsgCoach:
+		c.handleCoach(env.D)
+	case MsgView:
+		c.handleView(env.D)
+	case MsgMark:
+		c.handleMark(env.D)
+	case MsgNetPrefs:
+		c.handleNetPrefs(env.D)
+	case MsgBugReport:
+		c.handleBugReport(env.D)
+	case MsgClientErr:
+		c.handleClientError(env.D)
+	case MsgViewport:
+		c.handleViewport(env.D)
+	case MsgResume:
+		c.handleResume(env.D)
+	case MsgMarker:
+		c.handleMarker(env.D)
+	case MsgSpectate:
+		c.handleSpectate(env.D)
+	case MsgShipPick:
+		c.handleShipPick(env.D)
+	case MsgPing:
+		// Echo the payload so the client can time the round trip
+		c.SendJSON(Envelope{T: MsgPong, Data: env.D})
 	}
 }
 
@@ -187,6 +241,10 @@ func (c *Client) handleList() {
 }
 
 func (c *Client) handleCreate(data json.RawMessage) {
+	if c.sessionID != "" {
+		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "already in a session"}})
+		return
+	}
 	var msg CreateMsg
 	if err := json.Unmarshal(data, &msg); err != nil {
 		return
@@ -217,6 +275,12 @@ func (c *Client) handleCreate(data json.RawMessage) {
 }
 
 func (c *Client) handleJoin(data json.RawMessage) {
+	// A second join (say, one queued while offline and flushed after a
+	// resume) would give this connection another ship
+	if c.sessionID != "" {
+		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "already in a session"}})
+		return
+	}
 	var msg JoinMsg
 	if err := json.Unmarshal(data, &msg); err != nil {
 		return
@@ -235,7 +299,11 @@ func (c *Client) handleJoin(data json.RawMessage) {
 		return
 	}
 
-	player := sess.Game.AddPlayer(name)
+	ship := -1
+	if msg.Ship != nil {
+		ship = *msg.Ship
+	}
+	player := sess.Game.AddPlayerShip(name, ship)
 	if player == nil {
 		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "session full"}})
 		return
@@ -247,36 +315,77 @@ func (c *Client) handleJoin(data json.RawMessage) {
 	sess.Game.SetClient(player.ID, c)
 
 	c.SendJSON(Envelope{T: MsgJoined, Data: map[string]string{"sid": sess.ID}})
-	c.SendJSON(Envelope{T: MsgWelcome, Data: WelcomeMsg{ID: player.ID, Ship: player.ShipType}})
+	c.SendJSON(Envelope{T: MsgWelcome, Data: WelcomeMsg{
+		ID: player.ID, NetID: player.NetID, Ship: player.ShipType, Token: player.ResumeToken, CoachKey: player.CoachKey, Names: sess.Game.PlayerNames(),
+	}})
 }
 
-// handleBinaryInput decodes a compact 8-byte binary input message
+func (c *Client) handleResume(data json.RawMessage) {
+	if c.sessionID != "" {
+		return
+	}
+	var msg ResumeMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	sess := c.hub.sessions.GetSession(msg.SID)
+	if sess == nil {
+		c.SendJSON(Envelope{T: MsgResumeFail})
+		return
+	}
+	player := sess.Game.ResumePlayer(msg.PID, msg.Token, c)
+	if player == nil {
+		c.SendJSON(Envelope{T: MsgResumeFail})
+		return
+	}
+	c.hub.sessions.MarkActive(sess.ID)
+	c.playerID = player.ID
+	c.sessionID = sess.ID
+
+	c.SendJSON(Envelope{T: MsgJoined, Data: map[string]string{"sid": sess.ID}})
+	c.SendJSON(Envelope{T: MsgWelcome, Data: WelcomeMsg{
+		ID: player.ID, NetID: player.NetID, Ship: player.ShipType, Token: player.ResumeToken, CoachKey: player.CoachKey, Names: sess.Game.PlayerNames(),
+	}})
+}
+
+// handleBinaryInput decodes a compact 10-byte (or legacy 8-byte) binary input message
 func (c *Client) handleBinaryInput(msg []byte) {
-	if c.sessionID == "" || c.playerID == "" {
+	if c.sessionID == "" || c.playerID == "" || c.isCoach {
 		return
 	}
-	// Decode: [0x01, mx_hi, mx_lo, my_hi, my_lo, flags, thresh_hi, thresh_lo]
+	// Decode: [0x01, mx_hi, mx_lo, my_hi, my_lo, flags, thresh_hi, thresh_lo, seq_hi, seq_lo]
+	// flags: 0x01 fire, 0x02 boost, 0x04 dodge
 	mx := float64(int16(uint16(msg[1])<<8 | uint16(msg[2])))
 	my := float64(int16(uint16(msg[3])<<8 | uint16(msg[4])))
 	flags := msg[5]
 	thresh := float64(uint16(msg[6])<<8 | uint16(msg[7]))
+	var seq uint16
+	if len(msg) >= 10 {
+		seq = uint16(msg[8])<<8 | uint16(msg[9])
+	}
 
 	input := ClientInput{
 		MX:     mx,
 		MY:     my,
 		Fire:   flags&0x01 != 0,
 		Boost:  flags&0x02 != 0,
+		Dodge:  flags&0x04 != 0,
 		Thresh: thresh,
+		Seq:    seq,
 	}
 	sess := c.hub.sessions.GetSession(c.sessionID)
 	if sess == nil {
 		return
 	}
+	if c.isGunner {
+		sess.Game.HandleGunnerInput(c.playerID, input)
+		return
+	}
 	sess.Game.HandleInput(c.playerID, input)
 }
 
 func (c *Client) handleInput(data json.RawMessage) {
-	if c.sessionID == "" || c.playerID == "" {
+	if c.sessionID == "" || c.playerID == "" || c.isCoach {
 		return
 	}
 	var input ClientInput
@@ -287,6 +396,10 @@ func (c *Client) handleInput(data json.RawMessage) {
 	if sess == nil {
 		return
 	}
+	if c.isGunner {
+		sess.Game.HandleGunnerInput(c.playerID, input)
+		return
+	}
 	sess.Game.HandleInput(c.playerID, input)
 }
 
@@ -310,17 +423,29 @@ func (c *Client) handleCheck(data json.RawMessage) {
 
 func (c *Client) handleLeave() {
 	if c.sessionID != "" {
-		if c.isController {
+		if c.isGunner {
+			sess := c.hub.sessions.GetSession(c.sessionID)
+			if sess != nil {
+				sess.Game.RemoveGunner(c.playerID, c)
+			}
+		} else if c.isController {
 			sess := c.hub.sessions.GetSession(c.sessionID)
 			if sess != nil {
 				sess.Game.RemoveController(c.playerID)
 			}
+		} else if c.isCoach {
+			sess := c.hub.sessions.GetSession(c.sessionID)
+			if sess != nil {
+				sess.Game.RemoveCoach(c.playerID, c)
+			}
 		} else {
 			c.hub.sessions.RemovePlayer(c.sessionID, c.playerID)
 		}
 		c.sessionID = ""
 		c.playerID = ""
 		c.isController = false
+		c.isGunner = false
+		c.isCoach = false
 	}
 }
 
@@ -334,7 +459,8 @@ func (c *Client) handleControl(data json.RawMessage) {
 		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "session not found"}})
 		return
 	}
-	if !sess.Game.HasPlayer(msg.PlayerID) {
+	nid, ok := sess.Game.PlayerNetID(msg.PlayerID)
+	if !ok {
 		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "player not found"}})
 		return
 	}
@@ -342,7 +468,215 @@ func (c *Client) handleControl(data json.RawMessage) {
 	c.sessionID = msg.SID
 	c.playerID = msg.PlayerID
 	c.isController = true
+	c.isGunner = msg.Role == "gunner"
+
+	if c.isGunner {
+		sess.Game.SetGunner(msg.PlayerID, c)
+	} else {
+		sess.Game.SetController(msg.PlayerID, c)
+	}
+	c.SendJSON(Envelope{T: MsgControlOK, Data: AttachOKMsg{PID: msg.PlayerID, NetID: nid}})
+}
+
+func (c *Client) handleCoach(data json.RawMessage) {
+	// Turning a player or controller socket into a coach would leave its ship
+	// behind with nothing to clean it up on disconnect
+	if c.sessionID != "" {
+		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "already in a session"}})
+		return
+	}
+	var msg CoachMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	sess := c.hub.sessions.GetSession(msg.SID)
+	if sess == nil {
+		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "session not found"}})
+		return
+	}
+	nid, ok := sess.Game.SetCoach(msg.PlayerID, msg.Key, c)
+	if !ok {
+		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "player not found"}})
+		return
+	}
+
+	c.sessionID = msg.SID
+	c.playerID = msg.PlayerID
+	c.isCoach = true
+
+	c.SendJSON(Envelope{T: MsgCoachOK, Data: AttachOKMsg{PID: msg.PlayerID, NetID: nid, Names: sess.Game.PlayerNames()}})
+}
+
+// handleView relays the player's camera and input overlay to their coach
+func (c *Client) handleView(data json.RawMessage) {
+	if c.sessionID == "" || c.playerID == "" || c.isController || c.isCoach {
+		return
+	}
+	var view ViewMsg
+	if err := json.Unmarshal(data, &view); err != nil {
+		return
+	}
+	sess := c.hub.sessions.GetSession(c.sessionID)
+	if sess == nil {
+		return
+	}
+	sess.Game.RelayView(c.playerID, view)
+}
+
+// handleMark relays a coach annotation to the coached player
+func (c *Client) handleMark(data json.RawMessage) {
+	if c.sessionID == "" || !c.isCoach {
+		return
+	}
+	var mark MarkMsg
+	if err := json.Unmarshal(data, &mark); err != nil {
+		return
+	}
+	if mark.Kind != "line" && mark.Kind != "ping" {
+		return
+	}
+	if len(mark.Pts) < 2 || len(mark.Pts)%2 != 0 || len(mark.Pts) > maxMarkPoints*2 {
+		return
+	}
+	sess := c.hub.sessions.GetSession(c.sessionID)
+	if sess == nil {
+		return
+	}
+	sess.Game.RelayMark(c.playerID, mark)
+}
+
+func (c *Client) handleMarker(data json.RawMessage) {
+	if c.sessionID == "" || c.playerID == "" || c.isCoach {
+		return
+	}
+	now := time.Now()
+	if now.Sub(c.lastMarkerAt) < markerCooldown {
+		return
+	}
+	var msg MarkerMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	if math.IsNaN(msg.X) || math.IsNaN(msg.Y) {
+		return
+	}
+	sess := c.hub.sessions.GetSession(c.sessionID)
+	if sess == nil {
+		return
+	}
+	c.lastMarkerAt = now
+	sess.Game.PlaceMarker(c.playerID, msg)
+}
 
-	sess.Game.SetController(msg.PlayerID, c)
-	c.SendJSON(Envelope{T: MsgControlOK, Data: map[string]string{"pid": msg.PlayerID}})
+func (c *Client) handleNetPrefs(data json.RawMessage) {
+	if c.sessionID == "" || c.playerID == "" || c.isController || c.isCoach {
+		return
+	}
+	var msg NetPrefsMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	sess := c.hub.sessions.GetSession(c.sessionID)
+	if sess == nil {
+		return
+	}
+	sess.Game.SetNetPrefs(c.playerID, msg.Saver)
+}
+
+func (c *Client) handleShipPick(data json.RawMessage) {
+	if c.sessionID == "" || c.playerID == "" || c.isController || c.isCoach {
+		return
+	}
+	var msg ShipPickMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	sess := c.hub.sessions.GetSession(c.sessionID)
+	if sess == nil {
+		return
+	}
+	sess.Game.PickShip(c.playerID, msg.Ship)
+}
+
+func (c *Client) handleSpectate(data json.RawMessage) {
+	if c.sessionID == "" || c.playerID == "" || c.isController || c.isCoach {
+		return
+	}
+	var msg SpectateMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	sess := c.hub.sessions.GetSession(c.sessionID)
+	if sess == nil {
+		return
+	}
+	sess.Game.SetSpectate(c.playerID, msg.ID)
+}
+
+func (c *Client) handleViewport(data json.RawMessage) {
+	if c.sessionID == "" || c.playerID == "" || c.isController || c.isCoach {
+		return
+	}
+	var msg ViewportMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	if msg.VW <= 0 || msg.VH <= 0 || math.IsNaN(msg.VW) || math.IsNaN(msg.VH) {
+		return
+	}
+	sess := c.hub.sessions.GetSession(c.sessionID)
+	if sess == nil {
+		return
+	}
+	sess.Game.SetViewport(c.playerID, msg.VW, msg.VH)
+}
+
+func (c *Client) handleBugReport(data json.RawMessage) {
+	now := time.Now()
+	if now.Sub(c.lastBugAt) < bugReportCooldown {
+		c.SendJSON(Envelope{T: MsgError, Data: ErrorMsg{Msg: "bug report not sent, please wait a moment and try again"}})
+		return
+	}
+	var msg BugReportMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	msg.Desc = truncateUTF8(msg.Desc, maxBugDescLen)
+	c.lastBugAt = now
+	report, err := json.Marshal(msg)
+	if err != nil {
+		return
+	}
+	log.Printf("bug report from %s (session=%q player=%q): %s", c.remoteAddr, c.sessionID, c.playerID, report)
+	c.SendJSON(Envelope{T: MsgBugOK})
+}
+
+func (c *Client) handleClientError(data json.RawMessage) {
+	now := time.Now()
+	if now.Sub(c.lastErrAt) < clientErrCooldown {
+		return
+	}
+	var msg ClientErrorMsg
+	if err := json.Unmarshal(data, &msg); err != nil {
+		return
+	}
+	msg.Msg = truncateUTF8(msg.Msg, maxClientErrLen)
+	msg.Kind = truncateUTF8(msg.Kind, maxClientErrField)
+	msg.Version = truncateUTF8(msg.Version, maxClientErrField)
+	msg.Phase = truncateUTF8(msg.Phase, maxClientErrField)
+	c.lastErrAt = now
+	// Quoted, so a crafted message can't break the line and forge log entries
+	log.Printf("client %q from %s (session=%q player=%q ver=%q phase=%q): %q",
+		msg.Kind, c.remoteAddr, c.sessionID, c.playerID, msg.Version, msg.Phase, msg.Msg)
+}
+
+// truncateUTF8 cuts s to at most n bytes without splitting a rune
+func truncateUTF8(s string, n int) string {
+	if len(s) <= n {
+		return s
+	}
+	for n > 0 && !utf8.RuneStart(s[n]) {
+		n--
+	}
+	return s[:n]
 }
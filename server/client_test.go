@@ -0,0 +1,24 @@
+package main
+
+import "testing"
+
+func TestTruncateUTF8(t *testing.T) {
+	cases := []struct {
+		in   string
+		n    int
+		want string
+	}{
+		{"short", 10, "short"},
+		{"abcdef", 3, "abc"},
+		// é is two bytes and 🚀 four; neither may be cut in half
+		{"éé", 3, "é"},
+		{"aé", 2, "a"},
+		{"🚀x", 3, ""},
+		{"🚀x", 5, "🚀x"},
+	}
+	for _, tc := range cases {
+		if got := truncateUTF8(tc.in, tc.n); got != tc.want {
+			t.Errorf("truncateUTF8(%q, %d) = %q, want %q", tc.in, tc.n, got, tc.want)
+		}
+	}
+}
@@ -1,5 +1,26 @@
 package main
 
+import "math"
+
+// Player shots that hit a ship from behind strike its engines for extra damage
+const (
+	CritArc        = math.Pi / 4 // max angle between the shot's heading and the target's
+	CritMultiplier = 1.5
+	MobCrits       = false // mobs chase from behind by design, so their crits would mostly punish being hunted
+)
+
+// ShotDamage returns what a projectile deals to a ship facing targetR, and
+// whether that's a critical hit
+func ShotDamage(proj *Projectile, targetR float64) (int, bool) {
+	if proj.FromMob && !MobCrits {
+		return proj.Damage, false
+	}
+	if math.Abs(NormalizeAngle(proj.Rotation-targetR)) <= CritArc {
+		return int(float64(proj.Damage) * CritMultiplier), true
+	}
+	return proj.Damage, false
+}
+
 // ApplyDamage applies damage to a player and returns true if they died
 func ApplyDamage(player *Player, damage int) bool {
 	return player.TakeDamage(damage)
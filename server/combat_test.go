@@ -1,6 +1,9 @@
 package main
 
-import "testing"
+import (
+	"math"
+	"testing"
+)
 
 func TestApplyDamage(t *testing.T) {
 	p := &Player{
@@ -58,3 +61,38 @@ func TestRespawnPlayer(t *testing.T) {
 		t.Error("velocity should be zero after respawn")
 	}
 }
+
+func TestShotDamageCritFromBehind(t *testing.T) {
+	proj := &Projectile{Rotation: 0, Damage: ProjectileDamage}
+
+	// Target flying the same way the shot travels: hit in the engines
+	if dmg, crit := ShotDamage(proj, 0.3); !crit || dmg != int(ProjectileDamage*CritMultiplier) {
+		t.Errorf("expected a crit for %d, got %d (crit %v)", int(ProjectileDamage*CritMultiplier), dmg, crit)
+	}
+	// Angles wrap: -PI+0.1 vs PI-0.1 is a small difference
+	proj.Rotation = math.Pi - 0.1
+	if _, crit := ShotDamage(proj, -math.Pi+0.1); !crit {
+		t.Error("expected a crit across the angle wrap")
+	}
+
+	// Head-on and side hits are normal
+	proj.Rotation = 0
+	for _, r := range []float64{math.Pi, math.Pi / 2} {
+		if dmg, crit := ShotDamage(proj, r); crit || dmg != ProjectileDamage {
+			t.Errorf("facing %v: expected %d, got %d (crit %v)", r, ProjectileDamage, dmg, crit)
+		}
+	}
+}
+
+func TestShotDamageMobShotsDontCrit(t *testing.T) {
+	mob := NewMob()
+	mob.Rotation = 0
+	proj := NewMobProjectile(mob)
+	dmg, crit := ShotDamage(proj, 0.1)
+	if crit != MobCrits {
+		t.Errorf("mob shot from behind: crit %v, want %v", crit, MobCrits)
+	}
+	if !MobCrits && dmg != proj.Damage {
+		t.Errorf("expected plain damage %d, got %d", proj.Damage, dmg)
+	}
+}
@@ -0,0 +1,189 @@
+package main
+
+import "math"
+
+// DeltaKeyframeEvery is how many snapshots go out between full keyframes.
+// In between, a client only receives entities whose state changed since the
+// last snapshot it was sent, plus the IDs that disappeared from its view.
+const DeltaKeyframeEvery = 60
+
+// VelocityDelta is the smallest velocity change resent to a client that
+// already has the entity's velocity; below it the client keeps the old one
+const VelocityDelta = 5.0
+
+// deltaTracker remembers what one client was last sent
+type deltaTracker struct {
+	sent     map[uint32]uint64     // entity NetID -> fingerprint of the last sent state
+	vel      map[uint32][2]float64 // ship/mob NetID -> velocity the client last received
+	seen     map[uint32]struct{}   // scratch: IDs present in the current snapshot
+	removed  []uint32              // scratch: IDs to report as removed
+	sinceKey int
+}
+
+func newDeltaTracker() *deltaTracker {
+	return &deltaTracker{
+		sent: make(map[uint32]uint64, 64),
+		vel:  make(map[uint32][2]float64, 64),
+		seen: make(map[uint32]struct{}, 64),
+	}
+}
+
+const (
+	fnvOffset = 14695981039346656037
+	fnvPrime  = 1099511628211
+)
+
+func fpMix(h, v uint64) uint64 {
+	for i := 0; i < 8; i++ {
+		h ^= v & 0xff
+		h *= fnvPrime
+		v >>= 8
+	}
+	return h
+}
+
+func fpFloat(h uint64, f float64) uint64 { return fpMix(h, math.Float64bits(f)) }
+
+func fpInt(h uint64, i int) uint64 { return fpMix(h, uint64(i)) }
+
+func fpBool(h uint64, b bool) uint64 {
+	if b {
+		return fpMix(h, 1)
+	}
+	return fpMix(h, 0)
+}
+
+// fpVel folds in a velocity (nil once trimVelocity has dropped it)
+func fpVel(h uint64, v *float64) uint64 {
+	if v == nil {
+		return h
+	}
+	return fpFloat(h, *v)
+}
+
+func (s *PlayerState) fingerprint() uint64 {
+	h := fpFloat(fnvOffset, s.X)
+	h = fpFloat(h, s.Y)
+	h = fpFloat(h, s.R)
+	h = fpVel(h, s.VX)
+	h = fpVel(h, s.VY)
+	h = fpInt(h, s.HP)
+	h = fpInt(h, s.MaxHP)
+	h = fpInt(h, s.Ship)
+	h = fpInt(h, s.Score)
+	h = fpInt(h, s.Kills)
+	h = fpInt(h, s.Deaths)
+	h = fpInt(h, s.Assists)
+	h = fpBool(h, s.Alive)
+	return fpBool(h, s.Boost)
+}
+
+func (s *MobState) fingerprint() uint64 {
+	h := fpFloat(fnvOffset, s.X)
+	h = fpFloat(h, s.Y)
+	h = fpFloat(h, s.R)
+	h = fpVel(h, s.VX)
+	h = fpVel(h, s.VY)
+	h = fpInt(h, s.HP)
+	h = fpInt(h, s.MaxHP)
+	h = fpInt(h, s.Ship)
+	return fpBool(h, s.Alive)
+}
+
+func (s *ProjectileState) fingerprint() uint64 {
+	h := fpFloat(fnvOffset, s.X)
+	h = fpFloat(h, s.Y)
+	h = fpFloat(h, s.R)
+	h = fpMix(h, uint64(s.Kind))
+	return fpMix(h, uint64(s.Owner))
+}
+
+func (s *AsteroidState) fingerprint() uint64 {
+	h := fpFloat(fnvOffset, s.X)
+	h = fpFloat(h, s.Y)
+	h = fpFloat(h, s.R)
+	return fpMix(h, uint64(s.HP))
+}
+
+func (s *PickupState) fingerprint() uint64 {
+	h := fpFloat(fnvOffset, s.X)
+	return fpFloat(h, s.Y)
+}
+
+// filterChanged compacts items in place down to the entries whose
+// fingerprint differs from what was last sent (all of them on a keyframe)
+func filterChanged[T any](tr *deltaTracker, items []T, keyframe bool, id func(*T) uint32, fp func(*T) uint64) []T {
+	out := items[:0]
+	for i := range items {
+		it := &items[i]
+		key := id(it)
+		h := fp(it)
+		tr.seen[key] = struct{}{}
+		if prev, ok := tr.sent[key]; ok && prev == h && !keyframe {
+			continue
+		}
+		tr.sent[key] = h
+		out = append(out, *it)
+	}
+	return out
+}
+
+// trimVelocity drops vx/vy when this client already has a velocity within
+// VelocityDelta of it, and otherwise records what it's about to receive.
+// Tracking this per client matters: data saver clients skip broadcasts, and
+// culled entities come and go, so a global "last broadcast" goes stale.
+func (tr *deltaTracker) trimVelocity(id uint32, vx, vy **float64) {
+	if *vx == nil || *vy == nil {
+		return
+	}
+	cur := [2]float64{**vx, **vy}
+	if prev, ok := tr.vel[id]; ok && math.Abs(cur[0]-prev[0]) < VelocityDelta && math.Abs(cur[1]-prev[1]) < VelocityDelta {
+		*vx, *vy = nil, nil
+		return
+	}
+	tr.vel[id] = cur
+}
+
+// apply trims st down to a delta against the previous snapshot sent to this
+// client. The entity slices are filtered in place, so st must not be reused
+// for anyone else afterwards.
+func (tr *deltaTracker) apply(st *GameState) {
+	keyframe := tr.sinceKey == 0
+	tr.sinceKey = (tr.sinceKey + 1) % DeltaKeyframeEvery
+	if keyframe {
+		clear(tr.sent)
+		clear(tr.vel)
+	}
+	clear(tr.seen)
+
+	st.Players = filterChanged(tr, st.Players, keyframe,
+		func(s *PlayerState) uint32 { return s.ID }, (*PlayerState).fingerprint)
+	st.Projectiles = filterChanged(tr, st.Projectiles, keyframe,
+		func(s *ProjectileState) uint32 { return s.ID }, (*ProjectileState).fingerprint)
+	st.Mobs = filterChanged(tr, st.Mobs, keyframe,
+		func(s *MobState) uint32 { return s.ID }, (*MobState).fingerprint)
+	for i := range st.Players {
+		tr.trimVelocity(st.Players[i].ID, &st.Players[i].VX, &st.Players[i].VY)
+	}
+	for i := range st.Mobs {
+		tr.trimVelocity(st.Mobs[i].ID, &st.Mobs[i].VX, &st.Mobs[i].VY)
+	}
+	st.Asteroids = filterChanged(tr, st.Asteroids, keyframe,
+		func(s *AsteroidState) uint32 { return s.ID }, (*AsteroidState).fingerprint)
+	st.Pickups = filterChanged(tr, st.Pickups, keyframe,
+		func(s *PickupState) uint32 { return s.ID }, (*PickupState).fingerprint)
+
+	if keyframe {
+		return
+	}
+	tr.removed = tr.removed[:0]
+	for id := range tr.sent {
+		if _, ok := tr.seen[id]; !ok {
+			tr.removed = append(tr.removed, id)
+			delete(tr.sent, id)
+			delete(tr.vel, id) // the client drops it too, so re-entry needs the full velocity
+		}
+	}
+	st.Delta = true
+	st.Removed = tr.removed
+}
@@ -1,6 +1,7 @@
 package main
 
 import (
+	"crypto/subtle"
 	"encoding/json"
 	"math"
 	"sync"
@@ -14,6 +15,7 @@ const (
 	BroadcastRate = 30               // state broadcasts per second
 	TickDuration  = time.Second / TickRate
 	BroadcastEvery = TickRate / BroadcastRate
+	PingsEvery     = 2 * TickRate // ticks between ping table broadcasts
 )
 
 const (
@@ -26,6 +28,12 @@ const (
 	AsteroidSpawnInterval    = 10.0
 	PickupSpawnInterval      = 20.0
 	DeathScorePenalty        = 10
+	SaverBroadcastDivisor    = 3      // data saver: every 3rd broadcast (10Hz)
+	saverCullDist            = 1000.0 // data saver: tighter viewport culling
+	cullDist                 = 1200.0 // default culling radius (half-viewport + margin)
+	interestMargin           = 300.0  // beyond the client's reported half-viewport
+	minInterestDist          = 600.0
+	maxInterestDist          = 2500.0
 )
 
 // Broadcaster interface for sending messages to clients
@@ -33,6 +41,7 @@ type Broadcaster interface {
 	SendJSON(msg interface{})
 	SendRaw(data []byte)
 	SendBinary(data []byte)
+	RTT() time.Duration // latest round trip, 0 if unknown
 }
 
 // Game holds the state for one game session
@@ -43,8 +52,15 @@ type Game struct {
 	mobs        map[string]*Mob
 	asteroids   map[string]*Asteroid
 	pickups     map[string]*Pickup
-	clients     map[string]Broadcaster // playerID -> client
-	controllers map[string]Broadcaster // playerID -> phone controller
+	clients     map[string]Broadcaster   // playerID -> client
+	controllers map[string]Broadcaster   // playerID -> phone controller
+	coaches     map[string]Broadcaster   // playerID -> coach spectator
+	gunners     map[string]Broadcaster   // playerID -> co-pilot phone on the turret
+	dataSaver   map[string]bool          // playerID -> reduced snapshot traffic
+	deltas      map[string]*deltaTracker // playerID -> what their client was last sent
+	viewports   map[string][2]float64    // playerID -> visible world size reported by the client
+	spectating  map[string]uint32        // playerID -> NetID their death cam follows
+	detached    map[string]time.Time     // playerID -> when their client disconnected (resumable)
 	tick        uint64
 	running     bool
 	stop        chan struct{}
@@ -67,10 +83,6 @@ type Game struct {
 	// Reusable query buffer for spatial grid lookups
 	queryBuf []EntityRef
 
-	// Delta compression: last-sent velocity per entity
-	lastVX map[string]float64
-	lastVY map[string]float64
-
 	// Reusable broadcast buffers (reset with [:0] each tick)
 	bcastPlayers   []playerWithPos
 	bcastMobs      []mobWithPos
@@ -96,12 +108,17 @@ func NewGame() *Game {
 		pickups:         make(map[string]*Pickup),
 		clients:         make(map[string]Broadcaster),
 		controllers:     make(map[string]Broadcaster),
+		coaches:         make(map[string]Broadcaster),
+		gunners:         make(map[string]Broadcaster),
+		dataSaver:       make(map[string]bool),
+		deltas:          make(map[string]*deltaTracker),
+		viewports:       make(map[string][2]float64),
+		spectating:      make(map[string]uint32),
+		detached:        make(map[string]time.Time),
 		stop:            make(chan struct{}),
 		mobSpawnCD:      MobSpawnInterval,
 		asteroidSpawnCD: AsteroidSpawnInterval,
 		pickupSpawnCD:   PickupSpawnInterval,
-		lastVX:          make(map[string]float64, maxPlayersPerSession+maxMobsPerSession),
-		lastVY:          make(map[string]float64, maxPlayersPerSession+maxMobsPerSession),
 		bcastPlayers:    make([]playerWithPos, 0, maxPlayersPerSession),
 		bcastMobs:       make([]mobWithPos, 0, maxMobsPerSession),
 		bcastAsteroids:  make([]asteroidWithPos, 0, maxAsteroidsPerSession),
@@ -144,8 +161,14 @@ func (g *Game) Stop() {
 	}
 }
 
-// AddPlayer adds a new player to the game
+// AddPlayer adds a new player to the game, assigning ships in rotation
 func (g *Game) AddPlayer(name string) *Player {
+	return g.AddPlayerShip(name, -1)
+}
+
+// AddPlayerShip adds a new player flying the given hull, or the next one in
+// rotation if ship isn't a valid player hull
+func (g *Game) AddPlayerShip(name string, ship int) *Player {
 	g.mu.Lock()
 	defer g.mu.Unlock()
 
@@ -154,10 +177,16 @@ func (g *Game) AddPlayer(name string) *Player {
 	}
 
 	id := GenerateID(4)
-	ship := g.nextShip % 3
-	g.nextShip++
+	if !ValidPlayerShip(ship) {
+		ship = g.nextShip % PlayerShipTypes
+		g.nextShip++
+	}
 	player := NewPlayer(id, name, ship)
+	player.ResumeToken = GenerateID(16)
+	player.CoachKey = GenerateID(16)
 	g.players[id] = player
+	// Everyone already here learns the new name; the joiner gets the table in its welcome
+	g.broadcastMsg(Envelope{T: MsgNames, Data: NamesMsg{Names: g.names()}})
 	return player
 }
 
@@ -165,9 +194,76 @@ func (g *Game) AddPlayer(name string) *Player {
 func (g *Game) RemovePlayer(id string) {
 	g.mu.Lock()
 	defer g.mu.Unlock()
+	g.removePlayer(id)
+}
+
+// removePlayer drops every trace of a player; g.mu must be held
+func (g *Game) removePlayer(id string) {
 	delete(g.players, id)
 	delete(g.clients, id)
 	delete(g.controllers, id)
+	delete(g.gunners, id)
+	delete(g.dataSaver, id)
+	delete(g.deltas, id)
+	delete(g.viewports, id)
+	delete(g.spectating, id)
+	delete(g.detached, id)
+	// Let the coach know the player they were watching is gone
+	if coach, ok := g.coaches[id]; ok {
+		coach.SendJSON(Envelope{T: MsgCoachOff})
+		delete(g.coaches, id)
+	}
+}
+
+// DetachClient drops a disconnected client but leaves its ship in the match
+// so it can be resumed. Returns the detach time, or false if client is not
+// the player's current connection (e.g. it was already replaced).
+func (g *Game) DetachClient(playerID string, client Broadcaster) (time.Time, bool) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	p, ok := g.players[playerID]
+	if !ok || g.clients[playerID] != client {
+		return time.Time{}, false
+	}
+	delete(g.clients, playerID)
+	delete(g.deltas, playerID)
+	// Nobody is steering: stop shooting and coast to a halt
+	p.Firing = false
+	p.Boosting = false
+	p.TargetX, p.TargetY = p.X, p.Y
+	now := time.Now()
+	g.detached[playerID] = now
+	return now, true
+}
+
+// RemoveIfDetachedSince removes the player only if they are still detached
+// from the disconnect at `at`. Checked and removed under one lock so a
+// resume can't slip in between.
+func (g *Game) RemoveIfDetachedSince(playerID string, at time.Time) bool {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	t, ok := g.detached[playerID]
+	if !ok || !t.Equal(at) {
+		return false
+	}
+	g.removePlayer(playerID)
+	return true
+}
+
+// ResumePlayer reattaches a client to a detached player if the token matches
+func (g *Game) ResumePlayer(playerID, token string, client Broadcaster) *Player {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	p, ok := g.players[playerID]
+	if !ok || token == "" || subtle.ConstantTimeCompare([]byte(p.ResumeToken), []byte(token)) != 1 {
+		return nil
+	}
+	if _, ok := g.detached[playerID]; !ok {
+		return nil
+	}
+	delete(g.detached, playerID)
+	g.clients[playerID] = client
+	return p
 }
 
 // SetController associates a phone controller with a player
@@ -192,12 +288,228 @@ func (g *Game) RemoveController(playerID string) {
 	}
 }
 
-// HasPlayer returns true if the player exists in the game
-func (g *Game) HasPlayer(id string) bool {
+// SetGunner attaches a co-pilot phone that aims and fires for a player,
+// replacing any previous gunner
+func (g *Game) SetGunner(playerID string, client Broadcaster) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	g.gunners[playerID] = client
+	if p, ok := g.players[playerID]; ok {
+		p.Gunner = true
+		p.TurretR = p.Rotation
+		p.Firing = false
+	}
+}
+
+// RemoveGunner detaches a co-pilot, handing the trigger back to the pilot
+func (g *Game) RemoveGunner(playerID string, client Broadcaster) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	// A newer gunner may have replaced this one
+	if cur, ok := g.gunners[playerID]; !ok || cur != client {
+		return
+	}
+	delete(g.gunners, playerID)
+	if p, ok := g.players[playerID]; ok {
+		p.Gunner = false
+		p.Firing = false
+	}
+}
+
+// HandleGunnerInput applies a co-pilot's aim and trigger; steering stays with the pilot
+func (g *Game) HandleGunnerInput(playerID string, input ClientInput) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+
+	p, ok := g.players[playerID]
+	if !ok || !p.Gunner {
+		return
+	}
+	dx := input.MX - p.X
+	dy := input.MY - p.Y
+	if dx*dx+dy*dy > 25 { // same dead zone as the pilot's aim
+		p.TurretR = math.Atan2(dy, dx)
+	}
+	p.Firing = input.Fire
+}
+
+// SetCoach attaches a coach spectator to a player if key matches their coach key, replacing (and notifying) any previous
+// coach. Returns the player's NetID, or false if the player or key is wrong.
+func (g *Game) SetCoach(playerID, key string, client Broadcaster) (uint32, bool) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	p, ok := g.players[playerID]
+	if !ok || key == "" || subtle.ConstantTimeCompare([]byte(p.CoachKey), []byte(key)) != 1 {
+		return 0, false
+	}
+	if prev, ok := g.coaches[playerID]; ok && prev != client {
+		prev.SendJSON(Envelope{T: MsgCoachOff})
+	}
+	g.coaches[playerID] = client
+	if main, ok := g.clients[playerID]; ok {
+		main.SendJSON(Envelope{T: MsgCoachOn})
+	}
+	return p.NetID, true
+}
+
+// RemoveCoach detaches a coach from a player
+func (g *Game) RemoveCoach(playerID string, client Broadcaster) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	// A newer coach may have replaced this one
+	if cur, ok := g.coaches[playerID]; !ok || cur != client {
+		return
+	}
+	delete(g.coaches, playerID)
+	if main, ok := g.clients[playerID]; ok {
+		main.SendJSON(Envelope{T: MsgCoachOff})
+	}
+}
+
+// RelayView forwards a player's camera and input overlay to their coach
+func (g *Game) RelayView(playerID string, view ViewMsg) {
+	g.mu.RLock()
+	defer g.mu.RUnlock()
+	if coach, ok := g.coaches[playerID]; ok {
+		coach.SendJSON(Envelope{T: MsgView, Data: view})
+	}
+}
+
+// RelayMark forwards a coach annotation to the coached player
+func (g *Game) RelayMark(playerID string, mark MarkMsg) {
+	g.mu.RLock()
+	defer g.mu.RUnlock()
+	if main, ok := g.clients[playerID]; ok {
+		main.SendJSON(Envelope{T: MsgMark, Data: mark})
+	}
+}
+
+// PlaceMarker broadcasts a player's world ping to everyone in the session
+func (g *Game) PlaceMarker(playerID string, m MarkerMsg) {
 	g.mu.RLock()
 	defer g.mu.RUnlock()
-	_, ok := g.players[id]
-	return ok
+	p, ok := g.players[playerID]
+	if !ok {
+		return
+	}
+	x, y := m.X, m.Y
+	if m.Aim {
+		x, y = p.TargetX, p.TargetY
+	}
+	g.broadcastMsg(Envelope{T: MsgMarker, Data: MarkerMsg{
+		X:    math.Max(0, math.Min(WorldWidth, x)),
+		Y:    math.Max(0, math.Min(WorldHeight, y)),
+		ID:   p.ID,
+		Name: p.Name,
+	}})
+}
+
+// SetNetPrefs switches a player's snapshot stream between normal and data saver
+func (g *Game) SetNetPrefs(playerID string, saver bool) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	if saver {
+		g.dataSaver[playerID] = true
+	} else {
+		delete(g.dataSaver, playerID)
+	}
+}
+
+// PickShip sets the hull a player flies from their next spawn
+func (g *Game) PickShip(playerID string, ship int) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	if p, ok := g.players[playerID]; ok {
+		p.PickShip(ship)
+	}
+}
+
+// SetViewport records the world-space area a player's screen shows, so
+// snapshots only carry entities near what they can actually see
+func (g *Game) SetViewport(playerID string, vw, vh float64) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	if _, ok := g.players[playerID]; ok {
+		g.viewports[playerID] = [2]float64{vw, vh}
+	}
+}
+
+// SetSpectate records who a player's death cam is following (NetID, 0 for nobody)
+func (g *Game) SetSpectate(playerID string, netID uint32) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	if _, ok := g.players[playerID]; !ok {
+		return
+	}
+	if netID == 0 {
+		delete(g.spectating, playerID)
+	} else {
+		g.spectating[playerID] = netID
+	}
+}
+
+// cullCenter is where a player's snapshots are centered: their ship, or while
+// they're dead, whatever living ship or mob their death cam follows
+func (g *Game) cullCenter(p *Player) (float64, float64) {
+	id, ok := g.spectating[p.ID]
+	if p.Alive || !ok {
+		return p.X, p.Y
+	}
+	for _, o := range g.players {
+		if o.NetID == id && o.Alive {
+			return o.X, o.Y
+		}
+	}
+	for _, m := range g.mobs {
+		if m.NetID == id && m.Alive {
+			return m.X, m.Y
+		}
+	}
+	return p.X, p.Y
+}
+
+// interestRadius returns the per-axis culling distance for a player's snapshots
+func (g *Game) interestRadius(playerID string, saver bool) (float64, float64) {
+	limit := cullDist
+	if saver {
+		limit = saverCullDist
+	}
+	vp, ok := g.viewports[playerID]
+	if !ok {
+		return limit, limit
+	}
+	if !saver {
+		limit = maxInterestDist
+	}
+	cx := Clamp(vp[0]/2+interestMargin, minInterestDist, limit)
+	cy := Clamp(vp[1]/2+interestMargin, minInterestDist, limit)
+	return cx, cy
+}
+
+// PlayerNetID returns the player's snapshot ID, or false if they aren't in the game
+func (g *Game) PlayerNetID(id string) (uint32, bool) {
+	g.mu.RLock()
+	defer g.mu.RUnlock()
+	p, ok := g.players[id]
+	if !ok {
+		return 0, false
+	}
+	return p.NetID, true
+}
+
+// PlayerNames returns the NetID -> name table for every player in the game
+func (g *Game) PlayerNames() map[uint32]string {
+	g.mu.RLock()
+	defer g.mu.RUnlock()
+	return g.names()
+}
+
+func (g *Game) names() map[uint32]string {
+	names := make(map[uint32]string, len(g.players))
+	for _, p := range g.players {
+		names[p.NetID] = p.Name
+	}
+	return names
 }
 
 // SetClient associates a broadcaster with a player
@@ -205,6 +517,7 @@ func (g *Game) SetClient(playerID string, client Broadcaster) {
 	g.mu.Lock()
 	defer g.mu.Unlock()
 	g.clients[playerID] = client
+	delete(g.deltas, playerID) // new connection starts from a keyframe
 }
 
 // HandleInput processes input from a player
@@ -223,11 +536,22 @@ func (g *Game) HandleInput(playerID string, input ClientInput) {
 	if dx*dx+dy*dy > 25 { // > 5px distance
 		p.TargetR = math.Atan2(dy, dx)
 	}
-	p.Firing = input.Fire
+	if input.Dodge {
+		// Dash toward the pointer, or straight ahead when it's on the ship
+		a := p.Rotation
+		if dx*dx+dy*dy > 25 {
+			a = math.Atan2(dy, dx)
+		}
+		p.Dodge(a)
+	}
+	if !p.Gunner {
+		p.Firing = input.Fire
+	}
 	p.Boosting = input.Boost
 	p.TargetX = input.MX
 	p.TargetY = input.MY
 	p.SlowThresh = Clamp(input.Thresh, 50, 400)
+	p.InputSeq = input.Seq
 }
 
 // PlayerCount returns the number of players
@@ -275,7 +599,7 @@ func (g *Game) update() {
 		// Broadcast mob phrase if any
 		if mob.PendingPhrase != "" {
 			g.broadcastMsg(Envelope{T: MsgMobSay, Data: MobSayMsg{
-				MobID: mob.ID, Text: mob.PendingPhrase,
+				MobID: mob.NetID, Text: mob.PendingPhrase,
 			}})
 			mob.PendingPhrase = ""
 		}
@@ -324,6 +648,9 @@ func (g *Game) update() {
 	if g.tick%BroadcastEvery == 0 {
 		g.broadcastState()
 	}
+	if g.tick%PingsEvery == 0 {
+		g.broadcastPings()
+	}
 }
 
 // buildSpatialGrid populates the spatial hash with all alive entities
@@ -395,32 +722,41 @@ func (g *Game) checkCollisions() {
 				continue
 			}
 			if CheckCollision(proj.X, proj.Y, ProjectileRadius, p.X, p.Y, PlayerRadius) {
-				died := p.TakeDamage(proj.Damage)
+				dmg, crit := ShotDamage(proj, p.Rotation)
+				died := p.TakeDamage(dmg)
 				proj.Alive = false
+				if _, ok := g.players[proj.OwnerID]; ok {
+					p.NoteHit(proj.OwnerNet, g.tick)
+				}
 
 				// Broadcast hit event
 				g.broadcastMsg(Envelope{T: MsgHit, Data: HitMsg{
-					X: p.X, Y: p.Y, Dmg: proj.Damage,
-					VictimID: p.ID, AttackerID: proj.OwnerID,
+					X: p.X, Y: p.Y, Dmg: dmg,
+					VictimID: p.NetID, AttackerID: proj.OwnerNet, Crit: crit,
 				}})
 
 				if died {
 					p.Score -= DeathScorePenalty
+					p.Deaths++
 					// Award kill to shooter
 					if killer, ok := g.players[proj.OwnerID]; ok {
 						killer.Score++
-						killMsg := Envelope{T: MsgKill, Data: KillMsg{
-							KillerID:   killer.ID,
+						killer.Kills++
+						kill := KillMsg{
+							KillerID:   killer.NetID,
 							KillerName: killer.Name,
-							VictimID:   p.ID,
+							VictimID:   p.NetID,
 							VictimName: p.Name,
-						}}
-						g.broadcastMsg(killMsg)
+							Cause:      CauseLaser,
+						}
+						g.creditAssist(p, &kill)
+						g.broadcastMsg(Envelope{T: MsgKill, Data: kill})
 
 						if client, ok := g.clients[p.ID]; ok {
 							client.SendJSON(Envelope{T: MsgDeath, Data: DeathMsg{
-								KillerID:   killer.ID,
+								KillerID:   killer.NetID,
 								KillerName: killer.Name,
+								RespawnIn:  p.RespawnT,
 							}})
 						}
 					} else {
@@ -428,17 +764,21 @@ func (g *Game) checkCollisions() {
 						if killerMob, ok := g.mobs[proj.OwnerID]; ok && killerMob.Alive {
 							phrase := pickPhraseAlways("kill_player")
 							g.broadcastMsg(Envelope{T: MsgMobSay, Data: MobSayMsg{
-								MobID: killerMob.ID, Text: phrase,
+								MobID: killerMob.NetID, Text: phrase,
 							}})
 						}
-						g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-							KillerID: proj.OwnerID, KillerName: "Mob",
-							VictimID: p.ID, VictimName: p.Name,
-						}})
+						kill := KillMsg{
+							KillerID: proj.OwnerNet, KillerName: "Mob",
+							VictimID: p.NetID, VictimName: p.Name,
+							Cause: CauseLaser,
+						}
+						g.creditAssist(p, &kill)
+						g.broadcastMsg(Envelope{T: MsgKill, Data: kill})
 						if client, ok := g.clients[p.ID]; ok {
 							client.SendJSON(Envelope{T: MsgDeath, Data: DeathMsg{
-								KillerID:   proj.OwnerID,
+								KillerID:   proj.OwnerNet,
 								KillerName: "Mob",
+								RespawnIn:  p.RespawnT,
 							}})
 						}
 					}
@@ -463,27 +803,37 @@ func (g *Game) checkPlayerCollisions() {
 				b.TakeDamage(b.HP)
 				a.Score -= DeathScorePenalty
 				b.Score -= DeathScorePenalty
+				a.Deaths++
+				b.Deaths++
+				a.Kills++
+				b.Kills++
 
 				// Notify kills (mutual)
-				killMsg1 := Envelope{T: MsgKill, Data: KillMsg{
-					KillerID: a.ID, KillerName: a.Name,
-					VictimID: b.ID, VictimName: b.Name,
-				}}
-				killMsg2 := Envelope{T: MsgKill, Data: KillMsg{
-					KillerID: b.ID, KillerName: b.Name,
-					VictimID: a.ID, VictimName: a.Name,
-				}}
-				g.broadcastMsg(killMsg1)
-				g.broadcastMsg(killMsg2)
+				kill1 := KillMsg{
+					KillerID: a.NetID, KillerName: a.Name,
+					VictimID: b.NetID, VictimName: b.Name,
+					Cause: CauseRam,
+				}
+				kill2 := KillMsg{
+					KillerID: b.NetID, KillerName: b.Name,
+					VictimID: a.NetID, VictimName: a.Name,
+					Cause: CauseRam,
+				}
+				g.creditAssist(b, &kill1)
+				g.creditAssist(a, &kill2)
+				g.broadcastMsg(Envelope{T: MsgKill, Data: kill1})
+				g.broadcastMsg(Envelope{T: MsgKill, Data: kill2})
 
 				if client, ok := g.clients[a.ID]; ok {
 					client.SendJSON(Envelope{T: MsgDeath, Data: DeathMsg{
-						KillerID: b.ID, KillerName: b.Name,
+						KillerID: b.NetID, KillerName: b.Name,
+						RespawnIn: a.RespawnT,
 					}})
 				}
 				if client, ok := g.clients[b.ID]; ok {
 					client.SendJSON(Envelope{T: MsgDeath, Data: DeathMsg{
-						KillerID: a.ID, KillerName: a.Name,
+						KillerID: a.NetID, KillerName: a.Name,
+						RespawnIn: b.RespawnT,
 					}})
 				}
 			}
@@ -519,45 +869,16 @@ type pickupWithPos struct {
 
 // broadcastState sends the current game state to all clients with per-client viewport culling
 func (g *Game) broadcastState() {
-	// Delta compression threshold — skip velocity when change is tiny
-	const velDelta = 5.0
-
-	// Pre-convert all entities to state once, keeping raw positions for culling
+	// Pre-convert all entities to state once, keeping raw positions for culling.
+	// Velocities go out in full; each client's delta tracker trims the ones it already has.
 	g.bcastPlayers = g.bcastPlayers[:0]
 	for _, p := range g.players {
-		ps := p.ToState()
-		// Omit velocity if unchanged since last broadcast
-		vx := *ps.VX
-		vy := *ps.VY
-		prevVX, prevVY := g.lastVX[p.ID], g.lastVY[p.ID]
-		dx := vx - prevVX; if dx < 0 { dx = -dx }
-		dy := vy - prevVY; if dy < 0 { dy = -dy }
-		if dx < velDelta && dy < velDelta {
-			ps.VX = nil
-			ps.VY = nil
-		} else {
-			g.lastVX[p.ID] = vx
-			g.lastVY[p.ID] = vy
-		}
-		g.bcastPlayers = append(g.bcastPlayers, playerWithPos{state: ps, x: p.X, y: p.Y})
+		g.bcastPlayers = append(g.bcastPlayers, playerWithPos{state: p.ToState(), x: p.X, y: p.Y})
 	}
 	g.bcastMobs = g.bcastMobs[:0]
 	for _, mob := range g.mobs {
 		if mob.Alive {
-			ms := mob.ToState()
-			vx := *ms.VX
-			vy := *ms.VY
-			prevVX, prevVY := g.lastVX[mob.ID], g.lastVY[mob.ID]
-			dx := vx - prevVX; if dx < 0 { dx = -dx }
-			dy := vy - prevVY; if dy < 0 { dy = -dy }
-			if dx < velDelta && dy < velDelta {
-				ms.VX = nil
-				ms.VY = nil
-			} else {
-				g.lastVX[mob.ID] = vx
-				g.lastVY[mob.ID] = vy
-			}
-			g.bcastMobs = append(g.bcastMobs, mobWithPos{state: ms, x: mob.X, y: mob.Y})
+			g.bcastMobs = append(g.bcastMobs, mobWithPos{state: mob.ToState(), x: mob.X, y: mob.Y})
 		}
 	}
 	g.bcastAsteroids = g.bcastAsteroids[:0]
@@ -577,10 +898,7 @@ func (g *Game) broadcastState() {
 		g.bcastProjs = append(g.bcastProjs, projWithPos{state: proj.ToState(), x: proj.X, y: proj.Y})
 	}
 
-	// Viewport culling radius (half-viewport + margin)
-	const cullDist = 1200.0
-
-	// Cache marshaled data per player to reuse for controllers
+	// Cache marshaled data per player to reuse for controllers and coaches
 	playerData := make(map[string][]byte, len(g.clients))
 
 	for playerID, client := range g.clients {
@@ -588,14 +906,16 @@ func (g *Game) broadcastState() {
 		if !ok {
 			continue
 		}
-		px, py := player.X, player.Y
+		px, py := g.cullCenter(player)
+		saver := g.dataSaver[playerID]
+		cullX, cullY := g.interestRadius(playerID, saver)
 
 		// Filter all entity types by viewport distance
 		g.filtPlayers = g.filtPlayers[:0]
 		for _, p := range g.bcastPlayers {
 			dx := p.x - px; if dx < 0 { dx = -dx }
 			dy := p.y - py; if dy < 0 { dy = -dy }
-			if dx <= cullDist && dy <= cullDist {
+			if dx <= cullX && dy <= cullY {
 				g.filtPlayers = append(g.filtPlayers, p.state)
 			}
 		}
@@ -603,7 +923,7 @@ func (g *Game) broadcastState() {
 		for _, p := range g.bcastProjs {
 			dx := p.x - px; if dx < 0 { dx = -dx }
 			dy := p.y - py; if dy < 0 { dy = -dy }
-			if dx <= cullDist && dy <= cullDist {
+			if dx <= cullX && dy <= cullY {
 				g.filtProjs = append(g.filtProjs, p.state)
 			}
 		}
@@ -611,7 +931,7 @@ func (g *Game) broadcastState() {
 		for _, m := range g.bcastMobs {
 			dx := m.x - px; if dx < 0 { dx = -dx }
 			dy := m.y - py; if dy < 0 { dy = -dy }
-			if dx <= cullDist && dy <= cullDist {
+			if dx <= cullX && dy <= cullY {
 				g.filtMobs = append(g.filtMobs, m.state)
 			}
 		}
@@ -619,7 +939,7 @@ func (g *Game) broadcastState() {
 		for _, a := range g.bcastAsteroids {
 			dx := a.x - px; if dx < 0 { dx = -dx }
 			dy := a.y - py; if dy < 0 { dy = -dy }
-			if dx <= cullDist && dy <= cullDist {
+			if dx <= cullX && dy <= cullY {
 				g.filtAsteroids = append(g.filtAsteroids, a.state)
 			}
 		}
@@ -627,7 +947,7 @@ func (g *Game) broadcastState() {
 		for _, pk := range g.bcastPickups {
 			dx := pk.x - px; if dx < 0 { dx = -dx }
 			dy := pk.y - py; if dy < 0 { dy = -dy }
-			if dx <= cullDist && dy <= cullDist {
+			if dx <= cullX && dy <= cullY {
 				g.filtPickups = append(g.filtPickups, pk.state)
 			}
 		}
@@ -639,13 +959,34 @@ func (g *Game) broadcastState() {
 			Asteroids:   g.filtAsteroids,
 			Pickups:     g.filtPickups,
 			Tick:        g.tick,
+			Ack:         player.InputSeq,
+		}
+
+		// Controllers, gunners and coaches join mid-stream, so they get full snapshots
+		_, hasController := g.controllers[playerID]
+		_, hasGunner := g.gunners[playerID]
+		_, hasCoach := g.coaches[playerID]
+		if hasController || hasGunner || hasCoach {
+			data, err := msgpack.Marshal(&state)
+			if err != nil {
+				continue
+			}
+			playerData[playerID] = data
+		}
+		if saver && g.tick%(BroadcastEvery*SaverBroadcastDivisor) != 0 {
+			continue
 		}
 
+		tracker := g.deltas[playerID]
+		if tracker == nil {
+			tracker = newDeltaTracker()
+			g.deltas[playerID] = tracker
+		}
+		tracker.apply(&state)
 		data, err := msgpack.Marshal(&state)
 		if err != nil {
 			continue
 		}
-		playerData[playerID] = data
 		client.SendBinary(data)
 	}
 
@@ -691,9 +1032,54 @@ func (g *Game) broadcastState() {
 		}
 		client.SendBinary(data)
 	}
+
+	// Gunners and coaches see exactly what their player sees
+	for playerID, gunner := range g.gunners {
+		if data, ok := playerData[playerID]; ok {
+			gunner.SendBinary(data)
+		}
+	}
+	for playerID, coach := range g.coaches {
+		if data, ok := playerData[playerID]; ok {
+			coach.SendBinary(data)
+		}
+	}
+}
+
+// broadcastPings sends everyone the ping of each connected player
+func (g *Game) broadcastPings() {
+	pings := make(map[uint32]int, len(g.players))
+	for id, client := range g.clients {
+		p, ok := g.players[id]
+		if !ok {
+			continue
+		}
+		if rtt := client.RTT(); rtt > 0 {
+			pings[p.NetID] = int(rtt.Milliseconds())
+		}
+	}
+	if len(pings) > 0 {
+		g.broadcastMsg(Envelope{T: MsgPings, Data: PingsMsg{Pings: pings}})
+	}
+}
+
+// creditAssist gives an assist to the last other player to hit victim, if
+// recent enough, and names them in the kill message
+func (g *Game) creditAssist(victim *Player, kill *KillMsg) {
+	id := victim.Assister(kill.KillerID, g.tick)
+	if id == 0 {
+		return
+	}
+	for _, p := range g.players {
+		if p.NetID == id {
+			p.Assists++
+			kill.AssistID, kill.AssistName = p.NetID, p.Name
+			return
+		}
+	}
 }
 
-// broadcastMsg sends a message to all clients and controllers in the session
+// broadcastMsg sends a message to all clients, controllers, gunners and coaches in the session
 func (g *Game) broadcastMsg(msg Envelope) {
 	data, err := json.Marshal(msg)
 	if err != nil {
@@ -705,6 +1091,12 @@ func (g *Game) broadcastMsg(msg Envelope) {
 	for _, client := range g.controllers {
 		client.SendRaw(data)
 	}
+	for _, client := range g.gunners {
+		client.SendRaw(data)
+	}
+	for _, client := range g.coaches {
+		client.SendRaw(data)
+	}
 }
 
 // checkMobMobCollisions applies soft repulsion between mobs and kills both if relative velocity is high
@@ -736,23 +1128,25 @@ func (g *Game) checkMobMobCollisions() {
 					// Crash phrases
 					phraseA := pickPhraseAlways("mob_crash")
 					g.broadcastMsg(Envelope{T: MsgMobSay, Data: MobSayMsg{
-						MobID: a.ID, Text: phraseA,
+						MobID: a.NetID, Text: phraseA,
 					}})
 					phraseB := pickPhraseAlways("mob_crash")
 					g.broadcastMsg(Envelope{T: MsgMobSay, Data: MobSayMsg{
-						MobID: b.ID, Text: phraseB,
+						MobID: b.NetID, Text: phraseB,
 					}})
 					// Both explode
 					a.Alive = false
 					b.Alive = false
 					// Broadcast explosions
 					g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-						KillerID: a.ID, KillerName: "Mob",
-						VictimID: b.ID, VictimName: "Mob",
+						KillerID: a.NetID, KillerName: "Mob",
+						VictimID: b.NetID, VictimName: "Mob",
+						Cause: CauseRam,
 					}})
 					g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-						KillerID: b.ID, KillerName: "Mob",
-						VictimID: a.ID, VictimName: "Mob",
+						KillerID: b.NetID, KillerName: "Mob",
+						VictimID: a.NetID, VictimName: "Mob",
+						Cause: CauseRam,
 					}})
 					continue
 				}
@@ -787,13 +1181,14 @@ func (g *Game) checkProjectileMobCollisions() {
 				continue
 			}
 			if CheckCollision(proj.X, proj.Y, ProjectileRadius, mob.X, mob.Y, mob.Radius) {
-				died := mob.TakeDamage(proj.Damage)
+				dmg, crit := ShotDamage(proj, mob.Rotation)
+				died := mob.TakeDamage(dmg)
 				proj.Alive = false
 
 				// Broadcast hit event
 				g.broadcastMsg(Envelope{T: MsgHit, Data: HitMsg{
-					X: mob.X, Y: mob.Y, Dmg: proj.Damage,
-					VictimID: mob.ID, AttackerID: proj.OwnerID,
+					X: mob.X, Y: mob.Y, Dmg: dmg,
+					VictimID: mob.NetID, AttackerID: proj.OwnerNet, Crit: crit,
 				}})
 
 				if died {
@@ -805,8 +1200,9 @@ func (g *Game) checkProjectileMobCollisions() {
 						killerName = "Mob"
 					}
 					g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-						KillerID: proj.OwnerID, KillerName: killerName,
-						VictimID: mob.ID, VictimName: "Mob",
+						KillerID: proj.OwnerNet, KillerName: killerName,
+						VictimID: mob.NetID, VictimName: "Mob",
+						Cause: CauseLaser,
 					}})
 				}
 				break
@@ -836,17 +1232,22 @@ func (g *Game) checkAsteroidPlayerCollisions() {
 				died := p.TakeDamage(dmg)
 				g.broadcastMsg(Envelope{T: MsgHit, Data: HitMsg{
 					X: p.X, Y: p.Y, Dmg: dmg,
-					VictimID: p.ID, AttackerID: "asteroid",
+					VictimID: p.NetID, AttackerID: 0,
 				}})
 				if died {
 					p.Score -= DeathScorePenalty
-					g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-						KillerID: "asteroid", KillerName: "Asteroid",
-						VictimID: p.ID, VictimName: p.Name,
-					}})
+					p.Deaths++
+					kill := KillMsg{
+						KillerID: 0, KillerName: "Asteroid",
+						VictimID: p.NetID, VictimName: p.Name,
+						Cause: CauseAsteroid,
+					}
+					g.creditAssist(p, &kill)
+					g.broadcastMsg(Envelope{T: MsgKill, Data: kill})
 					if client, ok := g.clients[p.ID]; ok {
 						client.SendJSON(Envelope{T: MsgDeath, Data: DeathMsg{
-							KillerID: "asteroid", KillerName: "Asteroid",
+							KillerID: 0, KillerName: "Asteroid",
+							RespawnIn: p.RespawnT,
 						}})
 					}
 				}
@@ -875,19 +1276,21 @@ func (g *Game) checkAsteroidMobCollisions() {
 				// Mob phrase before dying
 				phrase := pickPhraseAlways("asteroid_death")
 				g.broadcastMsg(Envelope{T: MsgMobSay, Data: MobSayMsg{
-					MobID: mob.ID, Text: phrase,
+					MobID: mob.NetID, Text: phrase,
 				}})
 				mob.Alive = false
 				g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-					KillerID: "asteroid", KillerName: "Asteroid",
-					VictimID: mob.ID, VictimName: "Mob",
+					KillerID: 0, KillerName: "Asteroid",
+					VictimID: mob.NetID, VictimName: "Mob",
+					Cause: CauseAsteroid,
 				}})
 			}
 		}
 	}
 }
 
-// checkProjectileAsteroidCollisions — projectiles are destroyed by asteroids
+// checkProjectileAsteroidCollisions — projectiles are destroyed by asteroids,
+// chipping them until they break apart
 func (g *Game) checkProjectileAsteroidCollisions() {
 	const queryR = ProjectileRadius + AsteroidRadius
 	for _, proj := range g.flatProjs {
@@ -905,6 +1308,11 @@ func (g *Game) checkProjectileAsteroidCollisions() {
 			}
 			if CheckCollision(proj.X, proj.Y, ProjectileRadius, ast.X, ast.Y, AsteroidRadius) {
 				proj.Alive = false
+				ast.TakeDamage(proj.Damage)
+				g.broadcastMsg(Envelope{T: MsgHit, Data: HitMsg{
+					X: ast.X, Y: ast.Y, Dmg: proj.Damage,
+					VictimID: ast.NetID, AttackerID: proj.OwnerNet,
+				}})
 				break
 			}
 		}
@@ -965,25 +1373,31 @@ func (g *Game) checkPlayerMobCollisions() {
 				// Broadcast hit on player from mob collision
 				g.broadcastMsg(Envelope{T: MsgHit, Data: HitMsg{
 					X: p.X, Y: p.Y, Dmg: mob.CollisionDmg,
-					VictimID: p.ID, AttackerID: mob.ID,
+					VictimID: p.NetID, AttackerID: mob.NetID,
 				}})
 
 				// Player gets kill credit for the mob
 				p.Score += MobKillScore
 				g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-					KillerID: p.ID, KillerName: p.Name,
-					VictimID: mob.ID, VictimName: "Mob",
+					KillerID: p.NetID, KillerName: p.Name,
+					VictimID: mob.NetID, VictimName: "Mob",
+					Cause: CauseRam,
 				}})
 
 				if died {
 					p.Score -= DeathScorePenalty
-					g.broadcastMsg(Envelope{T: MsgKill, Data: KillMsg{
-						KillerID: mob.ID, KillerName: "Mob",
-						VictimID: p.ID, VictimName: p.Name,
-					}})
+					p.Deaths++
+					kill := KillMsg{
+						KillerID: mob.NetID, KillerName: "Mob",
+						VictimID: p.NetID, VictimName: p.Name,
+						Cause: CauseRam,
+					}
+					g.creditAssist(p, &kill)
+					g.broadcastMsg(Envelope{T: MsgKill, Data: kill})
 					if client, ok := g.clients[p.ID]; ok {
 						client.SendJSON(Envelope{T: MsgDeath, Data: DeathMsg{
-							KillerID: mob.ID, KillerName: "Mob",
+							KillerID: mob.NetID, KillerName: "Mob",
+							RespawnIn: p.RespawnT,
 						}})
 					}
 				}
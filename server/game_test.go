@@ -1,8 +1,13 @@
 package main
 
 import (
+	"encoding/json"
+	"math"
 	"sync"
 	"testing"
+	"time"
+
+	"github.com/vmihailenco/msgpack/v5"
 )
 
 // mockBroadcaster captures sent messages for testing
@@ -10,6 +15,7 @@ type mockBroadcaster struct {
 	mu       sync.Mutex
 	messages []interface{}
 	rawMsgs  [][]byte
+	rtt      time.Duration
 }
 
 func (m *mockBroadcaster) SendJSON(msg interface{}) {
@@ -30,6 +36,10 @@ func (m *mockBroadcaster) SendBinary(data []byte) {
 	m.rawMsgs = append(m.rawMsgs, data)
 }
 
+func (m *mockBroadcaster) RTT() time.Duration {
+	return m.rtt
+}
+
 func TestGameAddRemovePlayer(t *testing.T) {
 	g := NewGame()
 	p := g.AddPlayer("TestPilot")
@@ -46,6 +56,38 @@ func TestGameAddRemovePlayer(t *testing.T) {
 	}
 }
 
+func TestGameRemoveIfDetachedSince(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayer("Flaky")
+	client := &mockBroadcaster{}
+	g.SetClient(p.ID, client)
+
+	at, ok := g.DetachClient(p.ID, client)
+	if !ok {
+		t.Fatal("expected the client to detach")
+	}
+	if g.ResumePlayer(p.ID, p.ResumeToken, &mockBroadcaster{}) == nil {
+		t.Fatal("expected the player to resume")
+	}
+	if g.RemoveIfDetachedSince(p.ID, at) {
+		t.Error("a resumed player should not be removed by the old grace timer")
+	}
+	if g.PlayerCount() != 1 {
+		t.Errorf("expected 1 player, got %d", g.PlayerCount())
+	}
+
+	again, _ := g.DetachClient(p.ID, g.clients[p.ID])
+	if g.RemoveIfDetachedSince(p.ID, at) {
+		t.Error("a stale detach time should not remove the player")
+	}
+	if !g.RemoveIfDetachedSince(p.ID, again) {
+		t.Error("expected the player to be removed after the grace period")
+	}
+	if g.PlayerCount() != 0 {
+		t.Errorf("expected 0 players, got %d", g.PlayerCount())
+	}
+}
+
 func TestGameShipTypeRotation(t *testing.T) {
 	g := NewGame()
 	p1 := g.AddPlayer("A")
@@ -61,6 +103,34 @@ func TestGameShipTypeRotation(t *testing.T) {
 	}
 }
 
+func TestGameShipPick(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayerShip("A", 2)
+	if p.ShipType != 2 {
+		t.Errorf("expected picked ship 2, got %d", p.ShipType)
+	}
+	if q := g.AddPlayerShip("B", 4); q.ShipType != 0 {
+		t.Errorf("mob hull should fall back to rotation, got %d", q.ShipType)
+	}
+
+	// A pick while alive waits for the next spawn
+	g.PickShip(p.ID, 1)
+	if p.ShipType != 2 {
+		t.Errorf("ship should not change mid-life, got %d", p.ShipType)
+	}
+	p.TakeDamage(PlayerMaxHP)
+	p.Respawn()
+	if p.ShipType != 1 {
+		t.Errorf("expected ship 1 after respawn, got %d", p.ShipType)
+	}
+
+	g.PickShip(p.ID, 5)
+	p.Respawn()
+	if p.ShipType != 1 {
+		t.Errorf("invalid pick should be ignored, got %d", p.ShipType)
+	}
+}
+
 func TestGameHandleInput(t *testing.T) {
 	g := NewGame()
 	p := g.AddPlayer("Test")
@@ -117,3 +187,429 @@ func TestGameProjectileCreation(t *testing.T) {
 		t.Errorf("expected 1 projectile, got %d", projCount)
 	}
 }
+
+func TestGameCoachRelay(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayer("Student")
+
+	player := &mockBroadcaster{}
+	coach := &mockBroadcaster{}
+	g.SetClient(p.ID, player)
+	if _, ok := g.SetCoach(p.ID, p.CoachKey, coach); !ok {
+		t.Fatal("coach with the right key should attach")
+	}
+
+	if len(player.messages) != 1 || player.messages[0].(Envelope).T != MsgCoachOn {
+		t.Fatal("player should be notified when a coach attaches")
+	}
+
+	g.RelayView(p.ID, ViewMsg{VW: 1400, VH: 800, MX: 10, MY: 20, Fire: true})
+	if len(coach.messages) != 1 || coach.messages[0].(Envelope).T != MsgView {
+		t.Fatal("coach should receive the player's view")
+	}
+
+	g.RelayMark(p.ID, MarkMsg{Kind: "ping", Pts: []float64{100, 200}})
+	if len(player.messages) != 2 || player.messages[1].(Envelope).T != MsgMark {
+		t.Fatal("player should receive the coach's mark")
+	}
+
+	// Coach gets the same per-player snapshot every broadcast
+	for i := 0; i < BroadcastEvery; i++ {
+		g.update()
+	}
+	if len(coach.rawMsgs) == 0 {
+		t.Error("coach should receive state broadcasts")
+	}
+
+	// A stale coach detaching must not remove its replacement
+	g.RemoveCoach(p.ID, &mockBroadcaster{})
+	g.RemoveCoach(p.ID, coach)
+	if len(player.messages) != 3 || player.messages[2].(Envelope).T != MsgCoachOff {
+		t.Error("player should be notified when the coach leaves")
+	}
+}
+
+func TestGameCoachNeedsKey(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayer("Student")
+	player := &mockBroadcaster{}
+	g.SetClient(p.ID, player)
+
+	for _, key := range []string{"", "guess", p.ResumeToken} {
+		if _, ok := g.SetCoach(p.ID, key, &mockBroadcaster{}); ok {
+			t.Errorf("key %q should not attach a coach", key)
+		}
+	}
+	if len(player.messages) != 0 {
+		t.Error("player should not hear about rejected coaches")
+	}
+}
+
+func TestGameCoachReplacedIsNotified(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayer("Student")
+	first, second := &mockBroadcaster{}, &mockBroadcaster{}
+	g.SetCoach(p.ID, p.CoachKey, first)
+	g.SetCoach(p.ID, p.CoachKey, second)
+
+	if len(first.messages) != 1 || first.messages[0].(Envelope).T != MsgCoachOff {
+		t.Error("the replaced coach should be told it was detached")
+	}
+	if len(second.messages) != 0 {
+		t.Error("the new coach should not get coach_off")
+	}
+}
+
+func TestGameGunnerInput(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayer("Pilot")
+	gunner := &mockBroadcaster{}
+	g.SetGunner(p.ID, gunner)
+
+	// The pilot's trigger is ignored while a gunner is attached
+	g.HandleInput(p.ID, ClientInput{MX: p.X + 100, MY: p.Y, Fire: true})
+	if p.Firing {
+		t.Fatal("pilot should not fire while a gunner is attached")
+	}
+
+	// The gunner aims straight down and fires there, whatever way the ship faces
+	g.HandleGunnerInput(p.ID, ClientInput{MX: p.X, MY: p.Y + 100, Fire: true})
+	if !p.Firing {
+		t.Fatal("gunner should fire")
+	}
+	proj := NewProjectile(p)
+	if math.Abs(proj.Rotation-math.Pi/2) > 1e-9 {
+		t.Errorf("shot should follow the gunner's aim, got rotation %v", proj.Rotation)
+	}
+
+	// A stale gunner detaching must not remove its replacement
+	g.RemoveGunner(p.ID, &mockBroadcaster{})
+	if !p.Gunner {
+		t.Fatal("stale gunner should not detach the current one")
+	}
+	g.RemoveGunner(p.ID, gunner)
+	if p.Gunner || p.Firing {
+		t.Error("trigger should go back to the pilot when the gunner leaves")
+	}
+}
+
+func TestGamePlaceMarker(t *testing.T) {
+	g := NewGame()
+	a := g.AddPlayer("Leader")
+	b := g.AddPlayer("Wingman")
+	ca, cb := &mockBroadcaster{}, &mockBroadcaster{}
+	g.SetClient(a.ID, ca)
+	g.SetClient(b.ID, cb)
+
+	g.PlaceMarker(a.ID, MarkerMsg{X: 500, Y: -20})
+	if len(ca.rawMsgs) != 1 || len(cb.rawMsgs) != 1 {
+		t.Fatal("marker should reach everyone in the session")
+	}
+	var env struct {
+		T string    `json:"t"`
+		D MarkerMsg `json:"d"`
+	}
+	if err := json.Unmarshal(cb.rawMsgs[0], &env); err != nil {
+		t.Fatal(err)
+	}
+	if env.T != MsgMarker || env.D.Name != "Leader" || env.D.ID != a.ID {
+		t.Errorf("unexpected marker %+v", env)
+	}
+	if env.D.X != 500 || env.D.Y != 0 {
+		t.Errorf("marker should be clamped to the world, got %v,%v", env.D.X, env.D.Y)
+	}
+
+	// A controller marks the ship's aim point
+	g.HandleInput(a.ID, ClientInput{MX: 1234, MY: 2345})
+	g.PlaceMarker(a.ID, MarkerMsg{Aim: true})
+	if err := json.Unmarshal(cb.rawMsgs[1], &env); err != nil {
+		t.Fatal(err)
+	}
+	if env.D.X != 1234 || env.D.Y != 2345 {
+		t.Errorf("aim marker should be at the target, got %v,%v", env.D.X, env.D.Y)
+	}
+}
+
+func TestGameNamesTable(t *testing.T) {
+	g := NewGame()
+	a := g.AddPlayer("Leader")
+	ca := &mockBroadcaster{}
+	g.SetClient(a.ID, ca)
+
+	b := g.AddPlayer("Wingman")
+	if a.NetID == 0 || b.NetID == 0 || a.NetID == b.NetID {
+		t.Fatalf("expected distinct non-zero NetIDs, got %d and %d", a.NetID, b.NetID)
+	}
+	if len(ca.rawMsgs) != 1 {
+		t.Fatalf("existing players should get the names table on a join, got %d messages", len(ca.rawMsgs))
+	}
+	var env struct {
+		T string   `json:"t"`
+		D NamesMsg `json:"d"`
+	}
+	if err := json.Unmarshal(ca.rawMsgs[0], &env); err != nil {
+		t.Fatal(err)
+	}
+	if env.T != MsgNames || env.D.Names[a.NetID] != "Leader" || env.D.Names[b.NetID] != "Wingman" {
+		t.Errorf("unexpected names table %+v", env)
+	}
+}
+
+func TestGameDataSaverReducesBroadcasts(t *testing.T) {
+	g := NewGame()
+	normal := g.AddPlayer("Normal")
+	saver := g.AddPlayer("Saver")
+
+	mockNormal := &mockBroadcaster{}
+	mockSaver := &mockBroadcaster{}
+	g.SetClient(normal.ID, mockNormal)
+	g.SetClient(saver.ID, mockSaver)
+	g.SetNetPrefs(saver.ID, true)
+
+	for i := 0; i < BroadcastEvery*SaverBroadcastDivisor; i++ {
+		g.update()
+	}
+
+	if len(mockNormal.rawMsgs) != SaverBroadcastDivisor {
+		t.Errorf("expected %d snapshots for normal client, got %d", SaverBroadcastDivisor, len(mockNormal.rawMsgs))
+	}
+	if len(mockSaver.rawMsgs) != 1 {
+		t.Errorf("expected 1 snapshot for data saver client, got %d", len(mockSaver.rawMsgs))
+	}
+}
+
+func TestGameInputAck(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayer("Acker")
+	mock := &mockBroadcaster{}
+	g.SetClient(p.ID, mock)
+
+	g.HandleInput(p.ID, ClientInput{MX: p.X + 100, MY: p.Y, Seq: 42})
+	for i := 0; i < BroadcastEvery; i++ {
+		g.update()
+	}
+
+	if len(mock.rawMsgs) == 0 {
+		t.Fatal("expected a state snapshot")
+	}
+	var gs GameState
+	if err := msgpack.Unmarshal(mock.rawMsgs[len(mock.rawMsgs)-1], &gs); err != nil {
+		t.Fatalf("unmarshal state: %v", err)
+	}
+	if gs.Ack != 42 {
+		t.Errorf("expected ack 42, got %d", gs.Ack)
+	}
+}
+
+func TestGameDeltaSnapshots(t *testing.T) {
+	g := NewGame()
+	p := g.AddPlayer("Still")
+	mock := &mockBroadcaster{}
+	g.SetClient(p.ID, mock)
+	// Aim at the ship itself so it stays put inside the dead zone
+	g.HandleInput(p.ID, ClientInput{MX: p.X, MY: p.Y})
+
+	decode := func(i int) GameState {
+		t.Helper()
+		var gs GameState
+		if err := msgpack.Unmarshal(mock.rawMsgs[i], &gs); err != nil {
+			t.Fatalf("unmarshal state: %v", err)
+		}
+		return gs
+	}
+
+	for i := 0; i < BroadcastEvery*2; i++ {
+		g.update()
+	}
+	if len(mock.rawMsgs) != 2 {
+		t.Fatalf("expected 2 snapshots, got %d", len(mock.rawMsgs))
+	}
+	if first := decode(0); first.Delta || len(first.Players) != 1 {
+		t.Fatalf("first snapshot should be a full keyframe with the player, got delta=%v players=%d", first.Delta, len(first.Players))
+	}
+	// An idle ship doesn't change between snapshots, so the delta omits it
+	if second := decode(1); !second.Delta || len(second.Players) != 0 {
+		t.Errorf("expected empty delta, got delta=%v players=%d", second.Delta, len(second.Players))
+	}
+
+	// Entities that vanish are reported in the removal list
+	ast := NewAsteroid()
+	g.mu.Lock()
+	ast.X, ast.Y, ast.VX, ast.VY = p.X+300, p.Y, 0, 0
+	g.asteroids[ast.ID] = ast
+	g.mu.Unlock()
+	for i := 0; i < BroadcastEvery; i++ {
+		g.update()
+	}
+	g.mu.Lock()
+	delete(g.asteroids, ast.ID)
+	g.mu.Unlock()
+	for i := 0; i < BroadcastEvery; i++ {
+		g.update()
+	}
+	last := decode(len(mock.rawMsgs) - 1)
+	found := false
+	for _, id := range last.Removed {
+		if id == ast.NetID {
+			found = true
+		}
+	}
+	if !found {
+		t.Errorf("expected asteroid %d in removal list, got %v", ast.NetID, last.Removed)
+	}
+}
+
+// deltaSend runs one snapshot holding a single ship through tr and returns
+// what the client got for it, or nil if the ship was left out
+func deltaSend(tr *deltaTracker, id uint32, x, vx float64) *PlayerState {
+	vy := 0.0
+	st := GameState{Players: []PlayerState{{ID: id, X: x, VX: &vx, VY: &vy, Alive: true}}}
+	tr.apply(&st)
+	if len(st.Players) == 0 {
+		return nil
+	}
+	return &st.Players[0]
+}
+
+func TestDeltaVelocityTrackedPerClient(t *testing.T) {
+	tr := newDeltaTracker()
+	if p := deltaSend(tr, 1, 0, 0); p == nil || p.VX == nil {
+		t.Fatal("keyframe should carry the velocity")
+	}
+	if p := deltaSend(tr, 1, 1, 2); p == nil || p.VX != nil {
+		t.Error("a small velocity change should be left out")
+	}
+	// Whatever other clients were sent in between, this one still has 0
+	if p := deltaSend(tr, 1, 2, 4); p == nil || p.VX != nil {
+		t.Error("velocity still within VelocityDelta of what this client has")
+	}
+	if p := deltaSend(tr, 1, 3, 100); p == nil || p.VX == nil || *p.VX != 100 {
+		t.Error("a big change against this client's last velocity must be sent")
+	}
+}
+
+func TestDeltaReentryCarriesVelocity(t *testing.T) {
+	tr := newDeltaTracker()
+	deltaSend(tr, 1, 0, 100)
+	// Culled out of view for a snapshot, then back with the same velocity
+	tr.apply(&GameState{})
+	if p := deltaSend(tr, 1, 500, 100); p == nil || p.VX == nil || *p.VX != 100 {
+		t.Error("a ship re-entering the view must arrive with its velocity")
+	}
+}
+
+func TestGameViewportCulling(t *testing.T) {
+	g := NewGame()
+	viewer := g.AddPlayer("Viewer")
+	other := g.AddPlayer("Other")
+	mock := &mockBroadcaster{}
+	g.SetClient(viewer.ID, mock)
+
+	g.mu.Lock()
+	viewer.X, viewer.Y = 1000, 1000
+	other.X, other.Y = 1900, 1000
+	g.mu.Unlock()
+
+	lastPlayers := func() int {
+		t.Helper()
+		var gs GameState
+		if err := msgpack.Unmarshal(mock.rawMsgs[len(mock.rawMsgs)-1], &gs); err != nil {
+			t.Fatalf("unmarshal state: %v", err)
+		}
+		return len(gs.Players)
+	}
+
+	for i := 0; i < BroadcastEvery; i++ {
+		g.update()
+	}
+	if n := lastPlayers(); n != 2 {
+		t.Fatalf("default culling should include a ship 900 away, got %d players", n)
+	}
+
+	// A small screen only needs entities around its own view
+	g.SetViewport(viewer.ID, 800, 600)
+	g.mu.Lock()
+	delete(g.deltas, viewer.ID) // force a keyframe so the count is absolute
+	g.mu.Unlock()
+	for i := 0; i < BroadcastEvery; i++ {
+		g.update()
+	}
+	if n := lastPlayers(); n != 1 {
+		t.Errorf("ship outside the reported viewport should be culled, got %d players", n)
+	}
+}
+
+func TestGameSpectateMovesCulling(t *testing.T) {
+	g := NewGame()
+	viewer := g.AddPlayer("Viewer")
+	killer := g.AddPlayer("Killer")
+	mock := &mockBroadcaster{}
+	g.SetClient(viewer.ID, mock)
+	g.SetViewport(viewer.ID, 800, 600)
+
+	g.mu.Lock()
+	viewer.X, viewer.Y = 1000, 1000
+	viewer.Alive = false
+	viewer.RespawnT = 60
+	killer.X, killer.Y = 3000, 1000
+	g.mu.Unlock()
+
+	hasKiller := func() bool {
+		t.Helper()
+		g.mu.Lock()
+		delete(g.deltas, viewer.ID) // force a keyframe
+		g.mu.Unlock()
+		for i := 0; i < BroadcastEvery; i++ {
+			g.update()
+		}
+		var gs GameState
+		if err := msgpack.Unmarshal(mock.rawMsgs[len(mock.rawMsgs)-1], &gs); err != nil {
+			t.Fatalf("unmarshal state: %v", err)
+		}
+		for _, p := range gs.Players {
+			if p.ID == killer.NetID {
+				return true
+			}
+		}
+		return false
+	}
+
+	if hasKiller() {
+		t.Fatal("killer 2000 away should be culled around the wreck")
+	}
+	g.SetSpectate(viewer.ID, killer.NetID)
+	if !hasKiller() {
+		t.Error("snapshots should follow the death cam's target")
+	}
+	g.SetSpectate(viewer.ID, 0)
+	if hasKiller() {
+		t.Error("clearing the target should center culling on the player again")
+	}
+}
+
+func TestGameBroadcastPings(t *testing.T) {
+	g := NewGame()
+	p1 := g.AddPlayer("A")
+	p2 := g.AddPlayer("B")
+	mock1 := &mockBroadcaster{rtt: 45 * time.Millisecond}
+	mock2 := &mockBroadcaster{} // no pong yet
+	g.SetClient(p1.ID, mock1)
+	g.SetClient(p2.ID, mock2)
+
+	for i := 0; i < PingsEvery; i++ {
+		g.update()
+	}
+	var pings *PingsMsg
+	for _, m := range mock2.messages {
+		if env, ok := m.(Envelope); ok && env.T == MsgPings {
+			msg := env.Data.(PingsMsg)
+			pings = &msg
+		}
+	}
+	if pings == nil {
+		t.Fatal("expected a ping table broadcast")
+	}
+	if len(pings.Pings) != 1 || pings.Pings[p1.NetID] != 45 {
+		t.Errorf("expected only A at 45ms, got %v", pings.Pings)
+	}
+}
@@ -79,13 +79,24 @@ func (h *Hub) Run() {
 			h.mu.Unlock()
 			// Remove from session if in one
 			if client.sessionID != "" {
-				if client.isController {
+				if client.isGunner {
+					sess := h.sessions.GetSession(client.sessionID)
+					if sess != nil {
+						sess.Game.RemoveGunner(client.playerID, client)
+					}
+				} else if client.isController {
 					sess := h.sessions.GetSession(client.sessionID)
 					if sess != nil {
 						sess.Game.RemoveController(client.playerID)
 					}
+				} else if client.isCoach {
+					sess := h.sessions.GetSession(client.sessionID)
+					if sess != nil {
+						sess.Game.RemoveCoach(client.playerID, client)
+					}
 				} else {
-					h.sessions.RemovePlayer(client.sessionID, client.playerID)
+					// Keep the ship around briefly in case the client reconnects
+					h.sessions.DetachPlayer(client.sessionID, client.playerID, client)
 				}
 			}
 		}
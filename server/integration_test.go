@@ -26,6 +26,8 @@ func startTestServer(t *testing.T) (*httptest.Server, string, func()) {
 
 	prevIdleTimeout := SessionIdleTimeout
 	SessionIdleTimeout = 150 * time.Millisecond
+	prevResumeGrace := ResumeGrace
+	ResumeGrace = 100 * time.Millisecond
 
 	// Create a temp client dir with a minimal index.html
 	tmpDir := t.TempDir()
@@ -33,6 +35,8 @@ func startTestServer(t *testing.T) (*httptest.Server, string, func()) {
 	os.MkdirAll(jsDir, 0o755)
 	os.WriteFile(filepath.Join(tmpDir, "index.html"), []byte("<html>test</html>"), 0o644)
 	os.WriteFile(filepath.Join(jsDir, "main.js"), []byte("// test"), 0o644)
+	os.MkdirAll(filepath.Join(tmpDir, "controller"), 0o755)
+	os.WriteFile(filepath.Join(tmpDir, "controller", "index.html"), []byte("<html>controller</html>"), 0o644)
 
 	hub := NewHub()
 	go hub.Run()
@@ -44,6 +48,7 @@ func startTestServer(t *testing.T) (*httptest.Server, string, func()) {
 
 	return srv, wsURL, func() {
 		SessionIdleTimeout = prevIdleTimeout
+		ResumeGrace = prevResumeGrace
 		srv.Close()
 	}
 }
@@ -191,6 +196,29 @@ func TestSPARoutingUUIDPath(t *testing.T) {
 	}
 }
 
+func TestSPARoutingControllerLink(t *testing.T) {
+	srv, _, cleanup := startTestServer(t)
+	defer cleanup()
+
+	uuid := GenerateUUID()
+	resp, err := http.Get(srv.URL + "/" + uuid + "?c=" + GenerateUUID())
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != 200 {
+		t.Errorf("GET controller link status = %d, want 200", resp.StatusCode)
+	}
+	// Should serve the slim controller page, not the game client
+	buf := make([]byte, 100)
+	n, _ := resp.Body.Read(buf)
+	body := string(buf[:n])
+	if !strings.Contains(body, "controller") {
+		t.Errorf("controller link should serve controller/index.html, got %q", body)
+	}
+}
+
 func TestSPARoutingStaticFiles(t *testing.T) {
 	srv, _, cleanup := startTestServer(t)
 	defer cleanup()
@@ -537,6 +565,59 @@ func TestMultiplePlayersInSession(t *testing.T) {
 	}
 }
 
+func TestSecondJoinOrCreateIsRejected(t *testing.T) {
+	srv, wsURL, cleanup := startTestServer(t)
+	_ = srv
+	defer cleanup()
+
+	c1 := dialWS(t, wsURL)
+	defer c1.Close()
+	sid := createAndJoin(t, c1, "Alpha", "OnceOnly")
+
+	sendMsg(t, c1, "join", map[string]string{"name": "Alpha", "sid": sid})
+	sendMsg(t, c1, "create", map[string]string{"name": "Alpha", "sname": "Another"})
+	time.Sleep(50 * time.Millisecond)
+
+	c2 := dialWS(t, wsURL)
+	defer c2.Close()
+	sendMsg(t, c2, "check", map[string]string{"sid": sid})
+	if n := dataMap(t, readEnvelope(t, c2))["players"].(float64); n != 1 {
+		t.Errorf("expected 1 player after a repeated join, got %v", n)
+	}
+	sendMsg(t, c2, "list", nil)
+	if list := readEnvelope(t, c2); len(list.Data.([]interface{})) != 1 {
+		t.Errorf("expected one session after a repeated create, got %v", list.Data)
+	}
+}
+
+func TestPlayerCannotBecomeCoach(t *testing.T) {
+	srv, wsURL, cleanup := startTestServer(t)
+	_ = srv
+	defer cleanup()
+
+	c := dialWS(t, wsURL)
+	defer c.Close()
+	sendMsg(t, c, "create", map[string]string{"name": "Alpha", "sname": "Coached"})
+	sid := dataMap(t, readEnvelope(t, c))["sid"].(string)
+	sendMsg(t, c, "join", map[string]string{"name": "Alpha", "sid": sid})
+	if joined := readEnvelope(t, c); joined.T != MsgJoined {
+		t.Fatalf("expected joined, got %s", joined.T)
+	}
+	welcome := dataMap(t, readEnvelope(t, c))
+
+	sendMsg(t, c, "coach", map[string]string{"sid": sid, "pid": welcome["id"].(string), "ck": welcome["ck"].(string)})
+	for i := 0; i < 50; i++ {
+		env := readEnvelope(t, c)
+		switch env.T {
+		case MsgError:
+			return
+		case MsgCoachOK:
+			t.Fatal("a joined player's socket must not attach as a coach")
+		}
+	}
+	t.Fatal("expected an error for coach on a joined socket")
+}
+
 // ---------- Default names ----------
 
 func TestDefaultPlayerName(t *testing.T) {
@@ -625,6 +706,11 @@ func TestSessionManagerListSessions(t *testing.T) {
 	if len(list) != 2 {
 		t.Errorf("expected 2 sessions, got %d", len(list))
 	}
+	for _, info := range list {
+		if info.Max != maxPlayersPerSession {
+			t.Errorf("expected max %d, got %d", maxPlayersPerSession, info.Max)
+		}
+	}
 }
 
 func TestSessionManagerRemovePlayer(t *testing.T) {
@@ -766,8 +852,8 @@ func TestDisconnectCleansUpSession(t *testing.T) {
 	// Disconnect
 	c1.Close()
 
-	// Wait for hub to process unregister
-	time.Sleep(SessionIdleTimeout + 50*time.Millisecond)
+	// Wait for hub to process unregister, the resume grace period, then idle cleanup
+	time.Sleep(ResumeGrace + SessionIdleTimeout + 50*time.Millisecond)
 
 	// Check if session is gone
 	c2 := dialWS(t, wsURL)
@@ -778,3 +864,85 @@ func TestDisconnectCleansUpSession(t *testing.T) {
 		t.Error("session should be cleaned up after disconnect")
 	}
 }
+
+// ---------- Disconnect, then resume the same ship ----------
+
+func TestResumeAfterDisconnect(t *testing.T) {
+	srv, wsURL, cleanup := startTestServer(t)
+	_ = srv
+	defer cleanup()
+	ResumeGrace = 2 * time.Second
+
+	c1 := dialWS(t, wsURL)
+	sendMsg(t, c1, "create", map[string]string{"name": "Pilot", "sname": "ResumeArena"})
+	sid := dataMap(t, readEnvelope(t, c1))["sid"].(string)
+	sendMsg(t, c1, "join", map[string]string{"name": "Pilot", "sid": sid})
+	_ = readEnvelope(t, c1) // joined
+	welcome := dataMap(t, readEnvelope(t, c1))
+	pid, _ := welcome["id"].(string)
+	tok, _ := welcome["tok"].(string)
+	if pid == "" || tok == "" {
+		t.Fatalf("welcome should carry player id and resume token, got %v", welcome)
+	}
+	c1.Close()
+	time.Sleep(50 * time.Millisecond)
+
+	// A wrong token is rejected
+	c2 := dialWS(t, wsURL)
+	defer c2.Close()
+	sendMsg(t, c2, "resume", map[string]string{"sid": sid, "pid": pid, "tok": "nope"})
+	if env := readEnvelope(t, c2); env.T != MsgResumeFail {
+		t.Fatalf("expected resume_fail, got %s", env.T)
+	}
+
+	// The right one reattaches to the same player
+	sendMsg(t, c2, "resume", map[string]string{"sid": sid, "pid": pid, "tok": tok})
+	var resumed map[string]interface{}
+	for i := 0; i < 10 && resumed == nil; i++ {
+		env := readEnvelope(t, c2)
+		if env.T == MsgWelcome {
+			resumed = dataMap(t, env)
+		}
+	}
+	if resumed == nil || resumed["id"] != pid {
+		t.Fatalf("expected welcome for %s after resume, got %v", pid, resumed)
+	}
+}
+
+// ---------- Application-level heartbeat ----------
+
+func TestBugReportCooldownReplies(t *testing.T) {
+	srv, wsURL, cleanup := startTestServer(t)
+	_ = srv
+	defer cleanup()
+
+	c := dialWS(t, wsURL)
+	defer c.Close()
+
+	sendMsg(t, c, "bug_report", map[string]string{"desc": "ship vanished"})
+	if env := readEnvelope(t, c); env.T != MsgBugOK {
+		t.Fatalf("expected bug_ok, got %s", env.T)
+	}
+	// A second report inside the cooldown must still get an answer
+	sendMsg(t, c, "bug_report", map[string]string{"desc": "again"})
+	if env := readEnvelope(t, c); env.T != MsgError {
+		t.Fatalf("expected error during cooldown, got %s", env.T)
+	}
+}
+
+func TestPingPong(t *testing.T) {
+	_, wsURL, cleanup := startTestServer(t)
+	defer cleanup()
+
+	conn := dialWS(t, wsURL)
+	defer conn.Close()
+
+	sendMsg(t, conn, "ping", map[string]float64{"t": 1234.5})
+	env := readEnvelope(t, conn)
+	if env.T != MsgPong {
+		t.Fatalf("expected pong, got %s", env.T)
+	}
+	if ts, _ := dataMap(t, env)["t"].(float64); ts != 1234.5 {
+		t.Errorf("pong should echo the ping payload, got %v", env.Data)
+	}
+}
@@ -112,6 +112,7 @@ var mobPhrases = map[string][]string{
 // Mob is an AI-controlled enemy ship
 type Mob struct {
 	ID        string
+	NetID     uint32
 	X, Y      float64
 	VX, VY    float64
 	Rotation  float64
@@ -211,6 +212,7 @@ func newBaseMob() *Mob {
 	id := GenerateID(4)
 	m := &Mob{
 		ID:    id,
+		NetID: NextNetID(),
 		Alive: true,
 	}
 
@@ -486,7 +488,7 @@ func (m *Mob) ToState() MobState {
 	vx := round1(m.VX)
 	vy := round1(m.VY)
 	return MobState{
-		ID:    m.ID,
+		ID:    m.NetID,
 		X:     round1(m.X),
 		Y:     round1(m.Y),
 		R:     round2(m.Rotation),
@@ -9,6 +9,7 @@ const (
 // Pickup is a health orb that heals on contact
 type Pickup struct {
 	ID    string
+	NetID uint32
 	X, Y  float64
 	Life  float64
 	Alive bool
@@ -18,6 +19,7 @@ type Pickup struct {
 func NewPickup() *Pickup {
 	return &Pickup{
 		ID:    GenerateID(4),
+		NetID: NextNetID(),
 		X:     50 + randFloat()*3900,
 		Y:     50 + randFloat()*3900,
 		Life:  PickupTimeout,
@@ -39,7 +41,7 @@ func (p *Pickup) Update(dt float64) {
 // ToState converts to protocol state
 func (p *Pickup) ToState() PickupState {
 	return PickupState{
-		ID: p.ID,
+		ID: p.NetID,
 		X:  round1(p.X),
 		Y:  round1(p.Y),
 	}
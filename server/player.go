@@ -5,6 +5,8 @@ import (
 	"math"
 )
 
+// Player hull stats are mirrored in client-rust/src/constants.rs (SHIP_STATS)
+// for the ship-select cards; keep the two in sync
 const (
 	PlayerRadius     = 25.0
 	PlayerMaxHP      = 100
@@ -16,12 +18,18 @@ const (
 	RespawnTime      = 3.0    // seconds before respawn
 	WorldWidth       = 4000.0
 	WorldHeight      = 4000.0
-	TurnSpeed        = 8.0    // radians/s max turn rate
+	TurnSpeed        = 8.0          // radians/s max turn rate
+	DodgeSpeed       = 700.0        // dash velocity (pixels/s) added in the dodge direction
+	DodgeDuration    = 0.25         // seconds the dash may exceed max speed
+	DodgeCooldown    = 1.5          // seconds between dodges
+	PlayerShipTypes  = 3            // ship types 0..2 are player hulls; higher ones are mobs
+	AssistWindow     = 5 * TickRate // ticks a hit counts toward an assist (5s)
 )
 
 // Player represents a player in the game
 type Player struct {
 	ID       string
+	NetID    uint32 // compact ID used in snapshots and events; ID stays the control/resume handle
 	Name     string
 	X, Y     float64
 	VX, VY   float64
@@ -30,8 +38,13 @@ type Player struct {
 	MaxHP    int
 	ShipType int
 	Score    int
+	Kills    int
+	Deaths   int
+	Assists  int
 	Alive    bool
 	FireCD   float64 // fire cooldown remaining
+	DodgeT   float64 // dash time remaining
+	DodgeCD  float64 // dodge cooldown remaining
 	RespawnT float64 // respawn timer remaining
 	TargetR  float64 // target rotation (toward mouse)
 	Firing   bool
@@ -39,12 +52,22 @@ type Player struct {
 	TargetX   float64 // mouse world X (for distance calc)
 	TargetY   float64 // mouse world Y (for distance calc)
 	SlowThresh float64 // distance threshold for speed modulation
+	InputSeq   uint16  // sequence number of the last input applied
+	ResumeToken string // secret the owning client presents to reclaim this ship after a disconnect
+	CoachKey    string // secret in the player's coach link; attaches a spectator, can't reclaim the ship
+	NextShip    int    // hull to take on respawn, when ShipPicked
+	ShipPicked  bool
+	Gunner      bool    // a co-pilot phone aims and fires; the pilot's fire input is ignored
+	TurretR     float64 // gunner's aim, used for shots while Gunner
+	// NetID of each player who hit this ship -> tick of their last hit, for assists
+	Damagers    map[uint32]uint64
 }
 
 // NewPlayer creates a new player at a random position
 func NewPlayer(id, name string, shipType int) *Player {
 	return &Player{
 		ID:       id,
+		NetID:    NextNetID(),
 		Name:     name,
 		X:        WorldWidth/4 + randFloat()*WorldWidth/2,
 		Y:        WorldHeight/4 + randFloat()*WorldHeight/2,
@@ -55,6 +78,21 @@ func NewPlayer(id, name string, shipType int) *Player {
 	}
 }
 
+// ValidPlayerShip reports whether ship is a hull players may fly (the rest are mobs)
+func ValidPlayerShip(ship int) bool {
+	return ship >= 0 && ship < PlayerShipTypes
+}
+
+// PickShip queues a hull change for the next respawn; ship types players
+// can't fly are ignored
+func (p *Player) PickShip(ship int) {
+	if !ValidPlayerShip(ship) {
+		return
+	}
+	p.NextShip = ship
+	p.ShipPicked = ship != p.ShipType
+}
+
 // Update moves the player one tick (dt in seconds)
 func (p *Player) Update(dt float64) {
 	if !p.Alive {
@@ -116,6 +154,9 @@ func (p *Player) Update(dt float64) {
 	if p.Boosting {
 		maxSpd *= PlayerBoostMul
 	}
+	if p.DodgeT > 0 {
+		maxSpd += DodgeSpeed
+	}
 	speed := math.Sqrt(p.VX*p.VX + p.VY*p.VY)
 	if speed > maxSpd {
 		scale := maxSpd / speed
@@ -143,6 +184,25 @@ func (p *Player) Update(dt float64) {
 	if p.FireCD > 0 {
 		p.FireCD -= dt
 	}
+	if p.DodgeT > 0 {
+		p.DodgeT -= dt
+	}
+	if p.DodgeCD > 0 {
+		p.DodgeCD -= dt
+	}
+}
+
+// Dodge dashes toward angle a (radians) unless dead or on cooldown.
+// Returns true if the dash happened.
+func (p *Player) Dodge(a float64) bool {
+	if !p.Alive || p.DodgeCD > 0 {
+		return false
+	}
+	p.VX += math.Cos(a) * DodgeSpeed
+	p.VY += math.Sin(a) * DodgeSpeed
+	p.DodgeT = DodgeDuration
+	p.DodgeCD = DodgeCooldown
+	return true
 }
 
 // Respawn resets the player after death
@@ -154,7 +214,14 @@ func (p *Player) Respawn() {
 	p.HP = PlayerMaxHP
 	p.Alive = true
 	p.FireCD = 0
+	p.DodgeT = 0
+	p.DodgeCD = 0
 	p.RespawnT = 0
+	p.Damagers = nil
+	if p.ShipPicked {
+		p.ShipType = p.NextShip
+		p.ShipPicked = false
+	}
 }
 
 // TakeDamage reduces HP and returns true if player died
@@ -172,6 +239,39 @@ func (p *Player) TakeDamage(dmg int) bool {
 	return false
 }
 
+// NoteHit remembers that another player damaged this ship, for assist credit
+func (p *Player) NoteHit(attacker uint32, tick uint64) {
+	if p.Damagers == nil {
+		p.Damagers = make(map[uint32]uint64)
+	}
+	p.Damagers[attacker] = tick
+}
+
+// Assister returns whoever most recently hit this ship within AssistWindow,
+// other than the killer, or 0 if nobody did
+func (p *Player) Assister(killer uint32, tick uint64) uint32 {
+	var best uint32
+	var bestTick uint64
+	for id, at := range p.Damagers {
+		if id == killer || at+AssistWindow < tick {
+			continue
+		}
+		if best == 0 || at > bestTick || (at == bestTick && id < best) {
+			best, bestTick = id, at
+		}
+	}
+	return best
+}
+
+// FireAngle is the direction shots leave in: the gunner's aim when a
+// co-pilot is attached, otherwise the way the ship faces
+func (p *Player) FireAngle() float64 {
+	if p.Gunner {
+		return p.TurretR
+	}
+	return p.Rotation
+}
+
 // CanFire returns true if the player can fire a projectile
 func (p *Player) CanFire() bool {
 	return p.Alive && p.Firing && p.FireCD <= 0
@@ -182,19 +282,21 @@ func (p *Player) ToState() PlayerState {
 	vx := round1(p.VX)
 	vy := round1(p.VY)
 	return PlayerState{
-		ID:    p.ID,
-		Name:  p.Name,
-		X:     round1(p.X),
-		Y:     round1(p.Y),
-		R:     round2(p.Rotation),
-		VX:    &vx,
-		VY:    &vy,
-		HP:    p.HP,
-		MaxHP: p.MaxHP,
-		Ship:  p.ShipType,
-		Score: p.Score,
-		Alive: p.Alive,
-		Boost: p.Boosting,
+		ID:      p.NetID,
+		X:       round1(p.X),
+		Y:       round1(p.Y),
+		R:       round2(p.Rotation),
+		VX:      &vx,
+		VY:      &vy,
+		HP:      p.HP,
+		MaxHP:   p.MaxHP,
+		Ship:    p.ShipType,
+		Score:   p.Score,
+		Kills:   p.Kills,
+		Deaths:  p.Deaths,
+		Assists: p.Assists,
+		Alive:   p.Alive,
+		Boost:   p.Boosting,
 	}
 }
 
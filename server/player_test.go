@@ -204,6 +204,7 @@ func TestSpeedModulationPartial(t *testing.T) {
 func TestPlayerToState(t *testing.T) {
 	p := &Player{
 		ID:       "test",
+		NetID:    7,
 		Name:     "Pilot",
 		X:        100,
 		Y:        200,
@@ -217,10 +218,71 @@ func TestPlayerToState(t *testing.T) {
 		Alive:    true,
 	}
 	s := p.ToState()
-	if s.ID != "test" || s.Name != "Pilot" || s.X != 100 || s.Y != 200 {
+	if s.ID != 7 || s.X != 100 || s.Y != 200 {
 		t.Error("state mismatch")
 	}
 	if s.HP != 80 || s.MaxHP != 100 || s.Ship != 1 || s.Score != 5 {
 		t.Error("state field mismatch")
 	}
 }
+
+func TestPlayerDodge(t *testing.T) {
+	p := &Player{ID: "test", X: 500, Y: 500, Alive: true, HP: PlayerMaxHP, MaxHP: PlayerMaxHP}
+
+	if !p.Dodge(0) {
+		t.Fatal("expected first dodge to succeed")
+	}
+	if p.VX < DodgeSpeed*0.99 {
+		t.Errorf("expected dash velocity ~%v, got %v", DodgeSpeed, p.VX)
+	}
+	if p.Dodge(0) {
+		t.Error("expected dodge to be on cooldown")
+	}
+
+	// The dash isn't clamped back to normal max speed while it lasts
+	p.Update(1.0 / 60.0)
+	if speed := math.Hypot(p.VX, p.VY); speed <= PlayerMaxSpeed {
+		t.Errorf("expected dash to exceed max speed, got %v", speed)
+	}
+
+	for i := 0; i < int(DodgeCooldown*60)+1; i++ {
+		p.Update(1.0 / 60.0)
+	}
+	if !p.Dodge(math.Pi) {
+		t.Error("expected dodge to be ready after cooldown")
+	}
+
+	p.Alive = false
+	p.DodgeCD = 0
+	if p.Dodge(0) {
+		t.Error("dead players can't dodge")
+	}
+}
+
+func TestAssister(t *testing.T) {
+	p := NewPlayer("v", "Victim", 0)
+	if got := p.Assister(7, 100); got != 0 {
+		t.Errorf("expected no assist before any hits, got %d", got)
+	}
+
+	p.NoteHit(5, 100)
+	p.NoteHit(6, 200)
+	p.NoteHit(7, 250)
+	// The killer's own hits don't count; the latest other hitter does
+	if got := p.Assister(7, 300); got != 6 {
+		t.Errorf("expected assist for 6, got %d", got)
+	}
+	if got := p.Assister(6, 300); got != 7 {
+		t.Errorf("expected assist for 7, got %d", got)
+	}
+
+	// Hits older than the window have expired
+	if got := p.Assister(7, 200+AssistWindow+1); got != 0 {
+		t.Errorf("expected stale hits to be ignored, got %d", got)
+	}
+
+	p.Respawn()
+	if got := p.Assister(0, 300); got != 0 {
+		t.Errorf("expected respawn to forget hits, got %d", got)
+	}
+}
@@ -10,31 +10,46 @@ const (
 	ProjectileOffset   = 30.0 // spawn distance from ship center
 )
 
+// Projectile kinds, as sent in ProjectileState.Kind. Every weapon fires
+// lasers for now; clients already know how to draw the others.
+const (
+	ProjLaser = iota
+	ProjMissile
+	ProjBeam
+)
+
 // Projectile represents a laser projectile
 type Projectile struct {
 	ID       string
+	NetID    uint32
 	OwnerID  string
+	OwnerNet uint32 // owner's NetID, for snapshots and hit/kill events
 	X, Y     float64
 	VX, VY   float64
 	Rotation float64
 	Life     float64
 	Damage   int
+	Kind     int // ProjLaser, ProjMissile or ProjBeam
 	Alive    bool
+	FromMob  bool // fired by a mob; these only crit when MobCrits is set
 }
 
-// NewProjectile creates a projectile from a player's position and facing direction
+// NewProjectile creates a projectile from a player's position and firing direction
 func NewProjectile(owner *Player) *Projectile {
 	id := GenerateID(3)
-	vx := math.Cos(owner.Rotation) * ProjectileSpeed
-	vy := math.Sin(owner.Rotation) * ProjectileSpeed
+	a := owner.FireAngle()
+	vx := math.Cos(a) * ProjectileSpeed
+	vy := math.Sin(a) * ProjectileSpeed
 	return &Projectile{
 		ID:       id,
+		NetID:    NextNetID(),
 		OwnerID:  owner.ID,
-		X:        owner.X + math.Cos(owner.Rotation)*ProjectileOffset,
-		Y:        owner.Y + math.Sin(owner.Rotation)*ProjectileOffset,
+		OwnerNet: owner.NetID,
+		X:        owner.X + math.Cos(a)*ProjectileOffset,
+		Y:        owner.Y + math.Sin(a)*ProjectileOffset,
 		VX:       vx + owner.VX*0.3, // inherit some of ship velocity
 		VY:       vy + owner.VY*0.3,
-		Rotation: owner.Rotation,
+		Rotation: a,
 		Life:     ProjectileLifetime,
 		Damage:   ProjectileDamage,
 		Alive:    true,
@@ -48,7 +63,9 @@ func NewMobProjectile(mob *Mob) *Projectile {
 	vy := math.Sin(mob.Rotation) * ProjectileSpeed
 	return &Projectile{
 		ID:       id,
+		NetID:    NextNetID(),
 		OwnerID:  mob.ID,
+		OwnerNet: mob.NetID,
 		X:        mob.X + math.Cos(mob.Rotation)*mob.ProjOffset,
 		Y:        mob.Y + math.Sin(mob.Rotation)*mob.ProjOffset,
 		VX:       vx + mob.VX*0.3,
@@ -57,6 +74,7 @@ func NewMobProjectile(mob *Mob) *Projectile {
 		Life:     ProjectileLifetime,
 		Damage:   mob.ProjDamage,
 		Alive:    true,
+		FromMob:  true,
 	}
 }
 
@@ -89,10 +107,11 @@ func (p *Projectile) Update(dt float64) {
 // ToState converts to protocol state
 func (p *Projectile) ToState() ProjectileState {
 	return ProjectileState{
-		ID:    p.ID,
+		ID:    p.NetID,
 		X:     round1(p.X),
 		Y:     round1(p.Y),
 		R:     round1(p.Rotation),
-		Owner: p.OwnerID,
+		Owner: p.OwnerNet,
+		Kind:  p.Kind,
 	}
 }
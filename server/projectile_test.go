@@ -90,14 +90,16 @@ func TestProjectileWorldWrap(t *testing.T) {
 func TestProjectileToState(t *testing.T) {
 	proj := &Projectile{
 		ID:       "proj1",
+		NetID:    3,
 		OwnerID:  "owner1",
+		OwnerNet: 9,
 		X:        100,
 		Y:        200,
 		Rotation: 1.5,
 		Alive:    true,
 	}
 	s := proj.ToState()
-	if s.ID != "proj1" || s.Owner != "owner1" || s.X != 100 || s.Y != 200 {
+	if s.ID != 3 || s.Owner != 9 || s.X != 100 || s.Y != 200 {
 		t.Error("state mismatch")
 	}
 }
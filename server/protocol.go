@@ -6,13 +6,25 @@ import (
 
 // Client -> Server message types
 const (
-	MsgJoin    = "join"
-	MsgLeave   = "leave"
-	MsgInput   = "input"
-	MsgCreate  = "create"  // create session
-	MsgList    = "list"    // list sessions
-	MsgCheck   = "check"   // check if session exists
-	MsgControl = "control" // phone controller attach
+	MsgJoin      = "join"
+	MsgLeave     = "leave"
+	MsgInput     = "input"
+	MsgCreate    = "create"       // create session
+	MsgList      = "list"         // list sessions
+	MsgCheck     = "check"        // check if session exists
+	MsgControl   = "control"      // phone controller attach
+	MsgCoach     = "coach"        // coach spectator attach
+	MsgView      = "view"         // player camera + input overlay (relayed to coach)
+	MsgMark      = "mark"         // coach annotation (relayed to player)
+	MsgNetPrefs  = "net_prefs"    // bandwidth preferences (data saver)
+	MsgBugReport = "bug_report"   // user-submitted bug report (logged server-side)
+	MsgViewport  = "viewport"     // visible world size, for snapshot interest culling
+	MsgResume    = "resume"       // reclaim a ship after a disconnect or reload
+	MsgPing      = "ping"         // application-level heartbeat (echoed back as pong)
+	MsgShipPick  = "ship_pick"    // choose the hull flown from the next spawn
+	MsgMarker    = "marker"       // world ping placed by a player (broadcast back to the session)
+	MsgClientErr = "client_error" // client panic or undecodable message (logged server-side)
+	MsgSpectate  = "spectate"     // who the death cam follows, so snapshots cover them
 )
 
 // Server -> Client message types
@@ -31,6 +43,14 @@ const (
 	MsgCtrlOff    = "ctrl_off"    // notify desktop: controller detached
 	MsgHit        = "hit"         // damage dealt to an entity
 	MsgMobSay     = "mob_say"     // mob speech bubble
+	MsgCoachOK    = "coach_ok"    // coach attach confirmed
+	MsgCoachOn    = "coach_on"    // notify player: coach is watching
+	MsgCoachOff   = "coach_off"   // notify player: coach left
+	MsgBugOK      = "bug_ok"      // bug report received
+	MsgResumeFail = "resume_fail" // resume rejected (expired or bad token); join normally
+	MsgPong       = "pong"        // heartbeat reply, echoes the ping payload
+	MsgNames      = "names"       // NetID -> player name table (snapshots carry only NetIDs)
+	MsgPings      = "pings"       // NetID -> ping table for the scoreboard
 )
 
 // Envelope wraps all outgoing messages with a type field
@@ -52,12 +72,15 @@ type ClientInput struct {
 	Fire  bool    `json:"fire"`  // W key held
 	Boost bool    `json:"boost"` // Shift key held
 	Thresh float64 `json:"thresh"` // distance threshold for speed modulation
+	Seq    uint16  `json:"seq"`    // input sequence number (wraps), echoed back as GameState.Ack
+	Dodge  bool    `json:"dodge"`  // dash toward the target this input (edge-triggered)
 }
 
 // JoinMsg is sent when player wants to join a session
 type JoinMsg struct {
 	Name      string `json:"name"`
 	SessionID string `json:"sid"`
+	Ship      *int   `json:"ship,omitempty"` // hull picked in the lobby; omitted to be assigned one
 }
 
 // CreateMsg is sent when player wants to create a session
@@ -66,10 +89,10 @@ type CreateMsg struct {
 	SessionName string `json:"sname"`
 }
 
-// PlayerState is broadcast per player each tick
+// PlayerState is broadcast per player each tick. Names travel separately in
+// NamesMsg so snapshots stay free of strings.
 type PlayerState struct {
-	ID   string  `json:"id" msgpack:"id"`
-	Name string  `json:"n" msgpack:"n"`
+	ID   uint32  `json:"id" msgpack:"id"`
 	X    float64 `json:"x" msgpack:"x"`
 	Y    float64 `json:"y" msgpack:"y"`
 	R    float64 `json:"r" msgpack:"r"`
@@ -79,22 +102,26 @@ type PlayerState struct {
 	MaxHP int    `json:"mhp" msgpack:"mhp"`
 	Ship int     `json:"s" msgpack:"s"`
 	Score int    `json:"sc" msgpack:"sc"`
+	Kills int    `json:"k,omitempty" msgpack:"k,omitempty"` // players shot or rammed down
+	Deaths int   `json:"d,omitempty" msgpack:"d,omitempty"`
+	Assists int  `json:"as,omitempty" msgpack:"as,omitempty"`
 	Alive bool   `json:"a" msgpack:"a"`
 	Boost bool   `json:"b,omitempty" msgpack:"b,omitempty"`
 }
 
 // ProjectileState is broadcast per projectile
 type ProjectileState struct {
-	ID string  `json:"id" msgpack:"id"`
-	X  float64 `json:"x" msgpack:"x"`
-	Y  float64 `json:"y" msgpack:"y"`
-	R  float64 `json:"r" msgpack:"r"`
-	Owner string `json:"o" msgpack:"o"`
+	ID    uint32  `json:"id" msgpack:"id"`
+	X     float64 `json:"x" msgpack:"x"`
+	Y     float64 `json:"y" msgpack:"y"`
+	R     float64 `json:"r" msgpack:"r"`
+	Owner uint32  `json:"o" msgpack:"o"`
+	Kind  int     `json:"k,omitempty" msgpack:"k,omitempty"` // ProjLaser when left out
 }
 
 // MobState is broadcast per mob
 type MobState struct {
-	ID    string   `json:"id" msgpack:"id"`
+	ID    uint32   `json:"id" msgpack:"id"`
 	X     float64  `json:"x" msgpack:"x"`
 	Y     float64  `json:"y" msgpack:"y"`
 	R     float64  `json:"r" msgpack:"r"`
@@ -108,15 +135,17 @@ type MobState struct {
 
 // AsteroidState is broadcast per asteroid
 type AsteroidState struct {
-	ID string  `json:"id" msgpack:"id"`
-	X  float64 `json:"x" msgpack:"x"`
-	Y  float64 `json:"y" msgpack:"y"`
-	R  float64 `json:"r" msgpack:"r"`
+	ID   uint32  `json:"id" msgpack:"id"`
+	X    float64 `json:"x" msgpack:"x"`
+	Y    float64 `json:"y" msgpack:"y"`
+	R    float64 `json:"r" msgpack:"r"`
+	HP   int     `json:"hp" msgpack:"hp"`
+	Spin float64 `json:"sp" msgpack:"sp"` // rad/s, so clients can keep it turning between snapshots
 }
 
 // PickupState is broadcast per pickup
 type PickupState struct {
-	ID string  `json:"id" msgpack:"id"`
+	ID uint32  `json:"id" msgpack:"id"`
 	X  float64 `json:"x" msgpack:"x"`
 	Y  float64 `json:"y" msgpack:"y"`
 }
@@ -129,33 +158,52 @@ type GameState struct {
 	Asteroids   []AsteroidState   `json:"a" msgpack:"a"`
 	Pickups     []PickupState     `json:"pk" msgpack:"pk"`
 	Tick        uint64            `json:"tick" msgpack:"tick"`
+	Ack         uint16            `json:"ack,omitempty" msgpack:"ack,omitempty"` // last input seq applied for the recipient
+	Delta       bool              `json:"dl,omitempty" msgpack:"dl,omitempty"`   // only changed entities; merge instead of replace
+	Removed     []uint32          `json:"rm,omitempty" msgpack:"rm,omitempty"`   // IDs gone since the previous snapshot (deltas only)
 }
 
 // WelcomeMsg is sent to a player when they join
 type WelcomeMsg struct {
-	ID   string `json:"id"`
-	Ship int    `json:"s"`
+	ID       string            `json:"id"`
+	NetID    uint32            `json:"nid"` // the player's ID in snapshots and events
+	Ship     int               `json:"s"`
+	Token    string            `json:"tok,omitempty"`   // resume token, kept by the client to reclaim the ship
+	CoachKey string            `json:"ck,omitempty"`    // goes in coach links: lets a spectator watch, not take over
+	Names    map[uint32]string `json:"names,omitempty"` // NetID -> name for everyone in the session
 }
 
 // DeathMsg notifies a player they died
 type DeathMsg struct {
-	KillerID   string `json:"kid"`
-	KillerName string `json:"kn"`
+	KillerID   uint32  `json:"kid"`
+	KillerName string  `json:"kn"`
+	RespawnIn  float64 `json:"rt"` // seconds until the ship respawns
 }
 
 // KillMsg is broadcast to all players in session
 type KillMsg struct {
-	KillerID   string `json:"kid"`
+	KillerID   uint32 `json:"kid"` // 0 for an asteroid
 	KillerName string `json:"kn"`
-	VictimID   string `json:"vid"`
+	VictimID   uint32 `json:"vid"`
 	VictimName string `json:"vn"`
+	Cause      string `json:"c"` // what did it, for the kill feed icon
+	AssistID   uint32 `json:"aid,omitempty"` // latest other player to hit the victim, if any
+	AssistName string `json:"an,omitempty"`
 }
 
+// Kill causes
+const (
+	CauseLaser    = "laser"    // shot down
+	CauseRam      = "ram"      // ship-to-ship or mob collision
+	CauseAsteroid = "asteroid" // flew into an asteroid
+)
+
 // SessionInfo is used in the session list
 type SessionInfo struct {
 	ID      string `json:"id"`
 	Name    string `json:"name"`
 	Players int    `json:"players"`
+	Max     int    `json:"max"` // player cap, so the lobby can tell full sessions apart
 }
 
 // ErrorMsg sends error to client
@@ -167,6 +215,7 @@ type ErrorMsg struct {
 type ControlMsg struct {
 	SID      string `json:"sid"`
 	PlayerID string `json:"pid"`
+	Role     string `json:"role,omitempty"` // "gunner" for a co-pilot phone on the turret; empty for the pilot
 }
 
 // CheckMsg is sent by client to check if a session exists
@@ -187,12 +236,112 @@ type HitMsg struct {
 	X          float64 `json:"x"`
 	Y          float64 `json:"y"`
 	Dmg        int     `json:"dmg"`
-	VictimID   string  `json:"vid"`
-	AttackerID string  `json:"aid"`
+	VictimID   uint32  `json:"vid"`
+	AttackerID uint32  `json:"aid"` // 0 for an asteroid
+	Crit       bool    `json:"cr,omitempty"` // hit from behind, see ShotDamage
 }
 
 // MobSayMsg is broadcast when a mob says a phrase
 type MobSayMsg struct {
-	MobID string `json:"mid"`
+	MobID uint32 `json:"mid"`
 	Text  string `json:"text"`
 }
+
+// NamesMsg maps player NetIDs to display names. The whole table is resent
+// whenever someone joins, so it replaces whatever the client had.
+type NamesMsg struct {
+	Names map[uint32]string `json:"names"`
+}
+
+// PingsMsg maps player NetIDs to their latest round trip in ms. Players still
+// waiting on their first pong, or disconnected, are left out.
+type PingsMsg struct {
+	Pings map[uint32]int `json:"p"`
+}
+
+// AttachOKMsg confirms a controller or coach attach
+type AttachOKMsg struct {
+	PID   string            `json:"pid"`
+	NetID uint32            `json:"nid"`             // the attached player's ID in snapshots
+	Names map[uint32]string `json:"names,omitempty"` // coaches only, to label ships
+}
+
+// CoachMsg is sent by a coach to spectate a player's view
+type CoachMsg struct {
+	SID      string `json:"sid"`
+	PlayerID string `json:"pid"`
+	Key      string `json:"ck"` // the player's CoachKey, from the link they shared
+}
+
+// ViewMsg is the player's camera and input overlay, relayed to their coach
+type ViewMsg struct {
+	VW    float64 `json:"vw"` // visible world width
+	VH    float64 `json:"vh"` // visible world height
+	MX    float64 `json:"mx"` // cursor X (world coords)
+	MY    float64 `json:"my"` // cursor Y (world coords)
+	Fire  bool    `json:"f"`
+	Boost bool    `json:"b"`
+}
+
+// MarkMsg is a coach annotation: a freehand stroke or a single ping
+type MarkMsg struct {
+	Kind string    `json:"k"`   // "line" or "ping"
+	Pts  []float64 `json:"pts"` // flat world coords [x0, y0, x1, y1, ...]
+}
+
+// MarkerMsg is a world ping. Players send a position (or Aim, from a phone
+// controller, to mark where the ship is aiming); the server fills in who
+// placed it and broadcasts it to the session.
+type MarkerMsg struct {
+	X    float64 `json:"x"`
+	Y    float64 `json:"y"`
+	Aim  bool    `json:"aim,omitempty"`
+	ID   string  `json:"id,omitempty"`
+	Name string  `json:"n,omitempty"`
+}
+
+// NetPrefsMsg is sent by a client to request reduced snapshot traffic
+type NetPrefsMsg struct {
+	Saver bool `json:"saver"`
+}
+
+// ShipPickMsg chooses the hull a player respawns in
+type ShipPickMsg struct {
+	Ship int `json:"ship"`
+}
+
+// SpectateMsg names the ship or mob (NetID, 0 for none) a dead player's camera follows
+type SpectateMsg struct {
+	ID uint32 `json:"id"`
+}
+
+// ViewportMsg reports the world-space size of the client's screen
+type ViewportMsg struct {
+	VW float64 `json:"vw"`
+	VH float64 `json:"vh"`
+}
+
+// BugReportMsg is a player's bug report: description, client version,
+// snapshot metadata and the tail of the client's log buffer
+type BugReportMsg struct {
+	Desc    string          `json:"desc"`
+	Version string          `json:"ver"`
+	Meta    json.RawMessage `json:"meta,omitempty"`
+	Log     []string        `json:"log,omitempty"`
+}
+
+// ClientErrorMsg reports a client crash: "panic" for a Rust panic, "envelope"
+// for a server message the client could not decode or did not recognize
+type ClientErrorMsg struct {
+	Kind    string `json:"kind"`
+	Msg     string `json:"msg"`
+	Version string `json:"ver"`
+	Phase   string `json:"phase,omitempty"`
+}
+
+// ResumeMsg asks to reattach to a ship after a dropped connection
+type ResumeMsg struct {
+	SID   string `json:"sid"`
+	PID   string `json:"pid"`
+	Token string `json:"tok"`
+}
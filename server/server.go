@@ -6,6 +6,7 @@ import (
 	"net"
 	"net/http"
 	"net/url"
+	"os"
 	"path/filepath"
 	"regexp"
 	"runtime"
@@ -47,8 +48,18 @@ func SetupRoutes(hub *Hub, clientRustDir string) *http.ServeMux {
 	if clientRustDir != "" {
 		rustFs := http.FileServer(http.Dir(clientRustDir))
 
+		// Controller links (/<uuid>?c=<pid>) get the slim controller bundle when it was built
+		controllerPage := filepath.Join(clientRustDir, "controller", "index.html")
+		if _, err := os.Stat(controllerPage); err != nil {
+			controllerPage = ""
+		}
+
 		mux.Handle("/", http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
 			w.Header().Set("Cache-Control", "no-cache")
+			if controllerPage != "" && uuidPathRe.MatchString(r.URL.Path) && r.URL.Query().Get("c") != "" {
+				http.ServeFile(w, r, controllerPage)
+				return
+			}
 			if r.URL.Path == "/" || uuidPathRe.MatchString(r.URL.Path) {
 				http.ServeFile(w, r, filepath.Join(clientRustDir, "index.html"))
 				return
@@ -9,6 +9,9 @@ const maxSessions = 100
 
 var SessionIdleTimeout = time.Minute
 
+// ResumeGrace is how long a disconnected player's ship waits for its client to resume
+var ResumeGrace = 20 * time.Second
+
 // Session represents a game session that players can join
 type Session struct {
 	ID   string
@@ -80,8 +83,11 @@ func (sm *SessionManager) RemovePlayer(sessionID, playerID string) {
 		return
 	}
 	sess.Game.RemovePlayer(playerID)
+	sm.cleanupIfEmpty(sessionID, sess)
+}
 
-	// Clean up empty sessions after idle timeout
+// cleanupIfEmpty drops an empty session after the idle timeout
+func (sm *SessionManager) cleanupIfEmpty(sessionID string, sess *Session) {
 	if sess.Game.PlayerCount() == 0 {
 		sess.scheduleCleanup(SessionIdleTimeout, func() {
 			if sess.Game.PlayerCount() != 0 {
@@ -97,6 +103,24 @@ func (sm *SessionManager) RemovePlayer(sessionID, playerID string) {
 	}
 }
 
+// DetachPlayer keeps a disconnected player's ship around for ResumeGrace,
+// then removes it unless the client has resumed in the meantime
+func (sm *SessionManager) DetachPlayer(sessionID, playerID string, client Broadcaster) {
+	sess := sm.GetSession(sessionID)
+	if sess == nil {
+		return
+	}
+	at, ok := sess.Game.DetachClient(playerID, client)
+	if !ok {
+		return
+	}
+	time.AfterFunc(ResumeGrace, func() {
+		if sess.Game.RemoveIfDetachedSince(playerID, at) {
+			sm.cleanupIfEmpty(sessionID, sess)
+		}
+	})
+}
+
 // ListSessions returns info about all active sessions
 func (sm *SessionManager) ListSessions() []SessionInfo {
 	sm.mu.RLock()
@@ -108,6 +132,7 @@ func (sm *SessionManager) ListSessions() []SessionInfo {
 			ID:      sess.ID,
 			Name:    sess.Name,
 			Players: sess.Game.PlayerCount(),
+			Max:     maxPlayersPerSession,
 		})
 	}
 	return list
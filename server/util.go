@@ -5,8 +5,22 @@ import (
 	"encoding/hex"
 	"fmt"
 	"math"
+	"sync/atomic"
 )
 
+var netIDSeq atomic.Uint32
+
+// NextNetID returns a fresh wire ID for an entity. It is unique across all
+// entity kinds (delta tracking keys on it alone); 0 is never handed out and
+// means "no entity" in events, e.g. a kill by an asteroid.
+func NextNetID() uint32 {
+	for {
+		if id := netIDSeq.Add(1); id != 0 {
+			return id
+		}
+	}
+}
+
 // GenerateID returns a random hex string of the given byte length
 func GenerateID(byteLen int) string {
 	b := make([]byte, byteLen)